@@ -357,6 +357,30 @@ impl ActivityIndicator {
             });
         }
 
+        // Show any fetch, pull or push in progress.
+        let mut git_operations = self.project.read(cx).pending_git_operations();
+        if let Some(operation) = git_operations.next() {
+            let mut message = operation.to_string();
+            let additional_operation_count = git_operations.count();
+            if additional_operation_count > 0 {
+                write!(&mut message, " + {} more", additional_operation_count).unwrap();
+            }
+            return Some(Content {
+                icon: Some(
+                    Icon::new(IconName::ArrowCircle)
+                        .size(IconSize::Small)
+                        .with_animation(
+                            "arrow-circle",
+                            Animation::new(Duration::from_secs(2)).repeat(),
+                            |icon, delta| icon.transform(Transformation::rotate(percentage(delta))),
+                        )
+                        .into_any_element(),
+                ),
+                message,
+                on_click: None,
+            });
+        }
+
         // Show any application auto-update info.
         if let Some(updater) = &self.auto_updater {
             return match &updater.read(cx).status() {
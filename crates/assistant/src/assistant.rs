@@ -9,17 +9,19 @@ mod model_selector;
 mod patch;
 mod prompt_library;
 mod prompts;
+pub(crate) mod resource_picker;
 mod slash_command;
 pub(crate) mod slash_command_picker;
 pub mod slash_command_settings;
 mod streaming_diff;
 mod terminal_inline_assistant;
+pub mod tool_settings;
 mod tools;
 
 pub use assistant_panel::{AssistantPanel, AssistantPanelEvent};
 use assistant_settings::AssistantSettings;
 use assistant_slash_command::SlashCommandRegistry;
-use assistant_tool::ToolRegistry;
+use assistant_tool::{Tool, ToolRegistry};
 use client::{proto, Client};
 use command_palette_hooks::CommandPaletteFilter;
 pub use context::*;
@@ -45,8 +47,8 @@ use slash_command::workflow_command::WorkflowSlashCommand;
 use slash_command::{
     auto_command, cargo_workspace_command, context_server_command, default_command, delta_command,
     diagnostics_command, docs_command, fetch_command, file_command, now_command, project_command,
-    prompt_command, search_command, symbols_command, tab_command, terminal_command,
-    workflow_command,
+    prompt_command, resource_command, search_command, symbols_command, tab_command,
+    terminal_command, workflow_command,
 };
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -54,6 +56,7 @@ pub(crate) use streaming_diff::*;
 use util::ResultExt;
 
 use crate::slash_command_settings::SlashCommandSettings;
+use crate::tool_settings::ToolSettings;
 
 actions!(
     assistant,
@@ -200,6 +203,7 @@ pub fn init(
     cx.set_global(Assistant::default());
     AssistantSettings::register(cx);
     SlashCommandSettings::register(cx);
+    ToolSettings::register(cx);
 
     // TODO: remove this when 0.148.0 is released.
     if AssistantSettings::get_global(cx).using_outdated_settings_version {
@@ -292,6 +296,7 @@ fn register_context_server_handlers(cx: &mut AppContext) {
                     |manager: &mut context_servers::manager::ContextServerManager, cx| {
                         let slash_command_registry = SlashCommandRegistry::global(cx);
                         let context_server_registry = ContextServerRegistry::global(cx);
+                        let tool_registry = ToolRegistry::global(cx);
                         if let Some(server) = manager.get_server(server_id) {
                             cx.spawn(|_, _| async move {
                                 let Some(protocol) = server.client.read().clone() else {
@@ -319,6 +324,27 @@ fn register_context_server_handlers(cx: &mut AppContext) {
                                         );
                                     }
                                 }
+
+                                if protocol
+                                    .capable(context_servers::protocol::ServerCapability::Tools)
+                                {
+                                    tools::context_server_tool::refresh_context_server_tools(
+                                        &server.id,
+                                        &protocol,
+                                        &context_server_registry,
+                                        &tool_registry,
+                                    )
+                                    .await;
+                                }
+
+                                if protocol
+                                    .capable(context_servers::protocol::ServerCapability::Resources)
+                                {
+                                    slash_command_registry.register_command(
+                                        resource_command::ResourceSlashCommand,
+                                        true,
+                                    );
+                                }
                             })
                             .detach();
                         }
@@ -334,7 +360,44 @@ fn register_context_server_handlers(cx: &mut AppContext) {
                         context_server_registry.unregister_command(&server_id, &command_name);
                     }
                 }
+
+                tools::context_server_tool::unregister_context_server_tools(
+                    server_id,
+                    &context_server_registry,
+                    &ToolRegistry::global(cx),
+                );
+
+                let has_resources = manager.read(cx).servers().iter().any(|server| {
+                    server.client.read().as_ref().map_or(false, |protocol| {
+                        protocol.capable(context_servers::protocol::ServerCapability::Resources)
+                    })
+                });
+                if !has_resources {
+                    slash_command_registry
+                        .unregister_command_by_name(resource_command::ResourceSlashCommand::NAME);
+                }
+            }
+            context_servers::manager::Event::ToolsChanged { server_id } => {
+                let context_server_registry = ContextServerRegistry::global(cx);
+                let tool_registry = ToolRegistry::global(cx);
+                if let Some(server) = manager.read(cx).get_server(server_id) {
+                    cx.spawn(|_| async move {
+                        let Some(protocol) = server.client.read().clone() else {
+                            return;
+                        };
+                        tools::context_server_tool::refresh_context_server_tools(
+                            &server.id,
+                            &protocol,
+                            &context_server_registry,
+                            &tool_registry,
+                        )
+                        .await;
+                    })
+                    .detach();
+                }
             }
+            // Sections showing updated resources are refreshed by the context stores.
+            context_servers::manager::Event::ResourceUpdated { .. } => {}
         },
     )
     .detach();
@@ -475,6 +538,54 @@ fn update_slash_commands_from_settings(cx: &mut AppContext) {
 fn register_tools(cx: &mut AppContext) {
     let tool_registry = ToolRegistry::global(cx);
     tool_registry.register_tool(tools::now_tool::NowTool);
+
+    update_tools_from_settings(cx);
+    cx.observe_global::<SettingsStore>(update_tools_from_settings)
+        .detach();
+}
+
+fn update_tools_from_settings(cx: &mut AppContext) {
+    let tool_registry = ToolRegistry::global(cx);
+    let settings = ToolSettings::get_global(cx);
+
+    fn update_tool(tool_registry: &ToolRegistry, allowed: bool, tool: impl Tool) {
+        if allowed {
+            tool_registry.register_tool(tool);
+        } else {
+            tool_registry.unregister_tool(tool);
+        }
+    }
+
+    update_tool(
+        &tool_registry,
+        settings.read_file.allowed,
+        tools::read_file_tool::ReadFileTool,
+    );
+    update_tool(
+        &tool_registry,
+        settings.list_directory.allowed,
+        tools::list_directory_tool::ListDirectoryTool,
+    );
+    update_tool(
+        &tool_registry,
+        settings.search_project.allowed,
+        tools::search_project_tool::SearchProjectTool,
+    );
+    update_tool(
+        &tool_registry,
+        settings.diagnostics.allowed,
+        tools::diagnostics_tool::DiagnosticsTool,
+    );
+    update_tool(
+        &tool_registry,
+        settings.find_symbols.allowed,
+        tools::find_symbols_tool::FindSymbolsTool,
+    );
+    update_tool(
+        &tool_registry,
+        settings.propose_edits.allowed,
+        tools::propose_edits_tool::ProposeEditsTool,
+    );
 }
 
 pub fn humanize_token_count(count: usize) -> String {
@@ -3,10 +3,12 @@ use crate::{
     humanize_token_count,
     prompt_library::open_prompt_library,
     prompts::PromptBuilder,
+    resource_picker,
     slash_command::{
         default_command::DefaultSlashCommand,
         docs_command::{DocsSlashCommand, DocsSlashCommandArgs},
         file_command::{self, codeblock_fence_for_path},
+        resource_command::ResourceSlashCommand,
         SlashCommandCompletionProvider, SlashCommandRegistry,
     },
     slash_command_picker,
@@ -23,6 +25,7 @@ use assistant_slash_command::{SlashCommand, SlashCommandOutputSection};
 use assistant_tool::ToolRegistry;
 use client::{proto, zed_urls, Client, Status};
 use collections::{BTreeSet, HashMap, HashSet};
+use context_servers::{manager::ContextServerManager, protocol::ServerCapability};
 use editor::{
     actions::{FoldAt, MoveToEndOfLine, Newline, ShowCompletions, UnfoldAt},
     display_map::{
@@ -1677,24 +1680,7 @@ impl ContextEditor {
         if let Some(command) = SlashCommandRegistry::global(cx).command(name) {
             self.editor.update(cx, |editor, cx| {
                 editor.transact(cx, |editor, cx| {
-                    editor.change_selections(Some(Autoscroll::fit()), cx, |s| s.try_cancel());
-                    let snapshot = editor.buffer().read(cx).snapshot(cx);
-                    let newest_cursor = editor.selections.newest::<Point>(cx).head();
-                    if newest_cursor.column > 0
-                        || snapshot
-                            .chars_at(newest_cursor)
-                            .next()
-                            .map_or(false, |ch| ch != '\n')
-                    {
-                        editor.move_to_end_of_line(
-                            &MoveToEndOfLine {
-                                stop_at_soft_wraps: false,
-                            },
-                            cx,
-                        );
-                        editor.newline(&Newline, cx);
-                    }
-
+                    Self::move_to_empty_line(editor, cx);
                     editor.insert(&format!("/{name}"), cx);
                     if command.accepts_arguments() {
                         editor.insert(" ", cx);
@@ -1708,6 +1694,49 @@ impl ContextEditor {
         }
     }
 
+    /// Inserts a `/resource` command for a resource of a context server, and runs it
+    /// unless the URI is a template whose placeholders have to be filled in first.
+    pub fn insert_resource_command(
+        &mut self,
+        server_id: &str,
+        uri: &str,
+        run: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            editor.transact(cx, |editor, cx| {
+                Self::move_to_empty_line(editor, cx);
+                editor.insert(
+                    &format!("/{} {server_id} {uri}", ResourceSlashCommand::NAME),
+                    cx,
+                );
+            });
+        });
+        if run {
+            self.confirm_command(&ConfirmCommand, cx);
+        }
+    }
+
+    fn move_to_empty_line(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+        editor.change_selections(Some(Autoscroll::fit()), cx, |s| s.try_cancel());
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let newest_cursor = editor.selections.newest::<Point>(cx).head();
+        if newest_cursor.column > 0
+            || snapshot
+                .chars_at(newest_cursor)
+                .next()
+                .map_or(false, |ch| ch != '\n')
+        {
+            editor.move_to_end_of_line(
+                &MoveToEndOfLine {
+                    stop_at_soft_wraps: false,
+                },
+                cx,
+            );
+            editor.newline(&Newline, cx);
+        }
+    }
+
     pub fn confirm_command(&mut self, _: &ConfirmCommand, cx: &mut ViewContext<Self>) {
         if self.editor.read(cx).has_active_completions_menu() {
             return;
@@ -3949,6 +3978,9 @@ impl Render for ContextEditor {
                             h_flex()
                                 .gap_2()
                                 .child(render_inject_context_menu(cx.view().downgrade(), cx))
+                                .when(has_context_server_resources(cx), |this| {
+                                    this.child(render_insert_resource_menu(cx.view().downgrade()))
+                                })
                                 .child(
                                     IconButton::new("quote-button", IconName::Quote)
                                         .icon_size(IconSize::Small)
@@ -4255,6 +4287,27 @@ fn render_inject_context_menu(
     )
 }
 
+fn has_context_server_resources(cx: &AppContext) -> bool {
+    ContextServerManager::global(cx)
+        .read(cx)
+        .servers()
+        .iter()
+        .any(|server| {
+            server.client.read().as_ref().map_or(false, |protocol| {
+                protocol.capable(ServerCapability::Resources)
+            })
+        })
+}
+
+fn render_insert_resource_menu(active_context_editor: WeakView<ContextEditor>) -> impl IntoElement {
+    resource_picker::ResourceSelector::new(
+        active_context_editor,
+        IconButton::new("insert-resource", IconName::Server)
+            .icon_size(IconSize::Small)
+            .tooltip(|cx| Tooltip::text("Insert Resource", cx)),
+    )
+}
+
 impl ContextEditorToolbarItem {
     pub fn new(
         workspace: &Workspace,
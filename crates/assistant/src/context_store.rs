@@ -1,10 +1,11 @@
 use crate::{
-    prompts::PromptBuilder, Context, ContextEvent, ContextId, ContextOperation, ContextVersion,
-    SavedContext, SavedContextMetadata,
+    prompts::PromptBuilder, slash_command::resource_command, Context, ContextEvent, ContextId,
+    ContextOperation, ContextVersion, SavedContext, SavedContextMetadata,
 };
 use anyhow::{anyhow, Context as _, Result};
 use client::{proto, telemetry::Telemetry, Client, TypedEnvelope};
 use clock::ReplicaId;
+use context_servers::manager::ContextServerManager;
 use fs::Fs;
 use futures::StreamExt;
 use fuzzy::StringMatchCandidate;
@@ -53,6 +54,7 @@ pub struct ContextStore {
     project_is_shared: bool,
     client_subscription: Option<client::Subscription>,
     _project_subscriptions: Vec<gpui::Subscription>,
+    _context_server_subscription: Option<gpui::Subscription>,
     prompt_builder: Arc<PromptBuilder>,
 }
 
@@ -120,6 +122,8 @@ impl ContextStore {
                         cx.observe(&project, Self::handle_project_changed),
                         cx.subscribe(&project, Self::handle_project_event),
                     ],
+                    _context_server_subscription: ContextServerManager::try_global(cx)
+                        .map(|manager| cx.subscribe(&manager, Self::handle_context_server_event)),
                     project_is_shared: false,
                     client: project.read(cx).client(),
                     project: project.clone(),
@@ -578,6 +582,29 @@ impl ContextStore {
         }
     }
 
+    fn handle_context_server_event(
+        &mut self,
+        manager: Model<ContextServerManager>,
+        event: &context_servers::manager::Event,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if let context_servers::manager::Event::ResourceUpdated { server_id, uri } = event {
+            let Some(server) = manager.read(cx).get_server(server_id) else {
+                return;
+            };
+
+            // Remote contexts are refreshed by their host.
+            let contexts = self
+                .contexts
+                .iter()
+                .filter_map(ContextHandle::upgrade)
+                .filter(|context| context.read(cx).replica_id() == ReplicaId::default())
+                .collect();
+            resource_command::refresh_resource_sections(server, uri.clone(), contexts, cx)
+                .detach_and_log_err(cx);
+        }
+    }
+
     fn advertise_contexts(&self, cx: &AppContext) {
         let Some(project_id) = self.project.read(cx).remote_id() else {
             return;
@@ -0,0 +1,177 @@
+use std::sync::Arc;
+
+use gpui::{DismissEvent, SharedString, Task, WeakView};
+use picker::{Picker, PickerDelegate};
+use ui::{prelude::*, ListItem, ListItemSpacing, PopoverMenu, PopoverTrigger};
+
+use crate::assistant_panel::ContextEditor;
+use crate::slash_command::resource_command::{self, ResourceEntry};
+
+/// A picker of the resources of the running context servers, which inserts
+/// the selected one into the active context.
+#[derive(IntoElement)]
+pub(super) struct ResourceSelector<T: PopoverTrigger> {
+    active_context_editor: WeakView<ContextEditor>,
+    trigger: T,
+}
+
+pub(crate) struct ResourceDelegate {
+    all_entries: Vec<ResourceEntry>,
+    filtered_entries: Vec<ResourceEntry>,
+    active_context_editor: WeakView<ContextEditor>,
+    selected_index: usize,
+    is_loading: bool,
+}
+
+impl<T: PopoverTrigger> ResourceSelector<T> {
+    pub(crate) fn new(active_context_editor: WeakView<ContextEditor>, trigger: T) -> Self {
+        ResourceSelector {
+            active_context_editor,
+            trigger,
+        }
+    }
+}
+
+impl PickerDelegate for ResourceDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.filtered_entries.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix.min(self.filtered_entries.len().saturating_sub(1));
+        cx.notify();
+    }
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Select a resource...".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        if self.is_loading {
+            "Loading resources...".into()
+        } else {
+            "No resources found".into()
+        }
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let query = query.to_lowercase();
+        self.filtered_entries = self
+            .all_entries
+            .iter()
+            .filter(|entry| {
+                entry.name.to_lowercase().contains(&query)
+                    || entry.uri.to_lowercase().contains(&query)
+                    || entry.server_id.to_lowercase().contains(&query)
+            })
+            .cloned()
+            .collect();
+        self.set_selected_index(0, cx);
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, _secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(entry) = self.filtered_entries.get(self.selected_index) {
+            self.active_context_editor
+                .update(cx, |context_editor, cx| {
+                    context_editor.insert_resource_command(
+                        &entry.server_id,
+                        &entry.uri,
+                        !entry.is_template,
+                        cx,
+                    )
+                })
+                .ok();
+            cx.emit(DismissEvent);
+        }
+    }
+
+    fn dismissed(&mut self, _cx: &mut ViewContext<Picker<Self>>) {}
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = self.filtered_entries.get(ix)?;
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    v_flex()
+                        .min_w(px(250.))
+                        .child(
+                            h_flex()
+                                .gap_1()
+                                .child(Label::new(entry.name.clone()).size(LabelSize::Small))
+                                .child(
+                                    Label::new(entry.server_id.clone())
+                                        .size(LabelSize::XSmall)
+                                        .color(Color::Muted),
+                                ),
+                        )
+                        .child(
+                            div().font_buffer(cx).child(
+                                Label::new(entry.uri.clone())
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                        )
+                        .children(entry.description.clone().map(|description| {
+                            Label::new(description)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                        })),
+                ),
+        )
+    }
+}
+
+impl<T: PopoverTrigger> RenderOnce for ResourceSelector<T> {
+    fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
+        let active_context_editor = self.active_context_editor;
+        PopoverMenu::new("resource-picker")
+            .menu(move |cx| {
+                let delegate = ResourceDelegate {
+                    all_entries: Vec::new(),
+                    filtered_entries: Vec::new(),
+                    active_context_editor: active_context_editor.clone(),
+                    selected_index: 0,
+                    is_loading: true,
+                };
+                Some(cx.new_view(|cx| {
+                    let entries = resource_command::list_resources(cx);
+                    cx.spawn(|picker, mut cx| async move {
+                        let entries = entries.await;
+                        picker
+                            .update(&mut cx, |picker, cx| {
+                                picker.delegate.all_entries = entries;
+                                picker.delegate.is_loading = false;
+                                picker.refresh(cx);
+                            })
+                            .ok();
+                    })
+                    .detach();
+
+                    Picker::list(delegate, cx).max_height(Some(rems(20.).into()))
+                }))
+            })
+            .trigger(self.trigger)
+            .attach(gpui::AnchorCorner::TopLeft)
+            .anchor(gpui::AnchorCorner::BottomLeft)
+            .offset(gpui::Point {
+                x: px(0.0),
+                y: px(-16.0),
+            })
+    }
+}
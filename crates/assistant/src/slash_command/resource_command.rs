@@ -0,0 +1,503 @@
+use std::ops::Range;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Result};
+use assistant_slash_command::{
+    AfterCompletion, ArgumentCompletion, SlashCommand, SlashCommandOutput,
+    SlashCommandOutputSection,
+};
+use context_servers::manager::{ContextServer, ContextServerManager};
+use context_servers::protocol::ServerCapability;
+use gpui::{AppContext, Model, Task, WeakView, WindowContext};
+use language::{BufferSnapshot, CodeLabel, LspAdapterDelegate, OffsetRangeExt};
+use serde::{Deserialize, Serialize};
+use text::LineEnding;
+use ui::prelude::*;
+use url::Url;
+use util::ResultExt;
+use workspace::Workspace;
+
+use crate::Context;
+
+pub(crate) struct ResourceSlashCommand;
+
+impl ResourceSlashCommand {
+    pub const NAME: &'static str = "resource";
+}
+
+/// Identifies the resource an output section of `/resource` was created from,
+/// so that the section can be refreshed when the resource changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ResourceSectionMetadata {
+    server_id: String,
+    uri: Url,
+}
+
+/// A resource or resource template of a context server.
+#[derive(Debug, Clone)]
+pub(crate) struct ResourceEntry {
+    pub server_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// The URI of the resource, or the URI template of the resource template.
+    pub uri: String,
+    pub is_template: bool,
+}
+
+/// Lists the resources and resource templates of all running context servers.
+pub(crate) fn list_resources(cx: &AppContext) -> Task<Vec<ResourceEntry>> {
+    let servers = ContextServerManager::global(cx).read(cx).servers();
+    cx.foreground_executor().spawn(async move {
+        let mut entries = Vec::new();
+        for server in servers {
+            let Some(protocol) = server.client.read().clone() else {
+                continue;
+            };
+            if !protocol.capable(ServerCapability::Resources) {
+                continue;
+            }
+
+            let mut templates = Vec::new();
+            if let Some(response) = protocol.list_resources().await.log_err() {
+                entries.extend(response.resources.into_iter().flatten().map(|resource| {
+                    ResourceEntry {
+                        server_id: server.id.clone(),
+                        name: resource.name,
+                        description: resource.description,
+                        uri: resource.uri.to_string(),
+                        is_template: false,
+                    }
+                }));
+                templates.extend(response.resource_templates.into_iter().flatten());
+            }
+            if let Some(response) = protocol.list_resource_templates().await.log_err() {
+                templates.extend(response);
+            }
+
+            entries.extend(templates.into_iter().map(|template| ResourceEntry {
+                server_id: server.id.clone(),
+                name: template.name,
+                description: template.description,
+                uri: template.uri_template,
+                is_template: true,
+            }));
+        }
+        entries
+    })
+}
+
+impl SlashCommand for ResourceSlashCommand {
+    fn name(&self) -> String {
+        Self::NAME.into()
+    }
+
+    fn label(&self, cx: &AppContext) -> CodeLabel {
+        super::create_label_for_command(Self::NAME, &["<server> <uri>"], cx)
+    }
+
+    fn description(&self) -> String {
+        "Insert a resource of a context server".into()
+    }
+
+    fn menu_text(&self) -> String {
+        self.description()
+    }
+
+    fn requires_argument(&self) -> bool {
+        true
+    }
+
+    fn complete_argument(
+        self: Arc<Self>,
+        arguments: &[String],
+        _cancel: Arc<AtomicBool>,
+        _workspace: Option<WeakView<Workspace>>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<Vec<ArgumentCompletion>>> {
+        let query = arguments.join(" ").to_lowercase();
+        let entries = list_resources(cx);
+        cx.foreground_executor().spawn(async move {
+            Ok(entries
+                .await
+                .into_iter()
+                .filter(|entry| {
+                    entry.name.to_lowercase().contains(&query)
+                        || entry.uri.to_lowercase().contains(&query)
+                        || format!("{} {}", entry.server_id, entry.uri).starts_with(&query)
+                })
+                .map(|entry| ArgumentCompletion {
+                    label: CodeLabel::plain(format!("{} ({})", entry.name, entry.uri), None),
+                    new_text: format!("{} {}", entry.server_id, entry.uri),
+                    // The placeholders of templates have to be filled in first.
+                    after_completion: if entry.is_template {
+                        AfterCompletion::Compose
+                    } else {
+                        AfterCompletion::Run
+                    },
+                    replace_previous_arguments: true,
+                })
+                .collect())
+        })
+    }
+
+    fn run(
+        self: Arc<Self>,
+        arguments: &[String],
+        _context_slash_command_output_sections: &[SlashCommandOutputSection<language::Anchor>],
+        _context_buffer: BufferSnapshot,
+        _workspace: WeakView<Workspace>,
+        _delegate: Option<Arc<dyn LspAdapterDelegate>>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<SlashCommandOutput>> {
+        let [server_id, uri] = arguments else {
+            return Task::ready(Err(anyhow!("expected a context server and a resource URI")));
+        };
+        if uri.contains('{') {
+            return Task::ready(Err(anyhow!(
+                "fill in the placeholders of the resource template"
+            )));
+        }
+        let uri = match Url::parse(uri) {
+            Ok(uri) => uri,
+            Err(error) => return Task::ready(Err(anyhow!("invalid resource URI: {error}"))),
+        };
+        let Some(server) = ContextServerManager::global(cx)
+            .read(cx)
+            .get_server(server_id)
+        else {
+            return Task::ready(Err(anyhow!("Context server not found")));
+        };
+
+        cx.foreground_executor().spawn(async move {
+            let body = read_resource(&server, &uri).await?;
+            server.subscribe_to_resource(&uri).await.log_err();
+
+            let text = format!("```{uri}\n{body}\n```");
+            let metadata = ResourceSectionMetadata {
+                server_id: server.id.clone(),
+                uri: uri.clone(),
+            };
+            Ok(SlashCommandOutput {
+                sections: vec![SlashCommandOutputSection {
+                    range: 0..text.len(),
+                    icon: IconName::Server,
+                    label: uri.to_string().into(),
+                    metadata: Some(serde_json::to_value(metadata)?),
+                }],
+                text,
+                run_commands_in_text: false,
+            })
+        })
+    }
+}
+
+async fn read_resource(server: &ContextServer, uri: &Url) -> Result<String> {
+    let Some(protocol) = server.client.read().clone() else {
+        bail!("Context server not initialized");
+    };
+    let response = protocol.read_resource(uri).await?;
+
+    let mut body = response
+        .contents
+        .into_iter()
+        .filter_map(|content| content.text)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if body.is_empty() {
+        bail!("resource has no text content");
+    }
+
+    // We must normalize the line endings here, since servers might return CR characters.
+    LineEnding::normalize(&mut body);
+    body.truncate(body.trim_end_matches('\n').len());
+    Ok(body)
+}
+
+/// Returns the ranges between the opening and closing fence of the sections
+/// inserted for the given resource.
+fn resource_section_bodies(
+    context: &Context,
+    server_id: &str,
+    uri: &Url,
+    cx: &AppContext,
+) -> Vec<Range<usize>> {
+    let buffer = context.buffer().read(cx);
+    context
+        .slash_command_output_sections()
+        .iter()
+        .filter(|section| {
+            section.is_valid(buffer)
+                && section.metadata.as_ref().map_or(false, |metadata| {
+                    serde_json::from_value::<ResourceSectionMetadata>(metadata.clone())
+                        .map_or(false, |metadata| {
+                            metadata.server_id == server_id && &metadata.uri == uri
+                        })
+                })
+        })
+        .filter_map(|section| {
+            let range = section.range.to_offset(buffer);
+            let text = buffer.text_for_range(range.clone()).collect::<String>();
+            let body_start = text.find('\n')? + 1;
+            let body_end = text.rfind("\n```")?;
+            (body_start <= body_end).then(|| range.start + body_start..range.start + body_end)
+        })
+        .collect()
+}
+
+/// Reads a resource again and replaces the contents of its sections in the
+/// given contexts. Only the bodies of the sections are edited, which keeps
+/// their anchors and folds intact.
+pub(crate) fn refresh_resource_sections(
+    server: Arc<ContextServer>,
+    uri: Url,
+    contexts: Vec<Model<Context>>,
+    cx: &mut AppContext,
+) -> Task<Result<()>> {
+    let contexts = contexts
+        .into_iter()
+        .filter(|context| {
+            !resource_section_bodies(context.read(cx), &server.id, &uri, cx).is_empty()
+        })
+        .collect::<Vec<_>>();
+    if contexts.is_empty() {
+        return Task::ready(Ok(()));
+    }
+
+    cx.spawn(|mut cx| async move {
+        let body = read_resource(&server, &uri).await?;
+        for context in contexts {
+            context.update(&mut cx, |context, cx| {
+                let ranges = resource_section_bodies(context, &server.id, &uri, cx);
+                context.buffer().update(cx, |buffer, cx| {
+                    let edits = ranges
+                        .into_iter()
+                        .filter(|range| {
+                            buffer.text_for_range(range.clone()).collect::<String>() != body
+                        })
+                        .map(|range| (range, body.clone()))
+                        .collect::<Vec<_>>();
+                    buffer.edit(edits, None, cx);
+                });
+            })?;
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assistant_panel, prompts::PromptBuilder, ContextStore};
+    use context_servers::test::FakeContextServer;
+    use gpui::{Context as _, TestAppContext, VisualTestContext};
+    use language::LanguageRegistry;
+    use language_model::LanguageModelRegistry;
+    use project::{FakeFs, Project};
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_refreshing_resource_sections(cx: &mut TestAppContext) {
+        init_test(cx);
+        let notes_uri = Url::parse("file:///notes.md").unwrap();
+        let todo_uri = Url::parse("file:///todo.md").unwrap();
+        let fake_server = FakeContextServer::new();
+        fake_server.allow_resource_subscriptions();
+        fake_server.set_resource(&notes_uri, "first\r\nversion\n");
+        fake_server.set_resource(&todo_uri, "nothing");
+        cx.update(|cx| cx.set_http_client(fake_server.http_client()));
+
+        let manager = cx.update(ContextServerManager::global);
+        manager
+            .update(cx, |manager, cx| {
+                manager.add_server(FakeContextServer::config("notes"), cx)
+            })
+            .await
+            .unwrap();
+        let server = manager.read_with(cx, |manager, _| manager.get_server("notes").unwrap());
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project, cx));
+        let workspace = window.update(cx, |_, cx| cx.view().downgrade()).unwrap();
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+
+        let registry = Arc::new(LanguageRegistry::test(cx.executor()));
+        let prompt_builder = Arc::new(PromptBuilder::new(None).unwrap());
+        let context = cx.new_model(|cx| {
+            Context::local(registry.clone(), None, None, prompt_builder.clone(), cx)
+        });
+        let other_context =
+            cx.new_model(|cx| Context::local(registry, None, None, prompt_builder, cx));
+
+        // Insert the notes twice, and the todo list in between.
+        for uri in [&notes_uri, &todo_uri, &notes_uri] {
+            insert_resource(&context, uri, workspace.clone(), cx);
+        }
+        assert_eq!(
+            context.read_with(cx, |context, cx| context.buffer().read(cx).text()),
+            "```file:///notes.md\nfirst\nversion\n```\n\
+             ```file:///todo.md\nnothing\n```\n\
+             ```file:///notes.md\nfirst\nversion\n```\n"
+        );
+        assert_eq!(
+            section_bodies(&context, &notes_uri, cx),
+            ["first\nversion", "first\nversion"]
+        );
+        assert_eq!(section_bodies(&context, &todo_uri, cx), ["nothing"]);
+        assert!(section_bodies(&other_context, &notes_uri, cx).is_empty());
+        // The server is only asked once to send updates of the notes.
+        assert_eq!(
+            requested_methods(&fake_server),
+            [
+                "resources/read",
+                "resources/subscribe",
+                "resources/read",
+                "resources/subscribe",
+                "resources/read",
+            ]
+        );
+
+        // Only the bodies of the sections showing the notes are replaced, and
+        // contexts without such sections don't cause the notes to be read.
+        fake_server.set_resource(&notes_uri, "second version");
+        cx.update(|cx| {
+            refresh_resource_sections(
+                server.clone(),
+                notes_uri.clone(),
+                vec![context.clone(), other_context.clone()],
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            context.read_with(cx, |context, cx| context.buffer().read(cx).text()),
+            "```file:///notes.md\nsecond version\n```\n\
+             ```file:///todo.md\nnothing\n```\n\
+             ```file:///notes.md\nsecond version\n```\n"
+        );
+        assert_eq!(
+            section_bodies(&context, &notes_uri, cx),
+            ["second version", "second version"]
+        );
+        assert_eq!(section_bodies(&context, &todo_uri, cx), ["nothing"]);
+        assert_eq!(
+            requested_methods(&fake_server).last().unwrap(),
+            "resources/read"
+        );
+        assert_eq!(requested_methods(&fake_server).len(), 6);
+
+        // Refreshing a resource that isn't shown doesn't read it.
+        cx.update(|cx| {
+            refresh_resource_sections(server.clone(), notes_uri.clone(), vec![other_context], cx)
+        })
+        .await
+        .unwrap();
+        assert_eq!(requested_methods(&fake_server).len(), 6);
+    }
+
+    #[gpui::test]
+    async fn test_resource_updates_refresh_contexts(cx: &mut TestAppContext) {
+        init_test(cx);
+        let notes_uri = Url::parse("file:///notes.md").unwrap();
+        let fake_server = FakeContextServer::new();
+        fake_server.allow_resource_subscriptions();
+        fake_server.set_resource(&notes_uri, "first version");
+        cx.update(|cx| cx.set_http_client(fake_server.http_client()));
+
+        let manager = cx.update(ContextServerManager::global);
+        manager
+            .update(cx, |manager, cx| {
+                manager.add_server(FakeContextServer::config("notes"), cx)
+            })
+            .await
+            .unwrap();
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let prompt_builder = Arc::new(PromptBuilder::new(None).unwrap());
+        let context_store = cx
+            .update(|cx| ContextStore::new(project.clone(), prompt_builder, cx))
+            .await
+            .unwrap();
+        let window = cx.add_window(|cx| Workspace::test_new(project, cx));
+        let workspace = window.update(cx, |_, cx| cx.view().downgrade()).unwrap();
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+
+        let context = context_store.update(cx, |store, cx| store.create(cx));
+        insert_resource(&context, &notes_uri, workspace, cx);
+        assert_eq!(section_bodies(&context, &notes_uri, cx), ["first version"]);
+
+        // The server notifies about the update of the subscribed resource, and
+        // the context store refreshes the sections of its contexts.
+        fake_server.set_resource(&notes_uri, "second version");
+        fake_server.notify_resource_updated(&notes_uri);
+        cx.run_until_parked();
+        assert_eq!(section_bodies(&context, &notes_uri, cx), ["second version"]);
+    }
+
+    /// Runs the command for the resource, and appends its output to the context.
+    fn insert_resource(
+        context: &Model<Context>,
+        uri: &Url,
+        workspace: WeakView<Workspace>,
+        cx: &mut VisualTestContext,
+    ) {
+        let output = cx.update(|cx| {
+            Arc::new(ResourceSlashCommand).run(
+                &["notes".to_string(), uri.to_string()],
+                &[],
+                context.read(cx).buffer().read(cx).snapshot(),
+                workspace,
+                None,
+                cx,
+            )
+        });
+        context.update(cx, |context, cx| {
+            let end = context.buffer().read(cx).len();
+            let end = context.buffer().read(cx).anchor_after(end);
+            context.insert_command_output(end..end, output, true, false, cx);
+        });
+        cx.run_until_parked();
+    }
+
+    fn section_bodies(
+        context: &Model<Context>,
+        uri: &Url,
+        cx: &mut VisualTestContext,
+    ) -> Vec<String> {
+        context.read_with(cx, |context, cx| {
+            let buffer = context.buffer().read(cx);
+            resource_section_bodies(context, "notes", uri, cx)
+                .into_iter()
+                .map(|range| buffer.text_for_range(range).collect())
+                .collect()
+        })
+    }
+
+    fn requested_methods(server: &FakeContextServer) -> Vec<String> {
+        server
+            .received_requests()
+            .into_iter()
+            .map(|(method, _)| method)
+            .collect()
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            LanguageModelRegistry::test(cx);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init_settings(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            assistant_panel::init(cx);
+            assistant_slash_command::init(cx);
+            command_palette_hooks::init(cx);
+            context_servers::init(cx);
+        });
+    }
+}
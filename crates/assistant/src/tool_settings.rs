@@ -0,0 +1,101 @@
+use anyhow::Result;
+use gpui::AppContext;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+/// Settings for the built-in tools the assistant can use.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, JsonSchema)]
+pub struct ToolSettings {
+    /// Settings for the `read_file` tool.
+    #[serde(default)]
+    pub read_file: ToolPermission,
+    /// Settings for the `list_directory` tool.
+    #[serde(default)]
+    pub list_directory: ToolPermission,
+    /// Settings for the `search_project` tool.
+    #[serde(default)]
+    pub search_project: ToolPermission,
+    /// Settings for the `diagnostics` tool.
+    #[serde(default)]
+    pub diagnostics: ToolPermission,
+    /// Settings for the `find_symbols` tool.
+    #[serde(default)]
+    pub find_symbols: ToolPermission,
+    /// Settings for the `propose_edits` tool.
+    #[serde(default)]
+    pub propose_edits: ToolPermission,
+}
+
+/// Whether the assistant may use a tool.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, JsonSchema)]
+pub struct ToolPermission {
+    /// Whether the assistant is allowed to use the tool.
+    #[serde(default)]
+    pub allowed: bool,
+}
+
+impl Settings for ToolSettings {
+    const KEY: Option<&'static str> = Some("assistant_tools");
+
+    type FileContent = Self;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _cx: &mut AppContext) -> Result<Self> {
+        // Project settings are ignored, so that projects can't grant themselves access.
+        SettingsSources::<Self::FileContent>::json_merge_with(
+            [sources.default]
+                .into_iter()
+                .chain(sources.user)
+                .chain(sources.release_channel)
+                .chain(sources.server),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use settings::{LocalSettingsKind, SettingsLocation, SettingsStore, WorktreeId};
+    use std::path::Path;
+
+    #[gpui::test]
+    fn test_tool_settings_sources(cx: &mut AppContext) {
+        let mut store = SettingsStore::test(cx);
+        store.register_setting::<ToolSettings>(cx);
+        store
+            .set_user_settings(
+                &serde_json::json!({
+                    "assistant_tools": { "read_file": { "allowed": true } },
+                    (release_channel::RELEASE_CHANNEL.dev_name()): {
+                        "assistant_tools": { "list_directory": { "allowed": true } }
+                    }
+                })
+                .to_string(),
+                cx,
+            )
+            .unwrap();
+        let worktree_id = WorktreeId::from_usize(1);
+        store
+            .set_local_settings(
+                worktree_id,
+                Path::new("").into(),
+                LocalSettingsKind::Settings,
+                Some(r#"{ "assistant_tools": { "search_project": { "allowed": true } } }"#),
+                cx,
+            )
+            .unwrap();
+
+        let settings = store.get::<ToolSettings>(None);
+        assert!(settings.read_file.allowed);
+        assert!(settings.list_directory.allowed);
+        assert!(!settings.search_project.allowed);
+
+        // Settings of the project don't apply within the project either.
+        let settings = store.get::<ToolSettings>(Some(SettingsLocation {
+            worktree_id,
+            path: Path::new("src/main.rs"),
+        }));
+        assert!(settings.read_file.allowed);
+        assert!(!settings.search_project.allowed);
+    }
+}
@@ -1 +1,528 @@
+pub mod context_server_tool;
+pub mod diagnostics_tool;
+pub mod find_symbols_tool;
+pub mod list_directory_tool;
 pub mod now_tool;
+pub mod propose_edits_tool;
+pub mod read_file_tool;
+pub mod search_project_tool;
+
+use std::path::{Component, Path};
+
+use anyhow::{anyhow, Result};
+use gpui::{AppContext, Model, WeakView};
+use project::{Project, ProjectPath};
+use workspace::Workspace;
+
+/// Returns the project of the workspace a tool is run in.
+fn workspace_project(workspace: &WeakView<Workspace>, cx: &AppContext) -> Result<Model<Project>> {
+    let workspace = workspace
+        .upgrade()
+        .ok_or_else(|| anyhow!("workspace was dropped"))?;
+    Ok(workspace.read(cx).project().clone())
+}
+
+/// Resolves a path given to a tool, which starts with the name of one of the
+/// project's root directories.
+fn resolve_path(project: &Project, path: &str, cx: &AppContext) -> Result<ProjectPath> {
+    // Paths like `zed/../secrets` would otherwise resolve to files outside of the project.
+    let is_relative = Path::new(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    is_relative
+        .then(|| project.find_project_path(Path::new(path), cx))
+        .flatten()
+        .ok_or_else(|| anyhow!("{path} is not a path in the project"))
+}
+
+/// Returns the path of a project path the way tools expect it, starting with
+/// the name of its root directory.
+fn display_path(project: &Project, project_path: &ProjectPath, cx: &AppContext) -> String {
+    match project.worktree_for_id(project_path.worktree_id, cx) {
+        Some(worktree) => Path::new(worktree.read(cx).root_name())
+            .join(&project_path.path)
+            .to_string_lossy()
+            .into_owned(),
+        None => project_path.path.to_string_lossy().into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diagnostics_tool::DiagnosticsTool;
+    use super::find_symbols_tool::FindSymbolsTool;
+    use super::list_directory_tool::ListDirectoryTool;
+    use super::propose_edits_tool::ProposeEditsTool;
+    use super::read_file_tool::ReadFileTool;
+    use super::search_project_tool::SearchProjectTool;
+    use super::*;
+    use crate::tool_settings::ToolSettings;
+    use assistant_tool::{Tool, ToolRegistry};
+    use editor::ProposedChangesEditor;
+    use futures::StreamExt as _;
+    use gpui::{BorrowAppContext, Task, TestAppContext, VisualTestContext};
+    use language::{FakeLspAdapter, Language, LanguageConfig, LanguageMatcher};
+    use lsp::LanguageServerId;
+    use project::FakeFs;
+    use serde_json::{json, Value};
+    use settings::{Settings, SettingsStore};
+    use std::sync::Arc;
+
+    const MAIN_RS: &str = "fn main() {\n    println!(\"hello\");\n}\n";
+    const LIB_RS: &str = "pub fn one() -> usize {\n    1\n}\n";
+
+    #[gpui::test]
+    async fn test_read_file_tool(cx: &mut TestAppContext) {
+        let (_, workspace, mut cx) = build_workspace(cx).await;
+        let cx = &mut cx;
+
+        let output = run_tool(
+            ReadFileTool,
+            json!({ "path": "root/src/main.rs" }),
+            &workspace,
+            cx,
+        );
+        assert_eq!(output.await.unwrap(), MAIN_RS);
+        let output = run_tool(
+            ReadFileTool,
+            json!({ "path": "root/src/main.rs", "start_line": 2, "end_line": 2 }),
+            &workspace,
+            cx,
+        );
+        assert_eq!(output.await.unwrap(), "    println!(\"hello\");");
+
+        let output = run_tool(
+            ReadFileTool,
+            json!({ "path": "root/src/main.rs", "start_line": 6 }),
+            &workspace,
+            cx,
+        );
+        assert_eq!(
+            output.await.unwrap_err().to_string(),
+            "root/src/main.rs has 4 lines, which doesn't include the given range"
+        );
+        for path in ["root/../outside/secret.txt", "/outside/secret.txt"] {
+            let output = run_tool(ReadFileTool, json!({ "path": path }), &workspace, cx);
+            assert_eq!(
+                output.await.unwrap_err().to_string(),
+                format!("{path} is not a path in the project")
+            );
+        }
+        let output = run_tool(ReadFileTool, json!({ "path": "root/.env" }), &workspace, cx);
+        assert_eq!(
+            output.await.unwrap_err().to_string(),
+            "root/.env is a private file, which can't be read"
+        );
+        let output = run_tool(
+            ReadFileTool,
+            json!({ "file": "root/src/main.rs" }),
+            &workspace,
+            cx,
+        );
+        assert!(output
+            .await
+            .unwrap_err()
+            .to_string()
+            .contains("missing field `path`"));
+    }
+
+    #[gpui::test]
+    async fn test_list_directory_tool(cx: &mut TestAppContext) {
+        let (_, workspace, mut cx) = build_workspace(cx).await;
+        let cx = &mut cx;
+
+        let output = run_tool(ListDirectoryTool, json!({ "path": "root" }), &workspace, cx);
+        assert_eq!(
+            output.await.unwrap(),
+            "root/.env\nroot/README.md\nroot/src/\n"
+        );
+        let output = run_tool(
+            ListDirectoryTool,
+            json!({ "path": "root/src" }),
+            &workspace,
+            cx,
+        );
+        assert_eq!(output.await.unwrap(), "root/src/lib.rs\nroot/src/main.rs\n");
+
+        let output = run_tool(
+            ListDirectoryTool,
+            json!({ "path": "root/src/main.rs" }),
+            &workspace,
+            cx,
+        );
+        assert_eq!(
+            output.await.unwrap_err().to_string(),
+            "root/src/main.rs is not a directory"
+        );
+        for path in ["root/..", "/outside"] {
+            let output = run_tool(ListDirectoryTool, json!({ "path": path }), &workspace, cx);
+            assert_eq!(
+                output.await.unwrap_err().to_string(),
+                format!("{path} is not a path in the project")
+            );
+        }
+        let output = run_tool(ListDirectoryTool, json!({ "path": 1 }), &workspace, cx);
+        assert!(output.await.is_err());
+    }
+
+    #[gpui::test]
+    async fn test_search_project_tool(cx: &mut TestAppContext) {
+        let (_, workspace, mut cx) = build_workspace(cx).await;
+        let cx = &mut cx;
+
+        let output = run_tool(
+            SearchProjectTool,
+            json!({ "query": "println" }),
+            &workspace,
+            cx,
+        );
+        assert_eq!(
+            output.await.unwrap(),
+            "root/src/main.rs\n2: println!(\"hello\");\n"
+        );
+        let output = run_tool(
+            SearchProjectTool,
+            json!({ "query": "fn \\w+", "regex": true, "include": ["**/lib.rs"] }),
+            &workspace,
+            cx,
+        );
+        assert_eq!(
+            output.await.unwrap(),
+            "root/src/lib.rs\n1: pub fn one() -> usize {\n"
+        );
+        let output = run_tool(
+            SearchProjectTool,
+            json!({ "query": "PRINTLN", "case_sensitive": true }),
+            &workspace,
+            cx,
+        );
+        assert_eq!(output.await.unwrap(), "No matches found.");
+
+        // Files outside of the project and private files aren't searched.
+        let output = run_tool(
+            SearchProjectTool,
+            json!({ "query": "secret" }),
+            &workspace,
+            cx,
+        );
+        assert_eq!(output.await.unwrap(), "No matches found.");
+
+        let output = run_tool(
+            SearchProjectTool,
+            json!({ "query": "(", "regex": true }),
+            &workspace,
+            cx,
+        );
+        assert!(output.await.is_err());
+        let output = run_tool(SearchProjectTool, json!({ "regex": true }), &workspace, cx);
+        assert!(output.await.is_err());
+    }
+
+    #[gpui::test]
+    async fn test_diagnostics_tool(cx: &mut TestAppContext) {
+        let (project, workspace, mut cx) = build_workspace(cx).await;
+        let cx = &mut cx;
+        project.update(cx, |project, cx| {
+            project
+                .update_diagnostics(
+                    LanguageServerId(0),
+                    lsp::PublishDiagnosticsParams {
+                        uri: lsp::Url::from_file_path("/root/src/main.rs").unwrap(),
+                        version: None,
+                        diagnostics: vec![
+                            lsp::Diagnostic {
+                                range: lsp::Range::new(
+                                    lsp::Position::new(1, 4),
+                                    lsp::Position::new(1, 11),
+                                ),
+                                severity: Some(lsp::DiagnosticSeverity::ERROR),
+                                message: "cannot find macro `println`".into(),
+                                ..Default::default()
+                            },
+                            lsp::Diagnostic {
+                                range: lsp::Range::new(
+                                    lsp::Position::new(0, 3),
+                                    lsp::Position::new(0, 7),
+                                ),
+                                severity: Some(lsp::DiagnosticSeverity::WARNING),
+                                message: "function `main` is never used".into(),
+                                ..Default::default()
+                            },
+                        ],
+                    },
+                    &[],
+                    cx,
+                )
+                .unwrap();
+        });
+
+        let output = run_tool(DiagnosticsTool, json!({}), &workspace, cx);
+        assert_eq!(output.await.unwrap(), "root/src/main.rs: 1 errors\n");
+        let output = run_tool(
+            DiagnosticsTool,
+            json!({ "include_warnings": true }),
+            &workspace,
+            cx,
+        );
+        assert_eq!(
+            output.await.unwrap(),
+            "root/src/main.rs: 1 errors, 1 warnings\n"
+        );
+
+        let output = run_tool(
+            DiagnosticsTool,
+            json!({ "path": "root/src/main.rs" }),
+            &workspace,
+            cx,
+        );
+        let output = output.await.unwrap();
+        assert!(output.contains("cannot find macro `println`"), "{output}");
+        assert!(!output.contains("is never used"), "{output}");
+        let output = run_tool(
+            DiagnosticsTool,
+            json!({ "path": "root/src/lib.rs" }),
+            &workspace,
+            cx,
+        );
+        assert_eq!(
+            output.await.unwrap(),
+            "No diagnostics found in root/src/lib.rs."
+        );
+
+        let output = run_tool(
+            DiagnosticsTool,
+            json!({ "path": "root/../outside/secret.txt" }),
+            &workspace,
+            cx,
+        );
+        assert_eq!(
+            output.await.unwrap_err().to_string(),
+            "root/../outside/secret.txt is not a path in the project"
+        );
+        let output = run_tool(DiagnosticsTool, json!({ "path": 1 }), &workspace, cx);
+        assert!(output.await.is_err());
+    }
+
+    #[gpui::test]
+    async fn test_find_symbols_tool(cx: &mut TestAppContext) {
+        let (project, workspace, mut cx) = build_workspace(cx).await;
+        let cx = &mut cx;
+
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        )));
+        let mut fake_servers =
+            language_registry.register_fake_lsp("Rust", FakeLspAdapter::default());
+        let _buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/root/src/lib.rs", cx)
+            })
+            .await
+            .unwrap();
+        let fake_server = fake_servers.next().await.unwrap();
+        fake_server.handle_request::<lsp::WorkspaceSymbolRequest, _, _>(|params, _| async move {
+            #[allow(deprecated)]
+            let symbols = [("one", "/root/src/lib.rs"), ("main", "/root/src/main.rs")]
+                .into_iter()
+                .filter(|(name, _)| name.contains(&params.query))
+                .map(|(name, path)| lsp::SymbolInformation {
+                    name: name.to_string(),
+                    kind: lsp::SymbolKind::FUNCTION,
+                    tags: None,
+                    deprecated: None,
+                    container_name: None,
+                    location: lsp::Location::new(
+                        lsp::Url::from_file_path(path).unwrap(),
+                        lsp::Range::new(lsp::Position::new(0, 7), lsp::Position::new(0, 10)),
+                    ),
+                })
+                .collect();
+            Ok(Some(lsp::WorkspaceSymbolResponse::Flat(symbols)))
+        });
+
+        let output = run_tool(FindSymbolsTool, json!({ "query": "one" }), &workspace, cx);
+        let output = output.await.unwrap();
+        assert!(output.contains("one in root/src/lib.rs:1"), "{output}");
+        assert!(!output.contains("main"), "{output}");
+        let output = run_tool(FindSymbolsTool, json!({ "query": "two" }), &workspace, cx);
+        assert_eq!(output.await.unwrap(), "No symbols found for two.");
+
+        let output = run_tool(FindSymbolsTool, json!({}), &workspace, cx);
+        assert!(output.await.is_err());
+    }
+
+    #[gpui::test]
+    async fn test_propose_edits_tool(cx: &mut TestAppContext) {
+        let (_, workspace, mut cx) = build_workspace(cx).await;
+        let cx = &mut cx;
+
+        let output = run_tool(
+            ProposeEditsTool,
+            json!({
+                "title": "Greet the world",
+                "edits": [
+                    {
+                        "path": "root/src/main.rs",
+                        "old_text": "\"hello\"",
+                        "new_text": "\"hello world\"",
+                    },
+                    {
+                        "path": "root/src/lib.rs",
+                        "old_text": "    1\n",
+                        "new_text": "    2\n",
+                    },
+                ],
+            }),
+            &workspace,
+            cx,
+        );
+        assert_eq!(
+            output.await.unwrap(),
+            "Proposed 2 edits to 2 files. The user will review them before applying them."
+        );
+        let editor = workspace
+            .update(cx, |workspace, cx| {
+                workspace.active_item_as::<ProposedChangesEditor>(cx)
+            })
+            .unwrap();
+        assert!(editor.is_some());
+        // The files are only changed once the user applies the edits.
+        let output = run_tool(
+            ReadFileTool,
+            json!({ "path": "root/src/main.rs" }),
+            &workspace,
+            cx,
+        );
+        assert_eq!(output.await.unwrap(), MAIN_RS);
+
+        for (path, old_text, error) in [
+            (
+                "root/src/main.rs",
+                "goodbye",
+                "the text to replace was not found in root/src/main.rs",
+            ),
+            (
+                "root/src/main.rs",
+                "n",
+                "the text to replace occurs more than once in root/src/main.rs",
+            ),
+            (
+                "root/src/main.rs",
+                "",
+                "the text to replace in root/src/main.rs is empty",
+            ),
+            (
+                "root/../outside/secret.txt",
+                "secret",
+                "root/../outside/secret.txt is not a path in the project",
+            ),
+        ] {
+            let output = run_tool(
+                ProposeEditsTool,
+                json!({
+                    "title": "Edit",
+                    "edits": [{ "path": path, "old_text": old_text, "new_text": "" }],
+                }),
+                &workspace,
+                cx,
+            );
+            assert_eq!(output.await.unwrap_err().to_string(), error);
+        }
+        let output = run_tool(ProposeEditsTool, json!({ "title": "Edit" }), &workspace, cx);
+        assert!(output.await.is_err());
+    }
+
+    #[gpui::test]
+    fn test_update_tools_from_settings(cx: &mut AppContext) {
+        let settings_store = SettingsStore::test(cx);
+        cx.set_global(settings_store);
+        ToolSettings::register(cx);
+        assistant_tool::init(cx);
+
+        let tool_registry = ToolRegistry::global(cx);
+        let tool_names = || {
+            let mut names = tool_registry
+                .tools()
+                .iter()
+                .map(|tool| tool.name())
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+
+        // The tools aren't allowed by default.
+        crate::update_tools_from_settings(cx);
+        assert!(tool_names().is_empty());
+
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings::<ToolSettings>(cx, |settings| {
+                settings.read_file.allowed = true;
+                settings.propose_edits.allowed = true;
+            });
+        });
+        crate::update_tools_from_settings(cx);
+        assert_eq!(tool_names(), [ProposeEditsTool::NAME, ReadFileTool::NAME]);
+
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings::<ToolSettings>(cx, |settings| {
+                settings.propose_edits.allowed = false;
+            });
+        });
+        crate::update_tools_from_settings(cx);
+        assert_eq!(tool_names(), [ReadFileTool::NAME]);
+    }
+
+    fn run_tool(
+        tool: impl Tool,
+        input: Value,
+        workspace: &WeakView<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> Task<Result<String>> {
+        cx.update(|cx| Arc::new(tool).run(input, workspace.clone(), cx))
+    }
+
+    async fn build_workspace(
+        cx: &mut TestAppContext,
+    ) -> (Model<Project>, WeakView<Workspace>, VisualTestContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init_settings(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            json!({
+                ".env": "API_KEY=secret\n",
+                "README.md": "# Hello\n",
+                "src": {
+                    "lib.rs": LIB_RS,
+                    "main.rs": MAIN_RS,
+                },
+            }),
+        )
+        .await;
+        fs.insert_tree("/outside", json!({ "secret.txt": "secret" }))
+            .await;
+
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let workspace = window.update(cx, |_, cx| cx.view().downgrade()).unwrap();
+        let cx = VisualTestContext::from_window(*window, cx);
+        (project, workspace, cx)
+    }
+}
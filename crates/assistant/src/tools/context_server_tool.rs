@@ -0,0 +1,310 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Result};
+use assistant_tool::{Tool, ToolRegistry};
+use context_servers::manager::ContextServerManager;
+use context_servers::protocol::InitializedContextServerProtocol;
+use context_servers::{types, ContextServerRegistry};
+use gpui::{Task, WeakView, WindowContext};
+use util::ResultExt as _;
+use workspace::Workspace;
+
+/// The maximum length of tool names accepted by language model providers.
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+/// A tool of a context server, which the language model can call.
+pub struct ContextServerTool {
+    /// The name the tool is registered as, which maps back to the server and
+    /// the original name of the tool.
+    name: String,
+    server_id: String,
+    tool: types::Tool,
+}
+
+impl ContextServerTool {
+    pub fn new(server_id: impl Into<String>, tool: types::Tool) -> Self {
+        let server_id = server_id.into();
+        Self {
+            name: tool_name(&server_id, &tool.name),
+            server_id,
+            tool,
+        }
+    }
+}
+
+/// Returns the name of a tool of a context server, which is prefixed with the
+/// server's ID, as tools of different servers may have the same name.
+///
+/// Language models only accept names matching `^[a-zA-Z0-9_-]{1,64}$`, so other
+/// characters are replaced and long names are truncated. A hash of the original
+/// name is appended in that case, so that the names stay unique.
+fn tool_name(server_id: &str, tool_name: &str) -> String {
+    let name = format!("{server_id}-{tool_name}");
+    let mut sanitized_name = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    if sanitized_name == name && name.len() <= MAX_TOOL_NAME_LEN {
+        return name;
+    }
+
+    let mut hasher = DefaultHasher::new();
+    name.hash(&mut hasher);
+    let suffix = format!("-{:08x}", hasher.finish() as u32);
+    sanitized_name.truncate(MAX_TOOL_NAME_LEN - suffix.len());
+    sanitized_name.push_str(&suffix);
+    sanitized_name
+}
+
+/// Replaces the registered tools of a context server with the ones it currently lists.
+pub async fn refresh_context_server_tools(
+    server_id: &str,
+    protocol: &InitializedContextServerProtocol,
+    context_server_registry: &ContextServerRegistry,
+    tool_registry: &ToolRegistry,
+) {
+    let Some(tools) = protocol.list_tools().await.log_err() else {
+        return;
+    };
+
+    unregister_context_server_tools(server_id, context_server_registry, tool_registry);
+    for tool in tools {
+        let tool = ContextServerTool::new(server_id, tool);
+        log::info!("registering context server tool: {:?}", tool.name());
+        context_server_registry.register_tool(server_id.to_string(), &tool.name());
+        tool_registry.register_tool(tool);
+    }
+}
+
+/// Unregisters the tools of a context server.
+pub fn unregister_context_server_tools(
+    server_id: &str,
+    context_server_registry: &ContextServerRegistry,
+    tool_registry: &ToolRegistry,
+) {
+    if let Some(tools) = context_server_registry.get_tools(server_id) {
+        for tool_name in tools {
+            tool_registry.unregister_tool_by_name(&tool_name);
+            context_server_registry.unregister_tool(server_id, &tool_name);
+        }
+    }
+}
+
+impl Tool for ContextServerTool {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn description(&self) -> String {
+        self.tool
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("Run '{}' from {}", self.tool.name, self.server_id))
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        let schema = &self.tool.input_schema;
+        if schema.is_null() || schema.as_object().map_or(false, |map| map.is_empty()) {
+            serde_json::json!({ "type": "object", "properties": {} })
+        } else {
+            schema.clone()
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let manager = ContextServerManager::global(cx);
+        let Some(server) = manager.read(cx).get_server(&self.server_id) else {
+            return Task::ready(Err(anyhow!("Context server not found")));
+        };
+        let arguments = match input {
+            serde_json::Value::Object(map) => Some(map.into_iter().collect()),
+            serde_json::Value::Null => None,
+            _ => return Task::ready(Err(anyhow!("Tool input must be an object"))),
+        };
+
+        let tool_name = self.tool.name.clone();
+        cx.foreground_executor().spawn(async move {
+            let Some(protocol) = server.client.read().clone() else {
+                bail!("Context server not initialized");
+            };
+
+            let response = protocol.run_tool(&tool_name, arguments).await?;
+
+            let mut output = String::new();
+            for content in response.content {
+                match content {
+                    types::ToolResponseContent::Text { text } => output.push_str(&text),
+                    types::ToolResponseContent::Resource { resource } => {
+                        if let Some(text) = resource.text {
+                            output.push_str(&text);
+                        }
+                    }
+                    types::ToolResponseContent::Image { .. } => {
+                        log::warn!("ignoring image returned by context server tool {tool_name}");
+                    }
+                }
+            }
+
+            if response.is_error == Some(true) {
+                bail!(output);
+            }
+            Ok(output)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use context_servers::test::FakeContextServer;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+
+    #[test]
+    fn test_tool_name() {
+        assert_eq!(tool_name("github", "create_issue"), "github-create_issue");
+
+        let is_valid_name = |name: &str| {
+            (1..=MAX_TOOL_NAME_LEN).contains(&name.len())
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        };
+        let name = tool_name("github", "issues/create issue");
+        assert!(is_valid_name(&name), "invalid name {name}");
+        assert!(name.starts_with("github-issues_create_issue-"));
+        // Names that only differ in replaced characters stay unique.
+        assert_ne!(name, tool_name("github", "issues.create.issue"));
+
+        let name = tool_name("github", &"a".repeat(100));
+        assert!(is_valid_name(&name), "invalid name {name}");
+        assert_ne!(name, tool_name("github", &"a".repeat(101)));
+    }
+
+    #[gpui::test]
+    async fn test_context_server_tools(cx: &mut TestAppContext) {
+        init_test(cx);
+        let server = FakeContextServer::new();
+        server.add_tool("create_issue", "Creates an issue", "Created issue #1");
+        server.add_tool("search.issues", "Searches issues", "No issues found");
+        // Tools listed in several pages are all registered.
+        server.set_tools_page_size(1);
+        cx.update(|cx| cx.set_http_client(server.http_client()));
+
+        let manager = cx.update(ContextServerManager::global);
+        manager
+            .update(cx, |manager, cx| {
+                manager.add_server(FakeContextServer::config("github"), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        // The tools are registered when the server starts.
+        let tool_registry = cx.update(ToolRegistry::global);
+        let mut tool_names = tool_registry
+            .tools()
+            .iter()
+            .map(|tool| tool.name())
+            .collect::<Vec<_>>();
+        tool_names.sort();
+        let search_tool_name = tool_name("github", "search.issues");
+        assert_eq!(
+            tool_names,
+            ["github-create_issue".to_string(), search_tool_name.clone()]
+        );
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project, cx));
+        let workspace = window.update(cx, |_, cx| cx.view().downgrade()).unwrap();
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+
+        // Tools are called by their original name.
+        let tool = tool_registry.tool(&search_tool_name).unwrap();
+        let output = cx
+            .update(|cx| tool.run(json!({ "query": "crash" }), workspace.clone(), cx))
+            .await
+            .unwrap();
+        assert_eq!(output, "No issues found");
+        assert_eq!(
+            server.received_requests().last().unwrap(),
+            &(
+                "tools/call".to_string(),
+                json!({ "name": "search.issues", "arguments": { "query": "crash" } })
+            )
+        );
+
+        let tool = tool_registry.tool("github-create_issue").unwrap();
+        let error = cx
+            .update(|cx| tool.run(json!("title"), workspace.clone(), cx))
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Tool input must be an object");
+
+        // The tools are listed again when the server notifies that they changed.
+        server.add_tool("close_issue", "Closes an issue", "Closed issue #1");
+        server.notify("notifications/tools/list_changed");
+        cx.run_until_parked();
+        let mut tool_names = tool_registry
+            .tools()
+            .iter()
+            .map(|tool| tool.name())
+            .collect::<Vec<_>>();
+        tool_names.sort();
+        assert_eq!(
+            tool_names,
+            [
+                "github-close_issue".to_string(),
+                "github-create_issue".to_string(),
+                search_tool_name.clone()
+            ]
+        );
+
+        // The tools are unregistered when the server stops.
+        manager
+            .update(cx, |manager, cx| manager.remove_server("github", cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert!(tool_registry.tools().is_empty());
+        cx.update(|cx| {
+            assert_eq!(
+                ContextServerRegistry::global(cx).get_tools("github"),
+                Some(Vec::new())
+            )
+        });
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init_settings(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            command_palette_hooks::init(cx);
+            assistant_slash_command::init(cx);
+            assistant_tool::init(cx);
+            context_servers::init(cx);
+            crate::register_context_server_handlers(cx);
+        });
+    }
+}
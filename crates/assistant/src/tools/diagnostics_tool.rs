@@ -0,0 +1,104 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use assistant_tool::Tool;
+use gpui::{Task, WeakView, WindowContext};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use workspace::Workspace;
+
+use super::{display_path, resolve_path, workspace_project};
+use crate::slash_command::{diagnostics_command::collect_buffer_diagnostics, SlashCommandOutput};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DiagnosticsToolInput {
+    /// The path of the file to return the diagnostics of, starting with the
+    /// name of one of the project's root directories. Without a path, the
+    /// number of errors and warnings of each file is returned instead.
+    #[serde(default)]
+    path: Option<String>,
+    /// Whether to include warnings in addition to errors.
+    #[serde(default)]
+    include_warnings: bool,
+}
+
+pub struct DiagnosticsTool;
+
+impl DiagnosticsTool {
+    pub const NAME: &'static str = "diagnostics";
+}
+
+impl Tool for DiagnosticsTool {
+    fn name(&self) -> String {
+        Self::NAME.into()
+    }
+
+    fn description(&self) -> String {
+        "Returns the errors and warnings reported by language servers for a file of the project, along with the surrounding lines, or the files of the project which have any.".into()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        let schema = schemars::schema_for!(DiagnosticsToolInput);
+        serde_json::to_value(&schema).unwrap()
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let input: DiagnosticsToolInput = match serde_json::from_value(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))),
+        };
+        let project = match workspace_project(&workspace, cx) {
+            Ok(project) => project,
+            Err(err) => return Task::ready(Err(err)),
+        };
+
+        let Some(path) = input.path else {
+            let project = project.read(cx);
+            let mut output = String::new();
+            for (project_path, _, summary) in project.diagnostic_summaries(false, cx) {
+                if summary.error_count == 0 && !input.include_warnings {
+                    continue;
+                }
+                write!(
+                    output,
+                    "{}: {} errors",
+                    display_path(project, &project_path, cx),
+                    summary.error_count
+                )
+                .unwrap();
+                if input.include_warnings {
+                    write!(output, ", {} warnings", summary.warning_count).unwrap();
+                }
+                output.push('\n');
+            }
+            if output.is_empty() {
+                output.push_str("No diagnostics found.");
+            }
+            return Task::ready(Ok(output));
+        };
+
+        let project_path = match resolve_path(project.read(cx), &path, cx) {
+            Ok(project_path) => project_path,
+            Err(err) => return Task::ready(Err(err)),
+        };
+        let open_buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+        cx.spawn(|cx| async move {
+            let buffer = open_buffer.await?;
+            let snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot())?;
+
+            let mut output = SlashCommandOutput::default();
+            collect_buffer_diagnostics(&mut output, &snapshot, input.include_warnings);
+            if output.text.is_empty() {
+                Ok(format!("No diagnostics found in {path}."))
+            } else {
+                Ok(output.text)
+            }
+        })
+    }
+}
@@ -0,0 +1,89 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use assistant_tool::Tool;
+use gpui::{Task, WeakView, WindowContext};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use workspace::Workspace;
+
+use super::{display_path, workspace_project};
+
+/// The number of symbols after which the results are truncated.
+const MAX_SYMBOLS: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FindSymbolsToolInput {
+    /// The name of the symbols to look for, which may be fuzzily matched.
+    query: String,
+}
+
+pub struct FindSymbolsTool;
+
+impl FindSymbolsTool {
+    pub const NAME: &'static str = "find_symbols";
+}
+
+impl Tool for FindSymbolsTool {
+    fn name(&self) -> String {
+        Self::NAME.into()
+    }
+
+    fn description(&self) -> String {
+        "Looks up symbols like functions and types by name in the project, using its language servers, and returns where they are defined.".into()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        let schema = schemars::schema_for!(FindSymbolsToolInput);
+        serde_json::to_value(&schema).unwrap()
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let input: FindSymbolsToolInput = match serde_json::from_value(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))),
+        };
+        let project = match workspace_project(&workspace, cx) {
+            Ok(project) => project,
+            Err(err) => return Task::ready(Err(err)),
+        };
+
+        let symbols = project.update(cx, |project, cx| project.symbols(&input.query, cx));
+        cx.spawn(|cx| async move {
+            let symbols = symbols.await?;
+            if symbols.is_empty() {
+                return Ok(format!("No symbols found for {}.", input.query));
+            }
+
+            project.read_with(&cx, |project, cx| {
+                let mut output = String::new();
+                for symbol in symbols.iter().take(MAX_SYMBOLS) {
+                    writeln!(
+                        output,
+                        "{:?} {} in {}:{}",
+                        symbol.kind,
+                        symbol.name,
+                        display_path(project, &symbol.path, cx),
+                        symbol.range.start.0.row + 1
+                    )
+                    .unwrap();
+                }
+                if symbols.len() > MAX_SYMBOLS {
+                    writeln!(
+                        output,
+                        "{} more symbols were omitted.",
+                        symbols.len() - MAX_SYMBOLS
+                    )
+                    .unwrap();
+                }
+                output
+            })
+        })
+    }
+}
@@ -0,0 +1,84 @@
+use std::fmt::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use assistant_tool::Tool;
+use gpui::{Task, WeakView, WindowContext};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use workspace::Workspace;
+
+use super::{resolve_path, workspace_project};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListDirectoryToolInput {
+    /// The path of the directory, starting with the name of one of the
+    /// project's root directories, like `zed/src`. The name of a root
+    /// directory on its own lists the root directory.
+    path: String,
+}
+
+pub struct ListDirectoryTool;
+
+impl ListDirectoryTool {
+    pub const NAME: &'static str = "list_directory";
+}
+
+impl Tool for ListDirectoryTool {
+    fn name(&self) -> String {
+        Self::NAME.into()
+    }
+
+    fn description(&self) -> String {
+        "Lists the files and directories in a directory of the project. The paths of directories end with a `/`.".into()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        let schema = schemars::schema_for!(ListDirectoryToolInput);
+        serde_json::to_value(&schema).unwrap()
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let input: ListDirectoryToolInput = match serde_json::from_value(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))),
+        };
+
+        Task::ready(workspace_project(&workspace, cx).and_then(|project| {
+            let project = project.read(cx);
+            let project_path = resolve_path(project, &input.path, cx)?;
+            let worktree = project
+                .worktree_for_id(project_path.worktree_id, cx)
+                .ok_or_else(|| anyhow!("worktree not found"))?;
+            let worktree = worktree.read(cx);
+            let entry = worktree
+                .entry_for_path(&project_path.path)
+                .ok_or_else(|| anyhow!("{} does not exist", input.path))?;
+            if !entry.is_dir() {
+                return Err(anyhow!("{} is not a directory", input.path));
+            }
+
+            let mut output = String::new();
+            for entry in worktree.child_entries(&project_path.path) {
+                let path = Path::new(worktree.root_name()).join(&entry.path);
+                write!(output, "{}", path.display()).unwrap();
+                if entry.is_dir() {
+                    output.push('/');
+                }
+                output.push('\n');
+            }
+
+            if output.is_empty() {
+                Ok(format!("{} is empty.", input.path))
+            } else {
+                Ok(output)
+            }
+        }))
+    }
+}
@@ -0,0 +1,147 @@
+use std::ops::Range;
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Result};
+use assistant_tool::Tool;
+use editor::{ProposedChangeLocation, ProposedChangesEditor};
+use gpui::{Model, Task, WeakView, WindowContext};
+use language::Buffer;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use workspace::Workspace;
+
+use super::{resolve_path, workspace_project};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ProposeEditsToolInput {
+    /// A short title describing the edits.
+    title: String,
+    /// The edits to propose.
+    edits: Vec<ProposedEdit>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ProposedEdit {
+    /// The path of the file to edit, starting with the name of one of the
+    /// project's root directories.
+    path: String,
+    /// The text to replace, which must occur exactly once in the file. Include
+    /// surrounding lines to make it unique.
+    old_text: String,
+    /// The text to replace it with.
+    new_text: String,
+}
+
+pub struct ProposeEditsTool;
+
+impl ProposeEditsTool {
+    pub const NAME: &'static str = "propose_edits";
+}
+
+impl Tool for ProposeEditsTool {
+    fn name(&self) -> String {
+        Self::NAME.into()
+    }
+
+    fn description(&self) -> String {
+        "Proposes edits to files of the project, which the user reviews before applying them. Each edit replaces a text in a file with a new one.".into()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        let schema = schemars::schema_for!(ProposeEditsToolInput);
+        serde_json::to_value(&schema).unwrap()
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let input: ProposeEditsToolInput = match serde_json::from_value(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))),
+        };
+        let project = match workspace_project(&workspace, cx) {
+            Ok(project) => project,
+            Err(err) => return Task::ready(Err(err)),
+        };
+
+        cx.spawn(|mut cx| async move {
+            let edit_count = input.edits.len();
+            let mut edits_by_buffer = Vec::<(Model<Buffer>, Vec<(Range<usize>, String)>)>::new();
+            for edit in input.edits {
+                if edit.old_text.is_empty() {
+                    bail!("the text to replace in {} is empty", edit.path);
+                }
+
+                let project_path =
+                    project.read_with(&cx, |project, cx| resolve_path(project, &edit.path, cx))??;
+                let buffer = project
+                    .update(&mut cx, |project, cx| project.open_buffer(project_path, cx))?
+                    .await?;
+                let text = buffer.read_with(&cx, |buffer, _| buffer.text())?;
+
+                let mut matches = text.match_indices(&edit.old_text);
+                let start = match (matches.next(), matches.next()) {
+                    (Some((start, _)), None) => start,
+                    (None, _) => bail!("the text to replace was not found in {}", edit.path),
+                    (Some(_), Some(_)) => {
+                        bail!("the text to replace occurs more than once in {}", edit.path)
+                    }
+                };
+                let range = start..start + edit.old_text.len();
+
+                let ix = match edits_by_buffer.iter().position(|(b, _)| *b == buffer) {
+                    Some(ix) => ix,
+                    None => {
+                        edits_by_buffer.push((buffer, Vec::new()));
+                        edits_by_buffer.len() - 1
+                    }
+                };
+                let edits = &mut edits_by_buffer[ix].1;
+                if edits.iter().any(|(other_range, _)| {
+                    other_range.start < range.end && range.start < other_range.end
+                }) {
+                    bail!("the edits of {} overlap", edit.path);
+                }
+                edits.push((range, edit.new_text));
+            }
+            for (_, edits) in &mut edits_by_buffer {
+                edits.sort_by_key(|(range, _)| range.start);
+            }
+
+            let file_count = edits_by_buffer.len();
+            cx.update(|cx| {
+                let editor = cx.new_view(|cx| {
+                    let editor = ProposedChangesEditor::new(
+                        input.title,
+                        edits_by_buffer
+                            .iter()
+                            .map(|(buffer, edits)| ProposedChangeLocation {
+                                buffer: buffer.clone(),
+                                ranges: edits.iter().map(|(range, _)| range.clone()).collect(),
+                            })
+                            .collect(),
+                        Some(project.clone()),
+                        cx,
+                    );
+                    for (buffer, edits) in edits_by_buffer {
+                        if let Some(branch) = editor.branch_buffer_for_base(&buffer) {
+                            branch.update(cx, |branch, cx| branch.edit(edits, None, cx));
+                        }
+                    }
+                    editor.recalculate_all_buffer_diffs();
+                    editor
+                });
+                workspace.update(cx, |workspace, cx| {
+                    workspace.add_item_to_active_pane(Box::new(editor), None, false, cx)
+                })
+            })??;
+
+            Ok(format!(
+                "Proposed {edit_count} edits to {file_count} files. The user will review them before applying them."
+            ))
+        })
+    }
+}
@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Result};
+use assistant_tool::Tool;
+use gpui::{Task, WeakView, WindowContext};
+use rope::Point;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use workspace::Workspace;
+
+use super::{resolve_path, workspace_project};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ReadFileToolInput {
+    /// The path of the file, starting with the name of one of the project's
+    /// root directories, like `zed/src/main.rs`.
+    path: String,
+    /// The first line to read, starting at 1. Defaults to the first line of the file.
+    #[serde(default)]
+    start_line: Option<u32>,
+    /// The last line to read, inclusive. Defaults to the last line of the file.
+    #[serde(default)]
+    end_line: Option<u32>,
+}
+
+pub struct ReadFileTool;
+
+impl ReadFileTool {
+    pub const NAME: &'static str = "read_file";
+}
+
+impl Tool for ReadFileTool {
+    fn name(&self) -> String {
+        Self::NAME.into()
+    }
+
+    fn description(&self) -> String {
+        "Reads the contents of a file of the project, or of a range of its lines.".into()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        let schema = schemars::schema_for!(ReadFileToolInput);
+        serde_json::to_value(&schema).unwrap()
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let input: ReadFileToolInput = match serde_json::from_value(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))),
+        };
+        let project = match workspace_project(&workspace, cx) {
+            Ok(project) => project,
+            Err(err) => return Task::ready(Err(err)),
+        };
+        let project_path = match resolve_path(project.read(cx), &input.path, cx) {
+            Ok(project_path) => project_path,
+            Err(err) => return Task::ready(Err(err)),
+        };
+
+        let open_buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+        cx.spawn(|cx| async move {
+            let buffer = open_buffer.await?;
+            let (snapshot, is_private) = buffer.read_with(&cx, |buffer, _| {
+                (
+                    buffer.snapshot(),
+                    buffer.file().map_or(false, |file| file.is_private()),
+                )
+            })?;
+            // Private files, like `.env` files, may contain secrets.
+            if is_private {
+                bail!("{} is a private file, which can't be read", input.path);
+            }
+
+            let max_row = snapshot.max_point().row;
+            let start_row = input.start_line.unwrap_or(1).saturating_sub(1);
+            let end_row = input
+                .end_line
+                .map_or(max_row, |line| line.saturating_sub(1).min(max_row));
+            if start_row > end_row {
+                bail!(
+                    "{} has {} lines, which doesn't include the given range",
+                    input.path,
+                    max_row + 1
+                );
+            }
+
+            let range = Point::new(start_row, 0)..Point::new(end_row, snapshot.line_len(end_row));
+            Ok(snapshot.text_for_range(range).collect())
+        })
+    }
+}
@@ -0,0 +1,158 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use assistant_tool::Tool;
+use futures::StreamExt;
+use gpui::{Task, WeakView, WindowContext};
+use language::ToPoint;
+use project::search::{SearchQuery, SearchResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use util::paths::PathMatcher;
+use workspace::Workspace;
+
+use super::workspace_project;
+
+/// The number of matching lines after which the results are truncated.
+const MAX_MATCHING_LINES: usize = 200;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SearchProjectToolInput {
+    /// The text to search for.
+    query: String,
+    /// Whether the query is a regular expression.
+    #[serde(default)]
+    regex: bool,
+    /// Whether to match the case of the query.
+    #[serde(default)]
+    case_sensitive: bool,
+    /// Glob patterns of the paths to search, like `zed/src/**/*.rs`. All files
+    /// are searched by default.
+    #[serde(default)]
+    include: Vec<String>,
+}
+
+pub struct SearchProjectTool;
+
+impl SearchProjectTool {
+    pub const NAME: &'static str = "search_project";
+}
+
+impl Tool for SearchProjectTool {
+    fn name(&self) -> String {
+        Self::NAME.into()
+    }
+
+    fn description(&self) -> String {
+        "Searches the files of the project for a text or regular expression, and returns the matching lines with their line numbers.".into()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        let schema = schemars::schema_for!(SearchProjectToolInput);
+        serde_json::to_value(&schema).unwrap()
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let input: SearchProjectToolInput = match serde_json::from_value(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))),
+        };
+        let project = match workspace_project(&workspace, cx) {
+            Ok(project) => project,
+            Err(err) => return Task::ready(Err(err)),
+        };
+        let query = PathMatcher::new(&input.include)
+            .map_err(|err| anyhow!(err))
+            .and_then(|files_to_include| {
+                if input.regex {
+                    SearchQuery::regex(
+                        &input.query,
+                        false,
+                        input.case_sensitive,
+                        false,
+                        files_to_include,
+                        PathMatcher::default(),
+                        None,
+                    )
+                } else {
+                    SearchQuery::text(
+                        &input.query,
+                        false,
+                        input.case_sensitive,
+                        false,
+                        files_to_include,
+                        PathMatcher::default(),
+                        None,
+                    )
+                }
+            });
+        let query = match query {
+            Ok(query) => query,
+            Err(err) => return Task::ready(Err(err)),
+        };
+
+        let mut results = project.update(cx, |project, cx| project.search(query, cx));
+        cx.spawn(|cx| async move {
+            let mut output = String::new();
+            let mut matching_lines = 0;
+            'results: while let Some(result) = results.next().await {
+                let (buffer, ranges) = match result {
+                    SearchResult::Buffer { buffer, ranges } => (buffer, ranges),
+                    SearchResult::LimitReached => {
+                        output.push_str("The search stopped after too many matching files.\n");
+                        break;
+                    }
+                };
+
+                // Private files, like `.env` files, may contain secrets.
+                let (path, snapshot) = buffer.read_with(&cx, |buffer, cx| {
+                    (
+                        buffer
+                            .file()
+                            .filter(|file| !file.is_private())
+                            .map(|file| file.full_path(cx)),
+                        buffer.snapshot(),
+                    )
+                })?;
+                let Some(path) = path else {
+                    continue;
+                };
+                writeln!(output, "{}", path.display()).unwrap();
+
+                let mut last_row = None;
+                for range in ranges {
+                    let row = range.start.to_point(&snapshot).row;
+                    if last_row == Some(row) {
+                        continue;
+                    }
+                    last_row = Some(row);
+
+                    if matching_lines == MAX_MATCHING_LINES {
+                        output.push_str("The results were truncated after too many matches.\n");
+                        break 'results;
+                    }
+                    matching_lines += 1;
+
+                    let line = snapshot
+                        .text_for_range(
+                            rope::Point::new(row, 0)..rope::Point::new(row, snapshot.line_len(row)),
+                        )
+                        .collect::<String>();
+                    writeln!(output, "{}: {}", row + 1, line.trim()).unwrap();
+                }
+            }
+
+            if output.is_empty() {
+                Ok("No matches found.".into())
+            } else {
+                Ok(output)
+            }
+        })
+    }
+}
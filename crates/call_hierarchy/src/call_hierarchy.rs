@@ -0,0 +1,886 @@
+use collections::HashMap;
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{
+    actions, uniform_list, AppContext, EventEmitter, FocusHandle, FocusableView, KeyContext, Model,
+    Task, UniformListScrollHandle, WeakView,
+};
+use language::ToPoint;
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrev};
+use project::{CallHierarchyItem, Location, Project, TypeHierarchyItem};
+use ui::{prelude::*, ListItem, ListItemSpacing, Tooltip};
+use util::ResultExt;
+use workspace::{
+    item::{Item, ItemEvent},
+    SplitDirection, Workspace,
+};
+
+actions!(
+    call_hierarchy,
+    [
+        ShowIncomingCalls,
+        ShowOutgoingCalls,
+        ShowSupertypes,
+        ShowSubtypes,
+        ToggleDirection,
+        ExpandSelectedEntry,
+        CollapseSelectedEntry,
+        OpenSelectedDefinition,
+    ]
+);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ShowIncomingCalls, cx| {
+            HierarchyView::deploy(workspace, HierarchyMode::IncomingCalls, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowOutgoingCalls, cx| {
+            HierarchyView::deploy(workspace, HierarchyMode::OutgoingCalls, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowSupertypes, cx| {
+            HierarchyView::deploy(workspace, HierarchyMode::Supertypes, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowSubtypes, cx| {
+            HierarchyView::deploy(workspace, HierarchyMode::Subtypes, cx);
+        });
+    })
+    .detach();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HierarchyMode {
+    /// Show the callers of the symbol.
+    IncomingCalls,
+    /// Show the symbols called by the symbol.
+    OutgoingCalls,
+    /// Show the types the type inherits from or implements.
+    Supertypes,
+    /// Show the types inheriting from or implementing the type.
+    Subtypes,
+}
+
+impl HierarchyMode {
+    fn label(&self) -> &'static str {
+        match self {
+            HierarchyMode::IncomingCalls => "Incoming Calls",
+            HierarchyMode::OutgoingCalls => "Outgoing Calls",
+            HierarchyMode::Supertypes => "Supertypes",
+            HierarchyMode::Subtypes => "Subtypes",
+        }
+    }
+
+    /// The mode walking the same hierarchy in the other direction.
+    fn reversed(&self) -> Self {
+        match self {
+            HierarchyMode::IncomingCalls => HierarchyMode::OutgoingCalls,
+            HierarchyMode::OutgoingCalls => HierarchyMode::IncomingCalls,
+            HierarchyMode::Supertypes => HierarchyMode::Subtypes,
+            HierarchyMode::Subtypes => HierarchyMode::Supertypes,
+        }
+    }
+
+    fn icon(&self) -> IconName {
+        match self {
+            HierarchyMode::IncomingCalls | HierarchyMode::Subtypes => IconName::ArrowDown,
+            HierarchyMode::OutgoingCalls | HierarchyMode::Supertypes => IconName::ArrowUp,
+        }
+    }
+
+    fn empty_message(&self) -> &'static str {
+        match self {
+            HierarchyMode::IncomingCalls | HierarchyMode::OutgoingCalls => {
+                "No call hierarchy for the symbol under the cursor"
+            }
+            HierarchyMode::Supertypes | HierarchyMode::Subtypes => {
+                "No type hierarchy for the symbol under the cursor"
+            }
+        }
+    }
+}
+
+/// A symbol of the call hierarchy or a type of the type hierarchy.
+#[derive(Clone)]
+enum HierarchyItem {
+    Call(CallHierarchyItem),
+    Type(TypeHierarchyItem),
+}
+
+impl HierarchyItem {
+    fn name(&self) -> &str {
+        match self {
+            HierarchyItem::Call(item) => &item.name,
+            HierarchyItem::Type(item) => &item.name,
+        }
+    }
+
+    fn detail(&self) -> Option<&String> {
+        match self {
+            HierarchyItem::Call(item) => item.detail.as_ref(),
+            HierarchyItem::Type(item) => item.detail.as_ref(),
+        }
+    }
+
+    fn location(&self) -> &Location {
+        match self {
+            HierarchyItem::Call(item) => &item.location,
+            HierarchyItem::Type(item) => &item.location,
+        }
+    }
+}
+
+/// A tree of calls from or to the symbol the view was deployed for, or of the supertypes or
+/// subtypes of the type, loading children on expansion.
+pub struct HierarchyView {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    mode: HierarchyMode,
+    roots: Vec<HierarchyItem>,
+    nodes: Vec<HierarchyNode>,
+    visible_nodes: Vec<usize>,
+    selected_node: Option<usize>,
+    loading_nodes: HashMap<usize, Task<()>>,
+    prepare_task: Option<Task<()>>,
+}
+
+struct HierarchyNode {
+    item: HierarchyItem,
+    /// The calls between the node and its parent, empty for the roots and for types.
+    call_sites: Vec<Location>,
+    depth: usize,
+    expanded: bool,
+    /// Indices of the child nodes, `None` until the children are loaded.
+    children: Option<Vec<usize>>,
+}
+
+impl HierarchyView {
+    fn deploy(workspace: &mut Workspace, mode: HierarchyMode, cx: &mut ViewContext<Workspace>) {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let Some((buffer, position)) = editor.update(cx, |editor, cx| {
+            let head = editor.selections.newest_anchor().head();
+            editor.buffer().read(cx).text_anchor_for_position(head, cx)
+        }) else {
+            return;
+        };
+
+        let project = workspace.project().clone();
+        let prepare = project.update(cx, |project, cx| match mode {
+            HierarchyMode::IncomingCalls | HierarchyMode::OutgoingCalls => {
+                let prepare = project.prepare_call_hierarchy(&buffer, position, cx);
+                cx.spawn(|_, _| async move {
+                    let items = prepare.await?;
+                    anyhow::Ok(
+                        items
+                            .into_iter()
+                            .map(HierarchyItem::Call)
+                            .collect::<Vec<_>>(),
+                    )
+                })
+            }
+            HierarchyMode::Supertypes | HierarchyMode::Subtypes => {
+                let prepare = project.prepare_type_hierarchy(&buffer, position, cx);
+                cx.spawn(|_, _| async move {
+                    let items = prepare.await?;
+                    anyhow::Ok(
+                        items
+                            .into_iter()
+                            .map(HierarchyItem::Type)
+                            .collect::<Vec<_>>(),
+                    )
+                })
+            }
+        });
+        let workspace_handle = workspace.weak_handle();
+        let view = cx.new_view(|cx| {
+            let mut view = Self::new(workspace_handle, project, mode, cx);
+            view.prepare_task = Some(cx.spawn(|view, mut cx| async move {
+                let roots = prepare.await.log_err().unwrap_or_default();
+                view.update(&mut cx, |view, cx| {
+                    view.roots = roots;
+                    view.prepare_task = None;
+                    view.reset_nodes(cx);
+                })
+                .ok();
+            }));
+            view
+        });
+        workspace.split_item(SplitDirection::Right, Box::new(view), cx);
+    }
+
+    fn new(
+        workspace: WeakView<Workspace>,
+        project: Model<Project>,
+        mode: HierarchyMode,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        Self {
+            workspace,
+            project,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            mode,
+            roots: Vec::new(),
+            nodes: Vec::new(),
+            visible_nodes: Vec::new(),
+            selected_node: None,
+            loading_nodes: HashMap::default(),
+            prepare_task: None,
+        }
+    }
+
+    /// Rebuilds the tree from its roots for the current mode, expanding the roots.
+    fn reset_nodes(&mut self, cx: &mut ViewContext<Self>) {
+        self.loading_nodes.clear();
+        self.selected_node = None;
+        self.nodes = self
+            .roots
+            .iter()
+            .map(|item| HierarchyNode {
+                item: item.clone(),
+                call_sites: Vec::new(),
+                depth: 0,
+                expanded: false,
+                children: None,
+            })
+            .collect();
+        for node_ix in 0..self.nodes.len() {
+            self.expand(node_ix, cx);
+        }
+        if !self.nodes.is_empty() {
+            self.selected_node = Some(0);
+        }
+        self.update_visible_nodes(cx);
+    }
+
+    fn update_visible_nodes(&mut self, cx: &mut ViewContext<Self>) {
+        fn push_visible(nodes: &[HierarchyNode], node_ix: usize, visible_nodes: &mut Vec<usize>) {
+            visible_nodes.push(node_ix);
+            let node = &nodes[node_ix];
+            if node.expanded {
+                for &child_ix in node.children.iter().flatten() {
+                    push_visible(nodes, child_ix, visible_nodes);
+                }
+            }
+        }
+
+        self.visible_nodes.clear();
+        for root_ix in 0..self.roots.len().min(self.nodes.len()) {
+            push_visible(&self.nodes, root_ix, &mut self.visible_nodes);
+        }
+        cx.notify();
+    }
+
+    fn expand(&mut self, node_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(node) = self.nodes.get_mut(node_ix) else {
+            return;
+        };
+        node.expanded = true;
+        if node.children.is_some() || self.loading_nodes.contains_key(&node_ix) {
+            self.update_visible_nodes(cx);
+            return;
+        }
+
+        let item = node.item.clone();
+        let depth = node.depth + 1;
+        let children = self.load_children(&item, cx);
+        let load = cx.spawn(|view, mut cx| async move {
+            let children = children.await;
+            view.update(&mut cx, |view, cx| {
+                view.children_loaded(node_ix, depth, children, cx);
+            })
+            .ok();
+        });
+        self.loading_nodes.insert(node_ix, load);
+        cx.notify();
+    }
+
+    /// Loads the children of the item in the current mode, along with their calls to or from
+    /// the item.
+    fn load_children(
+        &self,
+        item: &HierarchyItem,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Vec<(HierarchyItem, Vec<Location>)>> {
+        let mode = self.mode;
+        match item {
+            HierarchyItem::Call(item) => {
+                let calls = self.project.update(cx, |project, cx| {
+                    if mode == HierarchyMode::IncomingCalls {
+                        project.incoming_calls(item, cx)
+                    } else {
+                        project.outgoing_calls(item, cx)
+                    }
+                });
+                cx.spawn(|_, _| async move {
+                    calls
+                        .await
+                        .log_err()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|call| (HierarchyItem::Call(call.item), call.call_sites))
+                        .collect()
+                })
+            }
+            HierarchyItem::Type(item) => {
+                let types = self.project.update(cx, |project, cx| {
+                    if mode == HierarchyMode::Supertypes {
+                        project.supertypes(item, cx)
+                    } else {
+                        project.subtypes(item, cx)
+                    }
+                });
+                cx.spawn(|_, _| async move {
+                    types
+                        .await
+                        .log_err()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|item| (HierarchyItem::Type(item), Vec::new()))
+                        .collect()
+                })
+            }
+        }
+    }
+
+    fn children_loaded(
+        &mut self,
+        node_ix: usize,
+        depth: usize,
+        children: Vec<(HierarchyItem, Vec<Location>)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.loading_nodes.remove(&node_ix).is_none() {
+            return;
+        }
+        let children = children
+            .into_iter()
+            .map(|(item, call_sites)| {
+                self.nodes.push(HierarchyNode {
+                    item,
+                    call_sites,
+                    depth,
+                    expanded: false,
+                    children: None,
+                });
+                self.nodes.len() - 1
+            })
+            .collect();
+        self.nodes[node_ix].children = Some(children);
+        self.update_visible_nodes(cx);
+    }
+
+    fn collapse(&mut self, node_ix: usize, cx: &mut ViewContext<Self>) {
+        if let Some(node) = self.nodes.get_mut(node_ix) {
+            node.expanded = false;
+            self.update_visible_nodes(cx);
+        }
+    }
+
+    fn toggle_expanded(&mut self, node_ix: usize, cx: &mut ViewContext<Self>) {
+        if self.nodes.get(node_ix).is_some_and(|node| node.expanded) {
+            self.collapse(node_ix, cx);
+        } else {
+            self.expand(node_ix, cx);
+        }
+    }
+
+    fn toggle_direction(&mut self, _: &ToggleDirection, cx: &mut ViewContext<Self>) {
+        self.mode = self.mode.reversed();
+        self.reset_nodes(cx);
+        cx.emit(ItemEvent::UpdateTab);
+    }
+
+    fn select_visible(&mut self, visible_ix: usize, cx: &mut ViewContext<Self>) {
+        if let Some(&node_ix) = self.visible_nodes.get(visible_ix) {
+            self.selected_node = Some(node_ix);
+            self.scroll_handle.scroll_to_item(visible_ix);
+            cx.notify();
+        }
+    }
+
+    fn selected_visible_ix(&self) -> Option<usize> {
+        let selected_node = self.selected_node?;
+        self.visible_nodes
+            .iter()
+            .position(|&node_ix| node_ix == selected_node)
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        let next_ix = self.selected_visible_ix().map_or(0, |ix| ix + 1);
+        self.select_visible(next_ix, cx);
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        if let Some(prev_ix) = self
+            .selected_visible_ix()
+            .unwrap_or(self.visible_nodes.len())
+            .checked_sub(1)
+        {
+            self.select_visible(prev_ix, cx);
+        }
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, cx: &mut ViewContext<Self>) {
+        self.select_visible(0, cx);
+    }
+
+    fn select_last(&mut self, _: &SelectLast, cx: &mut ViewContext<Self>) {
+        if let Some(last_ix) = self.visible_nodes.len().checked_sub(1) {
+            self.select_visible(last_ix, cx);
+        }
+    }
+
+    fn expand_selected_entry(&mut self, _: &ExpandSelectedEntry, cx: &mut ViewContext<Self>) {
+        if let Some(node_ix) = self.selected_node {
+            self.expand(node_ix, cx);
+        }
+    }
+
+    fn collapse_selected_entry(&mut self, _: &CollapseSelectedEntry, cx: &mut ViewContext<Self>) {
+        if let Some(node_ix) = self.selected_node {
+            self.collapse(node_ix, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        if let Some(node_ix) = self.selected_node {
+            self.open_call_site(node_ix, cx);
+        }
+    }
+
+    fn open_selected_definition(&mut self, _: &OpenSelectedDefinition, cx: &mut ViewContext<Self>) {
+        if let Some(node) = self
+            .selected_node
+            .and_then(|node_ix| self.nodes.get(node_ix))
+        {
+            self.open_location(node.item.location().clone(), cx);
+        }
+    }
+
+    /// Opens the first call of the node, or its symbol for the roots and types that have no calls.
+    fn open_call_site(&mut self, node_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(node) = self.nodes.get(node_ix) else {
+            return;
+        };
+        let location = node
+            .call_sites
+            .first()
+            .unwrap_or(node.item.location())
+            .clone();
+        self.open_location(location, cx);
+    }
+
+    fn open_location(&self, location: Location, cx: &mut ViewContext<Self>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let position = location.range.start.to_point(location.buffer.read(cx));
+                let pane = workspace.adjacent_pane(cx);
+                let editor =
+                    workspace.open_project_item::<Editor>(pane, location.buffer, true, true, cx);
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                        selections.select_ranges([position..position]);
+                    });
+                });
+            })
+            .ok();
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("CallHierarchy");
+        dispatch_context
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let title = match self.roots.as_slice() {
+            [root] => format!("{} of {}", self.mode.label(), root.name()),
+            _ => self.mode.label().to_string(),
+        };
+        let toggle_tooltip = format!("Show {}", self.mode.reversed().label());
+        h_flex()
+            .px_2()
+            .py_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(title).size(LabelSize::Small).color(Color::Muted))
+            .child(
+                IconButton::new("toggle-direction", self.mode.icon())
+                    .icon_size(IconSize::Small)
+                    .tooltip(move |cx| {
+                        Tooltip::for_action(toggle_tooltip.clone(), &ToggleDirection, cx)
+                    })
+                    .on_click(
+                        cx.listener(|view, _, cx| view.toggle_direction(&ToggleDirection, cx)),
+                    ),
+            )
+    }
+
+    fn render_node(&self, node_ix: usize, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let node = &self.nodes[node_ix];
+        let toggle = match &node.children {
+            Some(children) if children.is_empty() => None,
+            _ => Some(node.expanded),
+        };
+        let location = node.item.location();
+        let buffer = location.buffer.read(cx);
+        let position = location.range.start.to_point(buffer);
+        let path = buffer
+            .file()
+            .map(|file| file.path().to_string_lossy().to_string())
+            .unwrap_or_default();
+        let location_label = format!("{path}:{}", position.row + 1);
+        let call_count = node.call_sites.len();
+
+        ListItem::new(node_ix)
+            .spacing(ListItemSpacing::Sparse)
+            .indent_level(node.depth)
+            .indent_step_size(px(12.))
+            .selected(self.selected_node == Some(node_ix))
+            .toggle(toggle)
+            .on_toggle(cx.listener(move |view, _, cx| view.toggle_expanded(node_ix, cx)))
+            .on_click(cx.listener(move |view, _, cx| {
+                view.selected_node = Some(node_ix);
+                view.open_call_site(node_ix, cx);
+            }))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(node.item.name().to_string()))
+                    .when_some(node.item.detail().cloned(), |this, detail| {
+                        this.child(
+                            Label::new(detail)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .child(
+                        Label::new(location_label)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .end_slot(
+                h_flex()
+                    .gap_1()
+                    .when(self.loading_nodes.contains_key(&node_ix), |this| {
+                        this.child(
+                            Label::new("Loading…")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .when(call_count > 1, |this| {
+                        this.child(
+                            Label::new(format!("{call_count} calls"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+            .end_hover_slot(
+                IconButton::new(("open-definition", node_ix), IconName::ArrowUpRight)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|cx| {
+                        Tooltip::for_action("Open Definition", &OpenSelectedDefinition, cx)
+                    })
+                    .on_click(cx.listener(move |view, _, cx| {
+                        view.selected_node = Some(node_ix);
+                        view.open_selected_definition(&OpenSelectedDefinition, cx);
+                    })),
+            )
+    }
+}
+
+impl Render for HierarchyView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let empty_message = if self.prepare_task.is_some() {
+            Some("Loading…")
+        } else if self.roots.is_empty() {
+            Some(self.mode.empty_message())
+        } else {
+            None
+        };
+
+        v_flex()
+            .id("call-hierarchy")
+            .size_full()
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::expand_selected_entry))
+            .on_action(cx.listener(Self::collapse_selected_entry))
+            .on_action(cx.listener(Self::open_selected_definition))
+            .on_action(cx.listener(Self::toggle_direction))
+            .child(self.render_header(cx))
+            .map(|this| match empty_message {
+                Some(message) => this.child(
+                    v_flex().size_full().justify_center().child(
+                        h_flex()
+                            .justify_center()
+                            .child(Label::new(message).color(Color::Muted)),
+                    ),
+                ),
+                None => this.child(
+                    uniform_list(
+                        cx.view().clone(),
+                        "call-hierarchy-nodes",
+                        self.visible_nodes.len(),
+                        |view, range, cx| {
+                            range
+                                .filter_map(|visible_ix| {
+                                    let node_ix = *view.visible_nodes.get(visible_ix)?;
+                                    Some(view.render_node(node_ix, cx))
+                                })
+                                .collect()
+                        },
+                    )
+                    .size_full()
+                    .track_scroll(self.scroll_handle.clone()),
+                ),
+            })
+    }
+}
+
+impl EventEmitter<ItemEvent> for HierarchyView {}
+
+impl FocusableView for HierarchyView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for HierarchyView {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_content_text(&self, _: &WindowContext) -> Option<SharedString> {
+        Some(match self.roots.as_slice() {
+            [root] => format!("{}: {}", self.mode.label(), root.name()).into(),
+            _ => self.mode.label().into(),
+        })
+    }
+
+    fn tab_icon(&self, _: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::ListTree))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use gpui::{SemanticVersion, TestAppContext, View, VisualContext, VisualTestContext};
+    use language::{FakeLspAdapter, Language, LanguageConfig, LanguageMatcher};
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::{path::PathBuf, sync::Arc};
+
+    #[gpui::test]
+    async fn test_type_hierarchy_view(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "a.rs": "trait A {}",
+                "b.rs": "struct B; impl A for B {}",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        )));
+        let mut fake_servers = language_registry.register_fake_lsp(
+            "Rust",
+            FakeLspAdapter {
+                capabilities: serde_json::from_value(json!({ "typeHierarchyProvider": true }))
+                    .unwrap(),
+                ..FakeLspAdapter::default()
+            },
+        );
+
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let a_editor = open_editor(&workspace, "/dir/a.rs", 6, cx).await;
+
+        let a_item = type_item("/dir/a.rs", "A", lsp::SymbolKind::INTERFACE, 6);
+        let b_item = type_item("/dir/b.rs", "B", lsp::SymbolKind::STRUCT, 7);
+        let fake_server = fake_servers.next().await.unwrap();
+        fake_server.handle_request::<lsp::request::TypeHierarchyPrepare, _, _>({
+            let a_item = a_item.clone();
+            let b_item = b_item.clone();
+            move |params, _| {
+                let uri = params.text_document_position_params.text_document.uri;
+                let item = if uri.path().ends_with("b.rs") {
+                    b_item.clone()
+                } else {
+                    a_item.clone()
+                };
+                async move { Ok(Some(vec![item])) }
+            }
+        });
+        fake_server.handle_request::<lsp::request::TypeHierarchySupertypes, _, _>({
+            let a_item = a_item.clone();
+            move |params, _| {
+                let supertypes = if params.item.name == "B" {
+                    vec![a_item.clone()]
+                } else {
+                    Vec::new()
+                };
+                async move { Ok(Some(supertypes)) }
+            }
+        });
+        fake_server.handle_request::<lsp::request::TypeHierarchySubtypes, _, _>({
+            let b_item = b_item.clone();
+            move |params, _| {
+                let subtypes = if params.item.name == "A" {
+                    vec![b_item.clone()]
+                } else {
+                    Vec::new()
+                };
+                async move { Ok(Some(subtypes)) }
+            }
+        });
+
+        cx.focus_view(&a_editor);
+        cx.dispatch_action(ShowSubtypes);
+        cx.run_until_parked();
+        let subtypes_view = active_hierarchy_view(&workspace, cx);
+        subtypes_view.update(cx, |view, _| {
+            assert_eq!(view.mode, HierarchyMode::Subtypes);
+            assert_eq!(visible_entries(view), ["A", "  B"]);
+        });
+
+        subtypes_view.update(cx, |view, cx| view.toggle_direction(&ToggleDirection, cx));
+        cx.run_until_parked();
+        subtypes_view.update(cx, |view, _| {
+            assert_eq!(view.mode, HierarchyMode::Supertypes);
+            assert_eq!(visible_entries(view), ["A"]);
+        });
+
+        let b_editor = open_editor(&workspace, "/dir/b.rs", 7, cx).await;
+        cx.focus_view(&b_editor);
+        cx.dispatch_action(ShowSupertypes);
+        cx.run_until_parked();
+        let supertypes_view = active_hierarchy_view(&workspace, cx);
+        assert_ne!(supertypes_view.entity_id(), subtypes_view.entity_id());
+        supertypes_view.update(cx, |view, _| {
+            assert_eq!(view.mode, HierarchyMode::Supertypes);
+            assert_eq!(visible_entries(view), ["B", "  A"]);
+        });
+
+        supertypes_view.update(cx, |view, cx| view.toggle_direction(&ToggleDirection, cx));
+        cx.run_until_parked();
+        supertypes_view.update(cx, |view, _| {
+            assert_eq!(view.mode, HierarchyMode::Subtypes);
+            assert_eq!(visible_entries(view), ["B"]);
+        });
+    }
+
+    async fn open_editor(
+        workspace: &View<Workspace>,
+        path: &str,
+        offset: usize,
+        cx: &mut VisualTestContext,
+    ) -> View<Editor> {
+        let editor = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_abs_path(PathBuf::from(path), true, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+        editor.update(cx, |editor, cx| {
+            editor.change_selections(None, cx, |selections| {
+                selections.select_ranges([offset..offset])
+            });
+        });
+        editor
+    }
+
+    fn active_hierarchy_view(
+        workspace: &View<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> View<HierarchyView> {
+        workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_item_as::<HierarchyView>(cx)
+                .expect("no hierarchy view was deployed")
+        })
+    }
+
+    fn visible_entries(view: &HierarchyView) -> Vec<String> {
+        view.visible_nodes
+            .iter()
+            .map(|&node_ix| {
+                let node = &view.nodes[node_ix];
+                format!("{}{}", "  ".repeat(node.depth), node.item.name())
+            })
+            .collect()
+    }
+
+    fn type_item(
+        path: &str,
+        name: &str,
+        kind: lsp::SymbolKind,
+        column: u32,
+    ) -> lsp::TypeHierarchyItem {
+        let range = lsp::Range::new(
+            lsp::Position::new(0, column),
+            lsp::Position::new(0, column + 1),
+        );
+        lsp::TypeHierarchyItem {
+            name: name.to_string(),
+            kind,
+            tags: None,
+            detail: None,
+            uri: lsp::Url::from_file_path(path).unwrap(),
+            range,
+            selection_range: range,
+            data: None,
+        }
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            release_channel::init(SemanticVersion::default(), cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            workspace::init_settings(cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+    }
+}
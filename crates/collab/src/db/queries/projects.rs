@@ -769,6 +769,10 @@ impl Database {
                         proto::RepositoryEntry {
                             work_directory_id: db_repository_entry.work_directory_id as u64,
                             branch: db_repository_entry.branch,
+                            // Upstream divergence isn't persisted; guests receive it with the
+                            // next repository update from the host.
+                            commits_ahead: None,
+                            commits_behind: None,
                         },
                     );
                 }
@@ -702,6 +702,10 @@ impl Database {
                         worktree.updated_repositories.push(proto::RepositoryEntry {
                             work_directory_id: db_repository.work_directory_id as u64,
                             branch: db_repository.branch,
+                            // Upstream divergence isn't persisted; guests receive it with the
+                            // next repository update from the host.
+                            commits_ahead: None,
+                            commits_behind: None,
                         });
                     }
                 }
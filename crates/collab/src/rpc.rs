@@ -560,6 +560,84 @@ impl Server {
             .add_request_handler(user_handler(
                 forward_mutating_project_request::<proto::BlameBuffer>,
             ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::Stage>,
+            ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::Unstage>,
+            ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::SetIndexText>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::LoadCommittedText>,
+            ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::Commit>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GetHeadCommitMessage>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GetGitStatus>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::PrepareCallHierarchy>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GetIncomingCalls>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GetOutgoingCalls>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GetSemanticTokens>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GetCodeLens>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::PrepareTypeHierarchy>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GetSupertypes>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GetSubtypes>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GitLog>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GetCommitDetails>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::LoadConflictStages>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::LoadRevisionText>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GitStashList>,
+            ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::GitStashPush>,
+            ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::GitStashPop>,
+            ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::GitStashDrop>,
+            ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::GitFetch>,
+            ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::GitPull>,
+            ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::GitPush>,
+            ))
             .add_request_handler(user_handler(
                 forward_mutating_project_request::<proto::MultiLspQuery>,
             ))
@@ -572,6 +650,9 @@ impl Server {
             .add_message_handler(create_buffer_for_peer)
             .add_request_handler(update_buffer)
             .add_message_handler(broadcast_project_message_from_host::<proto::RefreshInlayHints>)
+            .add_message_handler(
+                broadcast_project_message_from_host::<proto::RefreshSemanticTokens>,
+            )
             .add_message_handler(broadcast_project_message_from_host::<proto::UpdateBufferFile>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferReloaded>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferSaved>)
@@ -12,7 +12,7 @@ use client::{User, RECEIVE_TIMEOUT};
 use collections::{HashMap, HashSet};
 use fs::{FakeFs, Fs as _, RemoveOptions};
 use futures::{channel::mpsc, StreamExt as _};
-use git::repository::GitFileStatus;
+use git::repository::{AheadBehind, GitFileStatus};
 use gpui::{
     px, size, AppContext, BackgroundExecutor, Model, Modifiers, MouseButton, MouseDownEvent,
     TestAppContext, UpdateGlobal,
@@ -2987,6 +2987,221 @@ async fn test_git_status_sync(
     });
 }
 
+#[gpui::test]
+async fn test_git_staging_and_committing_are_forwarded(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree(
+            "/dir",
+            json!({
+            ".git": {},
+            "a.txt": "one\nTWO\n",
+            }),
+        )
+        .await;
+    let dot_git = Path::new("/dir/.git");
+    client_a.fs().with_git_state(dot_git, true, |state| {
+        state
+            .head_contents
+            .insert("a.txt".into(), "one\ntwo\n".into());
+        state.index_contents = state.head_contents.clone();
+    });
+
+    let (project_local, worktree_id) = client_a.build_local_project("/dir", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| {
+            call.share_project(project_local.clone(), cx)
+        })
+        .await
+        .unwrap();
+    let project_remote = client_b.join_remote_project(project_id, cx_b).await;
+    executor.run_until_parked();
+
+    let a_txt = ProjectPath {
+        worktree_id,
+        path: Path::new("a.txt").into(),
+    };
+    let index_text = || {
+        let mut text = None;
+        client_a.fs().with_git_state(dot_git, false, |state| {
+            text = state.index_contents.get(Path::new("a.txt")).cloned();
+        });
+        text
+    };
+
+    project_remote
+        .update(cx_b, |project, cx| {
+            project.stage_paths(vec![a_txt.clone()], cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(index_text().as_deref(), Some("one\nTWO\n"));
+
+    project_remote
+        .update(cx_b, |project, cx| {
+            project.unstage_paths(vec![a_txt.clone()], cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(index_text().as_deref(), Some("one\ntwo\n"));
+
+    project_remote
+        .update(cx_b, |project, cx| {
+            project.set_index_text(a_txt.clone(), Some("one\nTwo\n".into()), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(index_text().as_deref(), Some("one\nTwo\n"));
+
+    project_remote
+        .update(cx_b, |project, cx| {
+            project.commit(a_txt.clone(), "Capitalize two".into(), false, cx)
+        })
+        .await
+        .unwrap();
+    let message = project_remote
+        .update(cx_b, |project, cx| {
+            project.head_commit_message(a_txt.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(message.as_deref(), Some("Capitalize two"));
+    let committed_text = project_remote
+        .update(cx_b, |project, cx| {
+            project.load_committed_text(a_txt.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(committed_text.as_deref(), Some("one\nTwo\n"));
+}
+
+#[gpui::test]
+async fn test_git_stash_and_remote_operations_are_forwarded(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree(
+            "/dir",
+            json!({
+            ".git": {},
+            "a.txt": "a",
+            }),
+        )
+        .await;
+    let dot_git = Path::new("/dir/.git");
+    client_a.fs().with_git_state(dot_git, true, |state| {
+        state.branch_name = Some("main".into());
+        state.upstream = Some(AheadBehind {
+            ahead: 2,
+            behind: 1,
+        });
+    });
+
+    let (project_local, worktree_id) = client_a.build_local_project("/dir", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| {
+            call.share_project(project_local.clone(), cx)
+        })
+        .await
+        .unwrap();
+    let project_remote = client_b.join_remote_project(project_id, cx_b).await;
+    executor.run_until_parked();
+
+    project_remote.read_with(cx_b, |project, cx| {
+        let worktree = project.worktrees(cx).next().unwrap().read(cx);
+        let repository = worktree.snapshot().root_git_entry().unwrap();
+        assert_eq!(
+            repository.ahead_behind(),
+            Some(AheadBehind {
+                ahead: 2,
+                behind: 1
+            })
+        );
+    });
+
+    let root_path = ProjectPath {
+        worktree_id,
+        path: Path::new("").into(),
+    };
+    project_remote
+        .update(cx_b, |project, cx| {
+            project.stash_push(root_path.clone(), Some("first".into()), cx)
+        })
+        .await
+        .unwrap();
+    project_remote
+        .update(cx_b, |project, cx| {
+            project.stash_push(root_path.clone(), None, cx)
+        })
+        .await
+        .unwrap();
+    let stashes = project_remote
+        .update(cx_b, |project, cx| {
+            project.stash_list(root_path.clone(), cx)
+        })
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|stash| stash.message)
+        .collect::<Vec<_>>();
+    assert_eq!(stashes, ["WIP on main", "On main: first"]);
+
+    project_remote
+        .update(cx_b, |project, cx| {
+            project.stash_pop(root_path.clone(), 0, cx)
+        })
+        .await
+        .unwrap();
+    project_remote
+        .update(cx_b, |project, cx| {
+            project.stash_drop(root_path.clone(), 0, cx)
+        })
+        .await
+        .unwrap();
+    client_a.fs().with_git_state(dot_git, false, |state| {
+        assert!(state.stashes.is_empty());
+    });
+
+    project_remote
+        .update(cx_b, |project, cx| project.fetch(root_path.clone(), cx))
+        .await
+        .unwrap();
+    project_remote
+        .update(cx_b, |project, cx| project.pull(root_path.clone(), cx))
+        .await
+        .unwrap();
+    project_remote
+        .update(cx_b, |project, cx| project.push(root_path.clone(), cx))
+        .await
+        .unwrap();
+    client_a.fs().with_git_state(dot_git, false, |state| {
+        assert_eq!(state.upstream, Some(AheadBehind::default()));
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_fs_operations(
     executor: BackgroundExecutor,
@@ -22,6 +22,8 @@ use std::{
 };
 use util::TryFutureExt;
 
+use crate::transport::HttpTransport;
+
 const JSON_RPC_VERSION: &str = "2.0";
 const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
 
@@ -206,6 +208,82 @@ impl Client {
         Ok(context_server)
     }
 
+    /// Creates a new Client instance for a context server served over HTTP.
+    ///
+    /// Messages are exchanged through the given transport, which reconnects to the
+    /// server when the connection is lost. Requests pending at that time fail.
+    pub fn new_http(
+        server_id: ContextServerId,
+        transport: HttpTransport,
+        cx: AsyncAppContext,
+    ) -> Result<Self> {
+        log::info!(
+            "connecting to context server (url={}, transport={:?})",
+            transport.url,
+            transport.kind
+        );
+
+        let name = transport
+            .url
+            .host_str()
+            .unwrap_or(transport.url.as_str())
+            .into();
+
+        let (outbound_tx, outbound_rx) = channel::unbounded::<String>();
+        let (inbound_tx, inbound_rx) = channel::unbounded::<String>();
+        let (output_done_tx, output_done_rx) = barrier::channel();
+
+        let notification_handlers =
+            Arc::new(Mutex::new(HashMap::<_, NotificationHandler>::default()));
+        let response_handlers =
+            Arc::new(Mutex::new(Some(HashMap::<_, ResponseHandler>::default())));
+
+        let input_task = cx.spawn({
+            let notification_handlers = notification_handlers.clone();
+            let response_handlers = response_handlers.clone();
+            move |cx| {
+                Self::handle_inbound(inbound_rx, notification_handlers, response_handlers, cx)
+                    .log_err()
+            }
+        });
+        let output_task = cx.background_executor().spawn({
+            let response_handlers = response_handlers.clone();
+            let executor = cx.background_executor().clone();
+            async move {
+                let _clear_response_handlers = util::defer({
+                    let response_handlers = response_handlers.clone();
+                    move || {
+                        response_handlers.lock().take();
+                    }
+                });
+                transport
+                    .run(
+                        outbound_rx,
+                        inbound_tx,
+                        || Self::fail_pending_requests(&response_handlers),
+                        executor,
+                    )
+                    .await?;
+                drop(output_done_tx);
+                anyhow::Ok(())
+            }
+            .log_err()
+        });
+
+        Ok(Self {
+            server_id,
+            notification_handlers,
+            response_handlers,
+            name,
+            next_id: Default::default(),
+            outbound_tx,
+            executor: cx.background_executor().clone(),
+            io_tasks: Mutex::new(Some((input_task, output_task))),
+            output_done_rx: Mutex::new(Some(output_done_rx)),
+            server: Arc::new(Mutex::new(None)),
+        })
+    }
+
     /// Handles input from the server's stdout.
     ///
     /// This function continuously reads lines from the provided stdout stream,
@@ -230,26 +308,71 @@ impl Client {
                 return Ok(());
             }
 
-            let content = buffer.trim();
+            Self::handle_message(
+                buffer.trim(),
+                &notification_handlers,
+                &response_handlers,
+                &cx,
+            );
 
-            if !content.is_empty() {
-                if let Ok(response) = serde_json::from_str::<AnyResponse>(content) {
-                    if let Some(handlers) = response_handlers.lock().as_mut() {
-                        if let Some(handler) = handlers.remove(&response.id) {
-                            handler(Ok(content.to_string()));
-                        }
-                    }
-                } else if let Ok(notification) = serde_json::from_str::<AnyNotification>(content) {
-                    let mut notification_handlers = notification_handlers.lock();
-                    if let Some(handler) =
-                        notification_handlers.get_mut(notification.method.as_str())
-                    {
-                        handler(notification.params.unwrap_or(Value::Null), cx.clone());
-                    }
+            smol::future::yield_now().await;
+        }
+    }
+
+    /// Handles the messages received from a context server served over HTTP.
+    async fn handle_inbound(
+        inbound_rx: channel::Receiver<String>,
+        notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
+        response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
+        cx: AsyncAppContext,
+    ) -> anyhow::Result<()> {
+        while let Ok(message) = inbound_rx.recv().await {
+            Self::handle_message(
+                message.trim(),
+                &notification_handlers,
+                &response_handlers,
+                &cx,
+            );
+        }
+        Ok(())
+    }
+
+    /// Dispatches a JSON-RPC response to the handler of its request, or a
+    /// notification to the handler registered for its method.
+    fn handle_message(
+        content: &str,
+        notification_handlers: &Mutex<HashMap<&'static str, NotificationHandler>>,
+        response_handlers: &Mutex<Option<HashMap<RequestId, ResponseHandler>>>,
+        cx: &AsyncAppContext,
+    ) {
+        if content.is_empty() {
+            return;
+        }
+
+        if let Ok(response) = serde_json::from_str::<AnyResponse>(content) {
+            if let Some(handlers) = response_handlers.lock().as_mut() {
+                if let Some(handler) = handlers.remove(&response.id) {
+                    handler(Ok(content.to_string()));
                 }
             }
+        } else if let Ok(notification) = serde_json::from_str::<AnyNotification>(content) {
+            let mut notification_handlers = notification_handlers.lock();
+            if let Some(handler) = notification_handlers.get_mut(notification.method.as_str()) {
+                handler(notification.params.unwrap_or(Value::Null), cx.clone());
+            }
+        }
+    }
 
-            smol::future::yield_now().await;
+    /// Fails the pending requests, whose responses can no longer be received.
+    fn fail_pending_requests(
+        response_handlers: &Mutex<Option<HashMap<RequestId, ResponseHandler>>>,
+    ) {
+        if let Some(handlers) = response_handlers.lock().as_mut() {
+            for (_, handler) in handlers.drain() {
+                handler(Err(Error {
+                    message: "lost connection to context server".to_string(),
+                }));
+            }
         }
     }
 
@@ -336,7 +459,7 @@ impl Client {
         let send = self
             .outbound_tx
             .try_send(request)
-            .context("failed to write to context server's stdin");
+            .context("failed to send message to context server");
 
         let executor = self.executor.clone();
         let started = Instant::now();
@@ -359,7 +482,7 @@ impl Client {
                             Err(anyhow!("Invalid response: no result or error"))
                         }
                     }
-                    Err(_) => anyhow::bail!("cancelled")
+                    Err(error) => Err(anyhow!(error.message))
                 }
             }
             _ = timeout => {
@@ -6,6 +6,9 @@ pub mod client;
 pub mod manager;
 pub mod protocol;
 mod registry;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test;
+pub mod transport;
 pub mod types;
 
 pub use registry::*;
@@ -14,17 +14,21 @@
 //! The module also includes initialization logic to set up the context server system
 //! and react to changes in settings.
 
+use anyhow::{anyhow, Context as _};
 use collections::{HashMap, HashSet};
 use command_palette_hooks::CommandPaletteFilter;
 use gpui::{AppContext, AsyncAppContext, Context, EventEmitter, Global, Model, ModelContext, Task};
 use log;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources, SettingsStore};
 use std::path::Path;
 use std::sync::Arc;
+use url::Url;
+use util::ResultExt as _;
 
+use crate::transport::{ContextServerTransport, HttpTransport};
 use crate::CONTEXT_SERVERS_NAMESPACE;
 use crate::{
     client::{self, Client},
@@ -39,9 +43,21 @@ pub struct ContextServerSettings {
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct ServerConfig {
     pub id: String,
+    /// How to connect to the server.
+    ///
+    /// Default: stdio
+    #[serde(default)]
+    pub transport: ContextServerTransport,
+    /// The executable to spawn, when using the stdio transport.
+    #[serde(default)]
     pub executable: String,
+    #[serde(default)]
     pub args: Vec<String>,
     pub env: Option<HashMap<String, String>>,
+    /// The URL of the server, when using the SSE or streamable HTTP transports.
+    pub url: Option<String>,
+    /// Headers to send with each HTTP request to the server, like `Authorization`.
+    pub headers: Option<HashMap<String, String>>,
 }
 
 impl Settings for ContextServerSettings {
@@ -61,6 +77,7 @@ pub struct ContextServer {
     pub id: String,
     pub config: ServerConfig,
     pub client: RwLock<Option<Arc<crate::protocol::InitializedContextServerProtocol>>>,
+    subscribed_resources: Mutex<HashSet<Url>>,
 }
 
 impl ContextServer {
@@ -69,20 +86,44 @@ impl ContextServer {
             id: config.id.clone(),
             config,
             client: RwLock::new(None),
+            subscribed_resources: Mutex::default(),
         }
     }
 
     async fn start(&self, cx: &AsyncAppContext) -> anyhow::Result<()> {
         log::info!("starting context server {}", self.config.id,);
-        let client = Client::new(
-            client::ContextServerId(self.config.id.clone()),
-            client::ModelContextServerBinary {
-                executable: Path::new(&self.config.executable).to_path_buf(),
-                args: self.config.args.clone(),
-                env: self.config.env.clone(),
-            },
-            cx.clone(),
-        )?;
+        let server_id = client::ContextServerId(self.config.id.clone());
+        let client = match self.config.transport {
+            ContextServerTransport::Stdio => Client::new(
+                server_id,
+                client::ModelContextServerBinary {
+                    executable: Path::new(&self.config.executable).to_path_buf(),
+                    args: self.config.args.clone(),
+                    env: self.config.env.clone(),
+                },
+                cx.clone(),
+            )?,
+            kind @ (ContextServerTransport::Sse | ContextServerTransport::StreamableHttp) => {
+                let url = self
+                    .config
+                    .url
+                    .as_deref()
+                    .ok_or_else(|| anyhow!("context server {} has no url", self.config.id))?;
+                let url = Url::parse(url).with_context(|| {
+                    format!("invalid url for context server {}", self.config.id)
+                })?;
+                Client::new_http(
+                    server_id,
+                    HttpTransport {
+                        kind,
+                        url,
+                        headers: self.config.headers.clone().unwrap_or_default(),
+                        http_client: cx.update(|cx| cx.http_client())?,
+                    },
+                    cx.clone(),
+                )?
+            }
+        };
 
         let protocol = crate::protocol::ModelContextProtocol::new(client);
         let client_info = types::Implementation {
@@ -97,10 +138,69 @@ impl ContextServer {
             initialized_protocol.initialize,
         );
 
+        let server_id = self.id.clone();
+        initialized_protocol.on_notification(
+            types::NotificationType::ToolsListChanged.as_str(),
+            move |_, cx| {
+                cx.update(|cx| {
+                    ContextServerManager::global(cx).update(cx, |_, cx| {
+                        cx.emit(Event::ToolsChanged {
+                            server_id: server_id.clone(),
+                        })
+                    })
+                })
+                .ok();
+            },
+        );
+
+        let server_id = self.id.clone();
+        initialized_protocol.on_notification(
+            types::NotificationType::ResourcesUpdated.as_str(),
+            move |params, cx| {
+                let Some(params) =
+                    serde_json::from_value::<types::ResourcesUpdatedParams>(params).log_err()
+                else {
+                    return;
+                };
+                cx.update(|cx| {
+                    ContextServerManager::global(cx).update(cx, |_, cx| {
+                        cx.emit(Event::ResourceUpdated {
+                            server_id: server_id.clone(),
+                            uri: params.uri,
+                        })
+                    })
+                })
+                .ok();
+            },
+        );
+
         *self.client.write() = Some(Arc::new(initialized_protocol));
         Ok(())
     }
 
+    /// Subscribes to updates of a resource, unless the server doesn't support
+    /// subscriptions or is already subscribed to it. Updates are emitted as
+    /// [`Event::ResourceUpdated`] by the [`ContextServerManager`].
+    ///
+    /// Subscriptions last until the server is stopped, as sections showing the
+    /// resource may be restored by undoing their removal.
+    pub async fn subscribe_to_resource(&self, uri: &Url) -> anyhow::Result<()> {
+        let Some(protocol) = self.client.read().clone() else {
+            return Err(anyhow!("Context server not initialized"));
+        };
+        if !protocol.can_subscribe_to_resources()
+            || !self.subscribed_resources.lock().insert(uri.clone())
+        {
+            return Ok(());
+        }
+
+        let result = protocol.subscribe_to_resource(uri).await;
+        if result.is_err() {
+            self.subscribed_resources.lock().remove(uri);
+        }
+        result
+    }
+
     async fn stop(&self) -> anyhow::Result<()> {
         let mut client = self.client.write();
         if let Some(protocol) = client.take() {
@@ -120,8 +220,21 @@ pub struct ContextServerManager {
 }
 
 pub enum Event {
-    ServerStarted { server_id: String },
-    ServerStopped { server_id: String },
+    ServerStarted {
+        server_id: String,
+    },
+    ServerStopped {
+        server_id: String,
+    },
+    /// The server announced that the list of its tools has changed.
+    ToolsChanged {
+        server_id: String,
+    },
+    /// A resource the server was subscribed to has changed.
+    ResourceUpdated {
+        server_id: String,
+        uri: Url,
+    },
 }
 
 impl Global for ContextServerManager {}
@@ -144,6 +257,11 @@ impl ContextServerManager {
         cx.global::<GlobalContextServerManager>().0.clone()
     }
 
+    pub fn try_global(cx: &AppContext) -> Option<Model<Self>> {
+        cx.try_global::<GlobalContextServerManager>()
+            .map(|manager| manager.0.clone())
+    }
+
     pub fn add_server(
         &mut self,
         config: ServerConfig,
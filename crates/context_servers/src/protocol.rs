@@ -7,6 +7,8 @@
 
 use anyhow::Result;
 use collections::HashMap;
+use gpui::AsyncAppContext;
+use url::Url;
 
 use crate::client::Client;
 use crate::types;
@@ -117,6 +119,55 @@ impl InitializedContextServerProtocol {
         Ok(response)
     }
 
+    /// List the MCP resource templates.
+    pub async fn list_resource_templates(&self) -> Result<Vec<types::ResourceTemplate>> {
+        self.check_capability(ServerCapability::Resources)?;
+
+        let response: types::ResourcesTemplatesListResponse = self
+            .inner
+            .request(types::RequestType::ResourcesTemplatesList.as_str(), ())
+            .await?;
+
+        Ok(response.resource_templates)
+    }
+
+    /// Reads the contents of a resource.
+    pub async fn read_resource(&self, uri: &Url) -> Result<types::ResourcesReadResponse> {
+        self.check_capability(ServerCapability::Resources)?;
+
+        let params = types::ResourcesReadParams { uri: uri.clone() };
+        let response: types::ResourcesReadResponse = self
+            .inner
+            .request(types::RequestType::ResourcesRead.as_str(), params)
+            .await?;
+
+        Ok(response)
+    }
+
+    /// Whether the server sends notifications when subscribed resources change.
+    pub fn can_subscribe_to_resources(&self) -> bool {
+        self.initialize
+            .capabilities
+            .resources
+            .as_ref()
+            .and_then(|resources| resources.subscribe)
+            .unwrap_or(false)
+    }
+
+    /// Subscribes to updates of a resource, which the server announces with
+    /// `notifications/resources/updated`.
+    pub async fn subscribe_to_resource(&self, uri: &Url) -> Result<()> {
+        self.check_capability(ServerCapability::Resources)?;
+
+        let params = types::ResourcesSubscribeParams { uri: uri.clone() };
+        let _: serde_json::Value = self
+            .inner
+            .request(types::RequestType::ResourcesSubscribe.as_str(), params)
+            .await?;
+
+        Ok(())
+    }
+
     /// Executes a prompt with the given arguments and returns the result.
     pub async fn run_prompt<P: AsRef<str>>(
         &self,
@@ -138,6 +189,49 @@ impl InitializedContextServerProtocol {
         Ok(response)
     }
 
+    /// List the MCP tools, following the cursors of paginated responses.
+    pub async fn list_tools(&self) -> Result<Vec<types::Tool>> {
+        self.check_capability(ServerCapability::Tools)?;
+
+        let mut tools = Vec::new();
+        let mut cursor = None;
+        loop {
+            let params = types::ListToolsParams { cursor };
+            let response: types::ListToolsResponse = self
+                .inner
+                .request(types::RequestType::ListTools.as_str(), params)
+                .await?;
+            tools.extend(response.tools);
+            match response.next_cursor {
+                Some(next_cursor) => cursor = Some(next_cursor),
+                None => break,
+            }
+        }
+
+        Ok(tools)
+    }
+
+    /// Executes a tool with the given arguments.
+    pub async fn run_tool<P: AsRef<str>>(
+        &self,
+        tool: P,
+        arguments: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<types::CallToolResponse> {
+        self.check_capability(ServerCapability::Tools)?;
+
+        let params = types::CallToolParams {
+            name: tool.as_ref().to_string(),
+            arguments,
+        };
+
+        let response: types::CallToolResponse = self
+            .inner
+            .request(types::RequestType::CallTool.as_str(), params)
+            .await?;
+
+        Ok(response)
+    }
+
     pub async fn completion<P: Into<String>>(
         &self,
         reference: types::CompletionReference,
@@ -176,4 +270,11 @@ impl InitializedContextServerProtocol {
     ) -> Result<R> {
         self.inner.request(method, params).await
     }
+
+    pub fn on_notification<F>(&self, method: &'static str, f: F)
+    where
+        F: 'static + Send + FnMut(serde_json::Value, AsyncAppContext),
+    {
+        self.inner.on_notification(method, f)
+    }
 }
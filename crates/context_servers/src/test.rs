@@ -0,0 +1,229 @@
+//! A fake context server for tests, which is served over the streamable HTTP
+//! transport by a fake HTTP client.
+
+use std::io;
+use std::sync::Arc;
+
+use anyhow::Result;
+use collections::HashMap;
+use futures::{channel::mpsc, AsyncReadExt as _, TryStreamExt as _};
+use http_client::{AsyncBody, FakeHttpClient, HttpClient, Method, Request, Response};
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::manager::ServerConfig;
+use crate::transport::ContextServerTransport;
+use crate::types;
+
+/// The URL the fake context servers are served at.
+const FAKE_SERVER_URL: &str = "http://localhost:8000/mcp";
+const SESSION_ID: &str = "1";
+
+#[derive(Default)]
+pub struct FakeContextServer {
+    state: Mutex<FakeContextServerState>,
+}
+
+#[derive(Default)]
+struct FakeContextServerState {
+    /// The tools of the server, with the text their calls return.
+    tools: Vec<(types::Tool, String)>,
+    /// The number of tools listed per page, if tools are paginated.
+    tools_page_size: Option<usize>,
+    /// The stream on which notifications are sent, once the client opened it.
+    stream: Option<mpsc::UnboundedSender<io::Result<Vec<u8>>>>,
+    resources: HashMap<Url, String>,
+    can_subscribe_to_resources: bool,
+    received_requests: Vec<(String, Value)>,
+}
+
+impl FakeContextServer {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Adds a tool, whose calls return the given text.
+    pub fn add_tool(&self, name: &str, description: &str, output: &str) {
+        self.state.lock().tools.push((
+            types::Tool {
+                name: name.to_string(),
+                description: Some(description.to_string()),
+                input_schema: json!({ "type": "object", "properties": {} }),
+            },
+            output.to_string(),
+        ));
+    }
+
+    /// Lists the tools in pages of the given size.
+    pub fn set_tools_page_size(&self, page_size: usize) {
+        self.state.lock().tools_page_size = Some(page_size);
+    }
+
+    /// Sends a notification to the client on the stream it opened.
+    pub fn notify(&self, method: &str) {
+        self.send_notification(json!({ "jsonrpc": "2.0", "method": method }));
+    }
+
+    /// Notifies the client that a resource it subscribed to has changed.
+    pub fn notify_resource_updated(&self, uri: &Url) {
+        self.send_notification(json!({
+            "jsonrpc": "2.0",
+            "method": types::NotificationType::ResourcesUpdated.as_str(),
+            "params": { "uri": uri },
+        }));
+    }
+
+    fn send_notification(&self, notification: Value) {
+        self.state
+            .lock()
+            .stream
+            .as_ref()
+            .expect("the client didn't open the stream")
+            .unbounded_send(Ok(
+                format!("event: message\ndata: {notification}\n\n").into_bytes()
+            ))
+            .unwrap();
+    }
+
+    /// Adds a resource, or changes the text of an existing one.
+    pub fn set_resource(&self, uri: &Url, text: &str) {
+        self.state
+            .lock()
+            .resources
+            .insert(uri.clone(), text.to_string());
+    }
+
+    /// Makes the server announce that resources can be subscribed to.
+    pub fn allow_resource_subscriptions(&self) {
+        self.state.lock().can_subscribe_to_resources = true;
+    }
+
+    /// Returns the methods and params of the requests the server received,
+    /// excluding the ones of the handshake.
+    pub fn received_requests(&self) -> Vec<(String, Value)> {
+        self.state
+            .lock()
+            .received_requests
+            .iter()
+            .filter(|(method, _)| method != types::RequestType::Initialize.as_str())
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the configuration of a context server connecting to this one
+    /// through the HTTP client returned by [`Self::http_client`].
+    pub fn config(id: &str) -> ServerConfig {
+        ServerConfig {
+            id: id.to_string(),
+            transport: ContextServerTransport::StreamableHttp,
+            executable: String::new(),
+            args: Vec::new(),
+            env: None,
+            url: Some(FAKE_SERVER_URL.to_string()),
+            headers: None,
+        }
+    }
+
+    pub fn http_client(self: &Arc<Self>) -> Arc<dyn HttpClient> {
+        let server = self.clone();
+        FakeHttpClient::create(move |request| {
+            let server = server.clone();
+            async move { server.handle_request(request).await }
+        })
+    }
+
+    async fn handle_request(&self, request: Request<AsyncBody>) -> Result<Response<AsyncBody>> {
+        let response = Response::builder().header("Mcp-Session-Id", SESSION_ID);
+        if request.method() == Method::GET {
+            let (stream_tx, stream_rx) = mpsc::unbounded();
+            self.state.lock().stream = Some(stream_tx);
+            return Ok(response
+                .status(200)
+                .header("Content-Type", "text/event-stream")
+                .body(AsyncBody::from_reader(stream_rx.into_async_read()))?);
+        }
+
+        let mut body = String::new();
+        request.into_body().read_to_string(&mut body).await?;
+        let message: Value = serde_json::from_str(&body)?;
+        let Some(id) = message.get("id") else {
+            return Ok(response.status(202).body(AsyncBody::empty())?);
+        };
+
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let reply = match self.reply(method, &params) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": { "message": error } }),
+        };
+        Ok(response
+            .status(200)
+            .header("Content-Type", "application/json")
+            .body(AsyncBody::from(reply.to_string()))?)
+    }
+
+    fn reply(&self, method: &str, params: &Value) -> Result<Value, String> {
+        let mut state = self.state.lock();
+        state
+            .received_requests
+            .push((method.to_string(), params.clone()));
+
+        match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": 1,
+                "capabilities": {
+                    "prompts": {},
+                    "resources": { "subscribe": state.can_subscribe_to_resources },
+                    "tools": { "listChanged": true },
+                },
+                "serverInfo": { "name": "fake", "version": "1.0.0" },
+            })),
+            "prompts/list" => Ok(json!({ "prompts": [] })),
+            "tools/list" => {
+                // The cursor is the index of the first tool of the page.
+                let start = params["cursor"]
+                    .as_str()
+                    .map_or(Ok(0), str::parse::<usize>)
+                    .map_err(|_| "invalid cursor".to_string())?;
+                let end = state
+                    .tools_page_size
+                    .map_or(state.tools.len(), |page_size| start + page_size)
+                    .min(state.tools.len());
+                let tools = state.tools[start.min(end)..end]
+                    .iter()
+                    .map(|(tool, _)| tool)
+                    .collect::<Vec<_>>();
+                let next_cursor = (end < state.tools.len()).then(|| end.to_string());
+                Ok(json!({ "tools": tools, "nextCursor": next_cursor }))
+            }
+            "tools/call" => {
+                let name = params["name"].as_str().unwrap_or_default();
+                let (_, output) = state
+                    .tools
+                    .iter()
+                    .find(|(tool, _)| tool.name == name)
+                    .ok_or_else(|| format!("no tool named {name}"))?;
+                Ok(json!({ "content": [{ "type": "text", "text": output }] }))
+            }
+            "resources/list" => Ok(json!({
+                "resources": state
+                    .resources
+                    .keys()
+                    .map(|uri| json!({ "uri": uri, "name": uri.path() }))
+                    .collect::<Vec<_>>(),
+            })),
+            "resources/templates/list" => Ok(json!({ "resourceTemplates": [] })),
+            "resources/read" => {
+                let uri = params["uri"].as_str().unwrap_or_default();
+                let text = Url::parse(uri)
+                    .ok()
+                    .and_then(|uri| state.resources.get(&uri))
+                    .ok_or_else(|| format!("no resource at {uri}"))?;
+                Ok(json!({ "contents": [{ "uri": uri, "text": text }] }))
+            }
+            "resources/subscribe" => Ok(json!({})),
+            _ => Err(format!("unknown method {method}")),
+        }
+    }
+}
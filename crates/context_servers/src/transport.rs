@@ -0,0 +1,997 @@
+//! This module implements the HTTP transports of the Model Context Protocol.
+//!
+//! With the SSE transport, the client receives messages over a long-lived
+//! server-sent event stream, whose first event tells the endpoint to post
+//! messages to. With the streamable HTTP transport, the client posts each
+//! message to the server, which answers with either a JSON body or a stream
+//! of server-sent events.
+//!
+//! Both transports exchange the same JSON-RPC messages as the stdio transport,
+//! so the client routes requests and notifications the same way.
+
+use anyhow::{anyhow, bail, Context, Result};
+use collections::HashMap;
+use futures::{select, stream::FuturesUnordered, AsyncReadExt, FutureExt, StreamExt};
+use gpui::{BackgroundExecutor, Task};
+use http_client::{AsyncBody, HttpClient, Method, Request, Response, StatusCode, Url};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use smol::{
+    channel,
+    io::{AsyncBufRead, AsyncBufReadExt, BufReader},
+};
+use std::{sync::Arc, time::Duration};
+use util::ResultExt;
+
+use crate::types;
+
+const SESSION_ID_HEADER: &str = "Mcp-Session-Id";
+const MAX_RECONNECT_ATTEMPTS: u32 = 5;
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// How Zed connects to a context server.
+#[derive(Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextServerTransport {
+    /// Spawn the server's executable and exchange messages over its stdin and stdout.
+    #[default]
+    Stdio,
+    /// Receive messages from the server's URL as server-sent events, and post
+    /// messages to the endpoint it announces.
+    Sse,
+    /// Post messages to the server's URL, which answers with JSON or server-sent events.
+    StreamableHttp,
+}
+
+/// The connection to a context server served over HTTP.
+pub struct HttpTransport {
+    pub kind: ContextServerTransport,
+    pub url: Url,
+    pub headers: HashMap<String, String>,
+    pub http_client: Arc<dyn HttpClient>,
+}
+
+/// The session of the streamable HTTP transport.
+#[derive(Default)]
+struct HttpSession {
+    id: Option<String>,
+    /// Incremented whenever a new session is started.
+    generation: usize,
+}
+
+/// An event of a server-sent event stream.
+#[derive(Debug)]
+struct SseEvent {
+    event: String,
+    data: String,
+}
+
+impl HttpTransport {
+    /// Sends the messages received from `outbound_rx` to the server, and the
+    /// messages received from the server to `inbound_tx`, until `outbound_rx`
+    /// is closed.
+    ///
+    /// `on_connection_lost` is called whenever the responses to pending requests
+    /// can no longer be received.
+    pub(crate) async fn run(
+        self,
+        outbound_rx: channel::Receiver<String>,
+        inbound_tx: channel::Sender<String>,
+        on_connection_lost: impl Fn(),
+        executor: BackgroundExecutor,
+    ) -> Result<()> {
+        match self.kind {
+            ContextServerTransport::Sse => {
+                self.run_sse(outbound_rx, inbound_tx, on_connection_lost, executor)
+                    .await
+            }
+            ContextServerTransport::StreamableHttp => {
+                self.run_streamable_http(outbound_rx, inbound_tx, executor)
+                    .await
+            }
+            ContextServerTransport::Stdio => Err(anyhow!("stdio is not an HTTP transport")),
+        }
+    }
+
+    async fn run_sse(
+        &self,
+        outbound_rx: channel::Receiver<String>,
+        inbound_tx: channel::Sender<String>,
+        on_connection_lost: impl Fn(),
+        executor: BackgroundExecutor,
+    ) -> Result<()> {
+        // The messages initializing the session, which are sent again after
+        // reconnecting, as the server starts a new session.
+        let mut handshake = Vec::new();
+        let mut failed_attempts = 0;
+        loop {
+            let connection = async {
+                let (events, reader) = self.connect_sse(&executor).await?;
+                let endpoint = wait_for_endpoint(&events, &self.url).await?;
+                anyhow::Ok((events, reader, endpoint))
+            }
+            .await;
+            let (events, _reader, endpoint) = match connection {
+                Ok(connection) => connection,
+                Err(error) => {
+                    failed_attempts += 1;
+                    if failed_attempts > MAX_RECONNECT_ATTEMPTS {
+                        return Err(error.context("failed to connect to context server"));
+                    }
+                    log::warn!("failed to connect to context server, retrying: {error:#}");
+                    executor.timer(RECONNECT_DELAY * failed_attempts).await;
+                    continue;
+                }
+            };
+            failed_attempts = 0;
+
+            for message in &handshake {
+                self.post_sse_message(&endpoint, message, &inbound_tx).await;
+            }
+
+            loop {
+                select! {
+                    event = events.recv().fuse() => {
+                        let Ok(event) = event else {
+                            break;
+                        };
+                        if event.event == "message" {
+                            inbound_tx.send(event.data).await?;
+                        }
+                    }
+                    message = outbound_rx.recv().fuse() => {
+                        let Ok(message) = message else {
+                            return Ok(());
+                        };
+                        if is_handshake_message(&message) {
+                            handshake.push(message.clone());
+                        }
+                        self.post_sse_message(&endpoint, &message, &inbound_tx)
+                            .await;
+                    }
+                }
+            }
+
+            log::warn!("lost connection to context server, reconnecting");
+            on_connection_lost();
+        }
+    }
+
+    /// Opens the server-sent event stream, whose events are read in the background.
+    async fn connect_sse(
+        &self,
+        executor: &BackgroundExecutor,
+    ) -> Result<(channel::Receiver<SseEvent>, Task<()>)> {
+        let request = self
+            .request(Method::GET, &self.url)
+            .header("Accept", "text/event-stream")
+            .body(AsyncBody::empty())?;
+        let response = self.http_client.send(request).await?;
+        if !response.status().is_success() {
+            bail!("unexpected status {}", response.status());
+        }
+
+        let (events_tx, events_rx) = channel::unbounded();
+        let mut body = BufReader::new(response.into_body());
+        let reader = executor.spawn(async move {
+            while let Some(event) = read_event(&mut body).await.log_err().flatten() {
+                if events_tx.send(event).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok((events_rx, reader))
+    }
+
+    /// Posts a message to the endpoint of the SSE stream. The server answers on
+    /// the stream, unless it fails, in which case an error response is sent for
+    /// the message.
+    async fn post_sse_message(
+        &self,
+        endpoint: &Url,
+        message: &str,
+        inbound_tx: &channel::Sender<String>,
+    ) {
+        let result = async {
+            let response = self.post(endpoint, message, None).await?;
+            if !response.status().is_success() {
+                bail!("unexpected status {}", response.status());
+            }
+            anyhow::Ok(())
+        }
+        .await;
+        if let Err(error) = result {
+            log::error!("failed to send message to context server: {error:#}");
+            if let Some(response) = error_response(message, &error) {
+                inbound_tx.send(response).await.ok();
+            }
+        }
+    }
+
+    async fn run_streamable_http(
+        &self,
+        outbound_rx: channel::Receiver<String>,
+        inbound_tx: channel::Sender<String>,
+        executor: BackgroundExecutor,
+    ) -> Result<()> {
+        // Messages are posted concurrently, so that a slow request doesn't hold
+        // up the others. The client matches the responses to requests by ID.
+        let session = smol::lock::Mutex::new(HttpSession::default());
+        // The messages initializing the session, which are sent again when the
+        // server ends the session.
+        let mut handshake = Vec::new();
+        let mut initialized = false;
+        // The stream of the messages the server sends outside of responses, and
+        // the generation of the session it belongs to.
+        let mut event_stream: Option<(usize, Task<()>)> = None;
+        let mut pending_messages = FuturesUnordered::new();
+        loop {
+            let message = select! {
+                message = outbound_rx.recv().fuse() => Some(message),
+                _ = pending_messages.select_next_some() => None,
+            };
+            if let Some(message) = message {
+                let Ok(message) = message else {
+                    return Ok(());
+                };
+
+                if is_handshake_message(&message) {
+                    // The session must be initialized before other messages are
+                    // sent, so handshake messages are sent once the others are done.
+                    while pending_messages.next().await.is_some() {}
+                    initialized |= is_initialized_notification(&message);
+                    handshake.push(message.clone());
+                    self.post_streamable_http_message(
+                        message,
+                        handshake.clone(),
+                        &session,
+                        &inbound_tx,
+                        &executor,
+                    )
+                    .await;
+                } else {
+                    pending_messages.push(self.post_streamable_http_message(
+                        message,
+                        handshake.clone(),
+                        &session,
+                        &inbound_tx,
+                        &executor,
+                    ));
+                }
+            }
+
+            // Once the session is initialized, the stream is opened, and opened
+            // again whenever the server starts a new session.
+            if initialized {
+                let session = session.lock().await;
+                let is_stream_outdated = event_stream
+                    .as_ref()
+                    .map_or(true, |(generation, _)| *generation != session.generation);
+                if let Some(session_id) = session.id.as_deref().filter(|_| is_stream_outdated) {
+                    event_stream = self
+                        .listen_streamable_http(session_id, inbound_tx.clone(), &executor)
+                        .log_err()
+                        .map(|task| (session.generation, task));
+                }
+            }
+        }
+    }
+
+    /// Opens the stream on which the server sends messages outside of responses,
+    /// such as notifications about changed tools. The stream is read in the
+    /// background until the returned task is dropped.
+    fn listen_streamable_http(
+        &self,
+        session_id: &str,
+        inbound_tx: channel::Sender<String>,
+        executor: &BackgroundExecutor,
+    ) -> Result<Task<()>> {
+        let request = self
+            .request(Method::GET, &self.url)
+            .header("Accept", "text/event-stream")
+            .header(SESSION_ID_HEADER, session_id)
+            .body(AsyncBody::empty())?;
+        let http_client = self.http_client.clone();
+        Ok(executor.spawn(async move {
+            let response = match http_client.send(request).await {
+                Ok(response) => response,
+                Err(error) => {
+                    log::warn!("failed to open context server stream: {error:#}");
+                    return;
+                }
+            };
+            // Servers that only send messages in responses don't offer the stream.
+            if response.status() == StatusCode::METHOD_NOT_ALLOWED {
+                return;
+            }
+            if !response.status().is_success() {
+                log::warn!(
+                    "failed to open context server stream: unexpected status {}",
+                    response.status()
+                );
+                return;
+            }
+
+            let mut body = BufReader::new(response.into_body());
+            while let Some(event) = read_event(&mut body).await.log_err().flatten() {
+                if event.event == "message" && inbound_tx.send(event.data).await.is_err() {
+                    break;
+                }
+            }
+        }))
+    }
+
+    /// Posts a message of the streamable HTTP transport, starting a new session
+    /// first if the server ended the current one.
+    async fn post_streamable_http_message(
+        &self,
+        message: String,
+        handshake: Vec<String>,
+        session: &smol::lock::Mutex<HttpSession>,
+        inbound_tx: &channel::Sender<String>,
+        executor: &BackgroundExecutor,
+    ) {
+        let result = async {
+            let (session_id, mut generation) = {
+                let session = session.lock().await;
+                (session.id.clone(), session.generation)
+            };
+            let mut response = self
+                .post_with_retries(&message, session_id.as_deref(), executor)
+                .await?;
+            if response.status() == StatusCode::NOT_FOUND && session_id.is_some() {
+                // Holding the lock makes the other messages wait for the new session.
+                let mut session = session.lock().await;
+                // Another message may have started a new session already.
+                if session.generation == generation {
+                    log::warn!("context server ended the session, starting a new one");
+                    session.id = None;
+                    session.generation += 1;
+                    for handshake_message in &handshake {
+                        let response = self
+                            .post_with_retries(handshake_message, session.id.as_deref(), executor)
+                            .await?;
+                        // The responses to the handshake were already handled, so
+                        // only the ID of the new session is kept.
+                        if let Some(new_session_id) = response_session_id(&response) {
+                            session.id = Some(new_session_id);
+                        }
+                    }
+                }
+                if is_handshake_message(&message) {
+                    return Ok(());
+                }
+                let session_id = session.id.clone();
+                generation = session.generation;
+                drop(session);
+                response = self
+                    .post_with_retries(&message, session_id.as_deref(), executor)
+                    .await?;
+            }
+
+            if !response.status().is_success() {
+                bail!("unexpected status {}", response.status());
+            }
+            if let Some(new_session_id) = response_session_id(&response) {
+                let mut session = session.lock().await;
+                // Ignore the responses of sessions that were replaced in the meantime.
+                if session.generation == generation {
+                    session.id = Some(new_session_id);
+                }
+            }
+            forward_response(response, inbound_tx.clone(), executor).await
+        }
+        .await;
+
+        if let Err(error) = result {
+            log::error!("failed to send message to context server: {error:#}");
+            if let Some(response) = error_response(&message, &error) {
+                inbound_tx.send(response).await.ok();
+            }
+        }
+    }
+
+    /// Posts a message, retrying when the server can't be reached if the message
+    /// can safely be sent twice.
+    async fn post_with_retries(
+        &self,
+        message: &str,
+        session_id: Option<&str>,
+        executor: &BackgroundExecutor,
+    ) -> Result<Response<AsyncBody>> {
+        let is_idempotent = is_idempotent_message(message);
+        let mut failed_attempts = 0;
+        loop {
+            match self.post(&self.url, message, session_id).await {
+                Ok(response) => return Ok(response),
+                Err(error) if is_idempotent && failed_attempts < MAX_RECONNECT_ATTEMPTS => {
+                    failed_attempts += 1;
+                    log::warn!("failed to reach context server, retrying: {error:#}");
+                    executor.timer(RECONNECT_DELAY * failed_attempts).await;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    async fn post(
+        &self,
+        url: &Url,
+        message: &str,
+        session_id: Option<&str>,
+    ) -> Result<Response<AsyncBody>> {
+        let mut request = self
+            .request(Method::POST, url)
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json, text/event-stream");
+        if let Some(session_id) = session_id {
+            request = request.header(SESSION_ID_HEADER, session_id);
+        }
+        let request = request.body(AsyncBody::from(message.to_string()))?;
+        self.http_client.send(request).await
+    }
+
+    fn request(&self, method: Method, url: &Url) -> http_client::http::request::Builder {
+        self.headers.iter().fold(
+            Request::builder().method(method).uri(url.as_str()),
+            |request, (name, value)| request.header(name.as_str(), value.as_str()),
+        )
+    }
+}
+
+/// Waits for the `endpoint` event of an SSE stream, whose data is the URL to
+/// post messages to, relative to the stream's URL.
+async fn wait_for_endpoint(events: &channel::Receiver<SseEvent>, url: &Url) -> Result<Url> {
+    while let Ok(event) = events.recv().await {
+        if event.event == "endpoint" {
+            return url
+                .join(event.data.trim())
+                .context("invalid endpoint URL sent by context server");
+        }
+    }
+    Err(anyhow!(
+        "context server closed the stream before sending its endpoint"
+    ))
+}
+
+/// Sends the messages of a response to a posted message to `inbound_tx`. Streamed
+/// responses are read in the background, as they may take a while to complete.
+async fn forward_response(
+    response: Response<AsyncBody>,
+    inbound_tx: channel::Sender<String>,
+    executor: &BackgroundExecutor,
+) -> Result<()> {
+    let is_stream = response
+        .headers()
+        .get("Content-Type")
+        .and_then(|content_type| content_type.to_str().ok())
+        .map_or(false, |content_type| {
+            content_type.starts_with("text/event-stream")
+        });
+
+    if is_stream {
+        let mut body = BufReader::new(response.into_body());
+        executor
+            .spawn(async move {
+                while let Some(event) = read_event(&mut body).await.log_err().flatten() {
+                    if event.event == "message" && inbound_tx.send(event.data).await.is_err() {
+                        break;
+                    }
+                }
+            })
+            .detach();
+        return Ok(());
+    }
+
+    let mut body = String::new();
+    response.into_body().read_to_string(&mut body).await?;
+    let body = body.trim();
+    if body.is_empty() {
+        return Ok(());
+    }
+    if body.starts_with('[') {
+        let messages: Vec<Value> = serde_json::from_str(body)?;
+        for message in messages {
+            inbound_tx.send(message.to_string()).await?;
+        }
+    } else {
+        inbound_tx.send(body.to_string()).await?;
+    }
+    Ok(())
+}
+
+fn response_session_id(response: &Response<AsyncBody>) -> Option<String> {
+    response
+        .headers()
+        .get(SESSION_ID_HEADER)
+        .and_then(|session_id| session_id.to_str().ok())
+        .map(ToString::to_string)
+}
+
+fn is_handshake_message(message: &str) -> bool {
+    let Ok(message) = serde_json::from_str::<Value>(message) else {
+        return false;
+    };
+    let method = message.get("method").and_then(Value::as_str);
+    method == Some(types::RequestType::Initialize.as_str())
+        || method == Some(types::NotificationType::Initialized.as_str())
+}
+
+fn is_initialized_notification(message: &str) -> bool {
+    serde_json::from_str::<Value>(message).map_or(false, |message| {
+        message.get("method").and_then(Value::as_str)
+            == Some(types::NotificationType::Initialized.as_str())
+    })
+}
+
+/// Whether a message can be sent again when it isn't known whether the server
+/// received it. Tool calls may have side effects, so they are sent only once,
+/// as are responses and messages of unknown methods.
+fn is_idempotent_message(message: &str) -> bool {
+    let Ok(message) = serde_json::from_str::<Value>(message) else {
+        return false;
+    };
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return false;
+    };
+    let idempotent_requests = [
+        types::RequestType::Initialize,
+        types::RequestType::ListTools,
+        types::RequestType::ResourcesUnsubscribe,
+        types::RequestType::ResourcesSubscribe,
+        types::RequestType::ResourcesRead,
+        types::RequestType::ResourcesList,
+        types::RequestType::ResourcesTemplatesList,
+        types::RequestType::LoggingSetLevel,
+        types::RequestType::PromptsGet,
+        types::RequestType::PromptsList,
+        types::RequestType::CompletionComplete,
+        types::RequestType::Ping,
+    ];
+    idempotent_requests
+        .iter()
+        .any(|request| request.as_str() == method)
+        || method == types::NotificationType::Initialized.as_str()
+}
+
+/// Returns an error response to the given message, if it is a request, so that
+/// the request fails without waiting for its timeout.
+fn error_response(message: &str, error: &anyhow::Error) -> Option<String> {
+    let message = serde_json::from_str::<Value>(message).ok()?;
+    let id = message.get("id")?;
+    Some(
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "message": format!("{error:#}") },
+        })
+        .to_string(),
+    )
+}
+
+/// Reads the next event of a server-sent event stream, or `None` at its end.
+async fn read_event(reader: &mut (impl AsyncBufRead + Unpin)) -> Result<Option<SseEvent>> {
+    let mut event = String::new();
+    let mut data = String::new();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+
+        let trimmed = line.trim_end_matches(['\r', '\n']);
+        if trimmed.is_empty() {
+            if event.is_empty() && data.is_empty() {
+                continue;
+            }
+            if event.is_empty() {
+                event.push_str("message");
+            }
+            return Ok(Some(SseEvent { event, data }));
+        }
+        // Lines starting with a colon are comments, used to keep connections alive.
+        if trimmed.starts_with(':') {
+            continue;
+        }
+
+        let (field, value) = trimmed.split_once(':').unwrap_or((trimmed, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => {
+                event.clear();
+                event.push_str(value);
+            }
+            "data" => {
+                if !data.is_empty() {
+                    data.push('\n');
+                }
+                data.push_str(value);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{Client, ContextServerId};
+    use futures::{
+        channel::{mpsc, oneshot},
+        TryStreamExt as _,
+    };
+    use gpui::TestAppContext;
+    use http_client::FakeHttpClient;
+    use parking_lot::Mutex;
+    use serde_json::json;
+    use std::{
+        io,
+        sync::atomic::{AtomicUsize, Ordering::SeqCst},
+    };
+
+    /// An in-process stand-in for a context server served over HTTP, which
+    /// answers each request with its method and params.
+    #[derive(Default)]
+    struct FakeServer {
+        session: AtomicUsize,
+        sse_stream: Mutex<Option<mpsc::UnboundedSender<io::Result<Vec<u8>>>>>,
+        received_methods: Mutex<Vec<String>>,
+        /// The number of upcoming posted messages that fail as if the server
+        /// couldn't be reached.
+        unreachable_requests: AtomicUsize,
+        /// Requests for the `block` method, which are answered once unblocked.
+        blocked_requests: Mutex<Vec<oneshot::Sender<()>>>,
+    }
+
+    impl FakeServer {
+        fn http_client(self: &Arc<Self>, kind: ContextServerTransport) -> Arc<dyn HttpClient> {
+            let server = self.clone();
+            FakeHttpClient::create(move |request| {
+                let server = server.clone();
+                async move {
+                    if request.method() == Method::POST
+                        && server
+                            .unreachable_requests
+                            .fetch_update(SeqCst, SeqCst, |count| count.checked_sub(1))
+                            .is_ok()
+                    {
+                        return Err(anyhow!("connection refused"));
+                    }
+                    match kind {
+                        ContextServerTransport::Sse => server.handle_sse_request(request).await,
+                        _ => server.handle_streamable_http_request(request).await,
+                    }
+                }
+            })
+        }
+
+        fn unblock_requests(&self) {
+            for blocked_request in self.blocked_requests.lock().drain(..) {
+                blocked_request.send(()).ok();
+            }
+        }
+
+        /// Ends the current session, closing its SSE stream.
+        fn end_session(&self) {
+            self.session.fetch_add(1, SeqCst);
+            self.sse_stream.lock().take();
+        }
+
+        /// Sends a notification on the SSE stream of the current session.
+        fn notify(&self, params: Value) {
+            let notification = json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/message",
+                "params": params,
+            });
+            self.sse_stream
+                .lock()
+                .as_ref()
+                .expect("no open stream")
+                .unbounded_send(Ok(
+                    format!("event: message\ndata: {notification}\n\n").into_bytes()
+                ))
+                .unwrap();
+        }
+
+        /// Returns the messages sent in reply to a message. Requests for the
+        /// `notify` method are also answered with a notification.
+        fn reply(&self, message: &Value) -> Vec<Value> {
+            let method = message["method"].as_str().unwrap_or_default().to_string();
+            self.received_methods.lock().push(method.clone());
+            let Some(id) = message.get("id") else {
+                return Vec::new();
+            };
+
+            let mut replies = Vec::new();
+            if method == "notify" {
+                replies.push(json!({
+                    "jsonrpc": "2.0",
+                    "method": "notifications/message",
+                    "params": message["params"],
+                }));
+            }
+            replies.push(json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": { "method": method, "params": message["params"] },
+            }));
+            replies
+        }
+
+        async fn handle_sse_request(
+            &self,
+            request: Request<AsyncBody>,
+        ) -> Result<Response<AsyncBody>> {
+            if request.method() == Method::GET {
+                let session = self.session.load(SeqCst);
+                let (stream_tx, stream_rx) = mpsc::unbounded();
+                stream_tx
+                    .unbounded_send(Ok(format!(
+                        "event: endpoint\ndata: /messages?session={session}\n\n"
+                    )
+                    .into_bytes()))
+                    .unwrap();
+                *self.sse_stream.lock() = Some(stream_tx);
+                return Ok(Response::builder()
+                    .status(200)
+                    .header("Content-Type", "text/event-stream")
+                    .body(AsyncBody::from_reader(stream_rx.into_async_read()))?);
+            }
+
+            let session = request
+                .uri()
+                .query()
+                .and_then(|query| query.strip_prefix("session="))
+                .and_then(|session| session.parse::<usize>().ok());
+            let message = read_message(request).await?;
+            let sse_stream = self.sse_stream.lock();
+            let Some(sse_stream) = sse_stream
+                .as_ref()
+                .filter(|_| session == Some(self.session.load(SeqCst)))
+            else {
+                return Ok(Response::builder().status(404).body(AsyncBody::empty())?);
+            };
+            for reply in self.reply(&message) {
+                sse_stream
+                    .unbounded_send(Ok(format!("event: message\ndata: {reply}\n\n").into_bytes()))
+                    .unwrap();
+            }
+            Ok(Response::builder().status(202).body(AsyncBody::empty())?)
+        }
+
+        async fn handle_streamable_http_request(
+            &self,
+            request: Request<AsyncBody>,
+        ) -> Result<Response<AsyncBody>> {
+            let session_id = request
+                .headers()
+                .get(SESSION_ID_HEADER)
+                .and_then(|session_id| session_id.to_str().ok())
+                .map(ToString::to_string);
+            if request.method() == Method::GET {
+                if session_id != Some(self.session.load(SeqCst).to_string()) {
+                    return Ok(Response::builder().status(404).body(AsyncBody::empty())?);
+                }
+                let (stream_tx, stream_rx) = mpsc::unbounded();
+                *self.sse_stream.lock() = Some(stream_tx);
+                return Ok(Response::builder()
+                    .status(200)
+                    .header("Content-Type", "text/event-stream")
+                    .body(AsyncBody::from_reader(stream_rx.into_async_read()))?);
+            }
+
+            let message = read_message(request).await?;
+            if message["method"] == "block" {
+                let (unblock_tx, unblock_rx) = oneshot::channel();
+                self.blocked_requests.lock().push(unblock_tx);
+                unblock_rx.await.ok();
+            }
+            let session = if message["method"] == "initialize" {
+                self.session.fetch_add(1, SeqCst) + 1
+            } else {
+                self.session.load(SeqCst)
+            };
+            if message["method"] != "initialize" && session_id != Some(session.to_string()) {
+                return Ok(Response::builder().status(404).body(AsyncBody::empty())?);
+            }
+
+            let response = Response::builder().header(SESSION_ID_HEADER, session.to_string());
+            let replies = self.reply(&message);
+            Ok(match replies.as_slice() {
+                [] => response.status(202).body(AsyncBody::empty())?,
+                [reply] => response
+                    .status(200)
+                    .header("Content-Type", "application/json")
+                    .body(AsyncBody::from(reply.to_string()))?,
+                replies => response
+                    .status(200)
+                    .header("Content-Type", "text/event-stream")
+                    .body(AsyncBody::from(
+                        replies
+                            .iter()
+                            .map(|reply| format!("event: message\ndata: {reply}\n\n"))
+                            .collect::<String>(),
+                    ))?,
+            })
+        }
+    }
+
+    async fn read_message(request: Request<AsyncBody>) -> Result<Value> {
+        let mut body = String::new();
+        request.into_body().read_to_string(&mut body).await?;
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    fn client(
+        server: &Arc<FakeServer>,
+        kind: ContextServerTransport,
+        cx: &mut TestAppContext,
+    ) -> (Client, Arc<Mutex<Vec<Value>>>) {
+        let client = Client::new_http(
+            ContextServerId("test".into()),
+            HttpTransport {
+                kind,
+                url: Url::parse("http://localhost:8000/mcp").unwrap(),
+                headers: HashMap::default(),
+                http_client: server.http_client(kind),
+            },
+            cx.to_async(),
+        )
+        .unwrap();
+
+        let notifications = Arc::new(Mutex::new(Vec::new()));
+        client.on_notification("notifications/message", {
+            let notifications = notifications.clone();
+            move |params, _| notifications.lock().push(params)
+        });
+        (client, notifications)
+    }
+
+    #[gpui::test]
+    async fn test_sse_transport(cx: &mut TestAppContext) {
+        let server = Arc::new(FakeServer::default());
+        let (client, notifications) = client(&server, ContextServerTransport::Sse, cx);
+
+        let response: Value = client.request("initialize", json!({})).await.unwrap();
+        assert_eq!(response["method"], "initialize");
+        client
+            .notify("notifications/initialized", json!({}))
+            .unwrap();
+
+        let response: Value = client
+            .request("notify", json!({ "text": "hello" }))
+            .await
+            .unwrap();
+        assert_eq!(response["params"], json!({ "text": "hello" }));
+        assert_eq!(*notifications.lock(), [json!({ "text": "hello" })]);
+
+        // The client reconnects when the stream is closed, and initializes the new session.
+        server.end_session();
+        cx.run_until_parked();
+        let response: Value = client.request("echo", json!([1, 2])).await.unwrap();
+        assert_eq!(response["params"], json!([1, 2]));
+        assert_eq!(
+            *server.received_methods.lock(),
+            [
+                "initialize",
+                "notifications/initialized",
+                "notify",
+                "initialize",
+                "notifications/initialized",
+                "echo"
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_streamable_http_transport(cx: &mut TestAppContext) {
+        let server = Arc::new(FakeServer::default());
+        let (client, notifications) = client(&server, ContextServerTransport::StreamableHttp, cx);
+
+        let response: Value = client.request("initialize", json!({})).await.unwrap();
+        assert_eq!(response["method"], "initialize");
+        client
+            .notify("notifications/initialized", json!({}))
+            .unwrap();
+
+        // A single reply is sent as JSON, several ones as server-sent events.
+        let response: Value = client.request("echo", json!([1, 2])).await.unwrap();
+        assert_eq!(response["params"], json!([1, 2]));
+        let response: Value = client
+            .request("notify", json!({ "text": "hello" }))
+            .await
+            .unwrap();
+        assert_eq!(response["params"], json!({ "text": "hello" }));
+        assert_eq!(*notifications.lock(), [json!({ "text": "hello" })]);
+
+        // The server sends other messages on a stream the client opens after
+        // initializing the session.
+        cx.run_until_parked();
+        server.notify(json!({ "text": "tools changed" }));
+        cx.run_until_parked();
+        assert_eq!(
+            *notifications.lock(),
+            [
+                json!({ "text": "hello" }),
+                json!({ "text": "tools changed" })
+            ]
+        );
+
+        // The client starts a new session when the server ends the current one,
+        // and opens the stream of the new session.
+        server.end_session();
+        let response: Value = client.request("echo", json!([3])).await.unwrap();
+        assert_eq!(response["params"], json!([3]));
+        cx.run_until_parked();
+        server.notify(json!({ "text": "new session" }));
+        cx.run_until_parked();
+        assert_eq!(
+            notifications.lock().last(),
+            Some(&json!({ "text": "new session" }))
+        );
+        assert_eq!(
+            *server.received_methods.lock(),
+            [
+                "initialize",
+                "notifications/initialized",
+                "echo",
+                "notify",
+                "initialize",
+                "notifications/initialized",
+                "echo"
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_streamable_http_retries_and_concurrency(cx: &mut TestAppContext) {
+        let server = Arc::new(FakeServer::default());
+        let (client, _) = client(&server, ContextServerTransport::StreamableHttp, cx);
+        let _: Value = client.request("initialize", json!({})).await.unwrap();
+        client
+            .notify("notifications/initialized", json!({}))
+            .unwrap();
+
+        // Requests that can be repeated are retried when the server can't be reached.
+        server.unreachable_requests.store(1, SeqCst);
+        let executor = cx.executor();
+        let list_tools = client.request("tools/list", json!({}));
+        let (response, _): (Result<Value>, _) = futures::join!(list_tools, async {
+            executor.advance_clock(RECONNECT_DELAY)
+        });
+        assert_eq!(response.unwrap()["method"], "tools/list");
+
+        // Tool calls aren't, as the server may have called the tool already.
+        server.unreachable_requests.store(1, SeqCst);
+        let response: Result<Value> = client
+            .request("tools/call", json!({ "name": "deploy" }))
+            .await;
+        assert!(response.is_err());
+
+        // A slow request doesn't hold up the others.
+        let blocked_request = client.request("block", json!({}));
+        let echo_request = async {
+            let response: Result<Value> = client.request("echo", json!([1])).await;
+            server.unblock_requests();
+            response
+        };
+        let (blocked_response, echo_response): (Result<Value>, _) =
+            futures::join!(blocked_request, echo_request);
+        assert_eq!(echo_response.unwrap()["params"], json!([1]));
+        assert_eq!(blocked_response.unwrap()["method"], "block");
+
+        assert_eq!(
+            *server.received_methods.lock(),
+            [
+                "initialize",
+                "notifications/initialized",
+                "tools/list",
+                "echo",
+                "block"
+            ]
+        );
+    }
+}
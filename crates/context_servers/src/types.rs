@@ -7,10 +7,12 @@ use url::Url;
 pub enum RequestType {
     Initialize,
     CallTool,
+    ListTools,
     ResourcesUnsubscribe,
     ResourcesSubscribe,
     ResourcesRead,
     ResourcesList,
+    ResourcesTemplatesList,
     LoggingSetLevel,
     PromptsGet,
     PromptsList,
@@ -23,10 +25,12 @@ impl RequestType {
         match self {
             RequestType::Initialize => "initialize",
             RequestType::CallTool => "tools/call",
+            RequestType::ListTools => "tools/list",
             RequestType::ResourcesUnsubscribe => "resources/unsubscribe",
             RequestType::ResourcesSubscribe => "resources/subscribe",
             RequestType::ResourcesRead => "resources/read",
             RequestType::ResourcesList => "resources/list",
+            RequestType::ResourcesTemplatesList => "resources/templates/list",
             RequestType::LoggingSetLevel => "logging/setLevel",
             RequestType::PromptsGet => "prompts/get",
             RequestType::PromptsList => "prompts/list",
@@ -52,6 +56,13 @@ pub struct CallToolParams {
     pub arguments: Option<HashMap<String, serde_json::Value>>,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListToolsParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ResourcesUnsubscribeParams {
@@ -151,6 +162,12 @@ pub struct ResourcesListResponse {
     pub resources: Option<Vec<Resource>>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourcesTemplatesListResponse {
+    pub resource_templates: Vec<ResourceTemplate>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SamplingMessage {
@@ -188,6 +205,34 @@ pub struct PromptsListResponse {
     pub prompts: Vec<Prompt>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListToolsResponse {
+    pub tools: Vec<Tool>,
+    /// The cursor to list the next page of tools with, if there are more.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallToolResponse {
+    pub content: Vec<ToolResponseContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_error: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ToolResponseContent {
+    #[serde(rename = "text")]
+    Text { text: String },
+    #[serde(rename = "image", rename_all = "camelCase")]
+    Image { data: String, mime_type: String },
+    #[serde(rename = "resource")]
+    Resource { resource: ResourceContent },
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionCompleteResponse {
@@ -271,7 +316,7 @@ pub struct ToolsCapabilities {
     pub list_changed: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Tool {
     pub name: String,
@@ -287,7 +332,7 @@ pub struct Implementation {
     pub version: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Resource {
     pub uri: Url,
@@ -310,7 +355,7 @@ pub struct ResourceContent {
     pub blob: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ResourceTemplate {
     pub uri_template: String,
@@ -374,6 +419,12 @@ pub struct ProgressParams {
 
 pub type ProgressToken = String;
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourcesUpdatedParams {
+    pub uri: Url,
+}
+
 pub enum CompletionTotal {
     Exact(u32),
     HasMore,
@@ -0,0 +1,177 @@
+mod diff_view_item;
+mod line_diff;
+mod revision_prompt;
+
+use editor::Editor;
+use gpui::{
+    actions, impl_actions, AppContext, Global, Model, SharedString, ViewContext, WeakModel,
+};
+use language::{Buffer, Capability};
+use project::Item as _;
+use serde::Deserialize;
+use workspace::{notifications::DetachAndPromptErr, Workspace};
+
+pub use diff_view_item::DiffView;
+pub use line_diff::{diff_lines, DiffHunk};
+use revision_prompt::RevisionPrompt;
+
+actions!(
+    diff_view,
+    [SelectForCompare, CompareWithSelected, CompareWithClipboard]
+);
+
+/// Compares the active file with its contents as of a git revision. Without a revision,
+/// prompts for one.
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct CompareWithRevision {
+    #[serde(default)]
+    pub revision: Option<String>,
+}
+
+impl_actions!(diff_view, [CompareWithRevision]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(select_for_compare);
+        workspace.register_action(compare_with_selected);
+        workspace.register_action(compare_with_clipboard);
+        workspace.register_action(compare_with_revision);
+    })
+    .detach();
+}
+
+/// The buffer selected with [`SelectForCompare`], to be compared with another one later.
+struct SelectedForCompare(WeakModel<Buffer>);
+
+impl Global for SelectedForCompare {}
+
+fn select_for_compare(
+    workspace: &mut Workspace,
+    _: &SelectForCompare,
+    cx: &mut ViewContext<Workspace>,
+) {
+    if let Some(buffer) = active_buffer(workspace, cx) {
+        cx.set_global(SelectedForCompare(buffer.downgrade()));
+    }
+}
+
+fn compare_with_selected(
+    workspace: &mut Workspace,
+    _: &CompareWithSelected,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(new_buffer) = active_buffer(workspace, cx) else {
+        return;
+    };
+    let Some(old_buffer) = cx
+        .try_global::<SelectedForCompare>()
+        .and_then(|selected| selected.0.upgrade())
+    else {
+        return;
+    };
+    let old_title = buffer_title(&old_buffer, cx);
+    let new_title = buffer_title(&new_buffer, cx);
+    DiffView::open(workspace, old_buffer, old_title, new_buffer, new_title, cx);
+}
+
+fn compare_with_clipboard(
+    workspace: &mut Workspace,
+    _: &CompareWithClipboard,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(new_buffer) = active_buffer(workspace, cx) else {
+        return;
+    };
+    let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+        return;
+    };
+    let old_buffer = read_only_buffer_like(text, &new_buffer, cx);
+    let new_title = buffer_title(&new_buffer, cx);
+    DiffView::open(
+        workspace,
+        old_buffer,
+        "Clipboard".into(),
+        new_buffer,
+        new_title,
+        cx,
+    );
+}
+
+fn compare_with_revision(
+    workspace: &mut Workspace,
+    action: &CompareWithRevision,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(buffer) = active_buffer(workspace, cx) else {
+        return;
+    };
+    match action.revision.clone() {
+        Some(revision) => open_revision_diff(workspace, buffer, revision, cx),
+        None => {
+            let workspace_handle = cx.view().downgrade();
+            workspace.toggle_modal(cx, |cx| RevisionPrompt::new(workspace_handle, buffer, cx));
+        }
+    }
+}
+
+/// Opens a view comparing the given buffer with the contents of its file as of a git revision.
+/// A file that did not exist in the revision is compared with an empty one.
+fn open_revision_diff(
+    workspace: &mut Workspace,
+    buffer: Model<Buffer>,
+    revision: String,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(project_path) = buffer.read(cx).project_path(cx) else {
+        return;
+    };
+    let text = workspace
+        .project()
+        .read(cx)
+        .load_revision_text(project_path, revision.clone(), cx);
+    cx.spawn(|workspace, mut cx| async move {
+        let text = text.await?;
+        workspace.update(&mut cx, |workspace, cx| {
+            let old_buffer = read_only_buffer_like(text.unwrap_or_default(), &buffer, cx);
+            let title = buffer_title(&buffer, cx);
+            let old_title = format!("{title} ({revision})").into();
+            DiffView::open(workspace, old_buffer, old_title, buffer, title, cx);
+        })
+    })
+    .detach_and_prompt_err("Failed to compare with revision", cx, |_, _| None);
+}
+
+fn active_buffer(workspace: &Workspace, cx: &AppContext) -> Option<Model<Buffer>> {
+    let editor = workspace.active_item_as::<Editor>(cx)?;
+    let buffer = editor.read(cx).buffer().read(cx).as_singleton();
+    buffer
+}
+
+fn buffer_title(buffer: &Model<Buffer>, cx: &AppContext) -> SharedString {
+    buffer
+        .read(cx)
+        .file()
+        .map(|file| file.file_name(cx).to_string_lossy().to_string().into())
+        .unwrap_or_else(|| "untitled".into())
+}
+
+/// Creates a read-only buffer with the given text, in the language of another buffer.
+fn read_only_buffer_like(
+    text: String,
+    buffer: &Model<Buffer>,
+    cx: &mut AppContext,
+) -> Model<Buffer> {
+    let (language, language_registry) = {
+        let buffer = buffer.read(cx);
+        (buffer.language().cloned(), buffer.language_registry())
+    };
+    cx.new_model(|cx| {
+        let mut buffer = Buffer::local(text, cx);
+        if let Some(language_registry) = language_registry {
+            buffer.set_language_registry(language_registry);
+        }
+        buffer.set_language(language, cx);
+        buffer.set_capability(Capability::ReadOnly, cx);
+        buffer
+    })
+}
@@ -0,0 +1,742 @@
+use std::{ops::Range, time::Duration};
+
+use collections::HashSet;
+use editor::{
+    display_map::{BlockDisposition, BlockProperties, BlockStyle, CustomBlockId, RenderBlock},
+    scroll::ScrollAnchor,
+    Anchor, AnchorRangeExt as _, Editor, EditorEvent, MultiBufferSnapshot, ToOffset as _,
+};
+use gpui::{
+    point, AppContext, EventEmitter, FocusHandle, FocusableView, HighlightStyle, Hsla, Model,
+    Subscription, Task, View,
+};
+use language::{language_settings::SoftWrap, Buffer, BufferEvent, BufferSnapshot, Point};
+use project::Project;
+use ui::prelude::*;
+use workspace::{
+    item::{Item, ItemEvent},
+    Workspace,
+};
+
+use crate::line_diff::{diff_lines, DiffHunk};
+
+/// How long to wait after the last edit before comparing the buffers again.
+const DIFF_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Scroll offsets closer than this, in rows, are considered the same.
+const SCROLL_EPSILON: f32 = 0.001;
+
+enum DiffRowHighlight {}
+enum DiffWordHighlight {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Side {
+    Old,
+    New,
+}
+
+/// The highlights and filler blocks shown on one side of the diff. They are anchored in its
+/// buffer, so that only the ones that no longer match the diff are replaced after an edit.
+#[derive(Default)]
+struct ShownSide {
+    changed_rows: Vec<Range<Anchor>>,
+    changed_words: Vec<Range<Anchor>>,
+    filler_blocks: Vec<FillerBlock>,
+}
+
+/// A blank block padding one side of the diff where the other side has more lines.
+struct FillerBlock {
+    id: CustomBlockId,
+    position: Anchor,
+    height: u32,
+    disposition: BlockDisposition,
+}
+
+/// Compares two buffers side by side, aligning their unchanged lines and highlighting the
+/// lines and words that differ between them.
+pub struct DiffView {
+    old_editor: View<Editor>,
+    new_editor: View<Editor>,
+    old_buffer: Model<Buffer>,
+    new_buffer: Model<Buffer>,
+    old_title: SharedString,
+    new_title: SharedString,
+    old_shown: ShownSide,
+    new_shown: ShownSide,
+    hunks: Vec<DiffHunk>,
+    update_diff_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DiffView {
+    /// Opens a view comparing the given buffers in the active pane of the workspace.
+    pub fn open(
+        workspace: &mut Workspace,
+        old_buffer: Model<Buffer>,
+        old_title: SharedString,
+        new_buffer: Model<Buffer>,
+        new_title: SharedString,
+        cx: &mut ViewContext<Workspace>,
+    ) -> View<Self> {
+        let project = workspace.project().clone();
+        let diff_view =
+            cx.new_view(|cx| Self::new(old_buffer, old_title, new_buffer, new_title, project, cx));
+        workspace.add_item_to_active_pane(Box::new(diff_view.clone()), None, true, cx);
+        diff_view
+    }
+
+    pub fn new(
+        old_buffer: Model<Buffer>,
+        old_title: SharedString,
+        new_buffer: Model<Buffer>,
+        new_title: SharedString,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let old_editor = Self::build_editor(old_buffer.clone(), project.clone(), cx);
+        let new_editor = Self::build_editor(new_buffer.clone(), project, cx);
+        let subscriptions = vec![
+            cx.subscribe(&old_buffer, Self::on_buffer_event),
+            cx.subscribe(&new_buffer, Self::on_buffer_event),
+            cx.subscribe(&old_editor, Self::on_editor_event),
+            cx.subscribe(&new_editor, Self::on_editor_event),
+        ];
+
+        let mut this = Self {
+            old_editor,
+            new_editor,
+            old_buffer,
+            new_buffer,
+            old_title,
+            new_title,
+            old_shown: ShownSide::default(),
+            new_shown: ShownSide::default(),
+            hunks: Vec::new(),
+            update_diff_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.update_diff(false, cx);
+        this
+    }
+
+    fn build_editor(
+        buffer: Model<Buffer>,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> View<Editor> {
+        cx.new_view(|cx| {
+            let mut editor = Editor::for_buffer(buffer, Some(project), cx);
+            // Wrapped lines would take more rows on one side than on the other.
+            editor.set_soft_wrap_mode(SoftWrap::None, cx);
+            editor
+        })
+    }
+
+    fn on_buffer_event(
+        &mut self,
+        _: Model<Buffer>,
+        event: &BufferEvent,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let BufferEvent::Edited = event {
+            self.update_diff(true, cx);
+        }
+    }
+
+    fn on_editor_event(
+        &mut self,
+        editor: View<Editor>,
+        event: &EditorEvent,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let EditorEvent::ScrollPositionChanged { .. } = event {
+            let (side, other_editor) = if editor == self.old_editor {
+                (Side::Old, &self.new_editor)
+            } else {
+                (Side::New, &self.old_editor)
+            };
+            // Scroll positions are mapped in buffer rows rather than display rows, which
+            // differ between the sides once either of them has folds.
+            let (top_row, offset) = {
+                let editor = editor.read(cx);
+                let buffer = editor.buffer().read(cx).snapshot(cx);
+                scroll_top(&editor.scroll_manager.anchor(), &buffer)
+            };
+            let (other_row, row_offset) = corresponding_row(&self.hunks, side, top_row);
+            let offset = point(offset.x, offset.y + row_offset);
+            other_editor.update(cx, |other_editor, cx| {
+                let buffer = other_editor.buffer().read(cx).snapshot(cx);
+                let other_row = other_row.min(buffer.max_point().row);
+                // Setting the scroll anchor emits an event too, which must not echo back.
+                let (current_row, current_offset) =
+                    scroll_top(&other_editor.scroll_manager.anchor(), &buffer);
+                if current_row == other_row
+                    && (current_offset.x - offset.x).abs() < SCROLL_EPSILON
+                    && (current_offset.y - offset.y).abs() < SCROLL_EPSILON
+                {
+                    return;
+                }
+                other_editor.set_scroll_anchor(
+                    ScrollAnchor {
+                        anchor: buffer.anchor_before(Point::new(other_row, 0)),
+                        offset,
+                    },
+                    cx,
+                );
+            });
+        }
+    }
+
+    fn update_diff(&mut self, debounce: bool, cx: &mut ViewContext<Self>) {
+        self.update_diff_task = cx.spawn(|this, mut cx| async move {
+            if debounce {
+                cx.background_executor().timer(DIFF_DEBOUNCE).await;
+            }
+            let Ok((old_snapshot, new_snapshot)) = this.update(&mut cx, |this, cx| {
+                (
+                    this.old_buffer.read(cx).snapshot(),
+                    this.new_buffer.read(cx).snapshot(),
+                )
+            }) else {
+                return;
+            };
+            let (old_snapshot, new_snapshot, hunks) = cx
+                .background_executor()
+                .spawn(async move {
+                    let hunks = diff_lines(&old_snapshot.text(), &new_snapshot.text());
+                    (old_snapshot, new_snapshot, hunks)
+                })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.show_side(Side::Old, &old_snapshot, &hunks, cx);
+                this.show_side(Side::New, &new_snapshot, &hunks, cx);
+                this.hunks = hunks;
+            })
+            .ok();
+        });
+    }
+
+    /// Highlights the changed lines and words on one side of the diff, and pads it with
+    /// blank blocks where the other side has more lines, so that unchanged lines align.
+    /// Highlights and blocks that still match the diff are left in place.
+    fn show_side(
+        &mut self,
+        side: Side,
+        snapshot: &BufferSnapshot,
+        hunks: &[DiffHunk],
+        cx: &mut ViewContext<Self>,
+    ) {
+        let (editor, shown, color) = match side {
+            Side::Old => (
+                &self.old_editor,
+                &mut self.old_shown,
+                cx.theme().status().deleted,
+            ),
+            Side::New => (
+                &self.new_editor,
+                &mut self.new_shown,
+                cx.theme().status().created,
+            ),
+        };
+        let row_color = faded(color, 0.8);
+        let word_color = faded(color, 0.5);
+        let filler_color = cx.theme().colors().editor_subheader_background;
+        let max_point = snapshot.max_point();
+
+        editor.update(cx, |editor, cx| {
+            let multi_buffer = editor.buffer().read(cx).snapshot(cx);
+            let Some((excerpt_id, _, _)) = multi_buffer.as_singleton() else {
+                return;
+            };
+            let excerpt_id = *excerpt_id;
+            let anchor = |offset: usize| {
+                multi_buffer.anchor_in_excerpt(excerpt_id, snapshot.anchor_before(offset))
+            };
+            let point_anchor = |point: Point| anchor(snapshot.point_to_offset(point));
+            let offset_ranges = |ranges: &[Range<Anchor>]| {
+                ranges
+                    .iter()
+                    .map(|range| range.to_offset(&multi_buffer))
+                    .collect::<Vec<_>>()
+            };
+
+            let mut changed_rows = Vec::new();
+            let mut changed_words = Vec::new();
+            let mut fillers = Vec::new();
+            for hunk in hunks {
+                let (rows, words, other_rows) = match side {
+                    Side::Old => (&hunk.old_rows, &hunk.old_words, &hunk.new_rows),
+                    Side::New => (&hunk.new_rows, &hunk.new_words, &hunk.old_rows),
+                };
+
+                if !rows.is_empty() {
+                    let start = point_anchor(Point::new(rows.start, 0));
+                    let end = point_anchor(Point::new(rows.end, 0).min(max_point));
+                    if let Some((start, end)) = start.zip(end) {
+                        changed_rows.push(start..end);
+                    }
+                }
+                changed_words.extend(
+                    words
+                        .iter()
+                        .filter_map(|range| Some(anchor(range.start)?..anchor(range.end)?)),
+                );
+
+                let filler_rows = other_rows.len().saturating_sub(rows.len()) as u32;
+                if filler_rows > 0 {
+                    let (position, disposition) = if rows.end <= max_point.row {
+                        (Point::new(rows.end, 0), BlockDisposition::Above)
+                    } else {
+                        (max_point, BlockDisposition::Below)
+                    };
+                    if let Some(position) = point_anchor(position) {
+                        fillers.push((position, filler_rows, disposition));
+                    }
+                }
+            }
+
+            if offset_ranges(&changed_rows) != offset_ranges(&shown.changed_rows) {
+                editor.clear_row_highlights::<DiffRowHighlight>();
+                for range in &changed_rows {
+                    editor.highlight_rows::<DiffRowHighlight>(range.clone(), row_color, false, cx);
+                }
+                shown.changed_rows = changed_rows;
+            }
+            if offset_ranges(&changed_words) != offset_ranges(&shown.changed_words) {
+                editor.highlight_text::<DiffWordHighlight>(
+                    changed_words.clone(),
+                    HighlightStyle {
+                        background_color: Some(word_color),
+                        ..Default::default()
+                    },
+                    cx,
+                );
+                shown.changed_words = changed_words;
+            }
+
+            // Keep the filler blocks that are still at the same place with the same height.
+            let mut previous_blocks = std::mem::take(&mut shown.filler_blocks);
+            let mut new_fillers = Vec::new();
+            for (position, height, disposition) in fillers {
+                let offset = position.to_offset(&multi_buffer);
+                let previous_ix = previous_blocks.iter().position(|block| {
+                    block.height == height
+                        && block.disposition == disposition
+                        && block.position.to_offset(&multi_buffer) == offset
+                });
+                match previous_ix {
+                    Some(ix) => shown.filler_blocks.push(previous_blocks.swap_remove(ix)),
+                    None => new_fillers.push((position, height, disposition)),
+                }
+            }
+
+            let stale_blocks = previous_blocks
+                .into_iter()
+                .map(|block| block.id)
+                .collect::<HashSet<_>>();
+            if !stale_blocks.is_empty() {
+                editor.remove_blocks(stale_blocks, None, cx);
+            }
+            if !new_fillers.is_empty() {
+                let block_ids = editor.insert_blocks(
+                    new_fillers
+                        .iter()
+                        .map(|&(position, height, disposition)| BlockProperties {
+                            position,
+                            height,
+                            style: BlockStyle::Flex,
+                            disposition,
+                            priority: 0,
+                            render: render_filler(height, filler_color),
+                        }),
+                    None,
+                    cx,
+                );
+                shown
+                    .filler_blocks
+                    .extend(new_fillers.into_iter().zip(block_ids).map(
+                        |((position, height, disposition), id)| FillerBlock {
+                            id,
+                            position,
+                            height,
+                            disposition,
+                        },
+                    ));
+            }
+        });
+        cx.notify();
+    }
+
+    fn render_title(&self, title: SharedString, cx: &ViewContext<Self>) -> impl IntoElement {
+        h_flex()
+            .flex_1()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(title).size(LabelSize::Small).color(Color::Muted))
+    }
+}
+
+/// Returns the buffer row at the top of an editor, and the offset of its scroll position
+/// from it.
+fn scroll_top(
+    scroll_anchor: &ScrollAnchor,
+    buffer: &MultiBufferSnapshot,
+) -> (u32, gpui::Point<f32>) {
+    if scroll_anchor.anchor == Anchor::min() {
+        (0, point(scroll_anchor.offset.x, 0.))
+    } else {
+        (scroll_anchor.top_row(buffer), scroll_anchor.offset)
+    }
+}
+
+/// Maps a row of one side of the diff to the row of the other side shown next to it, along
+/// with an offset in rows from it for rows facing the filler blocks of the other side.
+fn corresponding_row(hunks: &[DiffHunk], side: Side, row: u32) -> (u32, f32) {
+    let mut other_row_delta = 0i64;
+    for hunk in hunks {
+        let (rows, other_rows) = match side {
+            Side::Old => (&hunk.old_rows, &hunk.new_rows),
+            Side::New => (&hunk.new_rows, &hunk.old_rows),
+        };
+        if row < rows.start {
+            break;
+        }
+        if row < rows.end {
+            let row_in_hunk = row - rows.start;
+            return if row_in_hunk < other_rows.len() as u32 {
+                (other_rows.start + row_in_hunk, 0.)
+            } else {
+                // The other side has filler rows above the end of its hunk here.
+                (other_rows.end, -((rows.end - row) as f32))
+            };
+        }
+        other_row_delta = other_rows.end as i64 - rows.end as i64;
+    }
+    ((row as i64 + other_row_delta).max(0) as u32, 0.)
+}
+
+fn faded(mut color: Hsla, fade_out: f32) -> Hsla {
+    color.fade_out(fade_out);
+    color
+}
+
+fn render_filler(rows: u32, color: Hsla) -> RenderBlock {
+    Box::new(move |cx| {
+        div()
+            .id(cx.block_id)
+            .w_full()
+            .h(cx.line_height * rows as f32)
+            .bg(color)
+            .into_any_element()
+    })
+}
+
+impl Render for DiffView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let border_color = cx.theme().colors().border;
+        v_flex()
+            .key_context("DiffView")
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .child(self.render_title(self.old_title.clone(), cx))
+                    .child(div().w_px().h_full().bg(border_color))
+                    .child(self.render_title(self.new_title.clone(), cx)),
+            )
+            .child(
+                h_flex()
+                    .flex_1()
+                    .size_full()
+                    .child(div().flex_1().h_full().child(self.old_editor.clone()))
+                    .child(div().w_px().h_full().bg(border_color))
+                    .child(div().flex_1().h_full().child(self.new_editor.clone())),
+            )
+    }
+}
+
+impl FocusableView for DiffView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.new_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<ItemEvent> for DiffView {}
+
+impl Item for DiffView {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_content_text(&self, _: &WindowContext) -> Option<SharedString> {
+        Some(format!("{} ↔ {}", self.old_title, self.new_title).into())
+    }
+
+    fn tab_icon(&self, _: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::Diff))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
+        self.old_editor.update(cx, Item::deactivated);
+        self.new_editor.update(cx, Item::deactivated);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CompareWithClipboard, CompareWithRevision};
+    use editor::{display_map::ToDisplayPoint, FoldPlaceholder};
+    use gpui::{ClipboardItem, TestAppContext, VisualTestContext};
+    use language::Capability;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+
+    #[gpui::test]
+    async fn test_filler_blocks_align_unchanged_lines(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            json!({
+                "old.txt": "one\ntwo\nthree\nfour\nfive\n",
+                "new.txt": "one\nthree\nfour score\nfive\nsix\nseven\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let (diff_view, cx) = open_diff_view(&project, "/root/old.txt", "/root/new.txt", cx).await;
+
+        // "one", "three", "four" and "five" are on the same display rows on both sides.
+        let aligned_rows = [(0, 0), (2, 1), (3, 2), (4, 3)];
+        assert_aligned(&diff_view, &aligned_rows, cx);
+        let (old_blocks, new_blocks) = filler_block_ids(&diff_view, cx);
+        assert_eq!((old_blocks.len(), new_blocks.len()), (1, 1));
+
+        // Editing a line that already differs leaves the filler blocks in place.
+        let new_buffer = diff_view.update(cx, |diff_view, _| diff_view.new_buffer.clone());
+        new_buffer.update(cx, |buffer, cx| {
+            buffer.edit([(Point::new(2, 10)..Point::new(2, 10), "s")], None, cx)
+        });
+        cx.executor().advance_clock(DIFF_DEBOUNCE);
+        cx.run_until_parked();
+        assert_aligned(&diff_view, &aligned_rows, cx);
+        assert_eq!(
+            filler_block_ids(&diff_view, cx),
+            (old_blocks.clone(), new_blocks)
+        );
+
+        // Inserting a line only adds a filler block on the other side.
+        new_buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "zero\n")], None, cx));
+        cx.executor().advance_clock(DIFF_DEBOUNCE);
+        cx.run_until_parked();
+        assert_aligned(&diff_view, &[(0, 1), (2, 2), (3, 3), (4, 4)], cx);
+        let (new_old_blocks, _) = filler_block_ids(&diff_view, cx);
+        assert_eq!(new_old_blocks.len(), 2);
+        assert!(new_old_blocks.contains(&old_blocks[0]));
+    }
+
+    #[gpui::test]
+    async fn test_scroll_sync(cx: &mut TestAppContext) {
+        init_test(cx);
+        let old_text = (0..20)
+            .map(|row| format!("line {row}\n"))
+            .collect::<String>();
+        let new_text = (0..20)
+            .filter(|row| !(2..5).contains(row))
+            .map(|row| format!("line {row}\n"))
+            .collect::<String>();
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "old.txt": old_text, "new.txt": new_text }))
+            .await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let (diff_view, cx) = open_diff_view(&project, "/root/old.txt", "/root/new.txt", cx).await;
+        let (old_editor, new_editor) = diff_view.update(cx, |diff_view, _| {
+            (diff_view.old_editor.clone(), diff_view.new_editor.clone())
+        });
+
+        // Rows facing the deleted lines show the filler block on the other side.
+        old_editor.update(cx, |editor, cx| {
+            editor.set_scroll_position(point(0., 3.), cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            new_editor.update(cx, |editor, cx| editor.scroll_position(cx)),
+            point(0., 3.)
+        );
+
+        new_editor.update(cx, |editor, cx| {
+            editor.set_scroll_position(point(0., 6.), cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(top_row(&old_editor, cx), 6);
+        assert_eq!(
+            old_editor.update(cx, |editor, cx| editor.scroll_position(cx)),
+            point(0., 6.)
+        );
+
+        // With lines folded on one side, the same buffer rows are shown at the top of both.
+        old_editor.update(cx, |editor, cx| {
+            editor.fold_ranges(
+                [(Point::new(8, 0)..Point::new(12, 0), FoldPlaceholder::test())],
+                false,
+                cx,
+            );
+            editor.set_scroll_position(point(0., 11.), cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(top_row(&old_editor, cx), 15);
+        assert_eq!(top_row(&new_editor, cx), 12);
+    }
+
+    #[gpui::test]
+    async fn test_compare_with_clipboard_and_revision(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            json!({
+                ".git": {},
+                "file.txt": "one\ntwo\nthree\n",
+            }),
+        )
+        .await;
+        fs.set_head_for_repo(
+            Path::new("/root/.git"),
+            &[(Path::new("file.txt"), "one\nthree\n".into())],
+        );
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let open_file = |cx: &mut VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                workspace.open_abs_path("/root/file.txt".into(), true, cx)
+            })
+        };
+
+        open_file(cx).await.unwrap();
+        cx.write_to_clipboard(ClipboardItem::new_string("one\n2\nthree\n".into()));
+        cx.dispatch_action(CompareWithClipboard);
+        cx.run_until_parked();
+        let diff_view = active_diff_view(&workspace, cx);
+        diff_view.update(cx, |diff_view, cx| {
+            assert_eq!(diff_view.old_title.as_ref(), "Clipboard");
+            assert_eq!(diff_view.new_title.as_ref(), "file.txt");
+            let old_buffer = diff_view.old_buffer.read(cx);
+            assert_eq!(old_buffer.text(), "one\n2\nthree\n");
+            assert_eq!(old_buffer.capability(), Capability::ReadOnly);
+            assert_eq!(diff_view.new_buffer.read(cx).text(), "one\ntwo\nthree\n");
+        });
+
+        open_file(cx).await.unwrap();
+        cx.dispatch_action(CompareWithRevision {
+            revision: Some("HEAD".into()),
+        });
+        cx.run_until_parked();
+        let diff_view = active_diff_view(&workspace, cx);
+        diff_view.update(cx, |diff_view, cx| {
+            assert_eq!(diff_view.old_title.as_ref(), "file.txt (HEAD)");
+            assert_eq!(diff_view.old_buffer.read(cx).text(), "one\nthree\n");
+            assert_eq!(diff_view.hunks.len(), 1);
+            assert_eq!(diff_view.hunks[0].new_rows, 1..2);
+        });
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            workspace::init_settings(cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+    }
+
+    async fn open_diff_view<'a>(
+        project: &Model<Project>,
+        old_path: &str,
+        new_path: &str,
+        cx: &'a mut TestAppContext,
+    ) -> (View<DiffView>, &'a mut VisualTestContext) {
+        let old_buffer = project
+            .update(cx, |project, cx| project.open_local_buffer(old_path, cx))
+            .await
+            .unwrap();
+        let new_buffer = project
+            .update(cx, |project, cx| project.open_local_buffer(new_path, cx))
+            .await
+            .unwrap();
+        let cx = cx.add_empty_window();
+        let diff_view = cx.new_view(|cx| {
+            DiffView::new(
+                old_buffer,
+                "old".into(),
+                new_buffer,
+                "new".into(),
+                project.clone(),
+                cx,
+            )
+        });
+        cx.run_until_parked();
+        (diff_view, cx)
+    }
+
+    fn active_diff_view(workspace: &View<Workspace>, cx: &mut VisualTestContext) -> View<DiffView> {
+        workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_item_as::<DiffView>(cx)
+                .expect("no diff view was opened")
+        })
+    }
+
+    #[track_caller]
+    fn assert_aligned(diff_view: &View<DiffView>, rows: &[(u32, u32)], cx: &mut VisualTestContext) {
+        let (old_editor, new_editor) = diff_view.update(cx, |diff_view, _| {
+            (diff_view.old_editor.clone(), diff_view.new_editor.clone())
+        });
+        for &(old_row, new_row) in rows {
+            assert_eq!(
+                display_row(&old_editor, old_row, cx),
+                display_row(&new_editor, new_row, cx),
+                "old row {old_row} and new row {new_row} are not aligned"
+            );
+        }
+    }
+
+    fn display_row(editor: &View<Editor>, row: u32, cx: &mut VisualTestContext) -> u32 {
+        editor.update(cx, |editor, cx| {
+            let snapshot = editor.snapshot(cx);
+            Point::new(row, 0).to_display_point(&snapshot).row().0
+        })
+    }
+
+    fn top_row(editor: &View<Editor>, cx: &mut VisualTestContext) -> u32 {
+        editor.update(cx, |editor, cx| {
+            let buffer = editor.buffer().read(cx).snapshot(cx);
+            scroll_top(&editor.scroll_manager.anchor(), &buffer).0
+        })
+    }
+
+    fn filler_block_ids(
+        diff_view: &View<DiffView>,
+        cx: &mut VisualTestContext,
+    ) -> (Vec<CustomBlockId>, Vec<CustomBlockId>) {
+        diff_view.update(cx, |diff_view, _| {
+            let ids =
+                |shown: &ShownSide| shown.filler_blocks.iter().map(|block| block.id).collect();
+            (ids(&diff_view.old_shown), ids(&diff_view.new_shown))
+        })
+    }
+}
@@ -0,0 +1,142 @@
+use std::ops::Range;
+
+use similar::{DiffTag, TextDiff};
+
+/// Hunks whose sides are longer than this many bytes are not diffed word by word.
+const MAX_WORD_DIFF_LEN: usize = 10 * 1024;
+
+/// A range of lines that differs between the old and the new text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffHunk {
+    /// The rows of the hunk in the old text. Empty if lines were only inserted.
+    pub old_rows: Range<u32>,
+    /// The rows of the hunk in the new text. Empty if lines were only deleted.
+    pub new_rows: Range<u32>,
+    /// The byte ranges of the words of the old text that were changed.
+    pub old_words: Vec<Range<usize>>,
+    /// The byte ranges of the words of the new text that were changed.
+    pub new_words: Vec<Range<usize>>,
+}
+
+/// Compares two texts line by line, and the lines of hunks that replaced others word by word.
+pub fn diff_lines(old_text: &str, new_text: &str) -> Vec<DiffHunk> {
+    let diff = TextDiff::from_lines(old_text, new_text);
+    let old_offsets = token_offsets(diff.old_slices());
+    let new_offsets = token_offsets(diff.new_slices());
+
+    let mut hunks = Vec::new();
+    for op in diff.ops() {
+        let (tag, old_rows, new_rows) = op.as_tag_tuple();
+        if tag == DiffTag::Equal {
+            continue;
+        }
+
+        let old_range = old_offsets[old_rows.start]..old_offsets[old_rows.end];
+        let new_range = new_offsets[new_rows.start]..new_offsets[new_rows.end];
+        let (old_words, new_words) = if tag == DiffTag::Replace
+            && old_range.len() <= MAX_WORD_DIFF_LEN
+            && new_range.len() <= MAX_WORD_DIFF_LEN
+        {
+            diff_words(&old_text[old_range.clone()], &new_text[new_range.clone()])
+        } else {
+            Default::default()
+        };
+
+        hunks.push(DiffHunk {
+            old_rows: old_rows.start as u32..old_rows.end as u32,
+            new_rows: new_rows.start as u32..new_rows.end as u32,
+            old_words: offset_ranges(old_words, old_range.start),
+            new_words: offset_ranges(new_words, new_range.start),
+        });
+    }
+    hunks
+}
+
+/// Returns the byte ranges of the changed words of both texts, without surrounding whitespace.
+fn diff_words(old_text: &str, new_text: &str) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    let diff = TextDiff::from_words(old_text, new_text);
+    let old_offsets = token_offsets(diff.old_slices());
+    let new_offsets = token_offsets(diff.new_slices());
+
+    let mut old_words = Vec::new();
+    let mut new_words = Vec::new();
+    for op in diff.ops() {
+        let (tag, old_tokens, new_tokens) = op.as_tag_tuple();
+        if tag == DiffTag::Equal {
+            continue;
+        }
+        let old_range = old_offsets[old_tokens.start]..old_offsets[old_tokens.end];
+        old_words.extend(trim_whitespace(old_text, old_range));
+        let new_range = new_offsets[new_tokens.start]..new_offsets[new_tokens.end];
+        new_words.extend(trim_whitespace(new_text, new_range));
+    }
+    (old_words, new_words)
+}
+
+/// Shrinks the range to exclude the whitespace at either end of it, if anything remains.
+fn trim_whitespace(text: &str, range: Range<usize>) -> Option<Range<usize>> {
+    let slice = &text[range.clone()];
+    let trimmed = slice.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    let start = range.start + slice.len() - slice.trim_start().len();
+    Some(start..start + trimmed.len())
+}
+
+/// Returns the offset at which each token starts, followed by the length of all tokens.
+fn token_offsets(tokens: &[&str]) -> Vec<usize> {
+    let mut offsets = Vec::with_capacity(tokens.len() + 1);
+    let mut offset = 0;
+    offsets.push(offset);
+    for token in tokens {
+        offset += token.len();
+        offsets.push(offset);
+    }
+    offsets
+}
+
+fn offset_ranges(ranges: Vec<Range<usize>>, offset: usize) -> Vec<Range<usize>> {
+    ranges
+        .into_iter()
+        .map(|range| range.start + offset..range.end + offset)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        let old_text = "one\ntwo\nthree\nfour\nfive\n";
+        let new_text = "one\nthree\nfour score\nfive\nsix\nseven\n";
+        let hunks = diff_lines(old_text, new_text);
+
+        assert_eq!(
+            hunks
+                .iter()
+                .map(|hunk| (hunk.old_rows.clone(), hunk.new_rows.clone()))
+                .collect::<Vec<_>>(),
+            vec![(1..2, 1..1), (3..4, 2..3), (5..5, 4..6)]
+        );
+
+        assert!(hunks[0].old_words.is_empty());
+        assert!(hunks[1].old_words.is_empty());
+        assert_eq!(
+            hunks[1]
+                .new_words
+                .iter()
+                .map(|range| &new_text[range.clone()])
+                .collect::<Vec<_>>(),
+            vec!["score"]
+        );
+    }
+
+    #[test]
+    fn test_diff_words() {
+        let (old_words, new_words) = diff_words("let a = 1;", "let b = 1;");
+        assert_eq!(old_words, vec![4..5]);
+        assert_eq!(new_words, vec![4..5]);
+    }
+}
@@ -0,0 +1,94 @@
+use editor::{Editor, EditorEvent};
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model, Subscription, View,
+    WeakView,
+};
+use language::Buffer;
+use ui::prelude::*;
+use workspace::{ModalView, Workspace};
+
+use crate::open_revision_diff;
+
+const DEFAULT_REVISION: &str = "HEAD";
+
+/// Asks for the git revision to compare the active file with.
+pub struct RevisionPrompt {
+    revision_editor: View<Editor>,
+    workspace: WeakView<Workspace>,
+    buffer: Model<Buffer>,
+    _subscription: Subscription,
+}
+
+impl ModalView for RevisionPrompt {}
+
+impl EventEmitter<DismissEvent> for RevisionPrompt {}
+
+impl FocusableView for RevisionPrompt {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.revision_editor.focus_handle(cx)
+    }
+}
+
+impl RevisionPrompt {
+    pub fn new(
+        workspace: WeakView<Workspace>,
+        buffer: Model<Buffer>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let revision_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text(DEFAULT_REVISION, cx);
+            editor
+        });
+        let subscription = cx.subscribe(&revision_editor, |_, _, event, cx| {
+            if let EditorEvent::Blurred = event {
+                cx.emit(DismissEvent);
+            }
+        });
+        Self {
+            revision_editor,
+            workspace,
+            buffer,
+            _subscription: subscription,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let revision = self.revision_editor.read(cx).text(cx);
+        let revision = match revision.trim() {
+            "" => DEFAULT_REVISION.to_string(),
+            revision => revision.to_string(),
+        };
+        let buffer = self.buffer.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                open_revision_diff(workspace, buffer, revision, cx)
+            })
+            .ok();
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for RevisionPrompt {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .elevation_2(cx)
+            .key_context("RevisionPrompt")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .w_96()
+            .child(div().px_2().py_1().child(self.revision_editor.clone()))
+            .child(div().h_px().w_full().bg(cx.theme().colors().border_variant))
+            .child(
+                h_flex().px_2().py_1().child(
+                    Label::new("Compare with a commit, branch or tag")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+            )
+    }
+}
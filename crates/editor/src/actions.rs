@@ -188,6 +188,9 @@ impl_actions!(
 gpui::actions!(
     editor,
     [
+        AcceptConflictBoth,
+        AcceptConflictOurs,
+        AcceptConflictTheirs,
         AcceptInlineCompletion,
         AcceptPartialCopilotSuggestion,
         AcceptPartialInlineCompletion,
@@ -238,6 +241,7 @@ gpui::actions!(
         ToggleFoldRecursive,
         Format,
         FormatSelections,
+        GoToConflict,
         GoToDeclaration,
         GoToDeclarationSplit,
         GoToDefinition,
@@ -246,6 +250,7 @@ gpui::actions!(
         GoToHunk,
         GoToImplementation,
         GoToImplementationSplit,
+        GoToPrevConflict,
         GoToPrevDiagnostic,
         GoToPrevHunk,
         GoToTypeDefinition,
@@ -304,8 +309,10 @@ gpui::actions!(
         ScrollCursorTop,
         SelectAll,
         SelectAllMatches,
+        SelectClass,
         SelectDown,
         SelectEnclosingSymbol,
+        SelectFunction,
         SelectLargerSyntaxNode,
         SelectLeft,
         SelectLine,
@@ -329,6 +336,8 @@ gpui::actions!(
         SortLinesCaseInsensitive,
         SortLinesCaseSensitive,
         SplitSelectionIntoLines,
+        StageFile,
+        StageSelectedHunks,
         SwitchSourceHeader,
         Tab,
         TabPrev,
@@ -352,6 +361,8 @@ gpui::actions!(
         UnfoldRecursive,
         UniqueLinesCaseInsensitive,
         UniqueLinesCaseSensitive,
+        UnstageFile,
+        UnstageSelectedHunks,
     ]
 );
 
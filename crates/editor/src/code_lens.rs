@@ -0,0 +1,270 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use collections::{HashMap, HashSet};
+use futures::future::join_all;
+use gpui::{Model, WeakView};
+use language::{Buffer, Point, ToPoint as _};
+use multi_buffer::ExcerptId;
+use project::CodeLens;
+use settings::Settings;
+use ui::{prelude::*, ButtonStyle, ViewContext};
+use util::ResultExt;
+
+use crate::{
+    display_map::CustomBlockId, BlockDisposition, BlockProperties, BlockStyle, Editor, EditorMode,
+    EditorSettings, RenderBlock,
+};
+
+/// How long to wait after the last edit before requesting new code lenses.
+const CODE_LENS_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// A block displaying the code lenses of a buffer line in one of the excerpts showing it.
+pub(crate) struct CodeLensBlock {
+    pub(crate) id: CustomBlockId,
+    pub(crate) excerpt_id: ExcerptId,
+    pub(crate) position: text::Anchor,
+    pub(crate) lenses: Vec<CodeLens>,
+}
+
+impl Editor {
+    /// Requests the code lenses of the editor's buffers, and replaces the blocks
+    /// displaying them as soon as they arrive.
+    pub(crate) fn refresh_code_lenses(&mut self, debounce: bool, cx: &mut ViewContext<Self>) {
+        let enabled = self.mode == EditorMode::Full && EditorSettings::get_global(cx).code_lens;
+        let provider = self.semantics_provider.clone().filter(|_| enabled);
+        let Some(provider) = provider else {
+            self.code_lens_task = None;
+            let blocks = self.code_lens_blocks.drain().flat_map(|(_, blocks)| blocks);
+            let block_ids = blocks.map(|block| block.id).collect::<HashSet<_>>();
+            if !block_ids.is_empty() {
+                self.remove_blocks(block_ids, None, cx);
+            }
+            return;
+        };
+        let buffers = self.buffer.read(cx).all_buffers();
+
+        self.code_lens_task = Some(cx.spawn(|editor, mut cx| async move {
+            if debounce {
+                cx.background_executor().timer(CODE_LENS_DEBOUNCE).await;
+            }
+            let Some(tasks) = editor
+                .update(&mut cx, |_, cx| {
+                    buffers
+                        .into_iter()
+                        .filter_map(|buffer| {
+                            let task = provider.code_lens(&buffer, cx)?;
+                            Some(async move { (buffer, task.await) })
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .ok()
+            else {
+                return;
+            };
+            let results = join_all(tasks).await;
+            editor
+                .update(&mut cx, |editor, cx| {
+                    let mut refreshed_buffers = HashSet::default();
+                    for (buffer, lenses) in results {
+                        refreshed_buffers.insert(buffer.read(cx).remote_id());
+                        if let Some(lenses) = lenses.log_err() {
+                            editor.show_code_lenses(&buffer, lenses, cx);
+                        }
+                    }
+                    let mut stale_block_ids = HashSet::default();
+                    editor.code_lens_blocks.retain(|buffer_id, blocks| {
+                        let refreshed = refreshed_buffers.contains(buffer_id);
+                        if !refreshed {
+                            stale_block_ids.extend(blocks.drain(..).map(|block| block.id));
+                        }
+                        refreshed
+                    });
+                    if !stale_block_ids.is_empty() {
+                        editor.remove_blocks(stale_block_ids, None, cx);
+                    }
+                })
+                .ok();
+        }));
+    }
+
+    /// Updates the code lens blocks of the given buffer to display the given lenses, one block above
+    /// each line that has lenses with a command, in every excerpt showing that line.
+    /// Blocks of the lines whose lenses did not change are kept as they are.
+    fn show_code_lenses(
+        &mut self,
+        buffer: &Model<Buffer>,
+        lenses: Vec<CodeLens>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let buffer_id = buffer.read(cx).remote_id();
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let mut lenses_by_row = BTreeMap::<u32, Vec<CodeLens>>::new();
+        for lens in lenses {
+            if lens.lsp_lens.command.is_some() {
+                let row = lens.range.start.to_point(&buffer_snapshot).row;
+                lenses_by_row.entry(row).or_default().push(lens);
+            }
+        }
+
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let excerpts = multi_buffer.excerpts_for_buffer(buffer, cx);
+        let editor = cx.view().downgrade();
+        let mut old_blocks = self.code_lens_blocks.remove(&buffer_id).unwrap_or_default();
+        let mut blocks = Vec::new();
+        let mut new_blocks = Vec::new();
+        let mut new_block_properties = Vec::new();
+        let mut new_renderers = HashMap::default();
+        for (row, lenses) in lenses_by_row {
+            let indent = buffer_snapshot.indent_size_for_line(row);
+            let position = buffer_snapshot.anchor_before(Point::new(row, indent.len));
+            for (excerpt_id, excerpt_range) in &excerpts {
+                let context = &excerpt_range.context;
+                if context.start.cmp(&position, &buffer_snapshot).is_gt()
+                    || context.end.cmp(&position, &buffer_snapshot).is_lt()
+                {
+                    continue;
+                }
+
+                let old_block_ix = old_blocks.iter().position(|block| {
+                    block.excerpt_id == *excerpt_id
+                        && block.position.to_point(&buffer_snapshot).row == row
+                });
+                if let Some(old_block_ix) = old_block_ix {
+                    let mut block = old_blocks.swap_remove(old_block_ix);
+                    if !same_commands(&block.lenses, &lenses) {
+                        let render = render_code_lenses(editor.clone(), buffer.clone(), &lenses);
+                        new_renderers.insert(block.id, render);
+                        block.lenses = lenses.clone();
+                    }
+                    blocks.push(block);
+                    continue;
+                }
+
+                let Some(multi_buffer_position) = snapshot.anchor_in_excerpt(*excerpt_id, position)
+                else {
+                    continue;
+                };
+                new_block_properties.push(BlockProperties {
+                    position: multi_buffer_position,
+                    height: 1,
+                    style: BlockStyle::Flex,
+                    disposition: BlockDisposition::Above,
+                    priority: 0,
+                    render: render_code_lenses(editor.clone(), buffer.clone(), &lenses),
+                });
+                new_blocks.push((*excerpt_id, position, lenses.clone()));
+            }
+        }
+
+        if !old_blocks.is_empty() {
+            let old_block_ids = old_blocks.into_iter().map(|block| block.id).collect();
+            self.remove_blocks(old_block_ids, None, cx);
+        }
+        if !new_renderers.is_empty() {
+            self.replace_blocks(new_renderers, None, cx);
+        }
+        if !new_blocks.is_empty() {
+            let block_ids = self.insert_blocks(new_block_properties, None, cx);
+            blocks.extend(block_ids.into_iter().zip(new_blocks).map(
+                |(id, (excerpt_id, position, lenses))| CodeLensBlock {
+                    id,
+                    excerpt_id,
+                    position,
+                    lenses,
+                },
+            ));
+        }
+        if !blocks.is_empty() {
+            self.code_lens_blocks.insert(buffer_id, blocks);
+        }
+    }
+
+    /// Runs the command of the given code lens on the language server that provided it,
+    /// the same way as the command of a code action.
+    /// Lenses with commands that only the client could run are ignored.
+    fn run_code_lens(
+        &mut self,
+        buffer: Model<Buffer>,
+        lens: &CodeLens,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let (Some(project), Some(workspace)) = (self.project.clone(), self.workspace()) else {
+            return;
+        };
+        let Some(action) = lens.code_action() else {
+            return;
+        };
+        let title = action.lsp_action.title.clone();
+        let apply_code_action = project.update(cx, |project, cx| {
+            project.apply_code_action(buffer, action, true, cx)
+        });
+        let workspace = workspace.downgrade();
+        cx.spawn(|editor, cx| async move {
+            let project_transaction = apply_code_action.await?;
+            Self::open_project_transaction(&editor, workspace, project_transaction, title, cx).await
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+/// Whether the lenses display and run the same commands.
+fn same_commands(old_lenses: &[CodeLens], new_lenses: &[CodeLens]) -> bool {
+    old_lenses
+        .iter()
+        .map(|lens| (&lens.lsp_lens.command, lens.executable))
+        .eq(new_lenses
+            .iter()
+            .map(|lens| (&lens.lsp_lens.command, lens.executable)))
+}
+
+fn render_code_lenses(
+    editor: WeakView<Editor>,
+    buffer: Model<Buffer>,
+    lenses: &[CodeLens],
+) -> RenderBlock {
+    let lenses = lenses.to_vec();
+    Box::new(move |cx| {
+        let mut row = h_flex()
+            .id(cx.block_id)
+            .h(cx.line_height)
+            .w_full()
+            .pl(cx.anchor_x)
+            .gap_1();
+        let lenses_with_commands = lenses
+            .iter()
+            .filter_map(|lens| Some((lens, lens.lsp_lens.command.as_ref()?)));
+        for (ix, (lens, command)) in lenses_with_commands.enumerate() {
+            if ix > 0 {
+                row = row.child(Label::new("|").size(LabelSize::Small).color(Color::Muted));
+            }
+            if !lens.executable {
+                row = row.child(
+                    Label::new(command.title.clone())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                );
+                continue;
+            }
+            row = row.child(
+                Button::new(("code-lens", ix), command.title.clone())
+                    .style(ButtonStyle::Transparent)
+                    .label_size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .on_click({
+                        let editor = editor.clone();
+                        let buffer = buffer.clone();
+                        let lens = lens.clone();
+                        move |_, cx| {
+                            editor
+                                .update(cx, |editor, cx| {
+                                    editor.run_code_lens(buffer.clone(), &lens, cx)
+                                })
+                                .ok();
+                        }
+                    }),
+            );
+        }
+        row.into_any_element()
+    })
+}
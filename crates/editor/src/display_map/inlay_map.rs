@@ -1,7 +1,7 @@
 use crate::{HighlightStyles, InlayId};
 use collections::{BTreeMap, BTreeSet};
 use gpui::HighlightStyle;
-use language::{Chunk, Edit, Point, TextSummary};
+use language::{Chunk, Edit, HighlightId, Point, TextSummary};
 use multi_buffer::{
     Anchor, MultiBufferChunks, MultiBufferRow, MultiBufferRows, MultiBufferSnapshot, ToOffset,
 };
@@ -240,6 +240,8 @@ pub struct InlayChunks<'a> {
     highlight_styles: HighlightStyles,
     highlight_endpoints: Peekable<vec::IntoIter<HighlightEndpoint>>,
     active_highlights: BTreeMap<Option<TypeId>, HighlightStyle>,
+    semantic_token_endpoints: Peekable<vec::IntoIter<(InlayOffset, Option<HighlightId>)>>,
+    active_semantic_token: Option<HighlightId>,
     highlights: Highlights<'a>,
     snapshot: &'a InlaySnapshot,
 }
@@ -284,6 +286,15 @@ impl<'a> Iterator for InlayChunks<'a> {
                 break;
             }
         }
+        while let Some((offset, highlight_id)) = self.semantic_token_endpoints.peek().copied() {
+            if offset <= self.output_offset {
+                self.active_semantic_token = highlight_id;
+                self.semantic_token_endpoints.next();
+            } else {
+                next_highlight_endpoint = cmp::min(next_highlight_endpoint, offset);
+                break;
+            }
+        }
 
         let chunk = match self.transforms.item()? {
             Transform::Isomorphic(_) => {
@@ -308,6 +319,9 @@ impl<'a> Iterator for InlayChunks<'a> {
                     text: prefix,
                     ..chunk.clone()
                 };
+                if let Some(highlight_id) = self.active_semantic_token {
+                    prefix.syntax_highlight_id = Some(highlight_id);
+                }
                 if !self.active_highlights.is_empty() {
                     let mut highlight_style = HighlightStyle::default();
                     for active_highlight in self.active_highlights.values() {
@@ -1058,6 +1072,16 @@ impl InlaySnapshot {
         }
         highlight_endpoints.sort();
         let buffer_range = self.to_buffer_offset(range.start)..self.to_buffer_offset(range.end);
+        let mut semantic_token_endpoints = Vec::new();
+        if language_aware {
+            if let Some(semantic_tokens) = highlights.semantic_tokens {
+                self.apply_semantic_tokens(
+                    &buffer_range,
+                    semantic_tokens,
+                    &mut semantic_token_endpoints,
+                );
+            }
+        }
         let buffer_chunks = self.buffer.chunks(buffer_range, language_aware);
 
         InlayChunks {
@@ -1071,11 +1095,40 @@ impl InlaySnapshot {
             highlight_styles: highlights.styles,
             highlight_endpoints: highlight_endpoints.into_iter().peekable(),
             active_highlights: Default::default(),
+            semantic_token_endpoints: semantic_token_endpoints.into_iter().peekable(),
+            active_semantic_token: None,
             highlights,
             snapshot: self,
         }
     }
 
+    /// Semantic tokens do not overlap, so instead of tracking them as highlights,
+    /// each endpoint replaces the highlight that is active from its offset on.
+    fn apply_semantic_tokens(
+        &self,
+        buffer_range: &Range<usize>,
+        semantic_tokens: &[(Range<Anchor>, HighlightId)],
+        endpoints: &mut Vec<(InlayOffset, Option<HighlightId>)>,
+    ) {
+        let range_start = self.buffer.anchor_after(buffer_range.start);
+        let range_end = self.buffer.anchor_before(buffer_range.end);
+        let start_ix = semantic_tokens
+            .partition_point(|(range, _)| range.end.cmp(&range_start, &self.buffer).is_le());
+        for (range, highlight_id) in &semantic_tokens[start_ix..] {
+            if range.start.cmp(&range_end, &self.buffer).is_ge() {
+                break;
+            }
+            endpoints.push((
+                self.to_inlay_offset(range.start.to_offset(&self.buffer)),
+                Some(*highlight_id),
+            ));
+            endpoints.push((
+                self.to_inlay_offset(range.end.to_offset(&self.buffer)),
+                None,
+            ));
+        }
+    }
+
     fn apply_text_highlights(
         &self,
         cursor: &mut Cursor<'_, Transform, (InlayOffset, usize)>,
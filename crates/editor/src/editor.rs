@@ -16,6 +16,7 @@ pub mod actions;
 mod blame_entry_tooltip;
 mod blink_manager;
 mod clangd_ext;
+mod code_lens;
 mod debounced_delay;
 pub mod display_map;
 mod editor_settings;
@@ -39,11 +40,13 @@ mod proposed_changes_editor;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod semantic_tokens;
 pub mod tasks;
 
 #[cfg(test)]
 mod editor_tests;
 mod signature_help;
+mod snippet_variables;
 #[cfg(any(test, feature = "test-support"))]
 pub mod test;
 
@@ -69,7 +72,10 @@ pub use element::{
 };
 use futures::{future, FutureExt};
 use fuzzy::{StringMatch, StringMatchCandidate};
-use git::blame::GitBlame;
+use git::{
+    blame::GitBlame,
+    conflicts::{Conflict, ConflictBlock},
+};
 use gpui::{
     div, impl_actions, point, prelude::*, px, relative, size, uniform_list, Action, AnyElement,
     AppContext, AsyncWindowContext, AvailableSpace, BackgroundExecutor, Bounds, ClipboardEntry,
@@ -93,7 +99,7 @@ use language::{
     language_settings::{self, all_language_settings, InlayHintSettings},
     markdown, point_from_lsp, AutoindentMode, BracketPair, Buffer, Capability, CharKind, CodeLabel,
     CursorShape, Diagnostic, Documentation, IndentKind, IndentSize, Language, OffsetRangeExt,
-    Point, Selection, SelectionGoal, TransactionId,
+    Point, Selection, SelectionGoal, TextObject, TransactionId,
 };
 use language::{
     point_to_lsp, BufferRow, CharClassifier, LanguageServerName, Runnable, RunnableRange,
@@ -125,8 +131,8 @@ use parking_lot::{Mutex, RwLock};
 use project::{
     lsp_store::{FormatTarget, FormatTrigger},
     project_settings::{GitGutterSetting, ProjectSettings},
-    CodeAction, Completion, CompletionIntent, DocumentHighlight, InlayHint, Item, Location,
-    LocationLink, Project, ProjectPath, ProjectTransaction, TaskSourceKind,
+    CodeAction, CodeLens, Completion, CompletionIntent, DocumentHighlight, InlayHint, Item,
+    Location, LocationLink, Project, ProjectPath, ProjectTransaction, TaskSourceKind,
 };
 use rand::prelude::*;
 use rpc::{proto::*, ErrorExt};
@@ -135,7 +141,7 @@ use selections_collection::{resolve_multiple, MutableSelectionsCollection, Selec
 use serde::{Deserialize, Serialize};
 use settings::{update_settings_file, Settings, SettingsLocation, SettingsStore};
 use smallvec::SmallVec;
-use snippet::Snippet;
+use snippet::{Snippet, Transform};
 use std::{
     any::TypeId,
     borrow::Cow,
@@ -586,6 +592,12 @@ pub struct Editor {
     code_actions_task: Option<Task<Result<()>>>,
     document_highlights_task: Option<Task<()>>,
     linked_editing_range_task: Option<Task<Option<()>>>,
+    semantic_tokens_task: Option<Task<()>>,
+    code_lens_task: Option<Task<()>>,
+    code_lens_blocks: HashMap<BufferId, Vec<code_lens::CodeLensBlock>>,
+    conflicts_task: Option<Task<()>>,
+    conflicts: HashMap<BufferId, Vec<Conflict>>,
+    conflict_blocks: Vec<ConflictBlock>,
     linked_edit_ranges: linked_editing_ranges::LinkedEditingRanges,
     pending_rename: Option<RenameState>,
     searchable: bool,
@@ -626,6 +638,8 @@ pub struct Editor {
     show_git_blame_inline_delay_task: Option<Task<()>>,
     git_blame_inline_enabled: bool,
     serialize_dirty_buffers: bool,
+    /// The selections, folds and creases last persisted, to skip persisting them unchanged.
+    serialized_state: Option<persistence::SerializedEditorState>,
     show_selection_menu: Option<bool>,
     blame: Option<Model<GitBlame>>,
     blame_subscription: Option<Subscription>,
@@ -861,6 +875,18 @@ struct AutocloseRegion {
 struct SnippetState {
     ranges: Vec<Vec<Range<Anchor>>>,
     active_index: usize,
+    choices: Vec<Option<Vec<String>>>,
+    transforms: Vec<Vec<SnippetTransform>>,
+    /// The transaction that inserted the snippet, which the edits of its transforms are grouped with.
+    transaction_id: Option<TransactionId>,
+}
+
+/// A mirror of a snippet tabstop whose text is transformed when leaving the tabstop.
+#[derive(Debug)]
+struct SnippetTransform {
+    source: Range<Anchor>,
+    target: Range<Anchor>,
+    transform: Transform,
 }
 
 #[doc(hidden)]
@@ -990,9 +1016,63 @@ struct CompletionsMenu {
     selected_item: usize,
     scroll_handle: UniformListScrollHandle,
     selected_completion_documentation_resolve_debounce: Arc<Mutex<DebouncedDelay>>,
+    /// Whether the completions are the choices of a snippet tabstop, which the completion
+    /// provider knows nothing about.
+    from_snippet_choices: bool,
 }
 
 impl CompletionsMenu {
+    fn new_snippet_choices(
+        id: CompletionId,
+        choices: &[String],
+        selection: Range<Anchor>,
+        buffer: Model<Buffer>,
+    ) -> Self {
+        let completions = choices
+            .iter()
+            .map(|choice| Completion {
+                old_range: selection.start.text_anchor..selection.end.text_anchor,
+                new_text: choice.clone(),
+                label: CodeLabel::plain(choice.clone(), None),
+                server_id: LanguageServerId(usize::MAX),
+                documentation: None,
+                lsp_completion: Default::default(),
+                confirm: None,
+            })
+            .collect::<Vec<_>>();
+        let match_candidates = choices
+            .iter()
+            .enumerate()
+            .map(|(id, choice)| StringMatchCandidate::new(id, choice.clone()))
+            .collect();
+        let matches = choices
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, choice)| StringMatch {
+                candidate_id,
+                score: Default::default(),
+                positions: Default::default(),
+                string: choice.clone(),
+            })
+            .collect();
+
+        Self {
+            id,
+            sort_completions: false,
+            initial_position: selection.start,
+            buffer,
+            completions: Arc::new(RwLock::new(completions.into())),
+            match_candidates,
+            matches,
+            selected_item: 0,
+            scroll_handle: UniformListScrollHandle::new(),
+            selected_completion_documentation_resolve_debounce: Arc::new(Mutex::new(
+                DebouncedDelay::new(),
+            )),
+            from_snippet_choices: true,
+        }
+    }
+
     fn select_first(
         &mut self,
         provider: Option<&dyn CompletionProvider>,
@@ -1081,7 +1161,7 @@ impl CompletionsMenu {
         cx: &mut ViewContext<Editor>,
     ) {
         let settings = EditorSettings::get_global(cx);
-        if !settings.show_completion_documentation {
+        if !settings.show_completion_documentation || self.from_snippet_choices {
             return;
         }
 
@@ -1878,6 +1958,13 @@ impl Editor {
                 project_subscriptions.push(cx.subscribe(project, |editor, _, event, cx| {
                     if let project::Event::RefreshInlayHints = event {
                         editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                    } else if let project::Event::LanguageServerAdded(..) = event {
+                        editor.refresh_semantic_tokens(false, cx);
+                        editor.refresh_code_lenses(false, cx);
+                    } else if let project::Event::RefreshSemanticTokens = event {
+                        editor.refresh_semantic_tokens(false, cx);
+                    } else if let project::Event::WorktreeUpdatedGitRepositories = event {
+                        editor.refresh_conflicts(false, cx);
                     } else if let project::Event::SnippetEdit(id, snippet_edits) = event {
                         if let Some(buffer) = editor.buffer.read(cx).buffer(*id) {
                             let focus_handle = editor.focus_handle(cx);
@@ -1990,6 +2077,12 @@ impl Editor {
             code_actions_task: Default::default(),
             document_highlights_task: Default::default(),
             linked_editing_range_task: Default::default(),
+            semantic_tokens_task: Default::default(),
+            code_lens_task: Default::default(),
+            code_lens_blocks: HashMap::default(),
+            conflicts_task: Default::default(),
+            conflicts: HashMap::default(),
+            conflict_blocks: Vec::new(),
             pending_rename: Default::default(),
             searchable: true,
             cursor_shape: EditorSettings::get_global(cx)
@@ -2034,6 +2127,7 @@ impl Editor {
             serialize_dirty_buffers: ProjectSettings::get_global(cx)
                 .session
                 .restore_unsaved_buffers,
+            serialized_state: None,
             blame: None,
             blame_subscription: None,
             tasks: Default::default(),
@@ -2065,6 +2159,9 @@ impl Editor {
             _scroll_cursor_center_top_bottom_task: Task::ready(()),
         };
         this.tasks_update_task = Some(this.refresh_runnables(cx));
+        this.refresh_semantic_tokens(false, cx);
+        this.refresh_code_lenses(false, cx);
+        this.refresh_conflicts(false, cx);
         this._subscriptions.extend(project_subscriptions);
 
         this.end_selection(cx);
@@ -2541,7 +2638,8 @@ impl Editor {
         self.select_prev_state = None;
         self.select_larger_syntax_node_stack.clear();
         self.invalidate_autoclose_regions(&self.selections.disjoint_anchors(), buffer);
-        self.snippet_stack
+        let invalidated_snippets = self
+            .snippet_stack
             .invalidate(&self.selections.disjoint_anchors(), buffer);
         self.take_rename(false, cx);
 
@@ -2626,6 +2724,13 @@ impl Editor {
             }
         }
 
+        if local {
+            // Leaving a snippet completes the tabstop that was active.
+            for snippet in &invalidated_snippets {
+                self.apply_snippet_transforms(snippet, snippet.active_index, cx);
+            }
+        }
+
         self.blink_manager.update(cx, BlinkManager::pause_blinking);
         cx.emit(EditorEvent::SelectionsChanged { local });
 
@@ -3140,7 +3245,8 @@ impl Editor {
             return true;
         }
 
-        if self.snippet_stack.pop().is_some() {
+        if let Some(snippet) = self.snippet_stack.pop() {
+            self.apply_snippet_transforms(&snippet, snippet.active_index, cx);
             return true;
         }
 
@@ -4365,6 +4471,7 @@ impl Editor {
                         selected_completion_documentation_resolve_debounce: Arc::new(Mutex::new(
                             DebouncedDelay::new(),
                         )),
+                        from_snippet_choices: false,
                     };
                     menu.filter(query.as_deref(), cx.background_executor().clone())
                         .await;
@@ -4471,6 +4578,7 @@ impl Editor {
             .matches
             .get(item_ix.unwrap_or(completions_menu.selected_item))?;
         let buffer_handle = completions_menu.buffer;
+        let from_snippet_choices = completions_menu.from_snippet_choices;
         let completions = completions_menu.completions.read();
         let completion = completions.get(mat.candidate_id)?;
         cx.stop_propagation();
@@ -4479,7 +4587,12 @@ impl Editor {
         let text;
 
         if completion.is_snippet() {
-            snippet = Some(Snippet::parse(&completion.new_text).log_err()?);
+            snippet = Some(
+                Snippet::parse_with_variables(&completion.new_text, |name| {
+                    snippet_variables::snippet_variable(self, name, cx)
+                })
+                .log_err()?,
+            );
             text = snippet.as_ref().unwrap().text.clone();
         } else {
             snippet = None;
@@ -4565,10 +4678,15 @@ impl Editor {
             text: text.into(),
         });
 
-        self.transact(cx, |this, cx| {
+        let snippet_count = self.snippet_stack.len();
+        let transaction_id = self.transact(cx, |this, cx| {
             if let Some(mut snippet) = snippet {
                 snippet.text = text.to_string();
-                for tabstop in snippet.tabstops.iter_mut().flatten() {
+                for tabstop in snippet
+                    .tabstops
+                    .iter_mut()
+                    .flat_map(|tabstop| tabstop.ranges.iter_mut())
+                {
                     tabstop.start -= common_prefix_len as isize;
                     tabstop.end -= common_prefix_len as isize;
                 }
@@ -4602,6 +4720,11 @@ impl Editor {
 
             this.refresh_inline_completion(true, false, cx);
         });
+        if self.snippet_stack.len() > snippet_count {
+            if let Some(snippet) = self.snippet_stack.last_mut() {
+                snippet.transaction_id = transaction_id;
+            }
+        }
 
         let show_new_completions_on_confirm = completion
             .confirm
@@ -4610,6 +4733,9 @@ impl Editor {
         if show_new_completions_on_confirm {
             self.show_completions(&ShowCompletions { trigger: None }, cx);
         }
+        if from_snippet_choices {
+            return Some(Task::ready(Ok(())));
+        }
 
         let provider = self.completion_provider.as_ref()?;
         let apply_edits = provider.apply_additional_edits_for_completion(
@@ -5517,6 +5643,8 @@ impl Editor {
         struct Tabstop<T> {
             is_end_tabstop: bool,
             ranges: Vec<Range<T>>,
+            choices: Option<Vec<String>>,
+            transforms: Vec<SnippetTransform>,
         }
 
         let tabstops = self.buffer.update(cx, |buffer, cx| {
@@ -5536,31 +5664,61 @@ impl Editor {
                 .tabstops
                 .iter()
                 .map(|tabstop| {
-                    let is_end_tabstop = tabstop.first().map_or(false, |tabstop| {
+                    let is_end_tabstop = tabstop.ranges.first().map_or(false, |tabstop| {
                         tabstop.is_empty() && tabstop.start == snippet.text.len() as isize
                     });
+                    let anchor_ranges = |tabstop_range: &Range<isize>| {
+                        let tabstop_range = tabstop_range.clone();
+                        let mut delta = 0_isize;
+                        insertion_ranges.iter().map(move |insertion_range| {
+                            let insertion_start = insertion_range.start as isize + delta;
+                            delta += snippet.text.len() as isize - insertion_range.len() as isize;
+
+                            let start = ((insertion_start + tabstop_range.start) as usize)
+                                .min(snapshot.len());
+                            let end = ((insertion_start + tabstop_range.end) as usize)
+                                .min(snapshot.len());
+                            snapshot.anchor_before(start)..snapshot.anchor_after(end)
+                        })
+                    };
+
+                    // Transformed mirrors aren't selected, but updated from the text of the
+                    // tabstop when leaving it, unless there's nothing else to select.
+                    let is_transformed =
+                        |range_ix: usize| tabstop.transforms.iter().any(|(ix, _)| *ix == range_ix);
+                    let source_ix = (0..tabstop.ranges.len()).find(|ix| !is_transformed(*ix));
                     let mut tabstop_ranges = tabstop
+                        .ranges
                         .iter()
-                        .flat_map(|tabstop_range| {
-                            let mut delta = 0_isize;
-                            insertion_ranges.iter().map(move |insertion_range| {
-                                let insertion_start = insertion_range.start as isize + delta;
-                                delta +=
-                                    snippet.text.len() as isize - insertion_range.len() as isize;
-
-                                let start = ((insertion_start + tabstop_range.start) as usize)
-                                    .min(snapshot.len());
-                                let end = ((insertion_start + tabstop_range.end) as usize)
-                                    .min(snapshot.len());
-                                snapshot.anchor_before(start)..snapshot.anchor_after(end)
-                            })
-                        })
+                        .enumerate()
+                        .filter(|(range_ix, _)| source_ix.is_none() || !is_transformed(*range_ix))
+                        .flat_map(|(_, tabstop_range)| anchor_ranges(tabstop_range))
                         .collect::<Vec<_>>();
                     tabstop_ranges.sort_unstable_by(|a, b| a.start.cmp(&b.start, snapshot));
 
+                    let transforms = source_ix
+                        .map(|source_ix| {
+                            tabstop
+                                .transforms
+                                .iter()
+                                .flat_map(|(range_ix, transform)| {
+                                    anchor_ranges(&tabstop.ranges[source_ix])
+                                        .zip(anchor_ranges(&tabstop.ranges[*range_ix]))
+                                        .map(|(source, target)| SnippetTransform {
+                                            source,
+                                            target,
+                                            transform: transform.clone(),
+                                        })
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default();
+
                     Tabstop {
                         is_end_tabstop,
                         ranges: tabstop_ranges,
+                        choices: tabstop.choices.clone(),
+                        transforms,
                     }
                 })
                 .collect::<Vec<_>>()
@@ -5569,17 +5727,28 @@ impl Editor {
             self.change_selections(Some(Autoscroll::fit()), cx, |s| {
                 s.select_ranges(tabstop.ranges.iter().cloned());
             });
+            if let Some((choices, selection)) = tabstop.choices.as_ref().zip(tabstop.ranges.first())
+            {
+                self.show_snippet_choices(choices, selection.clone(), cx);
+            }
 
             // If we're already at the last tabstop and it's at the end of the snippet,
             // we're done, we don't need to keep the state around.
             if !tabstop.is_end_tabstop {
-                let ranges = tabstops
-                    .into_iter()
-                    .map(|tabstop| tabstop.ranges)
-                    .collect::<Vec<_>>();
+                let mut ranges = Vec::with_capacity(tabstops.len());
+                let mut choices = Vec::with_capacity(tabstops.len());
+                let mut transforms = Vec::with_capacity(tabstops.len());
+                for tabstop in tabstops {
+                    ranges.push(tabstop.ranges);
+                    choices.push(tabstop.choices);
+                    transforms.push(tabstop.transforms);
+                }
                 self.snippet_stack.push(SnippetState {
                     active_index: 0,
                     ranges,
+                    choices,
+                    transforms,
+                    transaction_id: None,
                 });
             }
 
@@ -5632,6 +5801,7 @@ impl Editor {
 
     pub fn move_to_snippet_tabstop(&mut self, bias: Bias, cx: &mut ViewContext<Self>) -> bool {
         if let Some(mut snippet) = self.snippet_stack.pop() {
+            let previous_index = snippet.active_index;
             match bias {
                 Bias::Left => {
                     if snippet.active_index > 0 {
@@ -5650,10 +5820,17 @@ impl Editor {
                     }
                 }
             }
+            self.apply_snippet_transforms(&snippet, previous_index, cx);
             if let Some(current_ranges) = snippet.ranges.get(snippet.active_index) {
                 self.change_selections(Some(Autoscroll::fit()), cx, |s| {
                     s.select_anchor_ranges(current_ranges.iter().cloned())
                 });
+                if let Some((choices, selection)) = snippet.choices[snippet.active_index]
+                    .as_ref()
+                    .zip(current_ranges.first())
+                {
+                    self.show_snippet_choices(choices, selection.clone(), cx);
+                }
                 // If snippet state is not at the last tabstop, push it back on the stack
                 if snippet.active_index + 1 < snippet.ranges.len() {
                     self.snippet_stack.push(snippet);
@@ -5665,6 +5842,65 @@ impl Editor {
         false
     }
 
+    /// Updates the transformed mirrors of the tabstop from its text, when leaving it. The edits are
+    /// grouped with the transaction that inserted the snippet, so that undoing it undoes them too.
+    fn apply_snippet_transforms(
+        &mut self,
+        snippet: &SnippetState,
+        tabstop_ix: usize,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(transforms) = snippet.transforms.get(tabstop_ix) else {
+            return;
+        };
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let edits = transforms
+            .iter()
+            .filter_map(|transform| {
+                let text = snapshot
+                    .text_for_range(transform.source.clone())
+                    .collect::<String>();
+                let new_text = transform.transform.apply(&text);
+                let old_text = snapshot
+                    .text_for_range(transform.target.clone())
+                    .collect::<String>();
+                (new_text != old_text).then(|| (transform.target.clone(), new_text))
+            })
+            .collect::<Vec<_>>();
+        if edits.is_empty() {
+            return;
+        }
+
+        self.buffer.update(cx, |buffer, cx| {
+            buffer.edit(edits, None, cx);
+            if let Some(transaction_id) = snippet.transaction_id {
+                buffer.group_until_transaction(transaction_id, cx);
+            }
+        });
+    }
+
+    fn show_snippet_choices(
+        &mut self,
+        choices: &[String],
+        selection: Range<Anchor>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(buffer) = selection
+            .start
+            .buffer_id
+            .and_then(|buffer_id| self.buffer.read(cx).buffer(buffer_id))
+        else {
+            return;
+        };
+
+        let id = post_inc(&mut self.next_completion_id);
+        *self.context_menu.write() = Some(ContextMenu::Completions(
+            CompletionsMenu::new_snippet_choices(id, choices, selection, buffer),
+        ));
+        cx.notify();
+    }
+
     pub fn clear(&mut self, cx: &mut ViewContext<Self>) {
         self.transact(cx, |this, cx| {
             this.select_all(&SelectAll, cx);
@@ -9028,6 +9264,58 @@ impl Editor {
         self.select_larger_syntax_node_stack = stack;
     }
 
+    pub fn select_function(&mut self, _: &SelectFunction, cx: &mut ViewContext<Self>) {
+        self.select_enclosing_text_object(TextObject::AroundFunction, cx);
+    }
+
+    pub fn select_class(&mut self, _: &SelectClass, cx: &mut ViewContext<Self>) {
+        self.select_enclosing_text_object(TextObject::AroundClass, cx);
+    }
+
+    /// Expands each selection to the smallest text object of the given kind that is larger than it,
+    /// so that repeating the action selects the enclosing one.
+    fn select_enclosing_text_object(
+        &mut self,
+        text_object: TextObject,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let old_selections = self.selections.all::<usize>(cx).into_boxed_slice();
+
+        let mut selected_text_object = false;
+        let new_selections = old_selections
+            .iter()
+            .map(|selection| {
+                let old_range = selection.start..selection.end;
+                let mut new_range =
+                    buffer.innermost_enclosing_text_object(old_range.clone(), text_object);
+                // When a whole text object is selected already, select the one enclosing it.
+                if new_range.as_ref() == Some(&old_range) {
+                    let search_range =
+                        old_range.start.saturating_sub(1)..(old_range.end + 1).min(buffer.len());
+                    new_range = buffer.innermost_enclosing_text_object(search_range, text_object);
+                }
+                let new_range = new_range.unwrap_or_else(|| old_range.clone());
+
+                selected_text_object |= new_range != old_range;
+                Selection {
+                    id: selection.id,
+                    start: new_range.start,
+                    end: new_range.end,
+                    goal: SelectionGoal::None,
+                    reversed: selection.reversed,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if selected_text_object {
+            self.select_larger_syntax_node_stack.push(old_selections);
+            self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select(new_selections);
+            });
+        }
+    }
+
     pub fn select_smaller_syntax_node(
         &mut self,
         _: &SelectSmallerSyntaxNode,
@@ -12221,6 +12509,9 @@ impl Editor {
                     (telemetry, is_via_ssh)
                 };
                 refresh_linked_ranges(self, cx);
+                self.refresh_semantic_tokens(true, cx);
+                self.refresh_code_lenses(true, cx);
+                self.refresh_conflicts(true, cx);
                 telemetry.log_edit_event("editor", is_via_ssh);
             }
             multi_buffer::Event::ExcerptsAdded {
@@ -12235,9 +12526,13 @@ impl Editor {
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                self.refresh_semantic_tokens(false, cx);
+                self.refresh_code_lenses(false, cx);
+                self.refresh_conflicts(false, cx);
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
+                self.update_semantic_token_highlights(cx);
                 cx.emit(EditorEvent::ExcerptsRemoved { ids: ids.clone() })
             }
             multi_buffer::Event::ExcerptsEdited { ids } => {
@@ -12253,11 +12548,17 @@ impl Editor {
             }
             multi_buffer::Event::LanguageChanged(buffer_id) => {
                 linked_editing_ranges::refresh_linked_ranges(self, cx);
+                self.refresh_semantic_tokens(false, cx);
+                self.refresh_code_lenses(false, cx);
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
                 cx.notify();
             }
             multi_buffer::Event::DirtyChanged => cx.emit(EditorEvent::DirtyChanged),
-            multi_buffer::Event::Saved => cx.emit(EditorEvent::Saved),
+            multi_buffer::Event::Saved => {
+                self.refresh_code_lenses(false, cx);
+                self.serialize_state(cx);
+                cx.emit(EditorEvent::Saved);
+            }
             multi_buffer::Event::FileHandleChanged | multi_buffer::Event::Reloaded => {
                 cx.emit(EditorEvent::TitleChanged)
             }
@@ -12276,6 +12577,9 @@ impl Editor {
                 self.scrollbar_marker_state.dirty = true;
                 cx.notify();
             }
+            multi_buffer::Event::SemanticTokensUpdated(_) => {
+                self.update_semantic_token_highlights(cx);
+            }
             _ => {}
         };
     }
@@ -12286,6 +12590,9 @@ impl Editor {
 
     fn settings_changed(&mut self, cx: &mut ViewContext<Self>) {
         self.tasks_update_task = Some(self.refresh_runnables(cx));
+        self.update_semantic_token_highlights(cx);
+        self.refresh_semantic_tokens(false, cx);
+        self.refresh_code_lenses(false, cx);
         self.refresh_inline_completion(true, false, cx);
         self.refresh_inlay_hints(
             InlayHintRefreshReason::SettingsChange(inlay_hint_settings(
@@ -12997,6 +13304,18 @@ pub trait SemanticsProvider {
 
     fn supports_inlay_hints(&self, buffer: &Model<Buffer>, cx: &AppContext) -> bool;
 
+    fn semantic_tokens(
+        &self,
+        buffer_handle: Model<Buffer>,
+        cx: &mut AppContext,
+    ) -> Option<Task<anyhow::Result<()>>>;
+
+    fn code_lens(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &mut AppContext,
+    ) -> Option<Task<Result<Vec<CodeLens>>>>;
+
     fn document_highlights(
         &self,
         buffer: &Model<Buffer>,
@@ -13344,6 +13663,22 @@ impl SemanticsProvider for Model<Project> {
         }))
     }
 
+    fn semantic_tokens(
+        &self,
+        buffer_handle: Model<Buffer>,
+        cx: &mut AppContext,
+    ) -> Option<Task<anyhow::Result<()>>> {
+        Some(self.update(cx, |project, cx| project.semantic_tokens(buffer_handle, cx)))
+    }
+
+    fn code_lens(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &mut AppContext,
+    ) -> Option<Task<Result<Vec<CodeLens>>>> {
+        Some(self.update(cx, |project, cx| project.code_lens(buffer, cx)))
+    }
+
     fn range_for_rename(
         &self,
         buffer: &Model<Buffer>,
@@ -14040,10 +14375,12 @@ impl<T: ToPoint + ToOffset> SelectionExt for Selection<T> {
 }
 
 impl<T: InvalidationRegion> InvalidationStack<T> {
-    fn invalidate<S>(&mut self, selections: &[Selection<S>], buffer: &MultiBufferSnapshot)
+    /// Pops the regions that the selections left, returning them from the innermost one.
+    fn invalidate<S>(&mut self, selections: &[Selection<S>], buffer: &MultiBufferSnapshot) -> Vec<T>
     where
         S: Clone + ToOffset,
     {
+        let mut invalidated = Vec::new();
         while let Some(region) = self.last() {
             let all_selections_inside_invalidation_ranges =
                 if selections.len() == region.ranges().len() {
@@ -14061,9 +14398,10 @@ impl<T: InvalidationRegion> InvalidationStack<T> {
             if all_selections_inside_invalidation_ranges {
                 break;
             } else {
-                self.pop();
+                invalidated.extend(self.pop());
             }
         }
+        invalidated
     }
 }
 
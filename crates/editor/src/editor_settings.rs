@@ -10,6 +10,7 @@ pub struct EditorSettings {
     pub cursor_shape: Option<CursorShape>,
     pub current_line_highlight: CurrentLineHighlight,
     pub hover_popover_enabled: bool,
+    pub code_lens: bool,
     pub show_completions_on_input: bool,
     pub show_completion_documentation: bool,
     pub completion_documentation_secondary_query_debounce: u64,
@@ -192,6 +193,11 @@ pub struct EditorSettingsContent {
     ///
     /// Default: true
     pub hover_popover_enabled: Option<bool>,
+    /// Whether to show the code lenses provided by language servers above the lines they refer to,
+    /// such as reference counts or commands to run tests.
+    ///
+    /// Default: false
+    pub code_lens: Option<bool>,
 
     /// Whether to pop the completions menu while typing in an editor without
     /// explicitly requesting it.
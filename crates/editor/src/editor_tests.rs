@@ -2,11 +2,13 @@ use super::*;
 use crate::{
     scroll::scroll_amount::ScrollAmount,
     test::{
-        assert_text_with_selections, build_editor, editor_lsp_test_context::EditorLspTestContext,
-        editor_test_context::EditorTestContext, select_ranges,
+        assert_text_with_selections, build_editor, build_editor_with_project,
+        editor_lsp_test_context::EditorLspTestContext, editor_test_context::EditorTestContext,
+        select_ranges,
     },
     JoinLines,
 };
+use ::git::repository::GitFileStatus;
 use futures::StreamExt;
 use gpui::{
     div, SemanticVersion, TestAppContext, UpdateGlobal, VisualTestContext, WindowBounds,
@@ -6537,6 +6539,125 @@ async fn test_snippets(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_snippet_choices_and_transforms(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let (text, insertion_ranges) = marked_text_ranges("ˇ", false);
+    let buffer = cx.update(|cx| MultiBuffer::build_simple(&text, cx));
+    let (editor, cx) = cx.add_window_view(|cx| build_editor(buffer, cx));
+
+    editor.update(cx, |editor, cx| {
+        let snippet =
+            Snippet::parse("let ${1:foo_bar}: ${1/(.*)/${1:/pascalcase}/} = ${2|a,b|};$0").unwrap();
+        editor
+            .insert_snippet(&insertion_ranges, snippet, cx)
+            .unwrap();
+
+        fn assert(editor: &mut Editor, cx: &mut ViewContext<Editor>, marked_text: &str) {
+            let (expected_text, selection_ranges) = marked_text_ranges(marked_text, false);
+            assert_eq!(editor.text(cx), expected_text);
+            assert_eq!(editor.selections.ranges::<usize>(cx), selection_ranges);
+        }
+
+        // Transformed mirrors aren't selected, and are only updated when leaving the tabstop
+        assert(editor, cx, "let «foo_bar»: FooBar = a;");
+        editor.insert("my_type", cx);
+        assert(editor, cx, "let my_typeˇ: FooBar = a;");
+
+        assert!(editor.move_to_next_snippet_tabstop(cx));
+        assert(editor, cx, "let my_type: MyType = «a»;");
+        if let Some(ContextMenu::Completions(menu)) = editor.context_menu.read().as_ref() {
+            assert_eq!(
+                menu.matches.iter().map(|m| &m.string).collect::<Vec<_>>(),
+                &["a", "b"]
+            );
+        } else {
+            panic!("expected the snippet choices to be shown");
+        }
+
+        editor
+            .confirm_completion(&ConfirmCompletion { item_ix: Some(1) }, cx)
+            .unwrap()
+            .detach();
+        assert(editor, cx, "let my_type: MyType = bˇ;");
+
+        assert!(editor.move_to_next_snippet_tabstop(cx));
+        assert(editor, cx, "let my_type: MyType = b;ˇ");
+    });
+}
+
+#[gpui::test]
+async fn test_snippet_transforms_when_leaving_snippet(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            completion_provider: Some(lsp::CompletionOptions {
+                resolve_provider: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    async fn confirm_snippet_completion(cx: &mut EditorLspTestContext) {
+        let position = lsp::Position::new(0, 12);
+        let mut request =
+            cx.handle_request::<lsp::request::Completion, _, _>(move |_, _, _| async move {
+                Ok(Some(lsp::CompletionResponse::Array(vec![
+                    lsp::CompletionItem {
+                        label: "let".into(),
+                        insert_text_format: Some(lsp::InsertTextFormat::SNIPPET),
+                        text_edit: Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+                            range: lsp::Range::new(position, position),
+                            new_text: "let ${1:foo_bar}: ${1/(.*)/${1:/upcase}/};".to_string(),
+                        })),
+                        ..Default::default()
+                    },
+                ])))
+            });
+
+        cx.set_state("fn main() { ˇ }");
+        cx.update_editor(|editor, cx| {
+            editor.show_completions(&ShowCompletions { trigger: None }, cx)
+        });
+        request.next().await;
+        cx.condition(|editor, _| editor.context_menu_visible())
+            .await;
+        let apply_additional_edits = cx.update_editor(|editor, cx| {
+            editor
+                .confirm_completion(&ConfirmCompletion::default(), cx)
+                .unwrap()
+        });
+        handle_resolve_completion_request(cx, None).await;
+        apply_additional_edits.await.unwrap();
+        cx.assert_editor_state("fn main() { let «foo_barˇ»: FOO_BAR; }");
+    }
+
+    // Cancelling the snippet applies the transforms of its tabstop.
+    confirm_snippet_completion(&mut cx).await;
+    cx.update_editor(|editor, cx| {
+        editor.insert("my_type", cx);
+        editor.cancel(&Cancel, cx);
+    });
+    cx.assert_editor_state("fn main() { let my_typeˇ: MY_TYPE; }");
+
+    // The transformed mirror is undone along with the completion.
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state("fn main() { ˇ }");
+
+    // So does moving the cursor out of the tabstop.
+    confirm_snippet_completion(&mut cx).await;
+    cx.update_editor(|editor, cx| {
+        editor.insert("other", cx);
+        editor.move_to_end(&MoveToEnd, cx);
+    });
+    cx.assert_editor_state("fn main() { let other: OTHER; }ˇ");
+}
+
 #[gpui::test]
 async fn test_document_format_during_save(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -13099,6 +13220,299 @@ async fn test_goto_definition_with_find_all_references_fallback(cx: &mut gpui::T
     });
 }
 
+#[gpui::test]
+async fn test_code_lens_blocks(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.code_lens = Some(true);
+            });
+        });
+    });
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            code_lens_provider: Some(lsp::CodeLensOptions {
+                resolve_provider: None,
+            }),
+            execute_command_provider: Some(lsp::ExecuteCommandOptions {
+                commands: vec!["run".to_string()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+    cx.set_state(indoc! {"
+        fn main() {}ˇ
+
+        fn test() {}
+    "});
+
+    let lens = |line, title: &str, command: &str| lsp::CodeLens {
+        range: lsp::Range::new(lsp::Position::new(line, 3), lsp::Position::new(line, 7)),
+        command: Some(lsp::Command {
+            title: title.to_string(),
+            command: command.to_string(),
+            arguments: None,
+        }),
+        data: None,
+    };
+    let server_lenses = Arc::new(Mutex::new(vec![
+        lens(0, "Run", "run"),
+        lens(2, "1 reference", "showReferences"),
+    ]));
+    cx.handle_request::<lsp::request::CodeLensRequest, _, _>({
+        let server_lenses = server_lenses.clone();
+        move |_, _, _| {
+            let lenses = server_lenses.lock().clone();
+            async move { Ok(Some(lenses)) }
+        }
+    });
+    let code_lens_blocks = |cx: &mut EditorLspTestContext| {
+        cx.update_editor(|editor, cx| {
+            let buffer = editor.buffer.read(cx).as_singleton().unwrap();
+            let snapshot = buffer.read(cx).snapshot();
+            let mut blocks = editor
+                .code_lens_blocks
+                .values()
+                .flatten()
+                .map(|block| {
+                    let titles = block
+                        .lenses
+                        .iter()
+                        .map(|lens| lens.lsp_lens.command.as_ref().unwrap().title.clone())
+                        .collect::<Vec<_>>();
+                    let executable = block.lenses.iter().all(|lens| lens.executable);
+                    let row = snapshot.summary_for_anchor::<Point>(&block.position).row;
+                    (row, titles, executable, block.id)
+                })
+                .collect::<Vec<_>>();
+            blocks.sort_by_key(|(row, ..)| *row);
+            blocks
+        })
+    };
+
+    cx.update_editor(|editor, cx| editor.refresh_code_lenses(false, cx));
+    cx.run_until_parked();
+    let blocks = code_lens_blocks(&mut cx);
+    assert_eq!(
+        blocks
+            .iter()
+            .map(|(row, titles, executable, _)| (*row, titles.clone(), *executable))
+            .collect::<Vec<_>>(),
+        vec![
+            (0, vec!["Run".to_string()], true),
+            (2, vec!["1 reference".to_string()], false),
+        ],
+        "Commands unknown to the server should be displayed, but not run"
+    );
+    let run_block_id = blocks[0].3;
+    let references_block_id = blocks[1].3;
+
+    server_lenses.lock()[1] = lens(2, "2 references", "showReferences");
+    cx.update_editor(|editor, cx| editor.refresh_code_lenses(false, cx));
+    cx.run_until_parked();
+    let blocks = code_lens_blocks(&mut cx);
+    assert_eq!(
+        blocks
+            .iter()
+            .map(|(row, titles, _, id)| (*row, titles.clone(), *id))
+            .collect::<Vec<_>>(),
+        vec![
+            (0, vec!["Run".to_string()], run_block_id),
+            (2, vec!["2 references".to_string()], references_block_id),
+        ],
+        "Blocks should be kept on refresh, only changing their contents"
+    );
+
+    server_lenses.lock().remove(0);
+    cx.update_editor(|editor, cx| editor.refresh_code_lenses(false, cx));
+    cx.run_until_parked();
+    let blocks = code_lens_blocks(&mut cx);
+    assert_eq!(
+        blocks
+            .iter()
+            .map(|(row, titles, _, id)| (*row, titles.clone(), *id))
+            .collect::<Vec<_>>(),
+        vec![(2, vec!["2 references".to_string()], references_block_id)],
+        "Blocks of the lines without lenses should be removed"
+    );
+}
+
+#[gpui::test]
+async fn test_resolve_conflicts(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let conflicted_text = indoc! {"
+        fn main() {
+        <<<<<<< HEAD
+            one();
+        =======
+            two();
+        >>>>>>> feature
+            common();
+        <<<<<<< HEAD
+            three();
+        ||||||| base
+            zero();
+        =======
+            four();
+        >>>>>>> feature
+        }
+    "};
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/root",
+        json!({
+            ".git": {},
+            "file.rs": conflicted_text,
+            "other.rs": conflicted_text,
+        }),
+    )
+    .await;
+    fs.set_status_for_repo_via_git_operation(
+        Path::new("/root/.git"),
+        &[(Path::new("file.rs"), GitFileStatus::Conflict)],
+    );
+    fs.set_conflict_stages_for_repo(
+        Path::new("/root/.git"),
+        &[(
+            Path::new("file.rs"),
+            ::git::conflict::ConflictStages {
+                base: None,
+                ours: Some("fn main() {}\n".into()),
+                theirs: None,
+            },
+        )],
+    );
+    let project = Project::test(fs, ["/root".as_ref()], cx).await;
+
+    // Only files that git reports as conflicted are scanned for conflict markers.
+    let other_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/root/other.rs", cx)
+        })
+        .await
+        .unwrap();
+    let other_editor = cx.add_window(|cx| {
+        build_editor_with_project(
+            project.clone(),
+            MultiBuffer::build_from_buffer(other_buffer, cx),
+            cx,
+        )
+    });
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.run_until_parked();
+    other_editor
+        .update(cx, |editor, _| {
+            assert!(editor.conflicts.is_empty());
+            assert!(editor.conflict_blocks.is_empty());
+        })
+        .unwrap();
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/root/file.rs", cx)
+        })
+        .await
+        .unwrap();
+    let editor = cx.add_window(|cx| {
+        let editor =
+            build_editor_with_project(project, MultiBuffer::build_from_buffer(buffer, cx), cx);
+        editor.focus(cx);
+        editor
+    });
+    let mut cx = EditorTestContext::for_editor(editor, cx).await;
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.run_until_parked();
+    cx.update_editor(|editor, _| assert_eq!(editor.conflict_blocks.len(), 2));
+
+    cx.update_editor(|editor, cx| editor.go_to_next_conflict(&GoToConflict, cx));
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+        ˇ<<<<<<< HEAD
+            one();
+        =======
+            two();
+        >>>>>>> feature
+            common();
+        <<<<<<< HEAD
+            three();
+        ||||||| base
+            zero();
+        =======
+            four();
+        >>>>>>> feature
+        }
+    "});
+
+    // Resolving a conflict keeps the block of the other one.
+    let remaining_block = cx.update_editor(|editor, _| editor.conflict_blocks[1].id);
+    cx.update_editor(|editor, cx| editor.accept_conflict_theirs(&AcceptConflictTheirs, cx));
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.run_until_parked();
+    cx.update_editor(|editor, _| {
+        assert_eq!(
+            editor
+                .conflict_blocks
+                .iter()
+                .map(|block| block.id)
+                .collect::<Vec<_>>(),
+            vec![remaining_block]
+        );
+    });
+
+    // Outside of a conflict, the file isn't resolved as a whole while markers are left.
+    cx.update_editor(|editor, cx| editor.accept_conflict_ours(&AcceptConflictOurs, cx));
+    cx.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            two();
+            common();
+        ˇ<<<<<<< HEAD
+            three();
+        ||||||| base
+            zero();
+        =======
+            four();
+        >>>>>>> feature
+        }
+    "});
+
+    cx.update_editor(|editor, cx| editor.go_to_next_conflict(&GoToConflict, cx));
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            two();
+            common();
+        ˇ<<<<<<< HEAD
+            three();
+        ||||||| base
+            zero();
+        =======
+            four();
+        >>>>>>> feature
+        }
+    "});
+
+    cx.update_editor(|editor, cx| editor.accept_conflict_both(&AcceptConflictBoth, cx));
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            two();
+            common();
+        ˇ    three();
+            four();
+        }
+    "});
+
+    // Once no markers are left, the file is resolved from the index stages.
+    cx.update_editor(|editor, cx| editor.accept_conflict_ours(&AcceptConflictOurs, cx));
+    cx.run_until_parked();
+    cx.update_editor(|editor, cx| assert_eq!(editor.text(cx), "fn main() {}\n"));
+}
+
 fn empty_range(row: usize, column: usize) -> Range<DisplayPoint> {
     let point = DisplayPoint::new(DisplayRow(row as u32), column as u32);
     point..point
@@ -1 +1,2 @@
 pub mod blame;
+pub(crate) mod conflicts;
@@ -0,0 +1,603 @@
+use std::{ops::Range, time::Duration};
+
+use collections::{HashMap, HashSet};
+use futures::future::join_all;
+use git::{
+    conflict::{parse_conflict_markers, ConflictRegion},
+    repository::GitFileStatus,
+};
+use gpui::{AppContext, Hsla, WeakView};
+use language::BufferSnapshot;
+use multi_buffer::{Anchor, ExcerptId, ToOffset as _};
+use project::{Item as _, ProjectPath};
+use text::{BufferId, ToOffset as _};
+use ui::{prelude::*, ButtonStyle, Tooltip, ViewContext};
+
+use crate::{
+    actions::{
+        AcceptConflictBoth, AcceptConflictOurs, AcceptConflictTheirs, GoToConflict,
+        GoToPrevConflict,
+    },
+    display_map::CustomBlockId,
+    scroll::Autoscroll,
+    BlockDisposition, BlockProperties, BlockStyle, Direction, Editor, EditorMode, RenderBlock,
+};
+
+/// How long to wait after the last edit before looking for conflict markers again.
+const CONFLICTS_DEBOUNCE: Duration = Duration::from_millis(100);
+
+enum ConflictMarkerHighlight {}
+enum ConflictOursHighlight {}
+enum ConflictTheirsHighlight {}
+
+/// A region of a buffer delimited by conflict markers.
+#[derive(Clone, Debug)]
+pub(crate) struct Conflict {
+    buffer_id: BufferId,
+    range: Range<text::Anchor>,
+    ours: Range<text::Anchor>,
+    theirs: Range<text::Anchor>,
+    ours_label: SharedString,
+    theirs_label: SharedString,
+}
+
+impl Conflict {
+    fn new(snapshot: &BufferSnapshot, region: ConflictRegion) -> Self {
+        let anchor_range = |range: Range<usize>| {
+            snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end)
+        };
+        Self {
+            buffer_id: snapshot.remote_id(),
+            range: anchor_range(region.range),
+            ours: anchor_range(region.ours),
+            theirs: anchor_range(region.theirs),
+            ours_label: region.ours_label.into(),
+            theirs_label: region.theirs_label.into(),
+        }
+    }
+}
+
+/// The block offering to resolve a conflict, shown above it.
+pub(crate) struct ConflictBlock {
+    pub(crate) id: CustomBlockId,
+    excerpt_id: ExcerptId,
+    conflict: Conflict,
+}
+
+/// Identifies a conflict shown in an excerpt by its current position and labels, so that the
+/// blocks of the conflicts that are still there can be kept when looking for conflicts again.
+type ConflictKey = (
+    ExcerptId,
+    Range<usize>,
+    Range<usize>,
+    Range<usize>,
+    SharedString,
+    SharedString,
+);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ConflictResolution {
+    Ours,
+    Theirs,
+    Both,
+}
+
+impl Editor {
+    /// Looks for conflict markers in the editor's buffers whose files git reports as
+    /// conflicted, and highlights the regions they delimit with blocks offering to resolve
+    /// them above each one.
+    pub(crate) fn refresh_conflicts(&mut self, debounce: bool, cx: &mut ViewContext<Self>) {
+        if self.mode != EditorMode::Full {
+            return;
+        }
+        let snapshots = self.conflicted_buffer_snapshots(cx);
+        if snapshots.is_empty() && self.conflicts.is_empty() && self.conflict_blocks.is_empty() {
+            self.conflicts_task = None;
+            return;
+        }
+
+        self.conflicts_task = Some(cx.spawn(|editor, mut cx| async move {
+            if debounce {
+                cx.background_executor().timer(CONFLICTS_DEBOUNCE).await;
+            }
+            let conflicts = cx
+                .background_executor()
+                .spawn(async move { find_conflicts(snapshots) })
+                .await;
+            editor
+                .update(&mut cx, |editor, cx| editor.show_conflicts(conflicts, cx))
+                .ok();
+        }));
+    }
+
+    fn conflicted_buffer_snapshots(&self, cx: &AppContext) -> Vec<BufferSnapshot> {
+        let Some(project) = self.project.as_ref() else {
+            return Vec::new();
+        };
+        let project = project.read(cx);
+        self.buffer
+            .read(cx)
+            .all_buffers()
+            .into_iter()
+            .filter(|buffer| {
+                buffer
+                    .read(cx)
+                    .project_path(cx)
+                    .and_then(|path| project.entry_for_path(&path, cx))
+                    .is_some_and(|entry| entry.git_status == Some(GitFileStatus::Conflict))
+            })
+            .map(|buffer| buffer.read(cx).snapshot())
+            .collect()
+    }
+
+    fn show_conflicts(&mut self, conflicts: Vec<Vec<Conflict>>, cx: &mut ViewContext<Self>) {
+        self.conflicts = conflicts
+            .into_iter()
+            .filter_map(|conflicts| Some((conflicts.first()?.buffer_id, conflicts)))
+            .collect();
+
+        self.clear_row_highlights::<ConflictMarkerHighlight>();
+        self.clear_row_highlights::<ConflictOursHighlight>();
+        self.clear_row_highlights::<ConflictTheirsHighlight>();
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let conflict_key = |excerpt_id: ExcerptId, conflict: &Conflict| -> Option<ConflictKey> {
+            let buffer = snapshot.buffer_for_excerpt(excerpt_id)?;
+            let to_offsets = |range: &Range<text::Anchor>| {
+                range.start.to_offset(buffer)..range.end.to_offset(buffer)
+            };
+            Some((
+                excerpt_id,
+                to_offsets(&conflict.range),
+                to_offsets(&conflict.ours),
+                to_offsets(&conflict.theirs),
+                conflict.ours_label.clone(),
+                conflict.theirs_label.clone(),
+            ))
+        };
+
+        // Keep the blocks of the conflicts that didn't change, and remove the other ones.
+        let mut stale_block_ids = HashSet::default();
+        let mut previous_blocks = HashMap::default();
+        for block in self.conflict_blocks.drain(..) {
+            match conflict_key(block.excerpt_id, &block.conflict) {
+                Some(key) => {
+                    if let Some(duplicate) = previous_blocks.insert(key, block) {
+                        stale_block_ids.insert(duplicate.id);
+                    }
+                }
+                None => {
+                    stale_block_ids.insert(block.id);
+                }
+            }
+        }
+
+        let marker_color = conflict_color(cx.theme().status().git().conflict, 0.8);
+        let ours_color = conflict_color(cx.theme().status().git().created, 0.7);
+        let theirs_color = conflict_color(cx.theme().status().info, 0.7);
+        let editor = cx.view().downgrade();
+        let mut conflict_blocks = Vec::new();
+        let mut new_blocks = Vec::new();
+        for (excerpt_id, conflict) in self.visible_conflicts(cx) {
+            let anchor_range = |range: &Range<text::Anchor>| {
+                Some(
+                    snapshot.anchor_in_excerpt(excerpt_id, range.start)?
+                        ..snapshot.anchor_in_excerpt(excerpt_id, range.end)?,
+                )
+            };
+            let (Some(range), Some(ours), Some(theirs)) = (
+                anchor_range(&conflict.range),
+                anchor_range(&conflict.ours),
+                anchor_range(&conflict.theirs),
+            ) else {
+                continue;
+            };
+
+            self.highlight_rows::<ConflictMarkerHighlight>(range.clone(), marker_color, false, cx);
+            self.highlight_rows::<ConflictOursHighlight>(ours, ours_color, false, cx);
+            self.highlight_rows::<ConflictTheirsHighlight>(theirs, theirs_color, false, cx);
+
+            let previous_block =
+                conflict_key(excerpt_id, &conflict).and_then(|key| previous_blocks.remove(&key));
+            if let Some(block) = previous_block {
+                conflict_blocks.push(block);
+            } else {
+                new_blocks.push((
+                    excerpt_id,
+                    conflict.clone(),
+                    BlockProperties {
+                        position: range.start,
+                        height: 1,
+                        style: BlockStyle::Flex,
+                        disposition: BlockDisposition::Above,
+                        priority: 0,
+                        render: render_conflict_actions(editor.clone(), excerpt_id, conflict),
+                    },
+                ));
+            }
+        }
+
+        stale_block_ids.extend(previous_blocks.into_values().map(|block| block.id));
+        if !stale_block_ids.is_empty() {
+            self.remove_blocks(stale_block_ids, None, cx);
+        }
+        if !new_blocks.is_empty() {
+            let (conflicts, blocks): (Vec<_>, Vec<_>) = new_blocks
+                .into_iter()
+                .map(|(excerpt_id, conflict, block)| ((excerpt_id, conflict), block))
+                .unzip();
+            let block_ids = self.insert_blocks(blocks, None, cx);
+            conflict_blocks.extend(conflicts.into_iter().zip(block_ids).map(
+                |((excerpt_id, conflict), id)| ConflictBlock {
+                    id,
+                    excerpt_id,
+                    conflict,
+                },
+            ));
+        }
+        self.conflict_blocks = conflict_blocks;
+        cx.notify();
+    }
+
+    /// Returns the conflicts that start inside an excerpt, in the order they appear in the editor.
+    fn visible_conflicts(&self, cx: &AppContext) -> Vec<(ExcerptId, Conflict)> {
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let mut visible_conflicts = Vec::new();
+        for (buffer_id, conflicts) in &self.conflicts {
+            let Some(buffer) = multi_buffer.buffer(*buffer_id) else {
+                continue;
+            };
+            let buffer_snapshot = buffer.read(cx).snapshot();
+            for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(&buffer, cx) {
+                let context = &excerpt_range.context;
+                for conflict in conflicts {
+                    let start = &conflict.range.start;
+                    if context.start.cmp(start, &buffer_snapshot).is_le()
+                        && context.end.cmp(start, &buffer_snapshot).is_ge()
+                    {
+                        visible_conflicts.push((excerpt_id, conflict.clone()));
+                    }
+                }
+            }
+        }
+        visible_conflicts.sort_by_cached_key(|(excerpt_id, conflict)| {
+            snapshot
+                .anchor_in_excerpt(*excerpt_id, conflict.range.start)
+                .map(|anchor| anchor.to_offset(&snapshot))
+        });
+        visible_conflicts
+    }
+
+    pub(crate) fn accept_conflict_ours(
+        &mut self,
+        _: &AcceptConflictOurs,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.resolve_conflict_at_cursor(ConflictResolution::Ours, cx);
+    }
+
+    pub(crate) fn accept_conflict_theirs(
+        &mut self,
+        _: &AcceptConflictTheirs,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.resolve_conflict_at_cursor(ConflictResolution::Theirs, cx);
+    }
+
+    pub(crate) fn accept_conflict_both(
+        &mut self,
+        _: &AcceptConflictBoth,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.resolve_conflict_at_cursor(ConflictResolution::Both, cx);
+    }
+
+    /// Resolves the conflict containing the newest cursor. Once no conflict markers are left in
+    /// a conflicted file, it is resolved as a whole from the index stages of its path.
+    fn resolve_conflict_at_cursor(
+        &mut self,
+        resolution: ConflictResolution,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let head = self.selections.newest_anchor().head();
+        let conflict = head.buffer_id.and_then(|buffer_id| {
+            let buffer = self.buffer.read(cx).buffer(buffer_id)?;
+            let buffer = buffer.read(cx);
+            self.conflicts.get(&buffer_id)?.iter().find(|conflict| {
+                conflict.range.start.cmp(&head.text_anchor, buffer).is_le()
+                    && conflict.range.end.cmp(&head.text_anchor, buffer).is_ge()
+            })
+        });
+        if let Some(conflict) = conflict.cloned() {
+            self.resolve_conflict(head.excerpt_id, &conflict, resolution, cx);
+        } else if resolution != ConflictResolution::Both {
+            let markers_left = self.buffer.read(cx).as_singleton().map_or(true, |buffer| {
+                !parse_conflict_markers(buffer.read(cx).as_rope()).is_empty()
+            });
+            if !markers_left {
+                self.resolve_file_conflict(resolution, cx);
+            }
+        }
+    }
+
+    fn resolve_conflict(
+        &mut self,
+        excerpt_id: ExcerptId,
+        conflict: &Conflict,
+        resolution: ConflictResolution,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(buffer) = self.buffer.read(cx).buffer(conflict.buffer_id) else {
+            return;
+        };
+        let buffer = buffer.read(cx);
+        let mut text = String::new();
+        if resolution != ConflictResolution::Theirs {
+            text.extend(buffer.text_for_range(conflict.ours.clone()));
+        }
+        if resolution != ConflictResolution::Ours {
+            text.extend(buffer.text_for_range(conflict.theirs.clone()));
+        }
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let (Some(start), Some(end)) = (
+            snapshot.anchor_in_excerpt(excerpt_id, conflict.range.start),
+            snapshot.anchor_in_excerpt(excerpt_id, conflict.range.end),
+        ) else {
+            return;
+        };
+        self.transact(cx, |editor, cx| {
+            editor.buffer.update(cx, |buffer, cx| {
+                buffer.edit([(start..end, text)], None, cx);
+            });
+        });
+    }
+
+    fn resolve_file_conflict(
+        &mut self,
+        resolution: ConflictResolution,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some(project_path) = self
+            .buffer
+            .read(cx)
+            .as_singleton()
+            .and_then(|buffer| buffer.read(cx).project_path(cx))
+        else {
+            return;
+        };
+        let stages = project.read(cx).load_conflict_stages(project_path, cx);
+        cx.spawn(|editor, mut cx| async move {
+            let Some(stages) = stages.await? else {
+                return Ok(());
+            };
+            let text = match resolution {
+                ConflictResolution::Ours => stages.ours,
+                ConflictResolution::Theirs => stages.theirs,
+                ConflictResolution::Both => None,
+            };
+            let Some(text) = text else {
+                return Ok(());
+            };
+            editor.update(&mut cx, |editor, cx| {
+                editor.transact(cx, |editor, cx| {
+                    editor.buffer.update(cx, |buffer, cx| {
+                        buffer.edit([(Anchor::min()..Anchor::max(), text)], None, cx);
+                    });
+                });
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub(crate) fn go_to_next_conflict(&mut self, _: &GoToConflict, cx: &mut ViewContext<Self>) {
+        self.go_to_conflict(Direction::Next, cx);
+    }
+
+    pub(crate) fn go_to_prev_conflict(&mut self, _: &GoToPrevConflict, cx: &mut ViewContext<Self>) {
+        self.go_to_conflict(Direction::Prev, cx);
+    }
+
+    /// Moves the cursor to the next or previous conflict in the editor. Past the first or last
+    /// one, opens the next or previous conflicted file of the project instead.
+    fn go_to_conflict(&mut self, direction: Direction, cx: &mut ViewContext<Self>) {
+        let head = self.selections.newest::<usize>(cx).head();
+        if !self.select_conflict(direction, Some(head), cx) {
+            self.go_to_conflicted_file(direction, cx);
+        }
+    }
+
+    /// Selects the start of the first conflict after the given offset, or of the last one before
+    /// it, depending on the direction. Without an offset, selects the first or last conflict.
+    fn select_conflict(
+        &mut self,
+        direction: Direction,
+        offset: Option<usize>,
+        cx: &mut ViewContext<Self>,
+    ) -> bool {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let starts = self
+            .visible_conflicts(cx)
+            .into_iter()
+            .filter_map(|(excerpt_id, conflict)| {
+                let start = snapshot.anchor_in_excerpt(excerpt_id, conflict.range.start)?;
+                Some(start.to_offset(&snapshot))
+            })
+            .collect::<Vec<_>>();
+        let target = match (direction, offset) {
+            (Direction::Next, Some(offset)) => starts.into_iter().find(|start| *start > offset),
+            (Direction::Prev, Some(offset)) => {
+                starts.into_iter().rev().find(|start| *start < offset)
+            }
+            (Direction::Next, None) => starts.first().copied(),
+            (Direction::Prev, None) => starts.last().copied(),
+        };
+        let Some(target) = target else {
+            return false;
+        };
+        self.change_selections(Some(Autoscroll::center()), cx, |selections| {
+            selections.select_ranges([target..target]);
+        });
+        true
+    }
+
+    fn go_to_conflicted_file(&mut self, direction: Direction, cx: &mut ViewContext<Self>) {
+        let (Some(workspace), Some(project)) = (self.workspace(), self.project.clone()) else {
+            return;
+        };
+        let current_path = self
+            .buffer
+            .read(cx)
+            .as_singleton()
+            .and_then(|buffer| buffer.read(cx).project_path(cx));
+        let statuses = project
+            .read(cx)
+            .visible_worktrees(cx)
+            .map(|worktree| {
+                let worktree_id = worktree.read(cx).id();
+                let statuses = project.read(cx).git_status(worktree_id, cx);
+                async move { (worktree_id, statuses.await) }
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(|_, mut cx| async move {
+            let mut conflicted_paths = Vec::new();
+            for (worktree_id, statuses) in join_all(statuses).await {
+                conflicted_paths.extend(
+                    statuses?
+                        .into_iter()
+                        .filter(|(_, status)| status.is_conflicted())
+                        .map(|(path, _)| ProjectPath { worktree_id, path }),
+                );
+            }
+            conflicted_paths.sort();
+            let Some(path) = adjacent_path(&conflicted_paths, current_path.as_ref(), direction)
+            else {
+                return Ok(());
+            };
+
+            let item = workspace
+                .update(&mut cx, |workspace, cx| {
+                    workspace.open_path(path, None, true, cx)
+                })?
+                .await?;
+            workspace.update(&mut cx, |_, cx| {
+                if let Some(editor) = item.act_as::<Editor>(cx) {
+                    editor.update(cx, |editor, cx| {
+                        let snapshots = editor.conflicted_buffer_snapshots(cx);
+                        editor.show_conflicts(find_conflicts(snapshots), cx);
+                        editor.select_conflict(direction, None, cx);
+                    });
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+fn find_conflicts(snapshots: Vec<BufferSnapshot>) -> Vec<Vec<Conflict>> {
+    snapshots
+        .into_iter()
+        .map(|snapshot| {
+            parse_conflict_markers(snapshot.as_rope())
+                .into_iter()
+                .map(|region| Conflict::new(&snapshot, region))
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the path following or preceding the current one, wrapping around at either end.
+fn adjacent_path<'a>(
+    paths: &'a [ProjectPath],
+    current_path: Option<&ProjectPath>,
+    direction: Direction,
+) -> Option<&'a ProjectPath> {
+    let position = current_path.map(|current_path| paths.binary_search(current_path));
+    match direction {
+        Direction::Next => {
+            let ix = match position {
+                Some(Ok(ix)) => ix + 1,
+                Some(Err(ix)) => ix,
+                None => 0,
+            };
+            paths.get(ix).or_else(|| paths.first())
+        }
+        Direction::Prev => {
+            let ix = match position {
+                Some(Ok(ix) | Err(ix)) => ix,
+                None => paths.len(),
+            };
+            ix.checked_sub(1)
+                .and_then(|ix| paths.get(ix))
+                .or_else(|| paths.last())
+        }
+    }
+}
+
+fn conflict_color(mut color: Hsla, fade_out: f32) -> Hsla {
+    color.fade_out(fade_out);
+    color
+}
+
+fn render_conflict_actions(
+    editor: WeakView<Editor>,
+    excerpt_id: ExcerptId,
+    conflict: Conflict,
+) -> RenderBlock {
+    Box::new(move |cx| {
+        let actions = [
+            (
+                "Accept Ours",
+                ConflictResolution::Ours,
+                conflict.ours_label.clone(),
+            ),
+            (
+                "Accept Theirs",
+                ConflictResolution::Theirs,
+                conflict.theirs_label.clone(),
+            ),
+            (
+                "Accept Both",
+                ConflictResolution::Both,
+                SharedString::default(),
+            ),
+        ];
+        let mut row = h_flex()
+            .id(cx.block_id)
+            .h(cx.line_height)
+            .w_full()
+            .pl(cx.anchor_x)
+            .gap_1();
+        for (ix, (label, resolution, branch)) in actions.into_iter().enumerate() {
+            if ix > 0 {
+                row = row.child(Label::new("|").size(LabelSize::Small).color(Color::Muted));
+            }
+            row = row.child(
+                Button::new(("conflict-action", ix), label)
+                    .style(ButtonStyle::Transparent)
+                    .label_size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .when(!branch.is_empty(), |button| {
+                        button.tooltip(move |cx| Tooltip::text(branch.clone(), cx))
+                    })
+                    .on_click({
+                        let editor = editor.clone();
+                        let conflict = conflict.clone();
+                        move |_, cx| {
+                            editor
+                                .update(cx, |editor, cx| {
+                                    editor.resolve_conflict(excerpt_id, &conflict, resolution, cx)
+                                })
+                                .ok();
+                        }
+                    }),
+            );
+        }
+        row.into_any_element()
+    })
+}
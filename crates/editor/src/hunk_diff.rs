@@ -6,18 +6,22 @@ use multi_buffer::{
     Anchor, AnchorRangeExt, ExcerptRange, MultiBuffer, MultiBufferDiffHunk, MultiBufferRow,
     MultiBufferSnapshot, ToPoint,
 };
+use project::{Item as _, ProjectPath};
 use std::{ops::Range, sync::Arc};
+use text::OffsetRangeExt as _;
 use ui::{
     prelude::*, ActiveTheme, ContextMenu, IconButtonShape, InteractiveElement, IntoElement,
     ParentElement, PopoverMenu, Styled, Tooltip, ViewContext, VisualContext,
 };
 use util::RangeExt;
+use workspace::notifications::NotifyTaskExt as _;
 
 use crate::{
     editor_settings::CurrentLineHighlight, hunk_status, hunks_for_selections, ApplyDiffHunk,
     BlockDisposition, BlockProperties, BlockStyle, CustomBlockId, DiffRowHighlight, DisplayRow,
     DisplaySnapshot, Editor, EditorElement, ExpandAllHunkDiffs, GoToHunk, GoToPrevHunk, RevertFile,
-    RevertSelectedHunks, ToDisplayPoint, ToggleHunkDiff,
+    RevertSelectedHunks, StageFile, StageSelectedHunks, ToDisplayPoint, ToggleHunkDiff,
+    UnstageFile, UnstageSelectedHunks,
 };
 
 #[derive(Debug, Clone)]
@@ -599,6 +603,15 @@ impl Editor {
                                                                             RevertFile
                                                                                 .boxed_clone(),
                                                                         )
+                                                                        .action(
+                                                                            "Stage File",
+                                                                            StageFile.boxed_clone(),
+                                                                        )
+                                                                        .action(
+                                                                            "Unstage File",
+                                                                            UnstageFile
+                                                                                .boxed_clone(),
+                                                                        )
                                                                 },
                                                             );
                                                             Some(menu)
@@ -921,6 +934,164 @@ impl Editor {
         })
     }
 
+    pub fn stage_selected_hunks(&mut self, _: &StageSelectedHunks, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut hunks_by_buffer = HashMap::<BufferId, Vec<MultiBufferDiffHunk>>::default();
+        for hunk in hunks_for_selections(&snapshot, &self.selections.disjoint_anchors()) {
+            hunks_by_buffer
+                .entry(hunk.buffer_id)
+                .or_default()
+                .push(hunk);
+        }
+
+        let mut tasks = Vec::new();
+        for (buffer_id, hunks) in hunks_by_buffer {
+            let Some(buffer) = self.buffer.read(cx).buffer(buffer_id) else {
+                continue;
+            };
+            let buffer = buffer.read(cx);
+            let (Some(project_path), Some(diff_base)) =
+                (buffer.project_path(cx), buffer.diff_base())
+            else {
+                continue;
+            };
+            let buffer_snapshot = buffer.snapshot();
+            let index_text = git::diff::stage_hunks(
+                diff_base,
+                &buffer_snapshot,
+                hunks.into_iter().map(|hunk| {
+                    let buffer_range = hunk.buffer_range.to_point(&buffer_snapshot);
+                    git::diff::DiffHunk {
+                        row_range: buffer_range.start.row..buffer_range.end.row,
+                        buffer_range: hunk.buffer_range,
+                        diff_base_byte_range: hunk.diff_base_byte_range,
+                    }
+                }),
+            );
+            tasks.push(
+                project
+                    .read(cx)
+                    .set_index_text(project_path, Some(index_text), cx),
+            );
+        }
+
+        cx.spawn(|_, _| async move {
+            for task in tasks {
+                task.await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_notify_err(cx);
+    }
+
+    pub fn unstage_selected_hunks(&mut self, _: &UnstageSelectedHunks, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let mut rows_by_buffer = HashMap::<BufferId, (Model<Buffer>, Vec<Range<u32>>)>::default();
+        for selection in self.selections.all::<usize>(cx) {
+            for (buffer, range, _) in self
+                .buffer
+                .read(cx)
+                .range_to_buffer_ranges(selection.range(), cx)
+            {
+                let buffer_snapshot = buffer.read(cx).snapshot();
+                let rows = range.to_point(&buffer_snapshot);
+                rows_by_buffer
+                    .entry(buffer.read(cx).remote_id())
+                    .or_insert_with(|| (buffer.clone(), Vec::new()))
+                    .1
+                    .push(rows.start.row..rows.end.row + 1);
+            }
+        }
+
+        let mut tasks = Vec::new();
+        for (buffer, buffer_rows) in rows_by_buffer.into_values() {
+            let buffer = buffer.read(cx);
+            let (Some(project_path), Some(diff_base)) =
+                (buffer.project_path(cx), buffer.diff_base())
+            else {
+                continue;
+            };
+            let buffer_snapshot = buffer.snapshot();
+            let index_rows = buffer_rows
+                .into_iter()
+                .map(|rows| {
+                    let hunks = || {
+                        buffer_snapshot
+                            .git_diff_hunks_intersecting_range(text::Anchor::MIN..text::Anchor::MAX)
+                    };
+                    git::diff::buffer_row_to_diff_base_row(hunks(), diff_base, rows.start)
+                        ..git::diff::buffer_row_to_diff_base_row(hunks(), diff_base, rows.end)
+                })
+                .collect::<Vec<_>>();
+            let index_text = diff_base.to_string();
+            let committed_text = project
+                .read(cx)
+                .load_committed_text(project_path.clone(), cx);
+            let project = project.downgrade();
+            tasks.push(cx.spawn(|_, mut cx| async move {
+                let committed_text = committed_text.await?.unwrap_or_default();
+                let unstaged_text =
+                    git::diff::unstage_rows(&committed_text, &index_text, &index_rows);
+                project
+                    .update(&mut cx, |project, cx| {
+                        project.set_index_text(project_path, Some(unstaged_text), cx)
+                    })?
+                    .await
+            }));
+        }
+
+        cx.spawn(|_, _| async move {
+            for task in tasks {
+                task.await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_notify_err(cx);
+    }
+
+    pub fn stage_file(&mut self, _: &StageFile, cx: &mut ViewContext<Self>) {
+        if let Some(project) = self.project.clone() {
+            let paths = self.selected_project_paths(cx);
+            project
+                .read(cx)
+                .stage_paths(paths, cx)
+                .detach_and_notify_err(cx);
+        }
+    }
+
+    pub fn unstage_file(&mut self, _: &UnstageFile, cx: &mut ViewContext<Self>) {
+        if let Some(project) = self.project.clone() {
+            let paths = self.selected_project_paths(cx);
+            project
+                .read(cx)
+                .unstage_paths(paths, cx)
+                .detach_and_notify_err(cx);
+        }
+    }
+
+    fn selected_project_paths(&self, cx: &AppContext) -> Vec<ProjectPath> {
+        let mut paths = Vec::new();
+        for selection in self.selections.all::<usize>(cx) {
+            for (buffer, _, _) in self
+                .buffer
+                .read(cx)
+                .range_to_buffer_ranges(selection.range(), cx)
+            {
+                if let Some(project_path) = buffer.read(cx).project_path(cx) {
+                    if !paths.contains(&project_path) {
+                        paths.push(project_path);
+                    }
+                }
+            }
+        }
+        paths
+    }
+
     fn go_to_subsequent_hunk(&mut self, position: Anchor, cx: &mut ViewContext<Self>) {
         let snapshot = self.snapshot(cx);
         let position = position.to_point(&snapshot.buffer_snapshot);
@@ -1,9 +1,13 @@
 use crate::{
+    display_map::{Crease, CreaseMetadata},
     editor_settings::SeedQuerySetting,
-    persistence::{SerializedEditor, DB},
+    persistence::{
+        SerializedEditor, SerializedEditorState, SerializedRange, SerializedSelection, DB,
+    },
     scroll::ScrollAnchor,
-    Anchor, Autoscroll, Editor, EditorEvent, EditorSettings, ExcerptId, ExcerptRange, MultiBuffer,
-    MultiBufferSnapshot, NavigationData, SearchWithinRange, ToPoint as _,
+    Anchor, Autoscroll, Editor, EditorEvent, EditorMode, EditorSettings, ExcerptId, ExcerptRange,
+    FoldPlaceholder, MultiBuffer, MultiBufferSnapshot, NavigationData, SearchWithinRange,
+    ToPoint as _,
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::HashSet;
@@ -11,14 +15,14 @@ use file_icons::FileIcons;
 use futures::future::try_join_all;
 use git::repository::GitFileStatus;
 use gpui::{
-    point, AnyElement, AppContext, AsyncWindowContext, Context, Entity, EntityId, EventEmitter,
-    IntoElement, Model, ParentElement, Pixels, SharedString, Styled, Task, View, ViewContext,
-    VisualContext, WeakView, WindowContext,
+    point, AnyElement, AppContext, AsyncWindowContext, Context, Empty, Entity, EntityId,
+    EventEmitter, IntoElement, Model, ParentElement, Pixels, SharedString, Styled, Task, View,
+    ViewContext, VisualContext, WeakView, WindowContext,
 };
 use language::{
     proto::serialize_anchor as serialize_text_anchor, Bias, Buffer, CharKind, Point, SelectionGoal,
 };
-use multi_buffer::AnchorRangeExt;
+use multi_buffer::{AnchorRangeExt, MultiBufferRow};
 use project::{
     lsp_store::FormatTrigger, project_settings::ProjectSettings, search::SearchQuery, Item as _,
     Project, ProjectPath,
@@ -39,7 +43,7 @@ use std::{
 };
 use text::{BufferId, Selection};
 use theme::{Theme, ThemeSettings};
-use ui::{h_flex, prelude::*, Label};
+use ui::{h_flex, prelude::*, ButtonLike, Disclosure, ElevationIndex, Label};
 use util::{paths::PathExt, ResultExt, TryFutureExt};
 use workspace::item::{BreadcrumbText, FollowEvent};
 use workspace::{
@@ -50,6 +54,9 @@ use workspace::{
 
 pub const MAX_TAB_TITLE_LEN: usize = 24;
 
+/// The maximum length of the text stored around a persisted range to find it again.
+const MAX_FINGERPRINT_LEN: usize = 128;
+
 impl FollowableItem for Editor {
     fn remote_id(&self) -> Option<ViewId> {
         self.remote_id
@@ -689,10 +696,12 @@ impl Item for Editor {
     fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
         let selection = self.selections.newest_anchor();
         self.push_to_nav_history(selection.head(), None, cx);
+        self.serialize_state(cx);
     }
 
     fn workspace_deactivated(&mut self, cx: &mut ViewContext<Self>) {
         self.hide_hovered_link(cx);
+        self.serialize_state(cx);
     }
 
     fn is_dirty(&self, cx: &AppContext) -> bool {
@@ -1062,6 +1071,7 @@ impl SerializableItem for Editor {
                 cx.new_view(|cx| {
                     let mut editor = Editor::for_buffer(buffer, Some(project), cx);
 
+                    editor.read_state_from_db(item_id, workspace_id, cx);
                     editor.read_scroll_position_from_db(item_id, workspace_id, cx);
                     editor
                 })
@@ -1141,6 +1151,249 @@ impl SerializableItem for Editor {
     }
 }
 
+impl Editor {
+    /// Persists the selections, folds and creases of the editor, unless they didn't change since
+    /// they were last persisted. Called when the editor gets deactivated or saved, rather than on
+    /// every change.
+    pub(crate) fn serialize_state(&mut self, cx: &mut ViewContext<Self>) {
+        if self.mode != EditorMode::Full || self.buffer.read(cx).as_singleton().is_none() {
+            return;
+        }
+        let Some(workspace_id) = self.workspace.as_ref().and_then(|workspace| workspace.1) else {
+            return;
+        };
+        let item_id = cx.view().entity_id().as_u64() as ItemId;
+
+        let state = self.state_to_serialize(cx);
+        if self.serialized_state.as_ref() == Some(&state) {
+            return;
+        }
+        self.serialized_state = Some(state.clone());
+        cx.background_executor()
+            .spawn(async move {
+                DB.save_editor_state(item_id, workspace_id, state)
+                    .await
+                    .log_err();
+            })
+            .detach();
+    }
+
+    fn state_to_serialize(&mut self, cx: &mut ViewContext<Self>) -> SerializedEditorState {
+        let display_snapshot = self
+            .display_map
+            .update(cx, |display_map, cx| display_map.snapshot(cx));
+        let buffer = &display_snapshot.buffer_snapshot;
+
+        let selections = self
+            .selections
+            .all::<usize>(cx)
+            .into_iter()
+            .map(|selection| SerializedSelection {
+                range: serialize_range(selection.range(), buffer),
+                reversed: selection.reversed,
+            })
+            .collect();
+        let folds = display_snapshot
+            .folds_in_range(0..buffer.len())
+            .map(|fold| serialize_range(fold.range.to_offset(buffer), buffer))
+            .collect();
+        // Only creases with metadata can be recreated, as their toggles are rendered by whoever
+        // inserted them.
+        let creases = display_snapshot
+            .crease_snapshot
+            .creases_in_range(MultiBufferRow::MIN..MultiBufferRow::MAX, buffer)
+            .filter_map(|crease| {
+                let metadata = crease.metadata.clone()?;
+                Some((
+                    serialize_range(crease.range.to_offset(buffer), buffer),
+                    metadata,
+                ))
+            })
+            .collect();
+
+        SerializedEditorState {
+            selections,
+            folds,
+            creases,
+        }
+    }
+
+    /// Restores the persisted selections, folds and creases of the editor, dropping those that
+    /// can't be found in the buffer anymore.
+    pub(crate) fn read_state_from_db(
+        &mut self,
+        item_id: ItemId,
+        workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(state) = DB.get_editor_state(item_id, workspace_id).log_err() else {
+            return;
+        };
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let text = buffer.text();
+
+        let creases = state
+            .creases
+            .into_iter()
+            .filter_map(|(range, metadata)| {
+                let range = reanchor_range(&range, &text).filter(|range| !range.is_empty())?;
+                let range = buffer.anchor_after(range.start)..buffer.anchor_before(range.end);
+                Some(restored_crease(range, metadata, cx.view().downgrade()))
+            })
+            .collect::<Vec<_>>();
+
+        let default_placeholder = self.default_fold_placeholder(cx);
+        let folds = state
+            .folds
+            .iter()
+            .filter_map(|range| reanchor_range(range, &text))
+            .filter(|range| !range.is_empty())
+            .map(|range| {
+                // Folded creases keep rendering their own placeholder.
+                let placeholder = creases
+                    .iter()
+                    .find(|crease| crease.range.to_offset(&buffer) == range)
+                    .map_or_else(
+                        || default_placeholder.clone(),
+                        |crease| crease.placeholder.clone(),
+                    );
+                (range, placeholder)
+            })
+            .collect::<Vec<_>>();
+
+        let selections = state
+            .selections
+            .iter()
+            .filter_map(|selection| {
+                let range = reanchor_range(&selection.range, &text)?;
+                Some(if selection.reversed {
+                    range.end..range.start
+                } else {
+                    range
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if !creases.is_empty() {
+            self.insert_creases(creases, cx);
+        }
+        self.fold_ranges(folds, false, cx);
+        if !selections.is_empty() {
+            self.change_selections(None, cx, |s| s.select_ranges(selections));
+        }
+    }
+}
+
+fn restored_crease(
+    range: Range<Anchor>,
+    metadata: CreaseMetadata,
+    editor: WeakView<Editor>,
+) -> Crease {
+    let icon = metadata.icon;
+    let label = metadata.label.clone();
+    Crease::new(
+        range,
+        FoldPlaceholder {
+            render: Arc::new(move |fold_id, fold_range, _cx| {
+                let editor = editor.clone();
+                ButtonLike::new(fold_id)
+                    .style(ButtonStyle::Filled)
+                    .layer(ElevationIndex::ElevatedSurface)
+                    .child(Icon::new(icon))
+                    .child(Label::new(label.clone()).single_line())
+                    .on_click(move |_, cx| {
+                        editor
+                            .update(cx, |editor, cx| {
+                                editor.unfold_ranges(
+                                    [fold_range.start..fold_range.end],
+                                    true,
+                                    false,
+                                    cx,
+                                );
+                            })
+                            .ok();
+                    })
+                    .into_any_element()
+            }),
+            constrain_width: false,
+            merge_adjacent: false,
+        },
+        |row, folded, fold, _cx| {
+            Disclosure::new(("restored-crease-toggle", row.0 as u64), !folded)
+                .selected(folded)
+                .on_click(move |_, cx| fold(!folded, cx))
+        },
+        |_, _, _| Empty,
+    )
+    .with_metadata(metadata)
+}
+
+fn serialize_range(range: Range<usize>, buffer: &MultiBufferSnapshot) -> SerializedRange {
+    let fingerprint = |offset: usize| {
+        let line_start = buffer.point_to_offset(Point::new(buffer.offset_to_point(offset).row, 0));
+        let start = buffer.clip_offset(
+            line_start.max(offset.saturating_sub(MAX_FINGERPRINT_LEN)),
+            Bias::Right,
+        );
+        buffer.text_for_range(start..offset).collect::<String>()
+    };
+
+    SerializedRange {
+        start: range.start,
+        end: range.end,
+        start_fingerprint: fingerprint(range.start),
+        end_fingerprint: fingerprint(range.end),
+    }
+}
+
+/// Finds a persisted range in the given text. If the text preceding the range's boundaries
+/// changed, the boundaries are moved to the closest positions that are preceded by the same text.
+fn reanchor_range(range: &SerializedRange, text: &str) -> Option<Range<usize>> {
+    if range.start <= range.end
+        && follows_fingerprint(text, range.start, &range.start_fingerprint)
+        && follows_fingerprint(text, range.end, &range.end_fingerprint)
+    {
+        return Some(range.start..range.end);
+    }
+
+    let len = range.end.saturating_sub(range.start);
+    let start = fingerprint_positions(text, &range.start_fingerprint)
+        .min_by_key(|start| start.abs_diff(range.start))?;
+    let end = fingerprint_positions(text, &range.end_fingerprint)
+        .filter(|end| *end >= start)
+        .min_by_key(|end| end.abs_diff(start + len))?;
+    // The start might have been found on a line preceding the range, so look for it again relative
+    // to the end.
+    let start = fingerprint_positions(text, &range.start_fingerprint)
+        .filter(|start| *start <= end)
+        .min_by_key(|start| start.abs_diff(end.saturating_sub(len)))?;
+    Some(start..end)
+}
+
+fn follows_fingerprint(text: &str, offset: usize, fingerprint: &str) -> bool {
+    text.get(..offset).is_some_and(|preceding_text| {
+        if fingerprint.is_empty() {
+            preceding_text.is_empty() || preceding_text.ends_with('\n')
+        } else {
+            preceding_text.ends_with(fingerprint)
+        }
+    })
+}
+
+fn fingerprint_positions<'a>(
+    text: &'a str,
+    fingerprint: &'a str,
+) -> Box<dyn 'a + Iterator<Item = usize>> {
+    if fingerprint.is_empty() {
+        Box::new(iter::once(0).chain(text.match_indices('\n').map(|(ix, _)| ix + 1)))
+    } else {
+        Box::new(
+            text.match_indices(fingerprint)
+                .map(move |(ix, _)| ix + fingerprint.len()),
+        )
+    }
+}
+
 impl ProjectItem for Editor {
     type Item = Buffer;
 
@@ -1752,4 +2005,99 @@ mod tests {
             });
         }
     }
+
+    #[gpui::test]
+    async fn test_deserialize_state(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        // The state was stored before a header got prepended to the file.
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file(
+            "/file.rs",
+            "// header\nfn main() {\n    println!(\"hi\");\n}".into(),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/file.rs".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+        let item_id = 4321 as ItemId;
+
+        let serialized_editor = SerializedEditor {
+            path: Some(PathBuf::from("/file.rs")),
+            ..Default::default()
+        };
+        DB.save_serialized_editor(item_id, workspace_id, serialized_editor)
+            .await
+            .unwrap();
+
+        let state = SerializedEditorState {
+            selections: vec![SerializedSelection {
+                range: SerializedRange {
+                    start: 16,
+                    end: 16,
+                    start_fingerprint: "    ".to_string(),
+                    end_fingerprint: "    ".to_string(),
+                },
+                reversed: false,
+            }],
+            folds: vec![
+                SerializedRange {
+                    start: 11,
+                    end: 31,
+                    start_fingerprint: "fn main() {".to_string(),
+                    end_fingerprint: "    println!(\"hi\");".to_string(),
+                },
+                SerializedRange {
+                    start: 0,
+                    end: 11,
+                    start_fingerprint: String::new(),
+                    end_fingerprint: "fn removed() {".to_string(),
+                },
+            ],
+            creases: Vec::new(),
+        };
+        DB.save_editor_state(item_id, workspace_id, state)
+            .await
+            .unwrap();
+
+        let deserialized = deserialize_editor(item_id, workspace_id, workspace, project, cx).await;
+
+        deserialized.update(cx, |editor, cx| {
+            assert_eq!(editor.display_text(cx), "// header\nfn main() {⋯\n}");
+            assert_eq!(
+                editor
+                    .selections
+                    .all::<usize>(cx)
+                    .into_iter()
+                    .map(|selection| selection.range())
+                    .collect::<Vec<_>>(),
+                vec![26..26]
+            );
+        });
+    }
+
+    #[test]
+    fn test_reanchor_range() {
+        let range = SerializedRange {
+            start: 4,
+            end: 9,
+            start_fingerprint: "a = ".to_string(),
+            end_fingerprint: "a = b + c".to_string(),
+        };
+
+        // Unchanged text
+        assert_eq!(reanchor_range(&range, "a = b + c;"), Some(4..9));
+        // Lines inserted before the range
+        assert_eq!(
+            reanchor_range(&range, "x\ny\na = b + c;\na = d;"),
+            Some(8..13)
+        );
+        // The range's text changed, but its surroundings are the same
+        assert_eq!(reanchor_range(&range, "a = d;\na = b + c;"), Some(11..16));
+        // The text preceding the range is gone
+        assert_eq!(reanchor_range(&range, "b = a + c;"), None);
+        // The range is past the end of the text
+        assert_eq!(reanchor_range(&range, "a = b"), None);
+    }
 }
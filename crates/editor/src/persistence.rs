@@ -3,12 +3,15 @@ use db::sqlez::bindable::{Bind, Column, StaticColumnCount};
 use db::sqlez::statement::Statement;
 use std::path::PathBuf;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use util::ResultExt as _;
 
 use db::sqlez_macros::sql;
 use db::{define_connection, query};
 
 use workspace::{ItemId, WorkspaceDb, WorkspaceId};
 
+use crate::display_map::CreaseMetadata;
+
 #[derive(Clone, Debug, PartialEq, Default)]
 pub(crate) struct SerializedEditor {
     pub(crate) path: Option<PathBuf>,
@@ -75,6 +78,31 @@ impl Column for SerializedEditor {
     }
 }
 
+/// The selections, folds and creases of an editor, stored as offsets into its buffer.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub(crate) struct SerializedEditorState {
+    pub(crate) selections: Vec<SerializedSelection>,
+    pub(crate) folds: Vec<SerializedRange>,
+    pub(crate) creases: Vec<(SerializedRange, CreaseMetadata)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SerializedSelection {
+    pub(crate) range: SerializedRange,
+    pub(crate) reversed: bool,
+}
+
+/// A range in a buffer, along with the text preceding its start and following its end on their
+/// lines. The fingerprints are used to find the range again when the buffer changed since it was
+/// stored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SerializedRange {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    pub(crate) start_fingerprint: String,
+    pub(crate) end_fingerprint: String,
+}
+
 define_connection!(
     // Current schema shape using pseudo-rust syntax:
     // editors(
@@ -89,6 +117,35 @@ define_connection!(
     //   mtime_seconds: Option<i64>,
     //   mtime_nanos: Option<i32>,
     // )
+    //
+    // editor_selections(
+    //   editor_id: usize,
+    //   workspace_id: usize,
+    //   start: usize,
+    //   end: usize,
+    //   start_fingerprint: String,
+    //   end_fingerprint: String,
+    //   reversed: bool,
+    // )
+    //
+    // editor_folds(
+    //   editor_id: usize,
+    //   workspace_id: usize,
+    //   start: usize,
+    //   end: usize,
+    //   start_fingerprint: String,
+    //   end_fingerprint: String,
+    // )
+    //
+    // editor_creases(
+    //   editor_id: usize,
+    //   workspace_id: usize,
+    //   start: usize,
+    //   end: usize,
+    //   start_fingerprint: String,
+    //   end_fingerprint: String,
+    //   metadata: String, // JSON-encoded `CreaseMetadata`
+    // )
     pub static ref DB: EditorDb<WorkspaceDb> =
         &[sql! (
             CREATE TABLE editors(
@@ -136,6 +193,45 @@ define_connection!(
             ALTER TABLE editors ADD COLUMN mtime_seconds INTEGER DEFAULT NULL;
             ALTER TABLE editors ADD COLUMN mtime_nanos INTEGER DEFAULT NULL;
         ),
+        sql! (
+            CREATE TABLE editor_selections (
+                editor_id INTEGER NOT NULL,
+                workspace_id INTEGER NOT NULL,
+                start INTEGER NOT NULL,
+                end INTEGER NOT NULL,
+                start_fingerprint TEXT NOT NULL,
+                end_fingerprint TEXT NOT NULL,
+                reversed INTEGER NOT NULL,
+                FOREIGN KEY(editor_id, workspace_id) REFERENCES editors(item_id, workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
+
+            CREATE TABLE editor_folds (
+                editor_id INTEGER NOT NULL,
+                workspace_id INTEGER NOT NULL,
+                start INTEGER NOT NULL,
+                end INTEGER NOT NULL,
+                start_fingerprint TEXT NOT NULL,
+                end_fingerprint TEXT NOT NULL,
+                FOREIGN KEY(editor_id, workspace_id) REFERENCES editors(item_id, workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
+
+            CREATE TABLE editor_creases (
+                editor_id INTEGER NOT NULL,
+                workspace_id INTEGER NOT NULL,
+                start INTEGER NOT NULL,
+                end INTEGER NOT NULL,
+                start_fingerprint TEXT NOT NULL,
+                end_fingerprint TEXT NOT NULL,
+                metadata TEXT NOT NULL,
+                FOREIGN KEY(editor_id, workspace_id) REFERENCES editors(item_id, workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
+        ),
         ];
 );
 
@@ -190,6 +286,153 @@ impl EditorDb {
         }
     }
 
+    pub fn get_editor_state(
+        &self,
+        editor_id: ItemId,
+        workspace_id: WorkspaceId,
+    ) -> Result<SerializedEditorState> {
+        let selections = self.select_bound::<_, (usize, usize, String, String, bool)>(sql!(
+            SELECT start, end, start_fingerprint, end_fingerprint, reversed FROM editor_selections
+            WHERE editor_id = ? AND workspace_id = ?
+            ORDER BY rowid
+        ))?((editor_id, workspace_id))?
+        .into_iter()
+        .map(
+            |(start, end, start_fingerprint, end_fingerprint, reversed)| SerializedSelection {
+                range: SerializedRange {
+                    start,
+                    end,
+                    start_fingerprint,
+                    end_fingerprint,
+                },
+                reversed,
+            },
+        )
+        .collect();
+
+        let folds = self.select_bound::<_, (usize, usize, String, String)>(sql!(
+            SELECT start, end, start_fingerprint, end_fingerprint FROM editor_folds
+            WHERE editor_id = ? AND workspace_id = ?
+            ORDER BY rowid
+        ))?((editor_id, workspace_id))?
+        .into_iter()
+        .map(
+            |(start, end, start_fingerprint, end_fingerprint)| SerializedRange {
+                start,
+                end,
+                start_fingerprint,
+                end_fingerprint,
+            },
+        )
+        .collect();
+
+        let creases = self.select_bound::<_, (usize, usize, String, String, String)>(sql!(
+            SELECT start, end, start_fingerprint, end_fingerprint, metadata FROM editor_creases
+            WHERE editor_id = ? AND workspace_id = ?
+            ORDER BY rowid
+        ))?((editor_id, workspace_id))?
+        .into_iter()
+        .filter_map(
+            |(start, end, start_fingerprint, end_fingerprint, metadata)| {
+                let metadata = serde_json::from_str(&metadata).log_err()?;
+                let range = SerializedRange {
+                    start,
+                    end,
+                    start_fingerprint,
+                    end_fingerprint,
+                };
+                Some((range, metadata))
+            },
+        )
+        .collect();
+
+        Ok(SerializedEditorState {
+            selections,
+            folds,
+            creases,
+        })
+    }
+
+    pub async fn save_editor_state(
+        &self,
+        editor_id: ItemId,
+        workspace_id: WorkspaceId,
+        state: SerializedEditorState,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("save_editor_state", || {
+                // The editor's row is only written once the item got serialized, and there's
+                // nothing to attach the state to before that.
+                let editor_exists = conn.select_row_bound::<_, bool>(sql!(
+                    SELECT 1 FROM editors WHERE item_id = ? AND workspace_id = ?
+                ))?((editor_id, workspace_id))?
+                .is_some();
+                if !editor_exists {
+                    return Ok(());
+                }
+
+                conn.exec_bound(sql!(
+                    DELETE FROM editor_selections WHERE editor_id = ?1 AND workspace_id = ?2;
+                    DELETE FROM editor_folds WHERE editor_id = ?1 AND workspace_id = ?2;
+                    DELETE FROM editor_creases WHERE editor_id = ?1 AND workspace_id = ?2;
+                ))?((editor_id, workspace_id))?;
+
+                let mut insert_selection = conn.exec_bound(sql!(
+                    INSERT INTO editor_selections
+                        (editor_id, workspace_id, start, end, start_fingerprint, end_fingerprint, reversed)
+                    VALUES (?, ?, ?, ?, ?, ?, ?)
+                ))?;
+                for selection in state.selections {
+                    insert_selection((
+                        editor_id,
+                        workspace_id,
+                        selection.range.start,
+                        selection.range.end,
+                        selection.range.start_fingerprint,
+                        selection.range.end_fingerprint,
+                        selection.reversed,
+                    ))?;
+                }
+
+                let mut insert_fold = conn.exec_bound(sql!(
+                    INSERT INTO editor_folds
+                        (editor_id, workspace_id, start, end, start_fingerprint, end_fingerprint)
+                    VALUES (?, ?, ?, ?, ?, ?)
+                ))?;
+                for fold in state.folds {
+                    insert_fold((
+                        editor_id,
+                        workspace_id,
+                        fold.start,
+                        fold.end,
+                        fold.start_fingerprint,
+                        fold.end_fingerprint,
+                    ))?;
+                }
+
+                let mut insert_crease = conn.exec_bound(sql!(
+                    INSERT INTO editor_creases
+                        (editor_id, workspace_id, start, end, start_fingerprint, end_fingerprint, metadata)
+                    VALUES (?, ?, ?, ?, ?, ?, ?)
+                ))?;
+                for (range, metadata) in state.creases {
+                    insert_crease((
+                        editor_id,
+                        workspace_id,
+                        range.start,
+                        range.end,
+                        range.start_fingerprint,
+                        range.end_fingerprint,
+                        serde_json::to_string(&metadata)?,
+                    ))?;
+                }
+
+                Ok(())
+            })
+        })
+        .await
+    }
+
     pub async fn delete_unloaded_items(
         &self,
         workspace: WorkspaceId,
@@ -297,4 +540,95 @@ mod tests {
             .unwrap();
         assert_eq!(have, serialized_editor);
     }
+
+    #[gpui::test]
+    async fn test_save_and_get_editor_state() {
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+
+        let state = SerializedEditorState {
+            selections: vec![
+                SerializedSelection {
+                    range: SerializedRange {
+                        start: 0,
+                        end: 4,
+                        start_fingerprint: String::new(),
+                        end_fingerprint: "use ".to_owned(),
+                    },
+                    reversed: false,
+                },
+                SerializedSelection {
+                    range: SerializedRange {
+                        start: 10,
+                        end: 12,
+                        start_fingerprint: "use std::".to_owned(),
+                        end_fingerprint: "use std::fs".to_owned(),
+                    },
+                    reversed: true,
+                },
+            ],
+            folds: vec![SerializedRange {
+                start: 14,
+                end: 30,
+                start_fingerprint: "fn main() {".to_owned(),
+                end_fingerprint: "}".to_owned(),
+            }],
+            creases: vec![(
+                SerializedRange {
+                    start: 40,
+                    end: 50,
+                    start_fingerprint: String::new(),
+                    end_fingerprint: String::new(),
+                },
+                CreaseMetadata {
+                    icon: ui::IconName::File,
+                    label: "main.rs".into(),
+                },
+            )],
+        };
+
+        // Nothing is stored for an editor that wasn't serialized yet
+        DB.save_editor_state(5678, workspace_id, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_editor_state(5678, workspace_id).unwrap(),
+            SerializedEditorState::default()
+        );
+
+        DB.save_serialized_editor(5678, workspace_id, SerializedEditor::default())
+            .await
+            .unwrap();
+        DB.save_editor_state(5678, workspace_id, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(DB.get_editor_state(5678, workspace_id).unwrap(), state);
+
+        // Saving the state again replaces the previous one
+        let state = SerializedEditorState {
+            selections: vec![SerializedSelection {
+                range: SerializedRange {
+                    start: 3,
+                    end: 3,
+                    start_fingerprint: "use".to_owned(),
+                    end_fingerprint: "use".to_owned(),
+                },
+                reversed: false,
+            }],
+            folds: Vec::new(),
+            creases: Vec::new(),
+        };
+        DB.save_editor_state(5678, workspace_id, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(DB.get_editor_state(5678, workspace_id).unwrap(), state);
+
+        // The state is removed along with the editor
+        DB.delete_unloaded_items(workspace_id, Vec::new())
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_editor_state(5678, workspace_id).unwrap(),
+            SerializedEditorState::default()
+        );
+    }
 }
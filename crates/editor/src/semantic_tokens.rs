@@ -0,0 +1,99 @@
+use std::{sync::Arc, time::Duration};
+
+use collections::HashMap;
+use futures::future::join_all;
+use language::{language_settings::language_settings, HighlightId};
+use theme::ActiveTheme;
+use ui::ViewContext;
+use util::ResultExt;
+
+use crate::{Editor, EditorMode};
+
+/// How long to wait after the last edit before requesting new semantic tokens.
+const SEMANTIC_TOKENS_DEBOUNCE: Duration = Duration::from_millis(150);
+
+impl Editor {
+    /// Requests the semantic tokens of the editor's buffers that have them enabled in their language settings.
+    /// The buffers are highlighted with the new tokens as soon as they arrive.
+    pub(crate) fn refresh_semantic_tokens(&mut self, debounce: bool, cx: &mut ViewContext<Self>) {
+        if self.mode != EditorMode::Full {
+            return;
+        }
+        let Some(provider) = self.semantics_provider.clone() else {
+            return;
+        };
+        let buffers = self
+            .buffer
+            .read(cx)
+            .all_buffers()
+            .into_iter()
+            .filter(|buffer| {
+                let buffer = buffer.read(cx);
+                language_settings(buffer.language(), buffer.file(), cx).semantic_tokens
+            })
+            .collect::<Vec<_>>();
+        if buffers.is_empty() {
+            self.semantic_tokens_task = None;
+            self.update_semantic_token_highlights(cx);
+            return;
+        }
+
+        self.semantic_tokens_task = Some(cx.spawn(|editor, mut cx| async move {
+            if debounce {
+                cx.background_executor()
+                    .timer(SEMANTIC_TOKENS_DEBOUNCE)
+                    .await;
+            }
+            let Some(tasks) = editor
+                .update(&mut cx, |_, cx| {
+                    buffers
+                        .into_iter()
+                        .filter_map(|buffer| provider.semantic_tokens(buffer, cx))
+                        .collect::<Vec<_>>()
+                })
+                .ok()
+            else {
+                return;
+            };
+            for result in join_all(tasks).await {
+                result.log_err();
+            }
+        }));
+    }
+
+    /// Resolves the semantic tokens of the editor's buffers against the current syntax theme,
+    /// and replaces the tree-sitter highlights of the tokens that the theme has a style for.
+    pub(crate) fn update_semantic_token_highlights(&mut self, cx: &mut ViewContext<Self>) {
+        let syntax_theme = cx.theme().syntax().clone();
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut highlight_ids = HashMap::<Arc<str>, HighlightId>::default();
+        let mut highlights = Vec::new();
+        for (excerpt_id, buffer, excerpt_range) in snapshot.excerpts() {
+            if !language_settings(buffer.language(), buffer.file(), cx).semantic_tokens {
+                continue;
+            }
+            for token in buffer.semantic_tokens_in_range(excerpt_range.context) {
+                let highlight_id = *highlight_ids
+                    .entry(token.highlight_name.clone())
+                    .or_insert_with(|| {
+                        HighlightId::for_capture_name(&token.highlight_name, &syntax_theme)
+                    });
+                if highlight_id.style(&syntax_theme).is_none() {
+                    continue;
+                }
+                let (Some(start), Some(end)) = (
+                    snapshot.anchor_in_excerpt(excerpt_id, token.range.start),
+                    snapshot.anchor_in_excerpt(excerpt_id, token.range.end),
+                ) else {
+                    continue;
+                };
+                highlights.push((start..end, highlight_id));
+            }
+        }
+
+        self.display_map.update(cx, |display_map, _| {
+            display_map.set_semantic_token_highlights(highlights.into())
+        });
+        cx.notify();
+    }
+}
@@ -0,0 +1,101 @@
+use std::path::Path;
+
+use chrono::Local;
+use gpui::{AppContext, Model};
+use language::{File, Point};
+use multi_buffer::{MultiBufferRow, ToPoint as _};
+use project::Worktree;
+use rand::Rng as _;
+use uuid::Uuid;
+
+use crate::Editor;
+
+/// Resolves a snippet variable like `$TM_FILENAME` at the editor's newest selection, following the
+/// variables VS Code supports: https://code.visualstudio.com/docs/editor/userdefinedsnippets#_variables
+///
+/// Returns `None` for unknown variables, and an empty string for known variables that have no value
+/// here, like `$TM_FILEPATH` in an untitled buffer.
+pub(crate) fn snippet_variable(editor: &Editor, name: &str, cx: &AppContext) -> Option<String> {
+    let snapshot = editor.buffer.read(cx).snapshot(cx);
+    let selection = editor.selections.newest::<usize>(cx);
+    let row = selection.head().to_point(&snapshot).row;
+    let file = snapshot.file_at(selection.head());
+    let now = Local::now();
+
+    let value = match name {
+        "TM_SELECTED_TEXT" => Some(snapshot.text_for_range(selection.range()).collect()),
+        "TM_CURRENT_LINE" => {
+            let line_end = Point::new(row, snapshot.line_len(MultiBufferRow(row)));
+            Some(
+                snapshot
+                    .text_for_range(Point::new(row, 0)..line_end)
+                    .collect(),
+            )
+        }
+        "TM_CURRENT_WORD" => {
+            let (word_range, _) = snapshot.surrounding_word(selection.head(), false);
+            Some(snapshot.text_for_range(word_range).collect())
+        }
+        "TM_LINE_INDEX" => Some(row.to_string()),
+        "TM_LINE_NUMBER" => Some((row + 1).to_string()),
+        "TM_FILENAME" => file.map(|file| file.file_name(cx).to_string_lossy().into_owned()),
+        "TM_FILENAME_BASE" => file.and_then(|file| {
+            let file_stem = Path::new(file.file_name(cx)).file_stem()?;
+            Some(file_stem.to_string_lossy().into_owned())
+        }),
+        "TM_DIRECTORY" => file.and_then(|file| {
+            let abs_path = file.as_local()?.abs_path(cx);
+            Some(abs_path.parent()?.to_string_lossy().into_owned())
+        }),
+        "TM_FILEPATH" => {
+            file.and_then(|file| Some(file.as_local()?.abs_path(cx).to_string_lossy().into_owned()))
+        }
+        "RELATIVE_FILEPATH" => file.map(|file| file.path().to_string_lossy().into_owned()),
+        "CLIPBOARD" => cx.read_from_clipboard().and_then(|item| item.text()),
+        "WORKSPACE_NAME" => file
+            .and_then(|file| worktree(editor, file.as_ref(), cx))
+            .map(|worktree| worktree.read(cx).root_name().to_string()),
+        "WORKSPACE_FOLDER" => file
+            .and_then(|file| worktree(editor, file.as_ref(), cx))
+            .map(|worktree| worktree.read(cx).abs_path().to_string_lossy().into_owned()),
+        "CURRENT_YEAR" => Some(now.format("%Y").to_string()),
+        "CURRENT_YEAR_SHORT" => Some(now.format("%y").to_string()),
+        "CURRENT_MONTH" => Some(now.format("%m").to_string()),
+        "CURRENT_MONTH_NAME" => Some(now.format("%B").to_string()),
+        "CURRENT_MONTH_NAME_SHORT" => Some(now.format("%b").to_string()),
+        "CURRENT_DATE" => Some(now.format("%d").to_string()),
+        "CURRENT_DAY_NAME" => Some(now.format("%A").to_string()),
+        "CURRENT_DAY_NAME_SHORT" => Some(now.format("%a").to_string()),
+        "CURRENT_HOUR" => Some(now.format("%H").to_string()),
+        "CURRENT_MINUTE" => Some(now.format("%M").to_string()),
+        "CURRENT_SECOND" => Some(now.format("%S").to_string()),
+        "CURRENT_SECONDS_UNIX" => Some(now.timestamp().to_string()),
+        "CURRENT_TIMEZONE_OFFSET" => Some(now.format("%:z").to_string()),
+        "RANDOM" => Some(format!("{:06}", rand::thread_rng().gen_range(0..1_000_000))),
+        "RANDOM_HEX" => Some(format!(
+            "{:06x}",
+            rand::thread_rng().gen_range(0..0x1000000)
+        )),
+        "UUID" => Some(Uuid::new_v4().to_string()),
+        "LINE_COMMENT" => snapshot
+            .language_scope_at(selection.head())
+            .and_then(|scope| Some(scope.line_comment_prefixes().first()?.trim().to_string())),
+        "BLOCK_COMMENT_START" => snapshot
+            .language_scope_at(selection.head())
+            .and_then(|scope| Some(scope.block_comment_delimiters()?.0.trim().to_string())),
+        "BLOCK_COMMENT_END" => snapshot
+            .language_scope_at(selection.head())
+            .and_then(|scope| Some(scope.block_comment_delimiters()?.1.trim().to_string())),
+        _ => return None,
+    };
+
+    Some(value.unwrap_or_default())
+}
+
+fn worktree(editor: &Editor, file: &dyn File, cx: &AppContext) -> Option<Model<Worktree>> {
+    editor
+        .project
+        .as_ref()?
+        .read(cx)
+        .worktree_for_id(file.worktree_id(cx), cx)
+}
@@ -150,6 +150,24 @@ impl EditorLspTestContext {
                 ("<" @open ">" @close)
                 ("\"" @open "\"" @close)
                 (closure_parameters "|" @open "|" @close)"#})),
+            text_objects: Some(Cow::from(indoc! {r#"
+                (function_item
+                    body: (_ "{" _* @function.inside "}")) @function.around
+                (impl_item
+                    body: (_ "{" _* @class.inside "}")) @class.around
+                (line_comment)+ @comment.around
+                (parameters (_) @parameter.inside @parameter.around . "," @parameter.around)
+                (parameters "," @parameter.around . (_) @parameter.inside @parameter.around .)
+                (parameters . (_) @parameter.inside @parameter.around .)
+                (arguments (_) @parameter.inside @parameter.around . "," @parameter.around)
+                (arguments "," @parameter.around . (_) @parameter.inside @parameter.around .)
+                (arguments . (_) @parameter.inside @parameter.around .)
+                (type_parameters (_) @parameter.inside @parameter.around . "," @parameter.around)
+                (type_parameters "," @parameter.around . (_) @parameter.inside @parameter.around .)
+                (type_parameters . (_) @parameter.inside @parameter.around .)
+                (tuple_type (_) @parameter.inside @parameter.around . "," @parameter.around)
+                (tuple_type "," @parameter.around . (_) @parameter.inside @parameter.around .)
+                (tuple_type . (_) @parameter.inside @parameter.around .)"#})),
             ..Default::default()
         })
         .expect("Could not parse queries");
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use futures::FutureExt;
+use git_hosting_providers::{
+    CustomGitHostingProvider, GitHostingProviderConfig, GitHostingProviderKind,
+};
+
+use crate::wasm_host::{wit, WasmExtension};
+
+/// Asks the extension for the configuration of one of the Git hosting
+/// providers declared in its manifest.
+pub(crate) async fn load_git_hosting_provider(
+    extension: &WasmExtension,
+    provider_id: Arc<str>,
+) -> Result<CustomGitHostingProvider> {
+    let config = extension
+        .call(|extension, store| {
+            async move {
+                extension
+                    .call_describe_git_hosting_provider(store, provider_id.as_ref())
+                    .await?
+                    .map_err(|err| anyhow!("{err:?}"))
+            }
+            .boxed()
+        })
+        .await?;
+
+    CustomGitHostingProvider::new(GitHostingProviderConfig {
+        name: config.name,
+        host: config.host,
+        kind: match config.kind {
+            wit::GitHostingProviderKind::Github => GitHostingProviderKind::Github,
+            wit::GitHostingProviderKind::Gitlab => GitHostingProviderKind::Gitlab,
+            wit::GitHostingProviderKind::Gitea => GitHostingProviderKind::Gitea,
+            wit::GitHostingProviderKind::Bitbucket => GitHostingProviderKind::Bitbucket,
+        },
+        base_url: config.base_url,
+        permalink_url: config.permalink_url,
+        commit_url: config.commit_url,
+        pull_request_url: config.pull_request_url,
+    })
+}
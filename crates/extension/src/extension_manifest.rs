@@ -79,6 +79,8 @@ pub struct ExtensionManifest {
     #[serde(default)]
     pub indexed_docs_providers: BTreeMap<Arc<str>, IndexedDocsProviderEntry>,
     #[serde(default)]
+    pub git_hosting_providers: BTreeMap<Arc<str>, GitHostingProviderEntry>,
+    #[serde(default)]
     pub snippets: Option<PathBuf>,
 }
 
@@ -143,6 +145,9 @@ pub struct SlashCommandManifestEntry {
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct IndexedDocsProviderEntry {}
 
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct GitHostingProviderEntry {}
+
 impl ExtensionManifest {
     pub async fn load(fs: Arc<dyn Fs>, extension_dir: &Path) -> Result<Self> {
         let extension_name = extension_dir
@@ -207,6 +212,7 @@ fn manifest_from_old_manifest(
         language_servers: Default::default(),
         slash_commands: BTreeMap::default(),
         indexed_docs_providers: BTreeMap::default(),
+        git_hosting_providers: BTreeMap::default(),
         snippets: None,
     }
 }
@@ -1,4 +1,5 @@
 pub mod extension_builder;
+mod extension_git_hosting_provider;
 mod extension_indexed_docs_provider;
 mod extension_lsp_adapter;
 mod extension_manifest;
@@ -9,6 +10,7 @@ mod wasm_host;
 #[cfg(test)]
 mod extension_store_test;
 
+use crate::extension_git_hosting_provider::load_git_hosting_provider;
 use crate::extension_indexed_docs_provider::ExtensionIndexedDocsProvider;
 use crate::extension_manifest::SchemaVersion;
 use crate::extension_slash_command::ExtensionSlashCommand;
@@ -18,7 +20,7 @@ use assistant_slash_command::SlashCommandRegistry;
 use async_compression::futures::bufread::GzipDecoder;
 use async_tar::Archive;
 use client::{telemetry::Telemetry, Client, ExtensionMetadata, GetExtensionsResponse};
-use collections::{btree_map, BTreeMap, HashSet};
+use collections::{btree_map, BTreeMap, HashMap, HashSet};
 use extension_builder::{CompileExtensionOptions, ExtensionBuilder};
 use fs::{Fs, RemoveOptions};
 use futures::{
@@ -29,6 +31,7 @@ use futures::{
     io::BufReader,
     select_biased, AsyncReadExt as _, Future, FutureExt as _, StreamExt as _,
 };
+use git::{GitHostingProvider as _, GitHostingProviderRegistry};
 use gpui::{
     actions, AppContext, AsyncAppContext, Context, EventEmitter, Global, Model, ModelContext, Task,
     WeakModel,
@@ -121,6 +124,8 @@ pub struct ExtensionStore {
     modified_extensions: HashSet<Arc<str>>,
     wasm_host: Arc<WasmHost>,
     wasm_extensions: Vec<(Arc<ExtensionManifest>, WasmExtension)>,
+    /// The names of the Git hosting providers registered by each extension.
+    git_hosting_providers: HashMap<Arc<str>, Vec<String>>,
     tasks: Vec<Task<()>>,
 }
 
@@ -259,6 +264,7 @@ impl ExtensionStore {
                 cx,
             ),
             wasm_extensions: Vec::new(),
+            git_hosting_providers: HashMap::default(),
             fs,
             http_client,
             telemetry,
@@ -1049,6 +1055,16 @@ impl ExtensionStore {
 
         self.wasm_extensions
             .retain(|(extension, _)| !extensions_to_unload.contains(&extension.id));
+        let git_hosting_provider_registry = GitHostingProviderRegistry::default_global(cx);
+        for extension_id in &extensions_to_unload {
+            for name in self
+                .git_hosting_providers
+                .remove(extension_id)
+                .unwrap_or_default()
+            {
+                git_hosting_provider_registry.unregister_hosting_provider(&name);
+            }
+        }
         self.theme_registry.remove_user_themes(&themes_to_remove);
         self.language_registry
             .remove_languages(&languages_to_remove, &grammars_to_remove);
@@ -1153,6 +1169,7 @@ impl ExtensionStore {
                 .await;
 
             let mut wasm_extensions = Vec::new();
+            let mut git_hosting_providers = Vec::new();
             for extension in extension_entries {
                 if extension.manifest.lib.kind.is_none() {
                     continue;
@@ -1185,6 +1202,18 @@ impl ExtensionStore {
                 .await;
 
                 if let Some(wasm_extension) = wasm_extension.log_err() {
+                    for provider_id in extension.manifest.git_hosting_providers.keys() {
+                        let extension_id = &extension.manifest.id;
+                        let provider =
+                            load_git_hosting_provider(&wasm_extension, provider_id.clone())
+                                .await
+                                .with_context(|| {
+                                    format!("failed to load git hosting provider {provider_id} of extension {extension_id}")
+                                });
+                        if let Some(provider) = provider.log_err() {
+                            git_hosting_providers.push((extension_id.clone(), provider));
+                        }
+                    }
                     wasm_extensions.push((extension.manifest.clone(), wasm_extension));
                 } else {
                     this.update(&mut cx, |_, cx| {
@@ -1245,6 +1274,22 @@ impl ExtensionStore {
                     }
                 }
 
+                let git_hosting_provider_registry = GitHostingProviderRegistry::default_global(cx);
+                for (extension_id, provider) in git_hosting_providers {
+                    let name = provider.name();
+                    // Providers named like one that is already registered are rejected.
+                    if git_hosting_provider_registry
+                        .try_register_hosting_provider(Arc::new(provider))
+                        .log_err()
+                        .is_some()
+                    {
+                        this.git_hosting_providers
+                            .entry(extension_id)
+                            .or_default()
+                            .push(name);
+                    }
+                }
+
                 this.wasm_extensions.extend(wasm_extensions);
                 ThemeSettings::reload_current_theme(cx)
             })
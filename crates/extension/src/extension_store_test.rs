@@ -163,6 +163,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         language_servers: BTreeMap::default(),
                         slash_commands: BTreeMap::default(),
                         indexed_docs_providers: BTreeMap::default(),
+                        git_hosting_providers: BTreeMap::default(),
                         snippets: None,
                     }),
                     dev: false,
@@ -189,6 +190,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         language_servers: BTreeMap::default(),
                         slash_commands: BTreeMap::default(),
                         indexed_docs_providers: BTreeMap::default(),
+                        git_hosting_providers: BTreeMap::default(),
                         snippets: None,
                     }),
                     dev: false,
@@ -353,6 +355,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                 language_servers: BTreeMap::default(),
                 slash_commands: BTreeMap::default(),
                 indexed_docs_providers: BTreeMap::default(),
+                git_hosting_providers: BTreeMap::default(),
                 snippets: None,
             }),
             dev: false,
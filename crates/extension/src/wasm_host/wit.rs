@@ -20,6 +20,7 @@ use wasmtime::{
 #[cfg(test)]
 pub use latest::CodeLabelSpanLiteral;
 pub use latest::{
+    zed::extension::git_hosting_provider::{GitHostingProviderConfig, GitHostingProviderKind},
     zed::extension::lsp::{Completion, CompletionKind, InsertTextFormat, Symbol, SymbolKind},
     zed::extension::slash_command::{SlashCommandArgumentCompletion, SlashCommandOutput},
     CodeLabel, CodeLabelSpan, Command, Range, SlashCommand,
@@ -392,6 +393,24 @@ impl Extension {
             }
         }
     }
+
+    pub async fn call_describe_git_hosting_provider(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+    ) -> Result<Result<GitHostingProviderConfig, String>> {
+        match self {
+            Extension::V020(ext) => {
+                ext.call_describe_git_hosting_provider(store, provider_id)
+                    .await
+            }
+            Extension::V001(_) | Extension::V004(_) | Extension::V006(_) | Extension::V010(_) => {
+                Err(anyhow!(
+                    "`describe_git_hosting_provider` not available prior to v0.2.0"
+                ))
+            }
+        }
+    }
 }
 
 trait ToWasmtimeResult<T> {
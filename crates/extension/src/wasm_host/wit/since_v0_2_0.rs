@@ -379,6 +379,9 @@ impl platform::Host for WasmState {
     }
 }
 
+#[async_trait]
+impl git_hosting_provider::Host for WasmState {}
+
 #[async_trait]
 impl slash_command::Host for WasmState {}
 
@@ -15,6 +15,7 @@ pub use serde_json;
 // that we may want to shadow to provide a cleaner Rust API.
 pub use wit::{
     download_file, make_file_executable,
+    zed::extension::git_hosting_provider::{GitHostingProviderConfig, GitHostingProviderKind},
     zed::extension::github::{
         github_release_by_tag_name, latest_github_release, GithubRelease, GithubReleaseAsset,
         GithubReleaseOptions,
@@ -147,6 +148,15 @@ pub trait Extension: Send + Sync {
     ) -> Result<(), String> {
         Err("`index_docs` not implemented".to_string())
     }
+
+    /// Returns the Git hosting provider with the given ID, as declared in the
+    /// `git_hosting_providers` section of the extension manifest.
+    fn describe_git_hosting_provider(
+        &self,
+        _provider_id: String,
+    ) -> Result<GitHostingProviderConfig, String> {
+        Err("`describe_git_hosting_provider` not implemented".to_string())
+    }
 }
 
 /// Registers the provided type as a Zed extension.
@@ -281,6 +291,12 @@ impl wit::Guest for Component {
     ) -> Result<(), String> {
         extension().index_docs(provider, package, database)
     }
+
+    fn describe_git_hosting_provider(
+        provider_id: String,
+    ) -> Result<GitHostingProviderConfig, String> {
+        extension().describe_git_hosting_provider(provider_id)
+    }
 }
 
 /// The ID of a language server.
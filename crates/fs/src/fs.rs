@@ -1199,6 +1199,17 @@ impl FakeFs {
         });
     }
 
+    pub fn set_head_for_repo(&self, dot_git: &Path, head_state: &[(&Path, String)]) {
+        self.with_git_state(dot_git, true, |state| {
+            state.head_contents.clear();
+            state.head_contents.extend(
+                head_state
+                    .iter()
+                    .map(|(path, content)| (path.to_path_buf(), content.clone())),
+            );
+        });
+    }
+
     pub fn set_blame_for_repo(&self, dot_git: &Path, blames: Vec<(&Path, git::blame::Blame)>) {
         self.with_git_state(dot_git, true, |state| {
             state.blames.clear();
@@ -1210,6 +1221,25 @@ impl FakeFs {
         });
     }
 
+    pub fn set_commits_for_repo(&self, dot_git: &Path, commits: Vec<git::commit::CommitDetails>) {
+        self.with_git_state(dot_git, true, |state| state.commits = commits);
+    }
+
+    pub fn set_conflict_stages_for_repo(
+        &self,
+        dot_git: &Path,
+        stages: &[(&Path, git::conflict::ConflictStages)],
+    ) {
+        self.with_git_state(dot_git, true, |state| {
+            state.conflict_stages.clear();
+            state.conflict_stages.extend(
+                stages
+                    .iter()
+                    .map(|(path, stages)| ((**path).into(), stages.clone())),
+            );
+        });
+    }
+
     pub fn set_status_for_repo_via_working_copy_change(
         &self,
         dot_git: &Path,
@@ -1349,6 +1379,16 @@ impl FakeFsEntry {
         }
     }
 
+    fn descendant(entry: &Arc<Mutex<Self>>, path: &Path) -> Option<Arc<Mutex<Self>>> {
+        let mut entry = entry.clone();
+        for component in path.components() {
+            let name = component.as_os_str().to_str()?;
+            let child = entry.lock().dir_entries(path).ok()?.get(name)?.clone();
+            entry = child;
+        }
+        Some(entry)
+    }
+
     fn dir_entries(
         &mut self,
         path: &Path,
@@ -1820,12 +1860,21 @@ impl Fs for FakeFs {
     fn open_repo(&self, abs_dot_git: &Path) -> Option<Arc<dyn GitRepository>> {
         let state = self.state.lock();
         let entry = state.read_path(abs_dot_git).unwrap();
+        let work_directory = state.read_path(abs_dot_git.parent()?).ok()?;
         let mut entry = entry.lock();
         if let FakeFsEntry::Dir { git_repo_state, .. } = &mut *entry {
             let state = git_repo_state
                 .get_or_insert_with(|| Arc::new(Mutex::new(FakeGitRepositoryState::default())))
                 .clone();
-            Some(git::repository::FakeGitRepository::open(state))
+            Some(git::repository::FakeGitRepository::open(
+                state,
+                move |repo_path| {
+                    let entry = FakeFsEntry::descendant(&work_directory, repo_path)?;
+                    let entry = entry.lock();
+                    let content = entry.file_content(repo_path).ok()?;
+                    String::from_utf8(content.clone()).ok()
+                },
+            ))
         } else {
             None
         }
@@ -1,5 +1,6 @@
+use crate::repository::RepoPath;
 use crate::Oid;
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use collections::HashMap;
 use std::path::Path;
 use std::process::Command;
@@ -47,3 +48,146 @@ pub fn get_messages(working_directory: &Path, shas: &[Oid]) -> Result<HashMap<Oi
         )
         .collect::<HashMap<Oid, String>>())
 }
+
+/// A commit, as listed in the log of a repository.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitSummary {
+    pub sha: Oid,
+    pub author_name: String,
+    pub author_email: String,
+    /// When the commit was authored, in seconds since the Unix epoch.
+    pub timestamp: i64,
+    /// The first line of the commit message.
+    pub subject: String,
+}
+
+/// A page of the log of a repository.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommitLog {
+    pub commits: Vec<CommitSummary>,
+    /// The URL of the repository's `origin` remote, used to link commits to their hosting
+    /// provider.
+    pub remote_url: Option<String>,
+}
+
+/// The largest file, in bytes, whose contents are loaded to show the changes of a commit.
+pub const MAX_COMMIT_FILE_SIZE: usize = 1024 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitDetails {
+    pub summary: CommitSummary,
+    pub message: String,
+    /// The changes to the text files among the requested page of the files changed by the
+    /// commit, compared to its first parent. Binary files, and files larger than
+    /// [`MAX_COMMIT_FILE_SIZE`], are left out.
+    pub changes: Vec<CommitFileChange>,
+    /// The number of files changed by the commit.
+    pub changed_file_count: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitFileChange {
+    pub path: RepoPath,
+    /// The contents of the file before the commit, or `None` if the commit added it.
+    pub old_text: Option<String>,
+    /// The contents of the file after the commit, or `None` if the commit deleted it.
+    pub new_text: Option<String>,
+}
+
+const LOG_RECORD_SEPARATOR: char = '\x1e';
+const LOG_FIELD_SEPARATOR: char = '\0';
+
+pub(crate) fn run_git_log(
+    git_binary: &Path,
+    working_directory: &Path,
+    path: Option<&Path>,
+    skip: usize,
+    limit: usize,
+) -> Result<String> {
+    let mut command = Command::new(git_binary);
+
+    command
+        .current_dir(working_directory)
+        .arg("log")
+        .arg("--format=%x1e%H%x00%an%x00%ae%x00%at%x00%s")
+        .arg(format!("--skip={}", skip))
+        .arg(format!("--max-count={}", limit))
+        .arg("--");
+    if let Some(path) = path {
+        command.arg(path.as_os_str());
+    }
+
+    #[cfg(windows)]
+    command.creation_flags(windows::Win32::System::Threading::CREATE_NO_WINDOW.0);
+
+    let output = command
+        .output()
+        .map_err(|e| anyhow!("Failed to start git log process: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("git log process failed: {}", stderr));
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+pub(crate) fn parse_git_log(output: &str) -> Result<Vec<CommitSummary>> {
+    output
+        .split(LOG_RECORD_SEPARATOR)
+        .filter(|record| !record.trim().is_empty())
+        .map(|record| {
+            let mut fields = record.trim_end_matches('\n').split(LOG_FIELD_SEPARATOR);
+            let mut next_field = |name: &str| {
+                fields
+                    .next()
+                    .with_context(|| format!("missing {} in git log record {:?}", name, record))
+            };
+            Ok(CommitSummary {
+                sha: next_field("sha")?.parse()?,
+                author_name: next_field("author name")?.to_string(),
+                author_email: next_field("author email")?.to_string(),
+                timestamp: next_field("timestamp")?
+                    .parse()
+                    .context("failed to parse commit timestamp")?,
+                subject: next_field("subject")?.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_git_log() {
+        let output = concat!(
+            "\x1e0f2dd4b0f8bd2b5b7ccd1c4e1c5b9d1a3e3c1b7f\0Jane Doe\0jane@example.com\01700000000\0Fix the frobnicator\n",
+            "\x1e3a6c1a7e2d8b4f5c9e0d1b2a3c4d5e6f7a8b9c0d\0John Doe\0john@example.com\01690000000\0Initial commit\n",
+        );
+        let commits = parse_git_log(output).unwrap();
+        assert_eq!(
+            commits,
+            vec![
+                CommitSummary {
+                    sha: "0f2dd4b0f8bd2b5b7ccd1c4e1c5b9d1a3e3c1b7f".parse().unwrap(),
+                    author_name: "Jane Doe".into(),
+                    author_email: "jane@example.com".into(),
+                    timestamp: 1700000000,
+                    subject: "Fix the frobnicator".into(),
+                },
+                CommitSummary {
+                    sha: "3a6c1a7e2d8b4f5c9e0d1b2a3c4d5e6f7a8b9c0d".parse().unwrap(),
+                    author_name: "John Doe".into(),
+                    author_email: "john@example.com".into(),
+                    timestamp: 1690000000,
+                    subject: "Initial commit".into(),
+                },
+            ]
+        );
+
+        assert_eq!(parse_git_log("").unwrap(), Vec::new());
+        assert!(parse_git_log("\x1enot a sha\0a\0b\0c\0d").is_err());
+    }
+}
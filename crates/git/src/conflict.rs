@@ -0,0 +1,230 @@
+use std::ops::Range;
+
+use rope::Rope;
+
+/// The contents of a conflicted path at the index stages of a merge.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConflictStages {
+    /// The contents of the path in the merge base (stage 1).
+    pub base: Option<String>,
+    /// The contents of the path on the current branch (stage 2).
+    pub ours: Option<String>,
+    /// The contents of the path on the branch being merged (stage 3).
+    pub theirs: Option<String>,
+}
+
+/// A region of a file delimited by conflict markers, with byte ranges into the file's contents.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConflictRegion {
+    /// The whole region, from the start of its `<<<<<<<` line to the end of its `>>>>>>>` line.
+    pub range: Range<usize>,
+    /// The lines between the `<<<<<<<` marker and the `|||||||` or `=======` marker.
+    pub ours: Range<usize>,
+    /// The lines between the `|||||||` and `=======` markers, if the region has them.
+    pub base: Option<Range<usize>>,
+    /// The lines between the `=======` and `>>>>>>>` markers.
+    pub theirs: Range<usize>,
+    pub ours_label: String,
+    pub theirs_label: String,
+}
+
+enum ParseState {
+    Idle,
+    Ours {
+        start: usize,
+        ours_start: usize,
+        ours_label: String,
+    },
+    Base {
+        start: usize,
+        ours: Range<usize>,
+        base_start: usize,
+        ours_label: String,
+    },
+    Theirs {
+        start: usize,
+        ours: Range<usize>,
+        base: Option<Range<usize>>,
+        theirs_start: usize,
+        ours_label: String,
+    },
+}
+
+/// Finds the regions delimited by the conflict markers git writes when a merge conflicts,
+/// in both the default and the `diff3` conflict styles. Incomplete regions are ignored.
+pub fn parse_conflict_markers(text: &Rope) -> Vec<ConflictRegion> {
+    let mut regions = Vec::new();
+    let mut state = ParseState::Idle;
+    let mut lines = text.chunks().lines();
+    let mut offset = 0;
+    while let Some(line) = lines.next() {
+        let line_start = offset;
+        let line_end = (offset + line.len() + 1).min(text.len());
+        offset = line_end;
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        if let Some(label) = marker_label(line, '<') {
+            state = ParseState::Ours {
+                start: line_start,
+                ours_start: line_end,
+                ours_label: label.to_string(),
+            };
+            continue;
+        }
+
+        state = match state {
+            ParseState::Ours {
+                start,
+                ours_start,
+                ours_label,
+            } => {
+                if marker_label(line, '|').is_some() {
+                    ParseState::Base {
+                        start,
+                        ours: ours_start..line_start,
+                        base_start: line_end,
+                        ours_label,
+                    }
+                } else if line == "=======" {
+                    ParseState::Theirs {
+                        start,
+                        ours: ours_start..line_start,
+                        base: None,
+                        theirs_start: line_end,
+                        ours_label,
+                    }
+                } else {
+                    ParseState::Ours {
+                        start,
+                        ours_start,
+                        ours_label,
+                    }
+                }
+            }
+            ParseState::Base {
+                start,
+                ours,
+                base_start,
+                ours_label,
+            } => {
+                if line == "=======" {
+                    ParseState::Theirs {
+                        start,
+                        ours,
+                        base: Some(base_start..line_start),
+                        theirs_start: line_end,
+                        ours_label,
+                    }
+                } else {
+                    ParseState::Base {
+                        start,
+                        ours,
+                        base_start,
+                        ours_label,
+                    }
+                }
+            }
+            ParseState::Theirs {
+                start,
+                ours,
+                base,
+                theirs_start,
+                ours_label,
+            } => {
+                if let Some(theirs_label) = marker_label(line, '>') {
+                    regions.push(ConflictRegion {
+                        range: start..line_end,
+                        ours,
+                        base,
+                        theirs: theirs_start..line_start,
+                        ours_label,
+                        theirs_label: theirs_label.to_string(),
+                    });
+                    ParseState::Idle
+                } else {
+                    ParseState::Theirs {
+                        start,
+                        ours,
+                        base,
+                        theirs_start,
+                        ours_label,
+                    }
+                }
+            }
+            ParseState::Idle => ParseState::Idle,
+        };
+    }
+    regions
+}
+
+/// Returns the label following a marker made of seven of the given character,
+/// if the line is such a marker.
+fn marker_label(line: &str, marker: char) -> Option<&str> {
+    let rest = line.strip_prefix(&marker.to_string().repeat(7))?;
+    if rest.is_empty() {
+        Some(rest)
+    } else if rest.starts_with([' ', '\t']) {
+        Some(rest.trim())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_conflict_markers() {
+        let text = Rope::from(
+            "\
+fn main() {
+<<<<<<< HEAD
+    one();
+=======
+    two();
+    three();
+>>>>>>> feature
+}
+<<<<<<< ours
+a
+||||||| base
+b
+=======
+>>>>>>> theirs
+<<<<<<< incomplete
+c
+",
+        );
+        let regions = parse_conflict_markers(&text);
+        let text = text.to_string();
+        let slice = |range: &Range<usize>| &text[range.clone()];
+
+        assert_eq!(regions.len(), 2);
+        assert_eq!(
+            slice(&regions[0].range),
+            "<<<<<<< HEAD\n    one();\n=======\n    two();\n    three();\n>>>>>>> feature\n"
+        );
+        assert_eq!(slice(&regions[0].ours), "    one();\n");
+        assert_eq!(regions[0].base, None);
+        assert_eq!(slice(&regions[0].theirs), "    two();\n    three();\n");
+        assert_eq!(regions[0].ours_label, "HEAD");
+        assert_eq!(regions[0].theirs_label, "feature");
+
+        assert_eq!(slice(&regions[1].ours), "a\n");
+        assert_eq!(regions[1].base.as_ref().map(slice), Some("b\n"));
+        assert_eq!(slice(&regions[1].theirs), "");
+        assert_eq!(regions[1].theirs_label, "theirs");
+    }
+
+    #[test]
+    fn test_parse_conflict_markers_at_end_of_text() {
+        let text = Rope::from("<<<<<<<\r\nours\r\n=======\r\ntheirs\r\n>>>>>>>");
+        let regions = parse_conflict_markers(&text);
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].range, 0..text.len());
+        assert_eq!(regions[0].ours, 9..15);
+        assert_eq!(regions[0].theirs, 24..32);
+        assert_eq!(regions[0].ours_label, "");
+    }
+}
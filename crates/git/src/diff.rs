@@ -183,7 +183,13 @@ impl BufferDiff {
         if let Some(patch) = patch {
             let mut divergence = 0;
             for hunk_index in 0..patch.num_hunks() {
-                let hunk = Self::process_patch_hunk(&patch, hunk_index, buffer, &mut divergence);
+                let hunk = Self::process_patch_hunk(
+                    &patch,
+                    hunk_index,
+                    &diff_base_text,
+                    buffer,
+                    &mut divergence,
+                );
                 tree.push(hunk, buffer);
             }
         }
@@ -224,6 +230,7 @@ impl BufferDiff {
     fn process_patch_hunk(
         patch: &GitPatch<'_>,
         hunk_index: usize,
+        diff_base: &str,
         buffer: &text::BufferSnapshot,
         buffer_row_divergence: &mut i64,
     ) -> InternalDiffHunk {
@@ -275,8 +282,12 @@ impl BufferDiff {
             row..row
         });
 
-        //unwrap_or addition without deletion
-        let diff_base_byte_range = diff_base_byte_range.unwrap_or(0..0);
+        //unwrap_or addition without deletion, anchored at the diff base row following the insertion
+        let diff_base_byte_range = diff_base_byte_range.unwrap_or_else(|| {
+            let (hunk, _) = patch.hunk(hunk_index).unwrap();
+            let offset = row_offset(diff_base, hunk.old_start());
+            offset..offset
+        });
 
         let start = Point::new(buffer_row_range.start, 0);
         let end = Point::new(buffer_row_range.end, 0);
@@ -288,6 +299,114 @@ impl BufferDiff {
     }
 }
 
+/// Returns the diff base with the given hunks replaced by their current buffer contents,
+/// i.e. what the index should contain once those hunks are staged.
+pub fn stage_hunks(
+    diff_base: &Rope,
+    buffer: &BufferSnapshot,
+    hunks: impl IntoIterator<Item = DiffHunk>,
+) -> String {
+    let mut hunks = hunks.into_iter().collect::<Vec<_>>();
+    hunks.sort_by_key(|hunk| hunk.diff_base_byte_range.start);
+
+    let mut staged_text = String::new();
+    let mut diff_base_offset = 0;
+    for hunk in hunks {
+        if hunk.diff_base_byte_range.start < diff_base_offset {
+            continue;
+        }
+        staged_text
+            .extend(diff_base.chunks_in_range(diff_base_offset..hunk.diff_base_byte_range.start));
+        staged_text.extend(buffer.text_for_range(hunk.buffer_range.clone()));
+        diff_base_offset = hunk.diff_base_byte_range.end;
+    }
+    staged_text.extend(diff_base.chunks_in_range(diff_base_offset..diff_base.len()));
+    staged_text
+}
+
+/// Reverts every hunk between `head_text` and `index_text` that touches one of the
+/// given index rows, returning what the index should contain once those hunks are unstaged.
+pub fn unstage_rows(head_text: &str, index_text: &str, index_rows: &[Range<u32>]) -> String {
+    let Some(patch) = BufferDiff::diff(head_text, index_text) else {
+        return index_text.to_string();
+    };
+
+    let mut unstaged_text = String::new();
+    let mut index_offset = 0;
+    for hunk_index in 0..patch.num_hunks() {
+        let Ok((hunk, _)) = patch.hunk(hunk_index) else {
+            continue;
+        };
+        let head_rows = hunk_rows(hunk.old_start(), hunk.old_lines());
+        let hunk_index_rows = hunk_rows(hunk.new_start(), hunk.new_lines());
+        let is_selected = index_rows.iter().any(|rows| {
+            if hunk_index_rows.is_empty() {
+                // Deleted lines have no rows in the index, so we accept selections right next to them.
+                rows.start <= hunk_index_rows.start && hunk_index_rows.start <= rows.end
+            } else {
+                rows.start < hunk_index_rows.end && hunk_index_rows.start < rows.end
+            }
+        });
+        if !is_selected {
+            continue;
+        }
+
+        let index_range = row_offset(index_text, hunk_index_rows.start)
+            ..row_offset(index_text, hunk_index_rows.end);
+        let head_range =
+            row_offset(head_text, head_rows.start)..row_offset(head_text, head_rows.end);
+        unstaged_text.push_str(&index_text[index_offset..index_range.start]);
+        unstaged_text.push_str(&head_text[head_range]);
+        index_offset = index_range.end;
+    }
+    unstaged_text.push_str(&index_text[index_offset..]);
+    unstaged_text
+}
+
+/// Maps a buffer row to the corresponding row in the diff base, given the buffer's diff hunks
+/// in ascending order. Rows inside of a hunk are mapped to the start of that hunk's base range.
+pub fn buffer_row_to_diff_base_row(
+    hunks: impl IntoIterator<Item = DiffHunk>,
+    diff_base: &Rope,
+    buffer_row: u32,
+) -> u32 {
+    let mut divergence = 0i64;
+    for hunk in hunks {
+        if hunk.row_range.start > buffer_row {
+            break;
+        }
+        let diff_base_start_row = diff_base
+            .offset_to_point(hunk.diff_base_byte_range.start)
+            .row;
+        if buffer_row < hunk.row_range.end {
+            return diff_base_start_row;
+        }
+        let diff_base_end_row = diff_base.offset_to_point(hunk.diff_base_byte_range.end).row;
+        divergence = diff_base_end_row as i64 - hunk.row_range.end as i64;
+    }
+    (buffer_row as i64 + divergence).max(0) as u32
+}
+
+/// Converts a hunk's one-based start line and line count, as reported by git, into a row range.
+fn hunk_rows(start: u32, lines: u32) -> Range<u32> {
+    if lines == 0 {
+        // For empty ranges, git reports the line preceding the change.
+        start..start
+    } else {
+        start - 1..start - 1 + lines
+    }
+}
+
+/// Returns the byte offset at which the given row starts, or the text's length if it has fewer rows.
+fn row_offset(text: &str, row: u32) -> usize {
+    if row == 0 {
+        return 0;
+    }
+    text.match_indices('\n')
+        .nth(row as usize - 1)
+        .map_or(text.len(), |(ix, _)| ix + 1)
+}
+
 /// Range (crossing new lines), old, new
 #[cfg(any(test, feature = "test-support"))]
 #[track_caller]
@@ -423,4 +542,108 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_stage_hunks() {
+        let diff_base = "
+            one
+            two
+            three
+            four
+        "
+        .unindent();
+        let diff_base_rope = Rope::from(diff_base.clone());
+
+        let buffer_text = "
+            zero
+            one
+            TWO
+            three
+        "
+        .unindent();
+
+        let buffer = Buffer::new(0, BufferId::new(1).unwrap(), buffer_text.clone());
+        let mut diff = BufferDiff::new(&buffer);
+        smol::block_on(diff.update(&diff_base_rope, &buffer));
+        let hunks = diff.hunks(&buffer).collect::<Vec<_>>();
+        assert_eq!(hunks.len(), 3);
+
+        assert_eq!(
+            stage_hunks(&diff_base_rope, &buffer, [hunks[1].clone()]),
+            "
+            one
+            TWO
+            three
+            four
+            "
+            .unindent()
+        );
+        assert_eq!(
+            stage_hunks(&diff_base_rope, &buffer, [hunks[0].clone()]),
+            "
+            zero
+            one
+            two
+            three
+            four
+            "
+            .unindent()
+        );
+        assert_eq!(
+            stage_hunks(&diff_base_rope, &buffer, hunks.clone()),
+            buffer_text
+        );
+
+        assert_eq!(
+            buffer_row_to_diff_base_row(hunks.clone(), &diff_base_rope, 1),
+            0
+        );
+        assert_eq!(
+            buffer_row_to_diff_base_row(hunks.clone(), &diff_base_rope, 2),
+            1
+        );
+        assert_eq!(
+            buffer_row_to_diff_base_row(hunks.clone(), &diff_base_rope, 3),
+            2
+        );
+    }
+
+    #[test]
+    fn test_unstage_rows() {
+        let head_text = "
+            one
+            two
+            three
+            four
+        "
+        .unindent();
+        let index_text = "
+            one
+            TWO
+            three
+        "
+        .unindent();
+
+        assert_eq!(
+            unstage_rows(&head_text, &index_text, &[1..2]),
+            "
+            one
+            two
+            three
+            "
+            .unindent()
+        );
+        assert_eq!(
+            unstage_rows(&head_text, &index_text, &[3..3]),
+            "
+            one
+            TWO
+            three
+            four
+            "
+            .unindent()
+        );
+        assert_eq!(unstage_rows(&head_text, &index_text, &[0..4]), head_text);
+        assert_eq!(unstage_rows(&head_text, &index_text, &[]), index_text);
+    }
 }
@@ -2,7 +2,7 @@ use std::{ops::Range, sync::Arc};
 
 use anyhow::Result;
 use async_trait::async_trait;
-use collections::BTreeMap;
+use collections::{btree_map, BTreeMap};
 use derive_more::{Deref, DerefMut};
 use gpui::{AppContext, Global};
 use http_client::HttpClient;
@@ -151,6 +151,32 @@ impl GitHostingProviderRegistry {
             .providers
             .insert(provider.name(), provider);
     }
+
+    /// Adds the provided [`GitHostingProvider`] to the registry, unless a provider with the
+    /// same name is already registered.
+    pub fn try_register_hosting_provider(
+        &self,
+        provider: Arc<dyn GitHostingProvider + Send + Sync + 'static>,
+    ) -> Result<()> {
+        let name = provider.name();
+        match self.state.write().providers.entry(name) {
+            btree_map::Entry::Occupied(entry) => {
+                anyhow::bail!(
+                    "a git hosting provider named {:?} is already registered",
+                    entry.key()
+                )
+            }
+            btree_map::Entry::Vacant(entry) => {
+                entry.insert(provider);
+                Ok(())
+            }
+        }
+    }
+
+    /// Removes the [`GitHostingProvider`] with the given name from the registry.
+    pub fn unregister_hosting_provider(&self, name: &str) {
+        self.state.write().providers.remove(name);
+    }
 }
 
 #[derive(Debug)]
@@ -1,14 +1,21 @@
-use crate::GitHostingProviderRegistry;
-use crate::{blame::Blame, status::GitStatus};
-use anyhow::{Context, Result};
-use collections::HashMap;
+use crate::{
+    blame::Blame,
+    commit::{CommitDetails, CommitFileChange, CommitSummary, MAX_COMMIT_FILE_SIZE},
+    conflict::ConflictStages,
+    status::{FileStatus, GitStatus, StatusCode},
+};
+use crate::{GitHostingProviderRegistry, Oid};
+use anyhow::{anyhow, Context, Result};
+use collections::{HashMap, HashSet};
 use git2::BranchType;
 use parking_lot::Mutex;
 use rope::Rope;
 use serde::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
+    io::Write as _,
     path::{Component, Path, PathBuf},
+    process::{Command, Stdio},
     sync::Arc,
 };
 use sum_tree::MapSeekTarget;
@@ -22,6 +29,21 @@ pub struct Branch {
     pub unix_timestamp: Option<i64>,
 }
 
+/// An entry of the stash, as listed by `git stash list`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stash {
+    /// The position of the entry in the stash, `0` being the most recent one.
+    pub index: usize,
+    pub message: String,
+}
+
+/// How many commits the current branch and its upstream have that the other lacks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AheadBehind {
+    pub ahead: u32,
+    pub behind: u32,
+}
+
 pub trait GitRepository: Send + Sync {
     fn reload_index(&self);
 
@@ -29,6 +51,10 @@ pub trait GitRepository: Send + Sync {
     /// Note that for symlink entries, this will return the contents of the symlink, not the target.
     fn load_index_text(&self, relative_file_path: &Path) -> Option<String>;
 
+    /// Loads the contents of the given path as of the HEAD commit.
+    /// Returns `None` if the path is not part of HEAD, e.g. because it was added since.
+    fn load_committed_text(&self, relative_file_path: &Path) -> Option<String>;
+
     /// Returns the URL of the remote with the given name.
     fn remote_url(&self, name: &str) -> Option<String>;
     fn branch_name(&self) -> Option<String>;
@@ -42,7 +68,75 @@ pub trait GitRepository: Send + Sync {
     fn change_branch(&self, _: &str) -> Result<()>;
     fn create_branch(&self, _: &str) -> Result<()>;
 
+    /// Deletes the given local branch, refusing to if it is not merged into its upstream or HEAD.
+    fn delete_branch(&self, name: &str) -> Result<()>;
+    fn rename_branch(&self, old_name: &str, new_name: &str) -> Result<()>;
+
+    /// Returns how far the current branch has diverged from its upstream,
+    /// or `None` if it has no upstream.
+    fn upstream_ahead_behind(&self) -> Option<AheadBehind>;
+
+    /// Returns the entries of the stash, most recent first.
+    fn stash_list(&self) -> Result<Vec<Stash>>;
+
+    /// Saves the local changes to a new stash entry and reverts them in the working copy.
+    fn stash_push(&self, message: Option<&str>) -> Result<()>;
+
+    /// Applies the stash entry at the given index and removes it from the stash.
+    fn stash_pop(&self, index: usize) -> Result<()>;
+
+    /// Removes the stash entry at the given index without applying it.
+    fn stash_drop(&self, index: usize) -> Result<()>;
+
+    /// Downloads the objects and refs of the default remote.
+    fn fetch(&self) -> Result<()>;
+
+    /// Fetches the upstream of the current branch and integrates it.
+    fn pull(&self) -> Result<()>;
+
+    /// Updates the upstream of the current branch with its local commits.
+    fn push(&self) -> Result<()>;
+
     fn blame(&self, path: &Path, content: Rope) -> Result<crate::blame::Blame>;
+
+    /// Updates the index to match the working copy contents of the given paths.
+    /// Paths that no longer exist in the working copy are removed from the index.
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()>;
+
+    /// Resets the index entries of the given paths to their state in HEAD.
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()>;
+
+    /// Replaces the index entry of the given path with the given contents,
+    /// or removes it from the index if `content` is `None`.
+    fn set_index_text(&self, path: &RepoPath, content: Option<String>) -> Result<()>;
+
+    /// Creates a commit from the current index, or replaces the HEAD commit if `amend` is set.
+    fn commit(&self, message: &str, amend: bool) -> Result<()>;
+
+    /// Returns the message of the HEAD commit.
+    fn head_commit_message(&self) -> Option<String>;
+
+    /// Returns up to `limit` commits reachable from HEAD, newest first, after skipping the
+    /// first `skip` ones. If `path` is given, only the commits that changed it are returned.
+    fn log(&self, path: Option<&RepoPath>, skip: usize, limit: usize)
+        -> Result<Vec<CommitSummary>>;
+
+    /// Returns the message of the given commit, and the changes it made to up to `file_limit`
+    /// of its changed files, after skipping the first `file_offset` ones.
+    fn commit_details(
+        &self,
+        sha: Oid,
+        file_offset: usize,
+        file_limit: usize,
+    ) -> Result<CommitDetails>;
+
+    /// Loads the contents of the given path at the base, ours and theirs stages of the index.
+    /// Returns `None` if the path has no such stages, i.e. it is not conflicted.
+    fn load_conflict_stages(&self, path: &RepoPath) -> Result<Option<ConflictStages>>;
+
+    /// Loads the contents of the given path as of a revision, such as a commit SHA, a branch
+    /// name or `HEAD~1`. Returns `None` if the path does not exist in that revision.
+    fn load_revision_text(&self, path: &RepoPath, revision: &str) -> Result<Option<String>>;
 }
 
 impl std::fmt::Debug for dyn GitRepository {
@@ -69,6 +163,42 @@ impl RealGitRepository {
             hosting_provider_registry,
         }
     }
+
+    /// Runs the git binary with the given arguments in the working directory,
+    /// returning its standard output.
+    fn run_git_command(&self, args: &[&str]) -> Result<String> {
+        let working_directory = self
+            .repository
+            .lock()
+            .workdir()
+            .context("failed to read git work directory")?
+            .to_path_buf();
+
+        let mut command = Command::new(&self.git_binary_path);
+        command
+            .current_dir(&working_directory)
+            .args(args)
+            // There is no terminal to enter credentials in, so fail instead of waiting for them.
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            command.creation_flags(windows::Win32::System::Threading::CREATE_NO_WINDOW.0);
+        }
+
+        let output = command
+            .output()
+            .map_err(|e| anyhow!("Failed to start git {} process: {}", args[0], e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("git {} process failed: {}", args[0], stderr.trim()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
 }
 
 // https://git-scm.com/book/en/v2/Git-Internals-Git-Objects
@@ -105,6 +235,31 @@ impl GitRepository for RealGitRepository {
         None
     }
 
+    fn load_committed_text(&self, relative_file_path: &Path) -> Option<String> {
+        fn logic(repo: &git2::Repository, relative_file_path: &Path) -> Result<Option<String>> {
+            check_path_to_repo_path_errors(relative_file_path)?;
+
+            let head_tree = match repo.head() {
+                Ok(head) => head.peel_to_tree()?,
+                Err(err) if err.code() == git2::ErrorCode::UnbornBranch => return Ok(None),
+                Err(err) => return Err(err.into()),
+            };
+            let entry = match head_tree.get_path(relative_file_path) {
+                Ok(entry) if entry.filemode() as u32 != GIT_MODE_SYMLINK => entry,
+                _ => return Ok(None),
+            };
+
+            let content = repo.find_blob(entry.id())?.content().to_owned();
+            Ok(Some(String::from_utf8(content)?))
+        }
+
+        match logic(&self.repository.lock(), relative_file_path) {
+            Ok(value) => return value,
+            Err(err) => log::error!("Error loading committed text: {:?}", err),
+        }
+        None
+    }
+
     fn remote_url(&self, name: &str) -> Option<String> {
         let repo = self.repository.lock();
         let remote = repo.find_remote(name).ok()?;
@@ -179,6 +334,79 @@ impl GitRepository for RealGitRepository {
         Ok(())
     }
 
+    fn delete_branch(&self, name: &str) -> Result<()> {
+        self.run_git_command(&["branch", "--delete", "--", name])?;
+        Ok(())
+    }
+
+    fn rename_branch(&self, old_name: &str, new_name: &str) -> Result<()> {
+        let repo = self.repository.lock();
+        let mut branch = repo.find_branch(old_name, BranchType::Local)?;
+        branch.rename(new_name, false)?;
+        Ok(())
+    }
+
+    fn upstream_ahead_behind(&self) -> Option<AheadBehind> {
+        let repo = self.repository.lock();
+        let head = repo.head().ok()?;
+        if !head.is_branch() {
+            return None;
+        }
+        let local_oid = head.target()?;
+        let upstream_oid = git2::Branch::wrap(head).upstream().ok()?.get().target()?;
+        let (ahead, behind) = repo.graph_ahead_behind(local_oid, upstream_oid).ok()?;
+        Some(AheadBehind {
+            ahead: ahead as u32,
+            behind: behind as u32,
+        })
+    }
+
+    fn stash_list(&self) -> Result<Vec<Stash>> {
+        let mut repo = self.repository.lock();
+        let mut stashes = Vec::new();
+        repo.stash_foreach(|index, message, _| {
+            stashes.push(Stash {
+                index,
+                message: message.to_string(),
+            });
+            true
+        })?;
+        Ok(stashes)
+    }
+
+    fn stash_push(&self, message: Option<&str>) -> Result<()> {
+        let message = message.map(|message| format!("--message={message}"));
+        let mut args = vec!["stash", "push", "--quiet"];
+        args.extend(message.as_deref());
+        self.run_git_command(&args)?;
+        Ok(())
+    }
+
+    fn stash_pop(&self, index: usize) -> Result<()> {
+        self.run_git_command(&["stash", "pop", "--quiet", &format!("stash@{{{index}}}")])?;
+        Ok(())
+    }
+
+    fn stash_drop(&self, index: usize) -> Result<()> {
+        self.run_git_command(&["stash", "drop", "--quiet", &format!("stash@{{{index}}}")])?;
+        Ok(())
+    }
+
+    fn fetch(&self) -> Result<()> {
+        self.run_git_command(&["fetch", "--quiet"])?;
+        Ok(())
+    }
+
+    fn pull(&self) -> Result<()> {
+        self.run_git_command(&["pull", "--quiet"])?;
+        Ok(())
+    }
+
+    fn push(&self) -> Result<()> {
+        self.run_git_command(&["push", "--quiet"])?;
+        Ok(())
+    }
+
     fn blame(&self, path: &Path, content: Rope) -> Result<crate::blame::Blame> {
         let working_directory = self
             .repository
@@ -199,24 +427,321 @@ impl GitRepository for RealGitRepository {
             self.hosting_provider_registry.clone(),
         )
     }
+
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let repo = self.repository.lock();
+        let working_directory = repo
+            .workdir()
+            .context("failed to read git work directory")?
+            .to_path_buf();
+        let mut index = repo.index()?;
+        for path in paths {
+            check_path_to_repo_path_errors(path)?;
+            if working_directory.join(path).symlink_metadata().is_ok() {
+                index.add_path(path)?;
+            } else {
+                index.remove_path(path)?;
+            }
+        }
+        index.write()?;
+        Ok(())
+    }
+
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let repo = self.repository.lock();
+        match repo.head() {
+            Ok(head) => {
+                let head_commit = head.peel_to_commit()?;
+                repo.reset_default(
+                    Some(head_commit.as_object()),
+                    paths.iter().map(|path| path.as_path()),
+                )?;
+            }
+            // Nothing has been committed yet, so unstaging means dropping the entries entirely.
+            Err(err) if err.code() == git2::ErrorCode::UnbornBranch => {
+                let mut index = repo.index()?;
+                for path in paths {
+                    check_path_to_repo_path_errors(path)?;
+                    index.remove_path(path)?;
+                }
+                index.write()?;
+            }
+            Err(err) => return Err(err.into()),
+        }
+        Ok(())
+    }
+
+    fn set_index_text(&self, path: &RepoPath, content: Option<String>) -> Result<()> {
+        const STAGE_NORMAL: i32 = 0;
+        const GIT_MODE_FILE: u32 = 0o100644;
+
+        check_path_to_repo_path_errors(path)?;
+
+        let repo = self.repository.lock();
+        let mut index = repo.index()?;
+        match content {
+            Some(content) => {
+                let entry = match index.get_path(path, STAGE_NORMAL) {
+                    Some(entry) => entry,
+                    None => git2::IndexEntry {
+                        ctime: git2::IndexTime::new(0, 0),
+                        mtime: git2::IndexTime::new(0, 0),
+                        dev: 0,
+                        ino: 0,
+                        mode: GIT_MODE_FILE,
+                        uid: 0,
+                        gid: 0,
+                        file_size: 0,
+                        id: git2::Oid::zero(),
+                        flags: 0,
+                        flags_extended: 0,
+                        path: path
+                            .to_str()
+                            .with_context(|| format!("invalid repo path {:?}", path.0))?
+                            .as_bytes()
+                            .to_vec(),
+                    },
+                };
+                index.add_frombuffer(&entry, content.as_bytes())?;
+            }
+            None => index.remove_path(path)?,
+        }
+        index.write()?;
+        Ok(())
+    }
+
+    fn commit(&self, message: &str, amend: bool) -> Result<()> {
+        let working_directory = self
+            .repository
+            .lock()
+            .workdir()
+            .context("failed to read git work directory")?
+            .to_path_buf();
+
+        let mut command = Command::new(&self.git_binary_path);
+        command.current_dir(&working_directory).args([
+            "commit",
+            "--quiet",
+            "--cleanup=strip",
+            "--file=-",
+        ]);
+        if amend {
+            command.arg("--amend");
+        }
+        command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            command.creation_flags(windows::Win32::System::Threading::CREATE_NO_WINDOW.0);
+        }
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| anyhow!("Failed to start git commit process: {}", e))?;
+        child
+            .stdin
+            .take()
+            .context("failed to open stdin of git commit process")?
+            .write_all(message.as_bytes())?;
+
+        let output = child
+            .wait_with_output()
+            .map_err(|e| anyhow!("Failed to read git commit output: {}", e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("git commit process failed: {}", stderr));
+        }
+        Ok(())
+    }
+
+    fn head_commit_message(&self) -> Option<String> {
+        let repo = self.repository.lock();
+        let head_commit = repo.head().ok()?.peel_to_commit().ok()?;
+        let message = head_commit.message()?;
+        Some(message.to_string())
+    }
+
+    fn log(
+        &self,
+        path: Option<&RepoPath>,
+        skip: usize,
+        limit: usize,
+    ) -> Result<Vec<CommitSummary>> {
+        let working_directory = {
+            let repo = self.repository.lock();
+            match repo.head() {
+                Ok(_) => {}
+                Err(err) if err.code() == git2::ErrorCode::UnbornBranch => return Ok(Vec::new()),
+                Err(err) => return Err(err.into()),
+            }
+            repo.workdir()
+                .context("failed to read git work directory")?
+                .to_path_buf()
+        };
+
+        let output = crate::commit::run_git_log(
+            &self.git_binary_path,
+            &working_directory,
+            path.map(|path| path.as_path()),
+            skip,
+            limit,
+        )?;
+        crate::commit::parse_git_log(&output)
+    }
+
+    fn commit_details(
+        &self,
+        sha: Oid,
+        file_offset: usize,
+        file_limit: usize,
+    ) -> Result<CommitDetails> {
+        let repo = self.repository.lock();
+        let odb = repo.odb()?;
+        let commit = repo.find_commit(sha.0)?;
+        let tree = commit.tree()?;
+        let parent_tree = commit
+            .parents()
+            .next()
+            .map(|parent| parent.tree())
+            .transpose()?;
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+
+        // Returns `None` for binary files, which can't be shown as text, and for files too large
+        // to be shown.
+        let load_text = |file: git2::DiffFile| -> Result<Option<Option<String>>> {
+            if file.id().is_zero() {
+                return Ok(Some(None));
+            }
+            let (size, _) = odb.read_header(file.id())?;
+            if size > MAX_COMMIT_FILE_SIZE {
+                return Ok(None);
+            }
+            let blob = repo.find_blob(file.id())?;
+            if blob.is_binary() {
+                return Ok(None);
+            }
+            Ok(String::from_utf8(blob.content().to_owned()).ok().map(Some))
+        };
+
+        let mut changes = Vec::new();
+        for delta in diff.deltas().skip(file_offset).take(file_limit) {
+            let Some(path) = delta.new_file().path().or(delta.old_file().path()) else {
+                continue;
+            };
+            let (Some(old_text), Some(new_text)) =
+                (load_text(delta.old_file())?, load_text(delta.new_file())?)
+            else {
+                continue;
+            };
+            changes.push(CommitFileChange {
+                path: RepoPath::from(path),
+                old_text,
+                new_text,
+            });
+        }
+
+        let author = commit.author();
+        Ok(CommitDetails {
+            summary: CommitSummary {
+                sha,
+                author_name: author.name().unwrap_or_default().to_string(),
+                author_email: author.email().unwrap_or_default().to_string(),
+                timestamp: author.when().seconds(),
+                subject: commit.summary().unwrap_or_default().to_string(),
+            },
+            message: commit.message().unwrap_or_default().to_string(),
+            changes,
+            changed_file_count: diff.deltas().len(),
+        })
+    }
+
+    fn load_conflict_stages(&self, path: &RepoPath) -> Result<Option<ConflictStages>> {
+        const STAGE_BASE: i32 = 1;
+        const STAGE_OURS: i32 = 2;
+        const STAGE_THEIRS: i32 = 3;
+
+        check_path_to_repo_path_errors(path)?;
+        let repo = self.repository.lock();
+        let index = repo.index()?;
+        let load_stage = |stage| -> Result<Option<String>> {
+            match index.get_path(path, stage) {
+                Some(entry) if entry.mode != GIT_MODE_SYMLINK => {
+                    let content = repo.find_blob(entry.id)?.content().to_owned();
+                    Ok(Some(String::from_utf8(content)?))
+                }
+                _ => Ok(None),
+            }
+        };
+        let stages = ConflictStages {
+            base: load_stage(STAGE_BASE)?,
+            ours: load_stage(STAGE_OURS)?,
+            theirs: load_stage(STAGE_THEIRS)?,
+        };
+        if stages == ConflictStages::default() {
+            Ok(None)
+        } else {
+            Ok(Some(stages))
+        }
+    }
+
+    fn load_revision_text(&self, path: &RepoPath, revision: &str) -> Result<Option<String>> {
+        check_path_to_repo_path_errors(path)?;
+        let repo = self.repository.lock();
+        let tree = repo
+            .revparse_single(revision)
+            .with_context(|| format!("unknown revision {revision:?}"))?
+            .peel_to_tree()?;
+        let entry = match tree.get_path(path) {
+            Ok(entry) if entry.filemode() as u32 != GIT_MODE_SYMLINK => entry,
+            _ => return Ok(None),
+        };
+        let content = repo.find_blob(entry.id())?.content().to_owned();
+        Ok(Some(String::from_utf8(content)?))
+    }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Clone)]
 pub struct FakeGitRepository {
     state: Arc<Mutex<FakeGitRepositoryState>>,
+    /// Loads the text of a file in the working directory, so that staging it
+    /// can update the index.
+    load_working_copy_text: Arc<dyn Fn(&Path) -> Option<String> + Send + Sync>,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct FakeGitRepositoryState {
     pub index_contents: HashMap<PathBuf, String>,
+    pub head_contents: HashMap<PathBuf, String>,
+    pub commit_messages: Vec<String>,
+    /// The commits returned by [`GitRepository::log`], newest first.
+    pub commits: Vec<CommitDetails>,
     pub blames: HashMap<PathBuf, Blame>,
+    pub conflict_stages: HashMap<RepoPath, ConflictStages>,
     pub worktree_statuses: HashMap<RepoPath, GitFileStatus>,
+    pub staged_statuses: HashMap<RepoPath, GitFileStatus>,
     pub branch_name: Option<String>,
+    /// The local branches, besides the current one.
+    pub branches: HashSet<String>,
+    /// The messages of the stash entries, most recent first.
+    pub stashes: Vec<String>,
+    /// How far the current branch has diverged from its upstream, or `None` if it has none.
+    pub upstream: Option<AheadBehind>,
 }
 
 impl FakeGitRepository {
-    pub fn open(state: Arc<Mutex<FakeGitRepositoryState>>) -> Arc<dyn GitRepository> {
-        Arc::new(FakeGitRepository { state })
+    pub fn open(
+        state: Arc<Mutex<FakeGitRepositoryState>>,
+        load_working_copy_text: impl Fn(&Path) -> Option<String> + Send + Sync + 'static,
+    ) -> Arc<dyn GitRepository> {
+        Arc::new(FakeGitRepository {
+            state,
+            load_working_copy_text: Arc::new(load_working_copy_text),
+        })
     }
 }
 
@@ -228,6 +753,11 @@ impl GitRepository for FakeGitRepository {
         state.index_contents.get(path).cloned()
     }
 
+    fn load_committed_text(&self, path: &Path) -> Option<String> {
+        let state = self.state.lock();
+        state.head_contents.get(path).cloned()
+    }
+
     fn remote_url(&self, _name: &str) -> Option<String> {
         None
     }
@@ -258,13 +788,52 @@ impl GitRepository for FakeGitRepository {
             })
             .collect::<Vec<_>>();
         entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        let file_statuses = entries
+            .iter()
+            .map(|(repo_path, status)| {
+                let status_code = match status {
+                    GitFileStatus::Added => StatusCode::Added,
+                    GitFileStatus::Modified => StatusCode::Modified,
+                    GitFileStatus::Conflict => StatusCode::Unmerged,
+                };
+                let file_status = match state.staged_statuses.get(repo_path) {
+                    Some(_) if *status == GitFileStatus::Conflict => FileStatus {
+                        index_status: Some(status_code),
+                        worktree_status: Some(status_code),
+                    },
+                    Some(_) => FileStatus {
+                        index_status: Some(status_code),
+                        worktree_status: None,
+                    },
+                    None if *status == GitFileStatus::Added => FileStatus::untracked(),
+                    None => FileStatus {
+                        index_status: None,
+                        worktree_status: Some(status_code),
+                    },
+                };
+                (repo_path.clone(), file_status)
+            })
+            .collect::<Vec<_>>();
         Ok(GitStatus {
             entries: entries.into(),
+            file_statuses: file_statuses.into(),
         })
     }
 
     fn branches(&self) -> Result<Vec<Branch>> {
-        Ok(vec![])
+        let state = self.state.lock();
+        let mut names = state.branches.iter().collect::<Vec<_>>();
+        names.extend(&state.branch_name);
+        names.sort();
+        names.dedup();
+        Ok(names
+            .into_iter()
+            .map(|name| Branch {
+                is_head: state.branch_name.as_ref() == Some(name),
+                name: name.as_str().into(),
+                unix_timestamp: None,
+            })
+            .collect())
     }
 
     fn change_branch(&self, name: &str) -> Result<()> {
@@ -275,10 +844,106 @@ impl GitRepository for FakeGitRepository {
 
     fn create_branch(&self, name: &str) -> Result<()> {
         let mut state = self.state.lock();
+        state.branches.insert(name.to_owned());
         state.branch_name = Some(name.to_owned());
         Ok(())
     }
 
+    fn delete_branch(&self, name: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        anyhow::ensure!(
+            state.branch_name.as_deref() != Some(name),
+            "cannot delete the current branch {name:?}"
+        );
+        anyhow::ensure!(state.branches.remove(name), "branch {name:?} not found");
+        Ok(())
+    }
+
+    fn rename_branch(&self, old_name: &str, new_name: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        anyhow::ensure!(
+            !state.branches.contains(new_name),
+            "branch {new_name:?} already exists"
+        );
+        let is_current = state.branch_name.as_deref() == Some(old_name);
+        anyhow::ensure!(
+            state.branches.remove(old_name) || is_current,
+            "branch {old_name:?} not found"
+        );
+        state.branches.insert(new_name.to_owned());
+        if is_current {
+            state.branch_name = Some(new_name.to_owned());
+        }
+        Ok(())
+    }
+
+    fn upstream_ahead_behind(&self) -> Option<AheadBehind> {
+        let state = self.state.lock();
+        state.upstream
+    }
+
+    fn stash_list(&self) -> Result<Vec<Stash>> {
+        let state = self.state.lock();
+        Ok(state
+            .stashes
+            .iter()
+            .enumerate()
+            .map(|(index, message)| Stash {
+                index,
+                message: message.clone(),
+            })
+            .collect())
+    }
+
+    fn stash_push(&self, message: Option<&str>) -> Result<()> {
+        let mut state = self.state.lock();
+        let branch = state.branch_name.as_deref().unwrap_or("(no branch)");
+        let message = match message {
+            Some(message) => format!("On {branch}: {message}"),
+            None => format!("WIP on {branch}"),
+        };
+        state.stashes.insert(0, message);
+        Ok(())
+    }
+
+    fn stash_pop(&self, index: usize) -> Result<()> {
+        self.stash_drop(index)
+    }
+
+    fn stash_drop(&self, index: usize) -> Result<()> {
+        let mut state = self.state.lock();
+        anyhow::ensure!(
+            index < state.stashes.len(),
+            "stash@{{{index}}} is not a valid reference"
+        );
+        state.stashes.remove(index);
+        Ok(())
+    }
+
+    fn fetch(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn pull(&self) -> Result<()> {
+        let mut state = self.state.lock();
+        let upstream = state
+            .upstream
+            .as_mut()
+            .context("the current branch has no upstream")?;
+        upstream.behind = 0;
+        Ok(())
+    }
+
+    fn push(&self) -> Result<()> {
+        let mut state = self.state.lock();
+        let upstream = state
+            .upstream
+            .as_mut()
+            .context("the current branch has no upstream")?;
+        upstream.ahead = 0;
+        Ok(())
+    }
+
     fn blame(&self, path: &Path, _content: Rope) -> Result<crate::blame::Blame> {
         let state = self.state.lock();
         state
@@ -287,6 +952,141 @@ impl GitRepository for FakeGitRepository {
             .with_context(|| format!("failed to get blame for {:?}", path))
             .cloned()
     }
+
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let contents = paths
+            .iter()
+            .map(|path| (self.load_working_copy_text)(&path.0))
+            .collect::<Vec<_>>();
+        let mut state = self.state.lock();
+        for (path, content) in paths.iter().zip(contents) {
+            if let Some(status) = state.worktree_statuses.get(path).copied() {
+                state.staged_statuses.insert(path.clone(), status);
+            }
+            match content {
+                Some(content) => {
+                    state.index_contents.insert(path.0.clone(), content);
+                }
+                None => {
+                    state.index_contents.remove(&path.0);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let mut state = self.state.lock();
+        for path in paths {
+            state.staged_statuses.remove(path);
+            match state.head_contents.get(&path.0).cloned() {
+                Some(content) => {
+                    state.index_contents.insert(path.0.clone(), content);
+                }
+                None => {
+                    state.index_contents.remove(&path.0);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn set_index_text(&self, path: &RepoPath, content: Option<String>) -> Result<()> {
+        let mut state = self.state.lock();
+        match content {
+            Some(content) => {
+                state.index_contents.insert(path.0.clone(), content);
+            }
+            None => {
+                state.index_contents.remove(&path.0);
+            }
+        }
+        Ok(())
+    }
+
+    fn commit(&self, message: &str, amend: bool) -> Result<()> {
+        let mut state = self.state.lock();
+        if amend {
+            anyhow::ensure!(state.commit_messages.pop().is_some(), "no commit to amend");
+        } else {
+            anyhow::ensure!(
+                !state.staged_statuses.is_empty() || state.index_contents != state.head_contents,
+                "nothing to commit"
+            );
+        }
+        state.commit_messages.push(message.to_owned());
+        state.head_contents = state.index_contents.clone();
+        state.staged_statuses.clear();
+        Ok(())
+    }
+
+    fn head_commit_message(&self) -> Option<String> {
+        let state = self.state.lock();
+        state.commit_messages.last().cloned()
+    }
+
+    fn log(
+        &self,
+        path: Option<&RepoPath>,
+        skip: usize,
+        limit: usize,
+    ) -> Result<Vec<CommitSummary>> {
+        let state = self.state.lock();
+        Ok(state
+            .commits
+            .iter()
+            .filter(|commit| {
+                path.map_or(true, |path| {
+                    commit
+                        .changes
+                        .iter()
+                        .any(|change| change.path.starts_with(path))
+                })
+            })
+            .skip(skip)
+            .take(limit)
+            .map(|commit| commit.summary.clone())
+            .collect())
+    }
+
+    fn commit_details(
+        &self,
+        sha: Oid,
+        file_offset: usize,
+        file_limit: usize,
+    ) -> Result<CommitDetails> {
+        let state = self.state.lock();
+        let commit = state
+            .commits
+            .iter()
+            .find(|commit| commit.summary.sha == sha)
+            .with_context(|| format!("commit {} not found", sha))?;
+        Ok(CommitDetails {
+            summary: commit.summary.clone(),
+            message: commit.message.clone(),
+            changes: commit
+                .changes
+                .iter()
+                .skip(file_offset)
+                .take(file_limit)
+                .cloned()
+                .collect(),
+            changed_file_count: commit.changes.len(),
+        })
+    }
+
+    fn load_conflict_stages(&self, path: &RepoPath) -> Result<Option<ConflictStages>> {
+        let state = self.state.lock();
+        Ok(state.conflict_stages.get(path).cloned())
+    }
+
+    fn load_revision_text(&self, path: &RepoPath, revision: &str) -> Result<Option<String>> {
+        let state = self.state.lock();
+        if revision != "HEAD" {
+            anyhow::bail!("unknown revision {revision:?}");
+        }
+        Ok(state.head_contents.get(path.as_path()).cloned())
+    }
 }
 
 fn check_path_to_repo_path_errors(relative_file_path: &Path) -> Result<()> {
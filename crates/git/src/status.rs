@@ -9,6 +9,87 @@ use std::{
 #[derive(Clone)]
 pub struct GitStatus {
     pub entries: Arc<[(RepoPath, GitFileStatus)]>,
+    /// The index and working copy states of every changed path, sorted by path.
+    pub file_statuses: Arc<[(RepoPath, FileStatus)]>,
+}
+
+/// A single side of a porcelain status code, describing how a path changed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StatusCode {
+    Modified,
+    Added,
+    Deleted,
+    Renamed,
+    Copied,
+    Unmerged,
+    Untracked,
+}
+
+impl StatusCode {
+    fn from_porcelain(code: u8) -> Option<Self> {
+        match code {
+            b'M' | b'T' => Some(StatusCode::Modified),
+            b'A' => Some(StatusCode::Added),
+            b'D' => Some(StatusCode::Deleted),
+            b'R' => Some(StatusCode::Renamed),
+            b'C' => Some(StatusCode::Copied),
+            b'U' => Some(StatusCode::Unmerged),
+            b'?' => Some(StatusCode::Untracked),
+            _ => None,
+        }
+    }
+}
+
+/// How a path differs between HEAD and the index, and between the index and the working copy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FileStatus {
+    pub index_status: Option<StatusCode>,
+    pub worktree_status: Option<StatusCode>,
+}
+
+impl FileStatus {
+    pub fn untracked() -> Self {
+        Self {
+            index_status: None,
+            worktree_status: Some(StatusCode::Untracked),
+        }
+    }
+
+    pub fn is_untracked(&self) -> bool {
+        self.worktree_status == Some(StatusCode::Untracked)
+    }
+
+    pub fn is_conflicted(&self) -> bool {
+        self.index_status == Some(StatusCode::Unmerged)
+    }
+
+    /// Whether the index contains changes to this path that are not in HEAD.
+    pub fn is_staged(&self) -> bool {
+        self.index_status.is_some() && !self.is_conflicted()
+    }
+
+    /// Whether the working copy contains changes to this path that are not in the index.
+    pub fn is_unstaged(&self) -> bool {
+        self.worktree_status.is_some() && !self.is_untracked()
+    }
+
+    fn from_porcelain(code: &[u8]) -> Option<Self> {
+        let (x, y) = (*code.first()?, *code.get(1)?);
+        if x == b'U' || y == b'U' || (x == y && (x == b'A' || x == b'D')) {
+            return Some(Self {
+                index_status: Some(StatusCode::Unmerged),
+                worktree_status: Some(StatusCode::Unmerged),
+            });
+        }
+        if x == b'?' {
+            return Some(Self::untracked());
+        }
+        let status = Self {
+            index_status: StatusCode::from_porcelain(x),
+            worktree_status: StatusCode::from_porcelain(y),
+        };
+        (status.index_status.is_some() || status.worktree_status.is_some()).then_some(status)
+    }
 }
 
 impl GitStatus {
@@ -58,29 +139,44 @@ impl GitStatus {
             return Err(anyhow!("git status process failed: {}", stderr));
         }
         let stdout = String::from_utf8_lossy(&output.stdout);
-        let mut entries = stdout
-            .split('\0')
-            .filter_map(|entry| {
-                if entry.is_char_boundary(3) {
-                    let (status, path) = entry.split_at(3);
-                    let status = status.trim();
-                    Some((
-                        RepoPath(PathBuf::from(path)),
-                        match status {
-                            "A" | "??" => GitFileStatus::Added,
-                            "M" => GitFileStatus::Modified,
-                            _ => return None,
-                        },
-                    ))
-                } else {
-                    None
+        Ok(Self::parse(&stdout))
+    }
+
+    fn parse(stdout: &str) -> Self {
+        let mut entries = Vec::new();
+        let mut file_statuses = Vec::new();
+        let mut records = stdout.split('\0');
+        while let Some(entry) = records.next() {
+            if !entry.is_char_boundary(3) {
+                continue;
+            }
+            let (status, path) = entry.split_at(3);
+            // Renames and copies are followed by a record containing the original path.
+            if status.starts_with(['R', 'C']) {
+                records.next();
+            }
+            let repo_path = RepoPath(PathBuf::from(path));
+            let file_status = FileStatus::from_porcelain(status.as_bytes());
+            if let Some(file_status) = file_status {
+                file_statuses.push((repo_path.clone(), file_status));
+            }
+            let status = if file_status.is_some_and(|status| status.is_conflicted()) {
+                GitFileStatus::Conflict
+            } else {
+                match status.trim() {
+                    "A" | "??" => GitFileStatus::Added,
+                    "M" => GitFileStatus::Modified,
+                    _ => continue,
                 }
-            })
-            .collect::<Vec<_>>();
+            };
+            entries.push((repo_path, status));
+        }
         entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
-        Ok(Self {
+        file_statuses.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        Self {
             entries: entries.into(),
-        })
+            file_statuses: file_statuses.into(),
+        }
     }
 
     pub fn get(&self, path: &Path) -> Option<GitFileStatus> {
@@ -95,6 +191,48 @@ impl Default for GitStatus {
     fn default() -> Self {
         Self {
             entries: Arc::new([]),
+            file_statuses: Arc::new([]),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_porcelain_status() {
+        let status = GitStatus::parse(
+            "M  staged.rs\0 M unstaged.rs\0MM both.rs\0?? new.rs\0R  renamed.rs\0original.rs\0UU conflict.rs\0 D deleted.rs\0",
+        );
+        assert_eq!(
+            status
+                .file_statuses
+                .iter()
+                .map(|(path, status)| (
+                    path.to_str().unwrap(),
+                    status.is_staged(),
+                    status.is_unstaged()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("both.rs", true, true),
+                ("conflict.rs", false, true),
+                ("deleted.rs", false, true),
+                ("new.rs", false, false),
+                ("renamed.rs", true, false),
+                ("staged.rs", true, false),
+                ("unstaged.rs", false, true),
+            ]
+        );
+        assert!(status.file_statuses[3].1.is_untracked());
+        assert!(status.file_statuses[1].1.is_conflicted());
+        assert_eq!(status.get(Path::new("new.rs")), Some(GitFileStatus::Added));
+        assert_eq!(status.get(Path::new("both.rs")), None);
+        assert_eq!(
+            status.get(Path::new("conflict.rs")),
+            Some(GitFileStatus::Conflict)
+        );
+        assert_eq!(status.get(Path::new("original.rs")), None);
+    }
+}
@@ -1,14 +1,18 @@
 mod providers;
+mod settings;
 
 use std::sync::Arc;
 
+use ::settings::{Settings, SettingsStore};
 use git::GitHostingProviderRegistry;
 use gpui::AppContext;
+use util::ResultExt;
 
 pub use crate::providers::*;
+pub use crate::settings::*;
 
 /// Initializes the Git hosting providers.
-pub fn init(cx: &AppContext) {
+pub fn init(cx: &mut AppContext) {
     let provider_registry = GitHostingProviderRegistry::global(cx);
 
     // The providers are stored in a `BTreeMap`, so insertion order matters.
@@ -23,4 +27,108 @@ pub fn init(cx: &AppContext) {
     provider_registry.register_hosting_provider(Arc::new(Bitbucket));
     provider_registry.register_hosting_provider(Arc::new(Sourcehut));
     provider_registry.register_hosting_provider(Arc::new(Codeberg));
+
+    GitHostingProviderSettings::register(cx);
+
+    let mut settings_providers = SettingsProviders::default();
+    settings_providers.update(&provider_registry, cx);
+    cx.observe_global::<SettingsStore>(move |cx| {
+        settings_providers.update(&provider_registry, cx);
+    })
+    .detach();
+}
+
+/// The providers declared in the settings that were added to the registry.
+#[derive(Default)]
+struct SettingsProviders {
+    settings: GitHostingProviderSettings,
+    registered_names: Vec<String>,
+}
+
+impl SettingsProviders {
+    /// Replaces the providers declared in the previous settings with the ones declared in the
+    /// current settings. A provider named like a built-in provider or a provider of an
+    /// extension is rejected rather than replacing it.
+    fn update(&mut self, provider_registry: &GitHostingProviderRegistry, cx: &AppContext) {
+        let settings = GitHostingProviderSettings::get_global(cx);
+        if *settings == self.settings {
+            return;
+        }
+
+        for name in self.registered_names.drain(..) {
+            provider_registry.unregister_hosting_provider(&name);
+        }
+        for config in &settings.providers {
+            let name = config.name.clone();
+            let registered = CustomGitHostingProvider::new(config.clone()).and_then(|provider| {
+                provider_registry.try_register_hosting_provider(Arc::new(provider))
+            });
+            if registered.log_err().is_some() {
+                self.registered_names.push(name);
+            }
+        }
+        self.settings = settings.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{TestAppContext, UpdateGlobal};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn provider_names(provider_registry: &GitHostingProviderRegistry) -> Vec<String> {
+        provider_registry
+            .list_hosting_providers()
+            .into_iter()
+            .map(|provider| provider.name())
+            .collect()
+    }
+
+    #[gpui::test]
+    fn test_settings_providers_do_not_replace_other_providers(cx: &mut TestAppContext) {
+        let provider_registry = Arc::new(GitHostingProviderRegistry::new());
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            GitHostingProviderRegistry::set_global(provider_registry.clone(), cx);
+            init(cx);
+        });
+        let built_in_names = provider_names(&provider_registry);
+
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store
+                    .set_user_settings(
+                        r#"{
+                            "git_hosting_providers": [
+                                { "name": "GitHub", "host": "github.example.com" },
+                                { "name": "Example", "host": "git.example.com" }
+                            ]
+                        }"#,
+                        cx,
+                    )
+                    .unwrap();
+            });
+        });
+        let github = provider_registry
+            .list_hosting_providers()
+            .into_iter()
+            .find(|provider| provider.name() == "GitHub")
+            .unwrap();
+        assert_eq!(github.base_url().as_str(), "https://github.com/");
+        let mut expected_names = built_in_names.clone();
+        expected_names.push("Example".to_string());
+        expected_names.sort();
+        assert_eq!(provider_names(&provider_registry), expected_names);
+
+        // Removing the providers from the settings keeps the built-in provider with the same name.
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.set_user_settings("{}", cx).unwrap();
+            });
+        });
+        assert_eq!(provider_names(&provider_registry), built_in_names);
+    }
 }
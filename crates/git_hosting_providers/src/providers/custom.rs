@@ -0,0 +1,448 @@
+use anyhow::{anyhow, Context, Result};
+use regex::Regex;
+use url::Url;
+
+use git::{
+    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, ParsedGitRemote,
+    PullRequest,
+};
+
+use crate::settings::{GitHostingProviderConfig, GitHostingProviderKind};
+
+/// A Git hosting provider declared in the settings or by an extension, whose
+/// URLs are built from templates.
+pub struct CustomGitHostingProvider {
+    name: String,
+    host_pattern: Vec<String>,
+    kind: GitHostingProviderKind,
+    base_url: Url,
+    permalink_template: String,
+    commit_template: String,
+    pull_request_template: String,
+    pull_request_regex: Regex,
+}
+
+impl CustomGitHostingProvider {
+    pub fn new(config: GitHostingProviderConfig) -> Result<Self> {
+        let GitHostingProviderConfig {
+            name,
+            host,
+            kind,
+            base_url,
+            permalink_url,
+            commit_url,
+            pull_request_url,
+        } = config;
+
+        if name.is_empty() {
+            return Err(anyhow!("git hosting provider has no name"));
+        }
+        let host = host.to_lowercase();
+        let host_pattern = host.split('.').map(ToString::to_string).collect::<Vec<_>>();
+        if host_pattern.iter().any(|label| label.is_empty()) {
+            return Err(anyhow!(
+                "invalid host {host:?} for git hosting provider {name}"
+            ));
+        }
+        let base_url = match base_url {
+            Some(base_url) => base_url,
+            None if host.contains('*') => {
+                return Err(anyhow!(
+                    "git hosting provider {name} needs a base URL, as its host contains a wildcard"
+                ))
+            }
+            None => format!("https://{host}"),
+        };
+        let base_url = Url::parse(&base_url)
+            .with_context(|| format!("invalid base URL for git hosting provider {name}"))?;
+
+        let (default_permalink, default_commit, default_pull_request, pull_request_regex) =
+            match kind {
+                GitHostingProviderKind::Github => (
+                    "{base_url}/{owner}/{repo}/blob/{sha}/{path}",
+                    "{base_url}/{owner}/{repo}/commit/{sha}",
+                    "{base_url}/{owner}/{repo}/pull/{number}",
+                    r"\(#(\d+)\)$",
+                ),
+                GitHostingProviderKind::Gitlab => (
+                    "{base_url}/{owner}/{repo}/-/blob/{sha}/{path}",
+                    "{base_url}/{owner}/{repo}/-/commit/{sha}",
+                    "{base_url}/{owner}/{repo}/-/merge_requests/{number}",
+                    r"See merge request \S*!(\d+)",
+                ),
+                GitHostingProviderKind::Gitea => (
+                    "{base_url}/{owner}/{repo}/src/commit/{sha}/{path}",
+                    "{base_url}/{owner}/{repo}/commit/{sha}",
+                    "{base_url}/{owner}/{repo}/pulls/{number}",
+                    r"\(#(\d+)\)$",
+                ),
+                GitHostingProviderKind::Bitbucket => (
+                    "{base_url}/{owner}/{repo}/src/{sha}/{path}",
+                    "{base_url}/{owner}/{repo}/commits/{sha}",
+                    "{base_url}/{owner}/{repo}/pull-requests/{number}",
+                    r"\(pull request #(\d+)\)",
+                ),
+            };
+
+        let this = Self {
+            name,
+            host_pattern,
+            kind,
+            base_url,
+            permalink_template: permalink_url.unwrap_or_else(|| default_permalink.to_string()),
+            commit_template: commit_url.unwrap_or_else(|| default_commit.to_string()),
+            pull_request_template: pull_request_url
+                .unwrap_or_else(|| default_pull_request.to_string()),
+            pull_request_regex: Regex::new(pull_request_regex)?,
+        };
+
+        for template in [
+            &this.permalink_template,
+            &this.commit_template,
+            &this.pull_request_template,
+        ] {
+            let example = this.expand(template, "owner", "repo", &[]);
+            Url::parse(&example).with_context(|| {
+                format!(
+                    "invalid URL template {template:?} for git hosting provider {}",
+                    this.name
+                )
+            })?;
+        }
+
+        Ok(this)
+    }
+
+    fn matches_host(&self, host: &str) -> bool {
+        let host = host.to_lowercase();
+        let labels = host.split('.').collect::<Vec<_>>();
+        labels.len() == self.host_pattern.len()
+            && labels
+                .iter()
+                .zip(&self.host_pattern)
+                .all(|(label, pattern)| pattern == "*" || label == pattern)
+    }
+
+    /// Replaces the `{base_url}`, `{owner}` and `{repo}` placeholders of the
+    /// template, as well as the given ones.
+    fn expand(&self, template: &str, owner: &str, repo: &str, values: &[(&str, &str)]) -> String {
+        let mut url = template
+            .replace("{base_url}", self.base_url.as_str().trim_end_matches('/'))
+            .replace("{owner}", owner)
+            .replace("{repo}", repo);
+        for (placeholder, value) in values {
+            url = url.replace(placeholder, value);
+        }
+        url
+    }
+
+    fn expand_url(&self, template: &str, remote: &ParsedGitRemote, values: &[(&str, &str)]) -> Url {
+        let url = self.expand(template, remote.owner, remote.repo, values);
+        Url::parse(&url).unwrap_or_else(|_| self.base_url())
+    }
+}
+
+/// Splits a remote URL, like `git@host:owner/repo.git` or
+/// `https://user@host:port/owner/repo`, into its host and path.
+fn split_remote_url(url: &str) -> Option<(&str, &str)> {
+    let (authority, path) = match url.split_once("://") {
+        Some((_, rest)) => {
+            let (authority, path) = rest.split_once('/')?;
+            let authority = authority
+                .split_once(':')
+                .map_or(authority, |(authority, _port)| authority);
+            (authority, path)
+        }
+        None => url.split_once(':')?,
+    };
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_user, host)| host);
+    Some((host, path))
+}
+
+impl GitHostingProvider for CustomGitHostingProvider {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn base_url(&self) -> Url {
+        self.base_url.clone()
+    }
+
+    fn supports_avatars(&self) -> bool {
+        false
+    }
+
+    fn format_line_number(&self, line: u32) -> String {
+        match self.kind {
+            GitHostingProviderKind::Bitbucket => format!("lines-{line}"),
+            _ => format!("L{line}"),
+        }
+    }
+
+    fn format_line_numbers(&self, start_line: u32, end_line: u32) -> String {
+        match self.kind {
+            GitHostingProviderKind::Github | GitHostingProviderKind::Gitea => {
+                format!("L{start_line}-L{end_line}")
+            }
+            GitHostingProviderKind::Gitlab => format!("L{start_line}-{end_line}"),
+            GitHostingProviderKind::Bitbucket => format!("lines-{start_line}:{end_line}"),
+        }
+    }
+
+    fn parse_remote_url<'a>(&self, url: &'a str) -> Option<ParsedGitRemote<'a>> {
+        let (host, path) = split_remote_url(url)?;
+        if !self.matches_host(host) {
+            return None;
+        }
+
+        let repo_with_owner = path.trim_matches('/').trim_end_matches(".git");
+        // Owners may contain slashes, as GitLab groups can be nested.
+        let (owner, repo) = repo_with_owner.rsplit_once('/')?;
+        if owner.is_empty() || repo.is_empty() {
+            return None;
+        }
+
+        Some(ParsedGitRemote { owner, repo })
+    }
+
+    fn build_commit_permalink(
+        &self,
+        remote: &ParsedGitRemote,
+        params: BuildCommitPermalinkParams,
+    ) -> Url {
+        let BuildCommitPermalinkParams { sha } = params;
+
+        self.expand_url(&self.commit_template, remote, &[("{sha}", sha)])
+    }
+
+    fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
+        let BuildPermalinkParams {
+            sha,
+            path,
+            selection,
+        } = params;
+
+        let mut permalink = self.expand_url(
+            &self.permalink_template,
+            &remote,
+            &[("{sha}", sha), ("{path}", path)],
+        );
+        if path.ends_with(".md")
+            && permalink.query().is_none()
+            && matches!(
+                self.kind,
+                GitHostingProviderKind::Github | GitHostingProviderKind::Gitlab
+            )
+        {
+            permalink.set_query(Some("plain=1"));
+        }
+        permalink.set_fragment(
+            selection
+                .map(|selection| self.line_fragment(&selection))
+                .as_deref(),
+        );
+        permalink
+    }
+
+    fn extract_pull_request(&self, remote: &ParsedGitRemote, message: &str) -> Option<PullRequest> {
+        let capture = match self.kind {
+            // GitLab mentions the merge request in the body of merge commits.
+            GitHostingProviderKind::Gitlab => self.pull_request_regex.captures(message)?,
+            _ => self.pull_request_regex.captures(message.lines().next()?)?,
+        };
+        let number = capture.get(1)?.as_str().parse::<u32>().ok()?;
+        let url = self.expand_url(
+            &self.pull_request_template,
+            remote,
+            &[("{number}", &number.to_string())],
+        );
+
+        Some(PullRequest { number, url })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn provider(host: &str, kind: GitHostingProviderKind) -> CustomGitHostingProvider {
+        CustomGitHostingProvider::new(GitHostingProviderConfig {
+            name: "Example".to_string(),
+            host: host.to_string(),
+            kind,
+            base_url: None,
+            permalink_url: None,
+            commit_url: None,
+            pull_request_url: None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_remote_url() {
+        let gitlab = provider("git.example.com", GitHostingProviderKind::Gitlab);
+
+        for url in [
+            "git@git.example.com:group/subgroup/zed.git",
+            "ssh://git@git.example.com:2222/group/subgroup/zed.git",
+            "https://git.example.com/group/subgroup/zed",
+            "https://user@git.example.com/group/subgroup/zed.git",
+        ] {
+            let remote = gitlab.parse_remote_url(url).unwrap();
+            assert_eq!(
+                (remote.owner, remote.repo),
+                ("group/subgroup", "zed"),
+                "{url}"
+            );
+        }
+
+        assert!(gitlab
+            .parse_remote_url("git@gitlab.com:zed-industries/zed.git")
+            .is_none());
+        assert!(gitlab
+            .parse_remote_url("https://other.git.example.com/zed-industries/zed")
+            .is_none());
+        assert!(gitlab.parse_remote_url("git@git.example.com:zed").is_none());
+    }
+
+    #[test]
+    fn test_parse_remote_url_with_wildcard_host() {
+        assert!(CustomGitHostingProvider::new(GitHostingProviderConfig {
+            name: "Example".to_string(),
+            host: "*.example.com".to_string(),
+            kind: GitHostingProviderKind::Gitea,
+            base_url: None,
+            permalink_url: None,
+            commit_url: None,
+            pull_request_url: None,
+        })
+        .is_err());
+
+        let gitea = CustomGitHostingProvider::new(GitHostingProviderConfig {
+            name: "Example".to_string(),
+            host: "*.example.com".to_string(),
+            kind: GitHostingProviderKind::Gitea,
+            base_url: Some("https://code.example.com".to_string()),
+            permalink_url: None,
+            commit_url: None,
+            pull_request_url: None,
+        })
+        .unwrap();
+
+        let remote = gitea
+            .parse_remote_url("git@ssh.example.com:zed-industries/zed.git")
+            .unwrap();
+        let permalink = gitea.build_commit_permalink(
+            &remote,
+            BuildCommitPermalinkParams {
+                sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
+            },
+        );
+        assert_eq!(
+            permalink.to_string(),
+            "https://code.example.com/zed-industries/zed/commit/e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7"
+        );
+        assert!(gitea
+            .parse_remote_url("git@example.com:zed-industries/zed.git")
+            .is_none());
+    }
+
+    #[test]
+    fn test_build_permalink() {
+        let remote = ParsedGitRemote {
+            owner: "zed-industries",
+            repo: "zed",
+        };
+        let params = || BuildPermalinkParams {
+            sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
+            path: "crates/editor/src/git/permalink.rs",
+            selection: Some(23..47),
+        };
+
+        let permalink = provider("git.example.com", GitHostingProviderKind::Gitlab)
+            .build_permalink(ParsedGitRemote { ..remote }, params());
+        assert_eq!(
+            permalink.to_string(),
+            "https://git.example.com/zed-industries/zed/-/blob/e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7/crates/editor/src/git/permalink.rs#L24-48"
+        );
+
+        let permalink = provider("git.example.com", GitHostingProviderKind::Gitea)
+            .build_permalink(ParsedGitRemote { ..remote }, params());
+        assert_eq!(
+            permalink.to_string(),
+            "https://git.example.com/zed-industries/zed/src/commit/e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7/crates/editor/src/git/permalink.rs#L24-L48"
+        );
+
+        let permalink = provider("git.example.com", GitHostingProviderKind::Bitbucket)
+            .build_permalink(remote, params());
+        assert_eq!(
+            permalink.to_string(),
+            "https://git.example.com/zed-industries/zed/src/e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7/crates/editor/src/git/permalink.rs#lines-24:48"
+        );
+    }
+
+    #[test]
+    fn test_build_permalink_from_templates() {
+        let provider = CustomGitHostingProvider::new(GitHostingProviderConfig {
+            name: "Example".to_string(),
+            host: "git.example.com".to_string(),
+            kind: GitHostingProviderKind::Github,
+            base_url: Some("https://example.com/git/".to_string()),
+            permalink_url: Some("{base_url}/{owner}/{repo}/files/{path}?at={sha}".to_string()),
+            commit_url: None,
+            pull_request_url: Some("{base_url}/reviews/{repo}/{number}".to_string()),
+        })
+        .unwrap();
+        let remote = ParsedGitRemote {
+            owner: "zed-industries",
+            repo: "zed",
+        };
+
+        let permalink = provider.build_permalink(
+            ParsedGitRemote { ..remote },
+            BuildPermalinkParams {
+                sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
+                path: "README.md",
+                selection: Some(6..6),
+            },
+        );
+        assert_eq!(
+            permalink.to_string(),
+            "https://example.com/git/zed-industries/zed/files/README.md?at=e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7#L7"
+        );
+
+        let pull_request = provider
+            .extract_pull_request(&remote, "Fix the permalinks (#123)\n\nDetails")
+            .unwrap();
+        assert_eq!(pull_request.number, 123);
+        assert_eq!(
+            pull_request.url.to_string(),
+            "https://example.com/git/reviews/zed/123"
+        );
+    }
+
+    #[test]
+    fn test_extract_gitlab_merge_request() {
+        let gitlab = provider("git.example.com", GitHostingProviderKind::Gitlab);
+        let remote = ParsedGitRemote {
+            owner: "group/subgroup",
+            repo: "zed",
+        };
+
+        let message = "Merge branch 'permalinks' into 'main'\n\nFix the permalinks\n\nSee merge request group/subgroup/zed!42";
+        let pull_request = gitlab.extract_pull_request(&remote, message).unwrap();
+        assert_eq!(pull_request.number, 42);
+        assert_eq!(
+            pull_request.url.to_string(),
+            "https://git.example.com/group/subgroup/zed/-/merge_requests/42"
+        );
+
+        assert!(gitlab
+            .extract_pull_request(&remote, "Fix the permalinks (#123)")
+            .is_none());
+    }
+}
@@ -0,0 +1,63 @@
+use anyhow::Result;
+use gpui::AppContext;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+/// The Git hosting providers declared in the settings, in addition to the
+/// built-in ones.
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(transparent)]
+pub struct GitHostingProviderSettings {
+    pub providers: Vec<GitHostingProviderConfig>,
+}
+
+/// A Git hosting provider, like a self-hosted GitLab or Gitea instance.
+#[derive(Clone, Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+pub struct GitHostingProviderConfig {
+    /// The name of the provider, which must differ from the names of the other providers.
+    pub name: String,
+    /// The host of the remote URLs of the provider's repositories, like `git.example.com`.
+    /// A `*` matches any single part of a domain name, as in `*.example.com`.
+    pub host: String,
+    /// The software serving the repositories, which determines the default URL
+    /// templates and the format of line numbers in permalinks.
+    ///
+    /// Default: github
+    #[serde(default)]
+    pub kind: GitHostingProviderKind,
+    /// The URL of the provider's web interface.
+    ///
+    /// Default: `https://` followed by the host, which must then not contain a `*`
+    pub base_url: Option<String>,
+    /// The template of permalinks to files, which may contain the `{base_url}`, `{owner}`,
+    /// `{repo}`, `{sha}` and `{path}` placeholders.
+    pub permalink_url: Option<String>,
+    /// The template of permalinks to commits, which may contain the `{base_url}`, `{owner}`,
+    /// `{repo}` and `{sha}` placeholders.
+    pub commit_url: Option<String>,
+    /// The template of links to pull requests, which may contain the `{base_url}`, `{owner}`,
+    /// `{repo}` and `{number}` placeholders.
+    pub pull_request_url: Option<String>,
+}
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GitHostingProviderKind {
+    #[default]
+    Github,
+    Gitlab,
+    /// Gitea, or one of its forks like Forgejo.
+    Gitea,
+    Bitbucket,
+}
+
+impl Settings for GitHostingProviderSettings {
+    const KEY: Option<&'static str> = Some("git_hosting_providers");
+
+    type FileContent = Self;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
@@ -0,0 +1,464 @@
+use std::{
+    any::{Any, TypeId},
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use editor::{actions::ExpandAllHunkDiffs, Editor, EditorEvent};
+use git::{commit::CommitSummary, Oid};
+use gpui::{
+    AnyView, AppContext, ClipboardItem, EventEmitter, FocusHandle, FocusableView, Model, Task, View,
+};
+use language::{Anchor, Buffer, Capability, LocalFile, Point};
+use multi_buffer::MultiBuffer;
+use project::{Project, ProjectPath, WorktreeId};
+use rpc::proto;
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, Tooltip};
+use util::ResultExt;
+use workspace::{
+    item::{Item, ItemEvent},
+    searchable::SearchableItemHandle,
+    ItemNavHistory, Workspace,
+};
+
+use crate::CommitPermalink;
+
+/// How many of a commit's changed files are loaded at a time.
+const FILES_PER_PAGE: usize = 50;
+
+/// Shows the message of a commit, and the hunks it changed in a multibuffer.
+pub struct CommitView {
+    project: Model<Project>,
+    path: ProjectPath,
+    summary: CommitSummary,
+    message: Option<SharedString>,
+    permalink: Option<CommitPermalink>,
+    editor: View<Editor>,
+    local_timezone: UtcOffset,
+    error: Option<SharedString>,
+    /// How many of the commit's changed files were loaded.
+    loaded_file_count: usize,
+    changed_file_count: Option<usize>,
+    load_task: Option<Task<()>>,
+}
+
+impl CommitView {
+    pub fn new(
+        project: Model<Project>,
+        path: ProjectPath,
+        summary: CommitSummary,
+        permalink: Option<CommitPermalink>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let multibuffer = cx.new_model(|_| MultiBuffer::new(Capability::ReadOnly));
+        let editor = cx.new_view(|cx| {
+            let mut editor =
+                Editor::for_multibuffer(multibuffer.clone(), Some(project.clone()), true, cx);
+            editor.set_read_only(true);
+            editor.set_expand_all_diff_hunks();
+            editor
+        });
+
+        let local_offset = chrono::Local::now().offset().local_minus_utc();
+        let mut this = Self {
+            project,
+            path,
+            summary,
+            message: None,
+            permalink,
+            editor,
+            local_timezone: UtcOffset::from_whole_seconds(local_offset).unwrap_or(UtcOffset::UTC),
+            error: None,
+            loaded_file_count: 0,
+            changed_file_count: None,
+            load_task: None,
+        };
+        this.load_more_files(cx);
+        this
+    }
+
+    /// Loads the next page of the commit's changed files, and appends the hunks they changed
+    /// to the multibuffer.
+    fn load_more_files(&mut self, cx: &mut ViewContext<Self>) {
+        if self.load_task.is_some() {
+            return;
+        }
+
+        let project = self.project.clone();
+        let path = self.path.clone();
+        let file_offset = self.loaded_file_count;
+        let details = project.read(cx).commit_details(
+            path.clone(),
+            self.summary.sha,
+            file_offset,
+            FILES_PER_PAGE,
+            cx,
+        );
+        let language_registry = project.read(cx).languages().clone();
+        self.load_task = Some(cx.spawn(|this, mut cx| async move {
+            let details = match details.await {
+                Ok(details) => details,
+                Err(error) => {
+                    this.update(&mut cx, |this, cx| {
+                        this.error = Some(error.to_string().into());
+                        this.load_task = None;
+                        cx.notify();
+                    })
+                    .ok();
+                    return;
+                }
+            };
+
+            let Ok(paths) = project.update(&mut cx, |project, cx| {
+                let worktree = project.worktree_for_id(path.worktree_id, cx)?;
+                let snapshot = worktree.read(cx).snapshot();
+                let repository = snapshot.repository_for_path(&path.path)?;
+                Some(
+                    details
+                        .changes
+                        .iter()
+                        .map(|change| repository.unrelativize(&snapshot, &change.path))
+                        .collect::<Vec<_>>(),
+                )
+            }) else {
+                return;
+            };
+
+            let mut buffers = Vec::with_capacity(details.changes.len());
+            for (ix, change) in details.changes.into_iter().enumerate() {
+                let file: Arc<dyn language::File> = Arc::new(CommitFile {
+                    worktree_id: path.worktree_id,
+                    path: paths
+                        .as_ref()
+                        .and_then(|paths| paths[ix].clone())
+                        .unwrap_or_else(|| Arc::from(change.path.as_path())),
+                });
+                let language = language_registry
+                    .language_for_file_path(&change.path)
+                    .await
+                    .ok();
+                let Some(buffer) = cx
+                    .new_model(|cx| {
+                        let text = text::Buffer::new(
+                            0,
+                            cx.entity_id().as_non_zero_u64().into(),
+                            change.new_text.unwrap_or_default(),
+                        );
+                        let mut buffer = Buffer::build(
+                            text,
+                            Some(change.old_text.unwrap_or_default()),
+                            Some(file),
+                            Capability::ReadOnly,
+                        );
+                        buffer.set_language_registry(language_registry.clone());
+                        buffer.set_language(language, cx);
+                        buffer
+                    })
+                    .log_err()
+                else {
+                    return;
+                };
+                let recalculate_diff = buffer
+                    .update(&mut cx, |buffer, cx| buffer.recalculate_diff(cx))
+                    .ok()
+                    .flatten();
+                if let Some(recalculate_diff) = recalculate_diff {
+                    recalculate_diff.await;
+                }
+                buffers.push(buffer);
+            }
+
+            this.update(&mut cx, |this, cx| {
+                this.message = Some(details.message.trim().to_string().into());
+                this.loaded_file_count =
+                    (file_offset + FILES_PER_PAGE).min(details.changed_file_count);
+                this.changed_file_count = Some(details.changed_file_count);
+                this.load_task = None;
+                this.editor.update(cx, |editor, cx| {
+                    editor.buffer().update(cx, |multibuffer, cx| {
+                        for buffer in buffers {
+                            let ranges = buffer
+                                .read(cx)
+                                .snapshot()
+                                .git_diff_hunks_intersecting_range(Anchor::MIN..Anchor::MAX)
+                                .map(|hunk| {
+                                    Point::new(hunk.row_range.start, 0)
+                                        ..Point::new(hunk.row_range.end, 0)
+                                })
+                                .collect::<Vec<_>>();
+                            if !ranges.is_empty() {
+                                multibuffer.push_excerpts_with_context_lines(
+                                    buffer,
+                                    ranges,
+                                    editor::DEFAULT_MULTIBUFFER_CONTEXT,
+                                    cx,
+                                );
+                            }
+                        }
+                    });
+                    editor.expand_all_hunk_diffs(&ExpandAllHunkDiffs, cx);
+                });
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    pub fn sha(&self) -> Oid {
+        self.summary.sha
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let timestamp = OffsetDateTime::from_unix_timestamp(self.summary.timestamp)
+            .map(|timestamp| {
+                time_format::format_localized_timestamp(
+                    timestamp,
+                    OffsetDateTime::now_utc(),
+                    self.local_timezone,
+                    time_format::TimestampFormat::EnhancedAbsolute,
+                )
+            })
+            .unwrap_or_default();
+        let sha = self.summary.sha.to_string();
+        let message = self
+            .message
+            .clone()
+            .unwrap_or_else(|| self.summary.subject.clone().into());
+
+        v_flex()
+            .p_2()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(self.summary.author_name.clone()))
+                            .child(
+                                Label::new(format!("<{}>", self.summary.author_email))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .child(
+                                Label::new(timestamp)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Button::new("copy-sha", self.summary.sha.display_short())
+                                    .icon(IconName::Copy)
+                                    .icon_size(IconSize::Small)
+                                    .icon_position(IconPosition::Start)
+                                    .label_size(LabelSize::Small)
+                                    .tooltip(|cx| Tooltip::text("Copy Commit SHA", cx))
+                                    .on_click(move |_, cx| {
+                                        cx.write_to_clipboard(ClipboardItem::new_string(
+                                            sha.clone(),
+                                        ))
+                                    }),
+                            )
+                            .when_some(self.permalink.clone(), |this, permalink| {
+                                this.child(
+                                    Button::new(
+                                        "open-permalink",
+                                        format!("Open on {}", permalink.provider_name),
+                                    )
+                                    .icon(IconName::ArrowUpRight)
+                                    .icon_size(IconSize::Small)
+                                    .label_size(LabelSize::Small)
+                                    .on_click(move |_, cx| cx.open_url(permalink.url.as_str())),
+                                )
+                            }),
+                    ),
+            )
+            .child(
+                div()
+                    .max_h(rems(12.))
+                    .overflow_hidden()
+                    .font_buffer(cx)
+                    .text_buffer(cx)
+                    .child(message),
+            )
+            .when_some(
+                self.changed_file_count
+                    .filter(|count| *count > self.loaded_file_count),
+                |this, changed_file_count| {
+                    this.child(
+                        h_flex()
+                            .gap_2()
+                            .child(
+                                Label::new(format!(
+                                    "Showing {} of {} changed files",
+                                    self.loaded_file_count, changed_file_count
+                                ))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                            )
+                            .child(
+                                Button::new("load-more-files", "Load More Files")
+                                    .label_size(LabelSize::Small)
+                                    .disabled(self.load_task.is_some())
+                                    .on_click(cx.listener(|this, _, cx| this.load_more_files(cx))),
+                            ),
+                    )
+                },
+            )
+            .when_some(self.error.clone(), |this, error| {
+                this.child(Label::new(error).color(Color::Error))
+            })
+    }
+}
+
+impl Render for CommitView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_header(cx))
+            .child(div().flex_1().child(self.editor.clone()))
+    }
+}
+
+impl FocusableView for CommitView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<EditorEvent> for CommitView {}
+
+impl Item for CommitView {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_content_text(&self, _: &WindowContext) -> Option<SharedString> {
+        Some(
+            format!(
+                "{} {}",
+                self.summary.sha.display_short(),
+                self.summary.subject
+            )
+            .into(),
+        )
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some(self.summary.subject.clone().into())
+    }
+
+    fn tab_icon(&self, _: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::FileGit))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn as_searchable(&self, _: &View<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| {
+            Item::added_to_workspace(editor, workspace, cx)
+        });
+    }
+
+    fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, Item::deactivated);
+    }
+
+    fn navigate(&mut self, data: Box<dyn Any>, cx: &mut ViewContext<Self>) -> bool {
+        self.editor
+            .update(cx, |editor, cx| Item::navigate(editor, data, cx))
+    }
+
+    fn set_nav_history(&mut self, nav_history: ItemNavHistory, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| {
+            Item::set_nav_history(editor, nav_history, cx)
+        });
+    }
+}
+
+/// The file of a buffer holding the contents of a file as of a commit. Its path is relative to
+/// the worktree when the file is inside it, so that jumping from an excerpt opens the file.
+struct CommitFile {
+    worktree_id: WorktreeId,
+    path: Arc<Path>,
+}
+
+impl language::File for CommitFile {
+    fn as_local(&self) -> Option<&dyn LocalFile> {
+        None
+    }
+
+    fn mtime(&self) -> Option<SystemTime> {
+        None
+    }
+
+    fn path(&self) -> &Arc<Path> {
+        &self.path
+    }
+
+    fn full_path(&self, _: &AppContext) -> PathBuf {
+        self.path.to_path_buf()
+    }
+
+    fn file_name<'a>(&'a self, _: &'a AppContext) -> &'a OsStr {
+        self.path.file_name().unwrap_or(self.path.as_os_str())
+    }
+
+    fn worktree_id(&self, _: &AppContext) -> WorktreeId {
+        self.worktree_id
+    }
+
+    fn is_deleted(&self) -> bool {
+        false
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn to_proto(&self, _: &AppContext) -> proto::File {
+        proto::File {
+            worktree_id: self.worktree_id.to_proto(),
+            entry_id: None,
+            path: self.path.to_string_lossy().to_string(),
+            mtime: None,
+            is_deleted: false,
+        }
+    }
+
+    fn is_private(&self) -> bool {
+        false
+    }
+}
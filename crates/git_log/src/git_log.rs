@@ -0,0 +1,503 @@
+mod commit_view;
+
+use std::{path::Path, sync::Arc};
+
+use git::{
+    commit::CommitSummary, parse_git_remote_url, BuildCommitPermalinkParams,
+    GitHostingProviderRegistry,
+};
+use gpui::{
+    actions, uniform_list, AnyElement, AppContext, ClipboardItem, EventEmitter, FocusHandle,
+    FocusableView, KeyContext, Model, Subscription, Task, UniformListScrollHandle, WeakView,
+};
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrev};
+use project::{Project, ProjectPath};
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, ListItem, ListItemSpacing, Tooltip};
+use url::Url;
+use workspace::{
+    item::{Item, ItemEvent, ItemHandle},
+    SplitDirection, Workspace,
+};
+
+pub use commit_view::CommitView;
+
+actions!(
+    git_log,
+    [ShowLog, ShowFileLog, CopyCommitSha, OpenCommitPermalink]
+);
+
+/// How many commits are loaded at a time.
+const PAGE_SIZE: usize = 200;
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(GitLogView::show_log);
+        workspace.register_action(GitLogView::show_file_log);
+    })
+    .detach();
+}
+
+/// A link to a commit on the hosting provider of its repository.
+#[derive(Clone, Debug)]
+pub struct CommitPermalink {
+    pub provider_name: SharedString,
+    pub url: Url,
+}
+
+/// Lists the commits of a repository, or the commits that changed a path, newest first.
+pub struct GitLogView {
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    path: ProjectPath,
+    filter_by_path: bool,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    commits: Vec<CommitSummary>,
+    selected_commit: Option<usize>,
+    has_more_commits: bool,
+    remote_url: Option<String>,
+    provider_registry: Arc<GitHostingProviderRegistry>,
+    error: Option<SharedString>,
+    local_timezone: UtcOffset,
+    load_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl GitLogView {
+    fn show_log(workspace: &mut Workspace, _: &ShowLog, cx: &mut ViewContext<Workspace>) {
+        let project = workspace.project().read(cx);
+        let path = workspace
+            .active_item(cx)
+            .and_then(|item| item.project_path(cx))
+            .or_else(|| {
+                let worktree = project.visible_worktrees(cx).next()?;
+                Some(ProjectPath {
+                    worktree_id: worktree.read(cx).id(),
+                    path: Arc::from(Path::new("")),
+                })
+            });
+        if let Some(path) = path {
+            Self::deploy(workspace, path, false, cx);
+        }
+    }
+
+    fn show_file_log(workspace: &mut Workspace, _: &ShowFileLog, cx: &mut ViewContext<Workspace>) {
+        if let Some(path) = workspace
+            .active_item(cx)
+            .and_then(|item| item.project_path(cx))
+        {
+            Self::deploy(workspace, path, true, cx);
+        }
+    }
+
+    fn deploy(
+        workspace: &mut Workspace,
+        path: ProjectPath,
+        filter_by_path: bool,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let existing_view = workspace.items_of_type::<Self>(cx).find(|view| {
+            let view = view.read(cx);
+            view.filter_by_path == filter_by_path
+                && (!filter_by_path || view.path == path)
+                && view.path.worktree_id == path.worktree_id
+        });
+        if let Some(existing_view) = existing_view {
+            workspace.activate_item(&existing_view, true, true, cx);
+            return;
+        }
+
+        let project = workspace.project().clone();
+        let workspace_handle = cx.view().downgrade();
+        let view = cx.new_view(|cx| Self::new(project, workspace_handle, path, filter_by_path, cx));
+        workspace.add_item_to_active_pane(Box::new(view), None, true, cx);
+    }
+
+    fn new(
+        project: Model<Project>,
+        workspace: WeakView<Workspace>,
+        path: ProjectPath,
+        filter_by_path: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let project_subscription = cx.subscribe(&project, |this, _, event, cx| {
+            if let project::Event::WorktreeUpdatedGitRepositories = event {
+                this.load_commits(0, cx);
+            }
+        });
+
+        let local_offset = chrono::Local::now().offset().local_minus_utc();
+        let mut this = Self {
+            project,
+            workspace,
+            path,
+            filter_by_path,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            commits: Vec::new(),
+            selected_commit: None,
+            has_more_commits: true,
+            remote_url: None,
+            provider_registry: GitHostingProviderRegistry::default_global(cx),
+            error: None,
+            local_timezone: UtcOffset::from_whole_seconds(local_offset).unwrap_or(UtcOffset::UTC),
+            load_task: None,
+            _subscriptions: vec![project_subscription],
+        };
+        this.load_commits(0, cx);
+        this
+    }
+
+    /// Loads a page of commits, starting at the given index. Loading the first page replaces
+    /// the listed commits, keeping the selected commit selected.
+    fn load_commits(&mut self, skip: usize, cx: &mut ViewContext<Self>) {
+        let log = self.project.read(cx).git_log(
+            self.path.clone(),
+            self.filter_by_path,
+            skip,
+            PAGE_SIZE,
+            cx,
+        );
+        self.load_task = Some(cx.spawn(|this, mut cx| async move {
+            let log = log.await;
+            this.update(&mut cx, |this, cx| {
+                this.load_task = None;
+                match log {
+                    Ok(log) => {
+                        let selected_sha = this.selected_commit().map(|commit| commit.sha);
+                        if skip == 0 {
+                            this.commits.clear();
+                        }
+                        this.has_more_commits = log.commits.len() == PAGE_SIZE;
+                        this.commits.extend(log.commits);
+                        this.remote_url = log.remote_url;
+                        this.error = None;
+                        this.selected_commit = selected_sha
+                            .and_then(|sha| {
+                                this.commits.iter().position(|commit| commit.sha == sha)
+                            })
+                            .or_else(|| (!this.commits.is_empty()).then_some(0));
+                    }
+                    Err(error) => {
+                        this.has_more_commits = false;
+                        this.error = Some(error.to_string().into());
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn load_more_commits(&mut self, cx: &mut ViewContext<Self>) {
+        if self.has_more_commits && self.load_task.is_none() {
+            self.load_commits(self.commits.len(), cx);
+        }
+    }
+
+    fn selected_commit(&self) -> Option<&CommitSummary> {
+        self.commits.get(self.selected_commit?)
+    }
+
+    fn select_commit(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        if ix >= self.commits.len() {
+            self.load_more_commits(cx);
+            return;
+        }
+        self.selected_commit = Some(ix);
+        self.scroll_handle.scroll_to_item(ix);
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        let next_ix = self.selected_commit.map_or(0, |ix| ix + 1);
+        self.select_commit(next_ix, cx);
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        if let Some(prev_ix) = self.selected_commit.and_then(|ix| ix.checked_sub(1)) {
+            self.select_commit(prev_ix, cx);
+        }
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, cx: &mut ViewContext<Self>) {
+        self.select_commit(0, cx);
+    }
+
+    fn select_last(&mut self, _: &SelectLast, cx: &mut ViewContext<Self>) {
+        if let Some(last_ix) = self.commits.len().checked_sub(1) {
+            self.select_commit(last_ix, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self.selected_commit {
+            self.open_commit(ix, cx);
+        }
+    }
+
+    fn copy_commit_sha(&mut self, _: &CopyCommitSha, cx: &mut ViewContext<Self>) {
+        if let Some(commit) = self.selected_commit() {
+            cx.write_to_clipboard(ClipboardItem::new_string(commit.sha.to_string()));
+        }
+    }
+
+    fn open_commit_permalink(&mut self, _: &OpenCommitPermalink, cx: &mut ViewContext<Self>) {
+        if let Some(permalink) = self
+            .selected_commit()
+            .and_then(|commit| self.permalink(commit))
+        {
+            cx.open_url(permalink.url.as_str());
+        }
+    }
+
+    fn permalink(&self, commit: &CommitSummary) -> Option<CommitPermalink> {
+        let (provider, remote) =
+            parse_git_remote_url(self.provider_registry.clone(), self.remote_url.as_deref()?)?;
+        let url = provider.build_commit_permalink(
+            &remote,
+            BuildCommitPermalinkParams {
+                sha: commit.sha.to_string().as_str(),
+            },
+        );
+        Some(CommitPermalink {
+            provider_name: provider.name().into(),
+            url,
+        })
+    }
+
+    /// Opens the given commit next to the log. Commits are opened as preview items, so
+    /// browsing the log doesn't leave a tab behind for every commit.
+    fn open_commit(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        if ix >= self.commits.len() {
+            return;
+        }
+        self.selected_commit = Some(ix);
+        cx.notify();
+
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let commit = &self.commits[ix];
+        let sha = commit.sha;
+        let commit_view = cx.new_view(|cx| {
+            CommitView::new(
+                self.project.clone(),
+                self.path.clone(),
+                commit.clone(),
+                self.permalink(commit),
+                cx,
+            )
+        });
+        workspace.update(cx, |workspace, cx| {
+            let existing_view = workspace
+                .items_of_type::<CommitView>(cx)
+                .find(|view| view.read(cx).sha() == sha);
+            if let Some(existing_view) = existing_view {
+                workspace.activate_item(&existing_view, false, false, cx);
+                return;
+            }
+
+            let commit_pane = workspace
+                .panes()
+                .iter()
+                .find(|pane| {
+                    pane.read(cx)
+                        .items()
+                        .any(|item| item.downcast::<CommitView>().is_some())
+                })
+                .cloned();
+            match commit_pane {
+                Some(pane) => pane.update(cx, |pane, cx| {
+                    let destination_index = pane.close_current_preview_item(cx);
+                    pane.add_item(
+                        Box::new(commit_view.clone()),
+                        true,
+                        false,
+                        destination_index,
+                        cx,
+                    );
+                    pane.set_preview_item_id(Some(commit_view.item_id()), cx);
+                }),
+                None => {
+                    workspace.split_item(SplitDirection::Right, Box::new(commit_view.clone()), cx);
+                    let pane = workspace.active_pane().clone();
+                    pane.update(cx, |pane, cx| {
+                        pane.set_preview_item_id(Some(commit_view.item_id()), cx)
+                    });
+                }
+            }
+        });
+        cx.focus(&self.focus_handle);
+    }
+
+    fn title(&self, cx: &AppContext) -> SharedString {
+        if self.filter_by_path {
+            if let Some(file_name) = self.path.path.file_name() {
+                return format!("Git Log: {}", file_name.to_string_lossy()).into();
+            }
+        }
+        match self
+            .project
+            .read(cx)
+            .worktree_for_id(self.path.worktree_id, cx)
+        {
+            Some(worktree) => format!("Git Log: {}", worktree.read(cx).root_name()).into(),
+            None => "Git Log".into(),
+        }
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("GitLog");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    fn render_commit(&self, ix: usize, cx: &mut ViewContext<Self>) -> AnyElement {
+        let Some(commit) = self.commits.get(ix) else {
+            let label = if self.load_task.is_some() {
+                "Loading…"
+            } else {
+                "Load More Commits"
+            };
+            return ListItem::new(ix)
+                .spacing(ListItemSpacing::Sparse)
+                .on_click(cx.listener(|this, _, cx| this.load_more_commits(cx)))
+                .child(Label::new(label).color(Color::Muted))
+                .into_any_element();
+        };
+
+        let timestamp = OffsetDateTime::from_unix_timestamp(commit.timestamp)
+            .map(|timestamp| {
+                time_format::format_localized_timestamp(
+                    timestamp,
+                    OffsetDateTime::now_utc(),
+                    self.local_timezone,
+                    time_format::TimestampFormat::Relative,
+                )
+            })
+            .unwrap_or_default();
+        let permalink = self.permalink(commit);
+
+        ListItem::new(ix)
+            .spacing(ListItemSpacing::Sparse)
+            .selected(self.selected_commit == Some(ix))
+            .on_click(cx.listener(move |this, _, cx| this.open_commit(ix, cx)))
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .justify_between()
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .overflow_hidden()
+                            .child(
+                                div()
+                                    .font_buffer(cx)
+                                    .text_color(cx.theme().colors().text_muted)
+                                    .child(commit.sha.display_short()),
+                            )
+                            .child(Label::new(commit.subject.clone()).single_line()),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .flex_none()
+                            .child(
+                                Label::new(commit.author_name.clone())
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .child(
+                                Label::new(timestamp)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                    ),
+            )
+            .end_hover_slot(permalink.map(|permalink| {
+                IconButton::new(("open-permalink", ix), IconName::ArrowUpRight)
+                    .icon_size(IconSize::Small)
+                    .tooltip(move |cx| {
+                        Tooltip::text(format!("Open on {}", permalink.provider_name), cx)
+                    })
+                    .on_click(move |_, cx| cx.open_url(permalink.url.as_str()))
+            }))
+            .into_any_element()
+    }
+}
+
+impl Render for GitLogView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let message = if let Some(error) = self.error.clone() {
+            Some(error)
+        } else if self.commits.is_empty() && self.load_task.is_some() {
+            Some("Loading…".into())
+        } else if self.commits.is_empty() {
+            Some("No commits".into())
+        } else {
+            None
+        };
+
+        v_flex()
+            .size_full()
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::copy_commit_sha))
+            .on_action(cx.listener(Self::open_commit_permalink))
+            .map(|this| match message {
+                Some(message) => this.justify_center().child(
+                    h_flex()
+                        .justify_center()
+                        .child(Label::new(message).color(Color::Muted)),
+                ),
+                None => this.child(
+                    uniform_list(
+                        cx.view().clone(),
+                        "git-log-commits",
+                        self.commits.len() + self.has_more_commits as usize,
+                        |this, range, cx| range.map(|ix| this.render_commit(ix, cx)).collect(),
+                    )
+                    .size_full()
+                    .track_scroll(self.scroll_handle.clone()),
+                ),
+            })
+    }
+}
+
+impl EventEmitter<ItemEvent> for GitLogView {}
+
+impl FocusableView for GitLogView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for GitLogView {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_content_text(&self, cx: &WindowContext) -> Option<SharedString> {
+        Some(self.title(cx))
+    }
+
+    fn tab_icon(&self, _: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::FileGit))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+}
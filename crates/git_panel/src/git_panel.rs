@@ -0,0 +1,1173 @@
+mod git_panel_settings;
+
+use std::{ops::Range, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use collections::HashMap;
+use db::kvp::KEY_VALUE_STORE;
+use editor::{actions::ExpandAllHunkDiffs, Editor};
+use git::{
+    diff::BufferDiff,
+    status::{FileStatus, StatusCode},
+};
+use gpui::{
+    actions, uniform_list, Action, AnyElement, AppContext, AsyncWindowContext, EventEmitter,
+    FocusHandle, FocusableView, InteractiveElement, IntoElement, KeyContext, Model, ParentElement,
+    Pixels, Render, Styled, Subscription, Task, UniformListScrollHandle, View, ViewContext,
+    VisualContext, WeakView, WindowContext,
+};
+use language::{Anchor, Buffer, BufferId, Point, Rope};
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrev};
+use multi_buffer::MultiBuffer;
+use project::{Fs, Project, ProjectPath};
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    ui::{
+        h_flex, v_flex, ActiveTheme, ButtonCommon, Clickable, Color, FluentBuilder, IconButton,
+        IconName, IconSize, Label, LabelCommon, LabelSize, ListItem, ListItemSpacing, Selectable,
+        StyledTypography, Tooltip,
+    },
+    Workspace,
+};
+
+use git_panel_settings::{GitPanelDockPosition, GitPanelSettings};
+
+actions!(
+    git_panel,
+    [OpenChanges, StageAll, ToggleFocus, ToggleStaged, UnstageAll]
+);
+
+const GIT_PANEL_KEY: &str = "GitPanel";
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(200);
+
+#[derive(Serialize, Deserialize)]
+struct SerializedGitPanel {
+    width: Option<Pixels>,
+    active: Option<bool>,
+}
+
+pub fn init_settings(cx: &mut AppContext) {
+    GitPanelSettings::register(cx);
+}
+
+pub fn init(cx: &mut AppContext) {
+    init_settings(cx);
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<GitPanel>(cx);
+        });
+    })
+    .detach();
+}
+
+/// Groups of changed files, in the order they are listed in the panel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Section {
+    Staged,
+    Unstaged,
+    Untracked,
+}
+
+impl Section {
+    const ALL: [Section; 3] = [Section::Staged, Section::Unstaged, Section::Untracked];
+
+    fn title(self) -> &'static str {
+        match self {
+            Section::Staged => "Staged Changes",
+            Section::Unstaged => "Changes",
+            Section::Untracked => "Untracked Files",
+        }
+    }
+
+    fn contains(self, status: &FileStatus) -> bool {
+        match self {
+            Section::Staged => status.is_staged(),
+            Section::Unstaged => status.is_unstaged(),
+            Section::Untracked => status.is_untracked(),
+        }
+    }
+
+    fn status_code(self, status: &FileStatus) -> Option<StatusCode> {
+        match self {
+            Section::Staged => status.index_status,
+            Section::Unstaged | Section::Untracked => status.worktree_status,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum GitListEntry {
+    Header(Section),
+    File {
+        section: Section,
+        path: ProjectPath,
+        status: FileStatus,
+    },
+}
+
+/// A panel listing the staged, unstaged and untracked files of the project's repositories.
+pub struct GitPanel {
+    fs: Arc<dyn Fs>,
+    width: Option<Pixels>,
+    active: bool,
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    pending_serialization: Task<Option<()>>,
+    file_statuses: Vec<(ProjectPath, FileStatus)>,
+    /// Whether the statuses changed while neither the panel nor the changes
+    /// editor were visible, so that they need reloading once one is shown.
+    file_statuses_outdated: bool,
+    entries: Vec<GitListEntry>,
+    selected_entry: Option<usize>,
+    update_task: Task<()>,
+    changes_editor: Option<WeakView<Editor>>,
+    /// The buffers of the changed files, kept open while the changes editor is.
+    changes_editor_buffers: HashMap<ProjectPath, Model<Buffer>>,
+    /// The hunks shown in the changes editor, used to skip reloading its
+    /// excerpts when they haven't changed.
+    changes_editor_hunks: Vec<(BufferId, Vec<Range<Point>>)>,
+    changes_editor_update_task: Task<Option<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl GitPanel {
+    pub async fn load(
+        workspace: WeakView<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<View<Self>> {
+        let serialized_panel = cx
+            .background_executor()
+            .spawn(async move { KEY_VALUE_STORE.read_kvp(GIT_PANEL_KEY) })
+            .await
+            .context("loading git panel")
+            .log_err()
+            .flatten()
+            .map(|panel| serde_json::from_str::<SerializedGitPanel>(&panel))
+            .transpose()
+            .log_err()
+            .flatten();
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let panel = Self::new(workspace, cx);
+            if let Some(serialized_panel) = serialized_panel {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized_panel.width.map(|px| px.round());
+                    panel.active = serialized_panel.active.unwrap_or(false);
+                    if panel.active {
+                        panel.schedule_update(false, cx);
+                    }
+                    cx.notify();
+                });
+            }
+            panel
+        })
+    }
+
+    fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let workspace_handle = cx.view().downgrade();
+        cx.new_view(|cx| {
+            let project_subscription =
+                cx.subscribe(&project, |git_panel, _, event, cx| match event {
+                    project::Event::WorktreeAdded
+                    | project::Event::WorktreeRemoved(_)
+                    | project::Event::WorktreeOrderChanged
+                    | project::Event::WorktreeUpdatedEntries(..)
+                    | project::Event::WorktreeUpdatedGitRepositories => {
+                        if git_panel.is_visible() {
+                            git_panel.schedule_update(true, cx)
+                        } else {
+                            git_panel.file_statuses_outdated = true;
+                        }
+                    }
+                    _ => {}
+                });
+
+            let mut git_panel_settings = *GitPanelSettings::get_global(cx);
+            let settings_subscription = cx.observe_global::<SettingsStore>(move |_, cx| {
+                let new_settings = *GitPanelSettings::get_global(cx);
+                if git_panel_settings != new_settings {
+                    git_panel_settings = new_settings;
+                    cx.notify();
+                }
+            });
+
+            let mut git_panel = Self {
+                fs,
+                width: None,
+                active: false,
+                project,
+                workspace: workspace_handle,
+                focus_handle: cx.focus_handle(),
+                scroll_handle: UniformListScrollHandle::new(),
+                pending_serialization: Task::ready(None),
+                file_statuses: Vec::new(),
+                file_statuses_outdated: true,
+                entries: Vec::new(),
+                selected_entry: None,
+                update_task: Task::ready(()),
+                changes_editor: None,
+                changes_editor_buffers: HashMap::default(),
+                changes_editor_hunks: Vec::new(),
+                changes_editor_update_task: Task::ready(None),
+                _subscriptions: vec![project_subscription, settings_subscription],
+            };
+            if git_panel.is_visible() {
+                git_panel.schedule_update(false, cx);
+            }
+            git_panel
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        let active = Some(self.active);
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        GIT_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedGitPanel { width, active })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("GitPanel");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    /// Whether the panel or the changes editor are shown, in which case the
+    /// statuses are kept up to date.
+    fn is_visible(&self) -> bool {
+        self.active
+            || self
+                .changes_editor
+                .as_ref()
+                .is_some_and(|editor| editor.upgrade().is_some())
+    }
+
+    /// Reloads the statuses of all visible worktrees, optionally waiting for
+    /// a burst of file system events to settle first.
+    fn schedule_update(&mut self, debounce: bool, cx: &mut ViewContext<Self>) {
+        self.file_statuses_outdated = false;
+        let project = self.project.clone();
+        self.update_task = cx.spawn(|git_panel, mut cx| async move {
+            if debounce {
+                cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+            }
+            let Ok(status_tasks) = project.update(&mut cx, |project, cx| {
+                project
+                    .visible_worktrees(cx)
+                    .map(|worktree| {
+                        let worktree_id = worktree.read(cx).id();
+                        (worktree_id, project.git_status(worktree_id, cx))
+                    })
+                    .collect::<Vec<_>>()
+            }) else {
+                return;
+            };
+
+            let mut file_statuses = Vec::new();
+            for (worktree_id, status_task) in status_tasks {
+                let Some(statuses) = status_task.await.log_err() else {
+                    continue;
+                };
+                file_statuses.extend(
+                    statuses
+                        .into_iter()
+                        .map(|(path, status)| (ProjectPath { worktree_id, path }, status)),
+                );
+            }
+
+            git_panel
+                .update(&mut cx, |git_panel, cx| {
+                    git_panel.set_file_statuses(file_statuses, cx)
+                })
+                .ok();
+        });
+    }
+
+    fn set_file_statuses(
+        &mut self,
+        file_statuses: Vec<(ProjectPath, FileStatus)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let selected_entry = self
+            .selected_entry
+            .and_then(|ix| self.entries.get(ix))
+            .cloned();
+        self.entries.clear();
+        for section in Section::ALL {
+            let mut files = file_statuses
+                .iter()
+                .filter(|(_, status)| section.contains(status))
+                .peekable();
+            if files.peek().is_none() {
+                continue;
+            }
+            self.entries.push(GitListEntry::Header(section));
+            self.entries
+                .extend(files.map(|(path, status)| GitListEntry::File {
+                    section,
+                    path: path.clone(),
+                    status: *status,
+                }));
+        }
+        self.file_statuses = file_statuses;
+
+        self.selected_entry = selected_entry.and_then(|selected_entry| {
+            self.entries
+                .iter()
+                .position(|entry| match (entry, &selected_entry) {
+                    (
+                        GitListEntry::File { section, path, .. },
+                        GitListEntry::File {
+                            section: selected_section,
+                            path: selected_path,
+                            ..
+                        },
+                    ) => section == selected_section && path == selected_path,
+                    _ => false,
+                })
+        });
+
+        // Hunks can change without any status changing, e.g. when a modified
+        // file is saved again, so the changes are reloaded on every update.
+        self.update_changes_editor(cx);
+        cx.notify();
+    }
+
+    fn selected_file(&self) -> Option<(Section, &ProjectPath, &FileStatus)> {
+        match self.entries.get(self.selected_entry?)? {
+            GitListEntry::File {
+                section,
+                path,
+                status,
+            } => Some((*section, path, status)),
+            GitListEntry::Header(_) => None,
+        }
+    }
+
+    fn select(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        self.selected_entry = Some(ix);
+        self.scroll_handle.scroll_to_item(ix);
+        cx.notify();
+    }
+
+    fn is_file(&self, ix: usize) -> bool {
+        matches!(self.entries.get(ix), Some(GitListEntry::File { .. }))
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        let start = self.selected_entry.map_or(0, |ix| ix + 1);
+        if let Some(ix) = (start..self.entries.len()).find(|ix| self.is_file(*ix)) {
+            self.select(ix, cx);
+        }
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        let end = self.selected_entry.unwrap_or(self.entries.len());
+        if let Some(ix) = (0..end).rev().find(|ix| self.is_file(*ix)) {
+            self.select(ix, cx);
+        }
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = (0..self.entries.len()).find(|ix| self.is_file(*ix)) {
+            self.select(ix, cx);
+        }
+    }
+
+    fn select_last(&mut self, _: &SelectLast, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = (0..self.entries.len()).rev().find(|ix| self.is_file(*ix)) {
+            self.select(ix, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        let Some((_, path, status)) = self.selected_file() else {
+            return;
+        };
+        if status.worktree_status == Some(StatusCode::Deleted) {
+            return;
+        }
+        let path = path.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_path(path, None, true, cx)
+                    .detach_and_log_err(cx);
+            })
+            .ok();
+    }
+
+    fn toggle_staged(&mut self, _: &ToggleStaged, cx: &mut ViewContext<Self>) {
+        let Some((section, path, _)) = self.selected_file() else {
+            return;
+        };
+        let paths = vec![path.clone()];
+        let project = self.project.read(cx);
+        let task = if section == Section::Staged {
+            project.unstage_paths(paths, cx)
+        } else {
+            project.stage_paths(paths, cx)
+        };
+        task.detach_and_log_err(cx);
+    }
+
+    fn stage_all(&mut self, _: &StageAll, cx: &mut ViewContext<Self>) {
+        let paths = self
+            .file_statuses
+            .iter()
+            .filter(|(_, status)| status.is_unstaged() || status.is_untracked())
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        if !paths.is_empty() {
+            self.project
+                .read(cx)
+                .stage_paths(paths, cx)
+                .detach_and_log_err(cx);
+        }
+    }
+
+    fn unstage_all(&mut self, _: &UnstageAll, cx: &mut ViewContext<Self>) {
+        let paths = self
+            .file_statuses
+            .iter()
+            .filter(|(_, status)| status.is_staged())
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        if !paths.is_empty() {
+            self.project
+                .read(cx)
+                .unstage_paths(paths, cx)
+                .detach_and_log_err(cx);
+        }
+    }
+
+    /// Opens a multibuffer containing the hunks of every changed file,
+    /// or activates it if it is already open.
+    fn open_changes(&mut self, _: &OpenChanges, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        if let Some(editor) = self
+            .changes_editor
+            .as_ref()
+            .and_then(|editor| editor.upgrade())
+        {
+            workspace.update(cx, |workspace, cx| {
+                workspace.activate_item(&editor, true, true, cx);
+            });
+            return;
+        }
+
+        let capability = self.project.read(cx).capability();
+        let multibuffer = cx.new_model(|_| {
+            MultiBuffer::new(capability).with_title("Uncommitted Changes".to_string())
+        });
+        let editor = cx.new_view(|cx| {
+            Editor::for_multibuffer(multibuffer, Some(self.project.clone()), true, cx)
+        });
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(editor.clone()), None, true, cx);
+        });
+        self.changes_editor = Some(editor.downgrade());
+        self.changes_editor_hunks.clear();
+        if self.file_statuses_outdated {
+            self.schedule_update(false, cx);
+        } else {
+            self.update_changes_editor(cx);
+        }
+    }
+
+    fn update_changes_editor(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(editor) = self
+            .changes_editor
+            .as_ref()
+            .and_then(|editor| editor.upgrade())
+        else {
+            self.changes_editor = None;
+            self.changes_editor_hunks.clear();
+            self.changes_editor_buffers.clear();
+            return;
+        };
+
+        // Files with staged changes are also compared with HEAD, so that the
+        // changes that are only staged are shown too.
+        let mut files = Vec::<(ProjectPath, bool)>::new();
+        for (path, status) in &self.file_statuses {
+            if status.worktree_status == Some(StatusCode::Deleted) {
+                continue;
+            }
+            match files.iter_mut().find(|(file_path, _)| file_path == path) {
+                Some((_, is_staged)) => *is_staged |= status.is_staged(),
+                None => files.push((path.clone(), status.is_staged())),
+            }
+        }
+        self.changes_editor_buffers
+            .retain(|path, _| files.iter().any(|(file_path, _)| file_path == path));
+        let open_buffers = self.changes_editor_buffers.clone();
+        let project = self.project.clone();
+        self.changes_editor_update_task = cx.spawn(|git_panel, mut cx| {
+            async move {
+                let mut changed_files = Vec::with_capacity(files.len());
+                for (path, is_staged) in files {
+                    let buffer = match open_buffers.get(&path) {
+                        Some(buffer) => buffer.clone(),
+                        None => {
+                            let open_buffer = project.update(&mut cx, |project, cx| {
+                                project.open_buffer(path.clone(), cx)
+                            })?;
+                            let Some(buffer) = open_buffer.await.log_err() else {
+                                continue;
+                            };
+                            buffer
+                        }
+                    };
+                    // Wait for the diff to reflect the latest index contents before reading hunks.
+                    let recalculate_diff =
+                        buffer.update(&mut cx, |buffer, cx| buffer.recalculate_diff(cx))?;
+                    if let Some(recalculate_diff) = recalculate_diff {
+                        recalculate_diff.await;
+                    }
+
+                    let mut head_ranges = Vec::new();
+                    if is_staged {
+                        let load_head_text = project.update(&mut cx, |project, cx| {
+                            project.load_committed_text(path.clone(), cx)
+                        })?;
+                        let head_text = load_head_text.await.log_err().flatten();
+                        let snapshot =
+                            buffer.update(&mut cx, |buffer, _| buffer.text_snapshot())?;
+                        head_ranges = cx
+                            .background_executor()
+                            .spawn(async move {
+                                let head_text = Rope::from(head_text.unwrap_or_default().as_str());
+                                let mut diff = BufferDiff::new(&snapshot);
+                                diff.update(&head_text, &snapshot).await;
+                                diff.hunks_intersecting_range(Anchor::MIN..Anchor::MAX, &snapshot)
+                                    .map(|hunk| hunk_range(&hunk))
+                                    .collect::<Vec<_>>()
+                            })
+                            .await;
+                    }
+                    changed_files.push((path, buffer, head_ranges));
+                }
+
+                git_panel.update(&mut cx, |git_panel, cx| {
+                    let excerpts = changed_files
+                        .into_iter()
+                        .filter_map(|(path, buffer, head_ranges)| {
+                            git_panel
+                                .changes_editor_buffers
+                                .insert(path, buffer.clone());
+                            let ranges = merge_ranges(
+                                changed_ranges(buffer.read(cx))
+                                    .into_iter()
+                                    .chain(head_ranges)
+                                    .collect(),
+                            );
+                            (!ranges.is_empty()).then_some((buffer, ranges))
+                        })
+                        .collect::<Vec<_>>();
+                    let hunks = excerpts
+                        .iter()
+                        .map(|(buffer, ranges)| (buffer.read(cx).remote_id(), ranges.clone()))
+                        .collect::<Vec<_>>();
+                    if hunks == git_panel.changes_editor_hunks {
+                        return;
+                    }
+                    git_panel.changes_editor_hunks = hunks;
+
+                    editor.update(cx, |editor, cx| {
+                        editor.buffer().update(cx, |multibuffer, cx| {
+                            multibuffer.clear(cx);
+                            for (buffer, ranges) in excerpts {
+                                multibuffer.push_excerpts_with_context_lines(
+                                    buffer,
+                                    ranges,
+                                    editor::DEFAULT_MULTIBUFFER_CONTEXT,
+                                    cx,
+                                );
+                            }
+                        });
+                        editor.expand_all_hunk_diffs(&ExpandAllHunkDiffs, cx);
+                    });
+                })?;
+                anyhow::Ok(())
+            }
+            .log_err()
+        });
+    }
+
+    fn render_entry(
+        &self,
+        ix: usize,
+        entry: &GitListEntry,
+        cx: &mut ViewContext<Self>,
+    ) -> AnyElement {
+        let (section, path, status) = match entry {
+            GitListEntry::Header(section) => {
+                return h_flex()
+                    .px_2()
+                    .pt_2()
+                    .pb_1()
+                    .child(
+                        Label::new(section.title())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .into_any_element();
+            }
+            GitListEntry::File {
+                section,
+                path,
+                status,
+            } => (*section, path, status),
+        };
+
+        let (status_label, status_color) = match section.status_code(status) {
+            Some(StatusCode::Modified) => ("M", Color::Modified),
+            Some(StatusCode::Added) => ("A", Color::Created),
+            Some(StatusCode::Deleted) => ("D", Color::Deleted),
+            Some(StatusCode::Renamed) => ("R", Color::Modified),
+            Some(StatusCode::Copied) => ("C", Color::Created),
+            Some(StatusCode::Unmerged) => ("!", Color::Conflict),
+            Some(StatusCode::Untracked) => ("U", Color::Created),
+            None => ("", Color::Default),
+        };
+        let file_name = path
+            .path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.path.to_string_lossy().to_string());
+        let directory = path
+            .path
+            .parent()
+            .map(|parent| parent.to_string_lossy().to_string())
+            .filter(|parent| !parent.is_empty());
+        let is_staged = section == Section::Staged;
+
+        ListItem::new(ix)
+            .spacing(ListItemSpacing::Sparse)
+            .selected(self.selected_entry == Some(ix))
+            .start_slot(
+                Label::new(status_label)
+                    .size(LabelSize::Small)
+                    .color(status_color),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Label::new(file_name).color(status_color))
+                    .when_some(directory, |this, directory| {
+                        this.child(
+                            Label::new(directory)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+            .end_hover_slot(
+                IconButton::new(
+                    ("toggle-staged", ix),
+                    if is_staged {
+                        IconName::Dash
+                    } else {
+                        IconName::Plus
+                    },
+                )
+                .icon_size(IconSize::Small)
+                .tooltip(move |cx| {
+                    let title = if is_staged {
+                        "Unstage File"
+                    } else {
+                        "Stage File"
+                    };
+                    Tooltip::for_action(title, &ToggleStaged, cx)
+                })
+                .on_click(cx.listener(move |git_panel, _, cx| {
+                    git_panel.selected_entry = Some(ix);
+                    git_panel.toggle_staged(&ToggleStaged, cx);
+                })),
+            )
+            .on_click(cx.listener(move |git_panel, _, cx| {
+                git_panel.selected_entry = Some(ix);
+                git_panel.confirm(&Confirm, cx);
+            }))
+            .into_any_element()
+    }
+
+    fn render_toolbar(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let changed_files = self.file_statuses.len();
+        h_flex()
+            .px_2()
+            .py_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                Label::new(match changed_files {
+                    0 => "No changes".to_string(),
+                    1 => "1 changed file".to_string(),
+                    n => format!("{n} changed files"),
+                })
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        IconButton::new("open-changes", IconName::Diff)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|cx| Tooltip::for_action("Open Changes", &OpenChanges, cx))
+                            .on_click(cx.listener(|git_panel, _, cx| {
+                                git_panel.open_changes(&OpenChanges, cx)
+                            })),
+                    )
+                    .child(
+                        IconButton::new("stage-all", IconName::Plus)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|cx| Tooltip::for_action("Stage All", &StageAll, cx))
+                            .on_click(
+                                cx.listener(|git_panel, _, cx| git_panel.stage_all(&StageAll, cx)),
+                            ),
+                    )
+                    .child(
+                        IconButton::new("unstage-all", IconName::Dash)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|cx| Tooltip::for_action("Unstage All", &UnstageAll, cx))
+                            .on_click(cx.listener(|git_panel, _, cx| {
+                                git_panel.unstage_all(&UnstageAll, cx)
+                            })),
+                    ),
+            )
+    }
+}
+
+/// The ranges of a buffer to show in the changes editor: its unstaged hunks,
+/// or the whole buffer if the file is untracked.
+fn changed_ranges(buffer: &Buffer) -> Vec<Range<Point>> {
+    if buffer.diff_base().is_some() {
+        buffer
+            .snapshot()
+            .git_diff_hunks_intersecting_range(Anchor::MIN..Anchor::MAX)
+            .map(|hunk| hunk_range(&hunk))
+            .collect()
+    } else {
+        vec![Point::zero()..buffer.max_point()]
+    }
+}
+
+fn hunk_range(hunk: &git::diff::DiffHunk) -> Range<Point> {
+    Point::new(hunk.row_range.start, 0)..Point::new(hunk.row_range.end, 0)
+}
+
+/// Sorts the ranges and merges the overlapping ones.
+fn merge_ranges(mut ranges: Vec<Range<Point>>) -> Vec<Range<Point>> {
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<Point>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+impl Panel for GitPanel {
+    fn persistent_name() -> &'static str {
+        "Git Panel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        match GitPanelSettings::get_global(cx).dock {
+            GitPanelDockPosition::Left => DockPosition::Left,
+            GitPanelDockPosition::Right => DockPosition::Right,
+        }
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<GitPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| {
+                let dock = match position {
+                    DockPosition::Left | DockPosition::Bottom => GitPanelDockPosition::Left,
+                    DockPosition::Right => GitPanelDockPosition::Right,
+                };
+                settings.dock = Some(dock);
+            },
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| GitPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        GitPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::FileGit)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Git Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn starts_open(&self, _: &WindowContext) -> bool {
+        self.active
+    }
+
+    fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
+        self.active = active;
+        if active && self.file_statuses_outdated {
+            self.schedule_update(false, cx);
+        }
+        self.serialize(cx);
+    }
+}
+
+impl FocusableView for GitPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for GitPanel {}
+
+impl Render for GitPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .id("git-panel")
+            .size_full()
+            .text_ui(cx)
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::toggle_staged))
+            .on_action(cx.listener(Self::stage_all))
+            .on_action(cx.listener(Self::unstage_all))
+            .on_action(cx.listener(Self::open_changes))
+            .child(self.render_toolbar(cx))
+            .map(|git_panel| {
+                if self.entries.is_empty() {
+                    git_panel.child(
+                        v_flex().size_full().justify_center().child(
+                            h_flex()
+                                .justify_center()
+                                .child(Label::new("No changes").color(Color::Muted)),
+                        ),
+                    )
+                } else {
+                    git_panel.child(
+                        uniform_list(
+                            cx.view().clone(),
+                            "entries",
+                            self.entries.len(),
+                            |git_panel, range, cx| {
+                                range
+                                    .filter_map(|ix| {
+                                        let entry = git_panel.entries.get(ix)?.clone();
+                                        Some(git_panel.render_entry(ix, &entry, cx))
+                                    })
+                                    .collect()
+                            },
+                        )
+                        .size_full()
+                        .track_scroll(self.scroll_handle.clone()),
+                    )
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use collections::HashSet;
+    use git::repository::GitFileStatus;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::FakeFs;
+    use serde_json::json;
+    use std::path::Path;
+
+    #[gpui::test]
+    async fn test_listing_and_staging(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/root",
+            json!({
+                ".git": {},
+                "a.txt": "a",
+                "b.txt": "b",
+                "c.txt": "c",
+            }),
+        )
+        .await;
+        let dot_git = Path::new("/root/.git");
+        fs.set_status_for_repo_via_git_operation(
+            dot_git,
+            &[
+                (Path::new("a.txt"), GitFileStatus::Modified),
+                (Path::new("b.txt"), GitFileStatus::Added),
+            ],
+        );
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace.update(cx, GitPanel::new).unwrap();
+        panel.update(cx, |panel, cx| panel.set_active(true, cx));
+        cx.run_until_parked();
+        assert_eq!(
+            entries_as_strings(&panel, cx),
+            ["Changes", "  M a.txt", "Untracked Files", "  ? b.txt"]
+        );
+
+        panel.update(cx, |panel, cx| {
+            panel.select_last(&SelectLast, cx);
+            panel.toggle_staged(&ToggleStaged, cx);
+        });
+        // The fake repository doesn't write to `.git` when staging, so the
+        // event a real index change would cause is emitted by hand.
+        cx.run_until_parked();
+        fs.with_git_state(dot_git, true, |_| {});
+        cx.run_until_parked();
+        assert_eq!(
+            entries_as_strings(&panel, cx),
+            ["Staged Changes", "  A b.txt", "Changes", "  M a.txt"]
+        );
+
+        panel.update(cx, |panel, cx| panel.stage_all(&StageAll, cx));
+        cx.run_until_parked();
+        fs.with_git_state(dot_git, true, |_| {});
+        cx.run_until_parked();
+        assert_eq!(
+            entries_as_strings(&panel, cx),
+            ["Staged Changes", "  M a.txt", "  A b.txt"]
+        );
+        fs.with_git_state(dot_git, false, |state| {
+            assert_eq!(state.index_contents.get(Path::new("a.txt")).unwrap(), "a");
+            assert_eq!(state.index_contents.get(Path::new("b.txt")).unwrap(), "b");
+        });
+
+        panel.update(cx, |panel, cx| panel.unstage_all(&UnstageAll, cx));
+        cx.run_until_parked();
+        fs.with_git_state(dot_git, true, |_| {});
+        cx.run_until_parked();
+        assert_eq!(
+            entries_as_strings(&panel, cx),
+            ["Changes", "  M a.txt", "Untracked Files", "  ? b.txt"]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_hidden_panel_is_updated_when_shown(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/root",
+            json!({
+                ".git": {},
+                "a.txt": "a",
+                "b.txt": "b",
+            }),
+        )
+        .await;
+        let dot_git = Path::new("/root/.git");
+        fs.set_status_for_repo_via_git_operation(
+            dot_git,
+            &[(Path::new("a.txt"), GitFileStatus::Modified)],
+        );
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace.update(cx, GitPanel::new).unwrap();
+        cx.run_until_parked();
+        assert_eq!(entries_as_strings(&panel, cx), Vec::<String>::new());
+
+        panel.update(cx, |panel, cx| panel.set_active(true, cx));
+        cx.run_until_parked();
+        assert_eq!(entries_as_strings(&panel, cx), ["Changes", "  M a.txt"]);
+
+        // Statuses aren't reloaded while the panel is hidden.
+        panel.update(cx, |panel, cx| panel.set_active(false, cx));
+        fs.set_status_for_repo_via_git_operation(
+            dot_git,
+            &[
+                (Path::new("a.txt"), GitFileStatus::Modified),
+                (Path::new("b.txt"), GitFileStatus::Modified),
+            ],
+        );
+        cx.run_until_parked();
+        assert_eq!(entries_as_strings(&panel, cx), ["Changes", "  M a.txt"]);
+
+        panel.update(cx, |panel, cx| panel.set_active(true, cx));
+        cx.run_until_parked();
+        assert_eq!(
+            entries_as_strings(&panel, cx),
+            ["Changes", "  M a.txt", "  M b.txt"]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_changes_editor(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/root",
+            json!({
+                ".git": {},
+                "a.txt": "one\nTWO\nthree\n",
+                "b.txt": "four\nfive\nSIX\n",
+            }),
+        )
+        .await;
+        let dot_git = Path::new("/root/.git");
+        fs.with_git_state(dot_git, true, |state| {
+            state
+                .head_contents
+                .insert("a.txt".into(), "one\ntwo\nthree\n".into());
+            state
+                .head_contents
+                .insert("b.txt".into(), "four\nfive\nsix\n".into());
+            state.index_contents = state.head_contents.clone();
+        });
+        fs.set_status_for_repo_via_git_operation(
+            dot_git,
+            &[
+                (Path::new("a.txt"), GitFileStatus::Modified),
+                (Path::new("b.txt"), GitFileStatus::Modified),
+            ],
+        );
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace.update(cx, GitPanel::new).unwrap();
+        panel.update(cx, |panel, cx| panel.set_active(true, cx));
+        cx.run_until_parked();
+
+        // Stage all the changes of `a.txt`, so that it only differs from HEAD.
+        panel.update(cx, |panel, cx| {
+            panel.select_first(&SelectFirst, cx);
+            panel.toggle_staged(&ToggleStaged, cx);
+        });
+        cx.run_until_parked();
+        fs.with_git_state(dot_git, true, |_| {});
+        cx.run_until_parked();
+        assert_eq!(
+            entries_as_strings(&panel, cx),
+            ["Staged Changes", "  M a.txt", "Changes", "  M b.txt"]
+        );
+
+        panel.update(cx, |panel, cx| panel.open_changes(&OpenChanges, cx));
+        cx.run_until_parked();
+        let editor = panel.read_with(cx, |panel, _| {
+            panel.changes_editor.as_ref().unwrap().upgrade().unwrap()
+        });
+        let text = editor.update(cx, |editor, cx| editor.text(cx));
+        assert!(text.contains("one\nTWO\nthree"), "{text:?}");
+        assert!(text.contains("four\nfive\nSIX"), "{text:?}");
+        let buffers = panel.read_with(cx, |panel, _| {
+            panel
+                .changes_editor_buffers
+                .values()
+                .map(|buffer| buffer.entity_id())
+                .collect::<HashSet<_>>()
+        });
+        assert_eq!(buffers.len(), 2);
+
+        // Updates reuse the buffers that are already open.
+        fs.with_git_state(dot_git, true, |_| {});
+        cx.run_until_parked();
+        let updated_buffers = panel.read_with(cx, |panel, _| {
+            panel
+                .changes_editor_buffers
+                .values()
+                .map(|buffer| buffer.entity_id())
+                .collect::<HashSet<_>>()
+        });
+        assert_eq!(updated_buffers, buffers);
+    }
+
+    fn entries_as_strings(panel: &View<GitPanel>, cx: &mut VisualTestContext) -> Vec<String> {
+        panel.read_with(cx, |panel, _| {
+            panel
+                .entries
+                .iter()
+                .map(|entry| match entry {
+                    GitListEntry::Header(section) => section.title().to_string(),
+                    GitListEntry::File {
+                        section,
+                        path,
+                        status,
+                    } => {
+                        let code = match section.status_code(status) {
+                            Some(StatusCode::Modified) => "M",
+                            Some(StatusCode::Added) => "A",
+                            Some(StatusCode::Untracked) => "?",
+                            code => panic!("unexpected status code {code:?}"),
+                        };
+                        format!("  {code} {}", path.path.display())
+                    }
+                })
+                .collect()
+        })
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init_settings(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            init_settings(cx);
+        });
+    }
+}
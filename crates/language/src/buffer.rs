@@ -9,12 +9,13 @@ use crate::{
     language_settings::{language_settings, IndentGuideSettings, LanguageSettings},
     markdown::parse_markdown,
     outline::OutlineItem,
+    semantic_tokens::SemanticToken,
     syntax_map::{
         SyntaxLayer, SyntaxMap, SyntaxMapCapture, SyntaxMapCaptures, SyntaxMapMatch,
         SyntaxMapMatches, SyntaxSnapshot, ToTreeSitterPoint,
     },
     task_context::RunnableRange,
-    LanguageScope, Outline, OutlineConfig, RunnableCapture, RunnableTag,
+    LanguageScope, Outline, OutlineConfig, RunnableCapture, RunnableTag, TextObject,
 };
 use anyhow::{anyhow, Context, Result};
 use async_watch as watch;
@@ -37,6 +38,7 @@ use smallvec::SmallVec;
 use smol::future::yield_now;
 use std::{
     any::Any,
+    borrow::Cow,
     cell::Cell,
     cmp::{self, Ordering, Reverse},
     collections::BTreeMap,
@@ -118,6 +120,7 @@ pub struct Buffer {
     pending_autoindent: Option<Task<()>>,
     sync_parse_timeout: Duration,
     syntax_map: Mutex<SyntaxMap>,
+    semantic_tokens: Arc<[SemanticToken]>,
     parsing_in_background: bool,
     parse_status: (watch::Sender<ParseStatus>, watch::Receiver<ParseStatus>),
     non_text_state_update_count: usize,
@@ -148,6 +151,7 @@ pub struct BufferSnapshot {
     text: text::BufferSnapshot,
     git_diff: git::diff::BufferDiff,
     pub(crate) syntax: SyntaxSnapshot,
+    semantic_tokens: Arc<[SemanticToken]>,
     file: Option<Arc<dyn File>>,
     diagnostics: SmallVec<[(LanguageServerId, DiagnosticSet); 2]>,
     remote_selections: TreeMap<ReplicaId, SelectionSet>,
@@ -345,6 +349,8 @@ pub enum BufferEvent {
     Reparsed,
     /// The buffer's diagnostics were updated.
     DiagnosticsUpdated,
+    /// The buffer's semantic tokens were updated.
+    SemanticTokensUpdated,
     /// The buffer gained or lost editing capabilities.
     CapabilityChanged,
     /// The buffer was explicitly requested to close.
@@ -759,6 +765,7 @@ impl Buffer {
             file,
             capability,
             syntax_map,
+            semantic_tokens: Arc::from([]),
             parsing_in_background: false,
             non_text_state_update_count: 0,
             sync_parse_timeout: Duration::from_millis(1),
@@ -788,6 +795,7 @@ impl Buffer {
         BufferSnapshot {
             text,
             syntax,
+            semantic_tokens: self.semantic_tokens.clone(),
             git_diff: self.git_diff.clone(),
             file: self.file.clone(),
             remote_selections: self.remote_selections.clone(),
@@ -1303,6 +1311,19 @@ impl Buffer {
         self.parse_status.1.clone()
     }
 
+    /// Assign to the buffer the semantic tokens provided by its language server,
+    /// sorted by their position and not overlapping each other.
+    pub fn set_semantic_tokens(
+        &mut self,
+        semantic_tokens: Arc<[SemanticToken]>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.semantic_tokens = semantic_tokens;
+        self.non_text_state_update_count += 1;
+        cx.emit(BufferEvent::SemanticTokensUpdated);
+        cx.notify();
+    }
+
     /// Assign to the buffer a set of diagnostics created by a given language server.
     pub fn update_diagnostics(
         &mut self,
@@ -2826,7 +2847,7 @@ impl BufferSnapshot {
         &self,
         position: D,
         cx: &'a AppContext,
-    ) -> &'a LanguageSettings {
+    ) -> Cow<'a, LanguageSettings> {
         language_settings(self.language_at(position), self.file.as_ref(), cx)
     }
 
@@ -3344,6 +3365,55 @@ impl BufferSnapshot {
         result
     }
 
+    /// Returns the ranges of the text objects captured by the `textobjects` query that intersect
+    /// the given range. Multiple captures of the same text object within one match, such as
+    /// consecutive line comments, are combined into a single range.
+    pub fn text_object_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> impl Iterator<Item = (Range<usize>, TextObject)> + '_ {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        let mut matches = self.syntax.matches(range.clone(), &self.text, |grammar| {
+            grammar.text_object_config.as_ref().map(|c| &c.query)
+        });
+        let configs = matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.text_object_config.as_ref().unwrap())
+            .collect::<Vec<_>>();
+
+        let mut pending = Vec::<(Range<usize>, TextObject)>::new();
+        iter::from_fn(move || loop {
+            if let Some(entry) = pending.pop() {
+                return Some(entry);
+            }
+            let mat = matches.peek()?;
+            let config = &configs[mat.grammar_index];
+            for capture in mat.captures {
+                let Some(Some(text_object)) = config
+                    .text_objects_by_capture_ix
+                    .get(capture.index as usize)
+                else {
+                    continue;
+                };
+                let node_range = capture.node.byte_range();
+                if let Some((existing, _)) = pending
+                    .iter_mut()
+                    .find(|(_, existing)| existing == text_object)
+                {
+                    existing.start = existing.start.min(node_range.start);
+                    existing.end = existing.end.max(node_range.end);
+                } else {
+                    pending.push((node_range, *text_object));
+                }
+            }
+            pending.retain(|(object_range, _)| {
+                object_range.start <= range.end && object_range.end >= range.start
+            });
+            matches.advance();
+        })
+    }
+
     /// Returns anchor ranges for any matches of the redaction query.
     /// The buffer can be associated with multiple languages, and the redaction query associated with each
     /// will be run on the relevant section of the buffer.
@@ -3839,6 +3909,26 @@ impl BufferSnapshot {
         !self.diagnostics.is_empty()
     }
 
+    /// Returns whether the buffer has any semantic tokens.
+    pub fn has_semantic_tokens(&self) -> bool {
+        !self.semantic_tokens.is_empty()
+    }
+
+    /// Returns the semantic tokens intersecting the given range, in order.
+    pub fn semantic_tokens_in_range<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> impl '_ + Iterator<Item = &SemanticToken> {
+        let start = range.start.to_offset(self);
+        let end = range.end.to_offset(self);
+        let start_ix = self
+            .semantic_tokens
+            .partition_point(|token| token.range.end.to_offset(self) <= start);
+        self.semantic_tokens[start_ix..]
+            .iter()
+            .take_while(move |token| token.range.start.to_offset(self) < end)
+    }
+
     /// Returns all the diagnostics intersecting the given range.
     pub fn diagnostics_in_range<'a, T, O>(
         &'a self,
@@ -3980,6 +4070,7 @@ impl Clone for BufferSnapshot {
             text: self.text.clone(),
             git_diff: self.git_diff.clone(),
             syntax: self.syntax.clone(),
+            semantic_tokens: self.semantic_tokens.clone(),
             file: self.file.clone(),
             remote_selections: self.remote_selections.clone(),
             diagnostics: self.diagnostics.clone(),
@@ -1063,6 +1063,59 @@ fn test_enclosing_bracket_ranges_where_brackets_are_not_outermost_children(cx: &
     );
 }
 
+#[gpui::test]
+fn test_text_object_ranges(cx: &mut AppContext) {
+    let language = rust_lang()
+        .with_text_object_query(
+            r#"
+            (function_item
+                body: (_ "{" _* @function.inside "}")) @function.around
+            (line_comment)+ @comment.around
+            "#,
+        )
+        .unwrap();
+    cx.new_model(|cx| {
+        let text = indoc! {"
+            // one
+            // two
+            fn a() {
+                b();
+                c();
+            }
+        "};
+        let buffer = Buffer::local(text, cx).with_language(Arc::new(language), cx);
+        let snapshot = buffer.snapshot();
+
+        let offset = text.find("c()").unwrap();
+        let mut text_objects = snapshot
+            .text_object_ranges(offset..offset)
+            .map(|(range, text_object)| (text_object, &text[range]))
+            .collect::<Vec<_>>();
+        text_objects.sort_by_key(|(_, text)| text.len());
+        assert_eq!(
+            text_objects,
+            &[
+                (TextObject::InsideFunction, "b();\n    c();"),
+                (
+                    TextObject::AroundFunction,
+                    "fn a() {\n    b();\n    c();\n}"
+                ),
+            ]
+        );
+
+        let offset = text.find("two").unwrap();
+        assert_eq!(
+            snapshot
+                .text_object_ranges(offset..offset)
+                .map(|(range, text_object)| (text_object, &text[range]))
+                .collect::<Vec<_>>(),
+            &[(TextObject::AroundComment, "// one\n// two")]
+        );
+
+        buffer
+    });
+}
+
 #[gpui::test]
 fn test_range_for_syntax_ancestor(cx: &mut AppContext) {
     cx.new_model(|cx| {
@@ -16,8 +16,11 @@ use serde::{
     Deserialize, Deserializer, Serialize,
 };
 use serde_json::Value;
-use settings::{add_references_to_properties, Settings, SettingsLocation, SettingsSources};
-use std::{num::NonZeroU32, path::Path, sync::Arc};
+use settings::{
+    add_references_to_properties, EditorconfigProperties, Settings, SettingsLocation,
+    SettingsSources, SettingsStore,
+};
+use std::{borrow::Cow, num::NonZeroU32, path::Path, sync::Arc};
 use util::serde::default_true;
 
 /// Initializes the language settings.
@@ -25,14 +28,44 @@ pub fn init(cx: &mut AppContext) {
     AllLanguageSettings::register(cx);
 }
 
-/// Returns the settings for the specified language from the provided file.
+/// Returns the settings for the specified language from the provided file,
+/// including the properties of any `.editorconfig` files that apply to it.
 pub fn language_settings<'a>(
     language: Option<&Arc<Language>>,
     file: Option<&Arc<dyn File>>,
     cx: &'a AppContext,
-) -> &'a LanguageSettings {
+) -> Cow<'a, LanguageSettings> {
     let language_name = language.map(|l| l.name());
-    all_language_settings(file, cx).language(language_name.as_ref())
+    let settings = all_language_settings(file, cx).language(language_name.as_ref());
+    let Some(file) = file else {
+        return Cow::Borrowed(settings);
+    };
+
+    let worktree_id = file.worktree_id(cx);
+    let path = file.path().as_ref();
+    let store = cx.global::<SettingsStore>();
+    let Some(properties) = store.editorconfig_properties(worktree_id, path) else {
+        return Cow::Borrowed(settings);
+    };
+
+    let mut settings = settings.clone();
+    settings.apply_editorconfig(&properties, |key, directory| {
+        // Local settings files in the `.editorconfig` file's directory, or below it, take
+        // precedence over its properties.
+        store
+            .local_settings_within(worktree_id, path, directory)
+            .any(|settings| {
+                settings.get(key).is_some()
+                    || language_name.as_ref().is_some_and(|language_name| {
+                        settings
+                            .get("languages")
+                            .and_then(|languages| languages.get(&*language_name.0))
+                            .and_then(|settings| settings.get(key))
+                            .is_some()
+                    })
+            })
+    });
+    Cow::Owned(settings)
 }
 
 /// Returns the settings for all languages from the provided file.
@@ -87,6 +120,8 @@ pub struct LanguageSettings {
     /// Whether or not to ensure there's a single newline at the end of a buffer
     /// when saving it.
     pub ensure_final_newline_on_save: bool,
+    /// Which line endings to use when saving a buffer.
+    pub line_ending: LineEndingSetting,
     /// How to perform a buffer format.
     pub formatter: SelectedFormatter,
     /// Zed's Prettier integration settings.
@@ -122,6 +157,8 @@ pub struct LanguageSettings {
     pub code_actions_on_format: HashMap<String, bool>,
     /// Whether to perform linked edits
     pub linked_edits: bool,
+    /// Whether to highlight the code with semantic tokens from the language server.
+    pub semantic_tokens: bool,
     /// Task configuration for this language.
     pub tasks: LanguageTaskConfig,
 }
@@ -173,6 +210,66 @@ impl LanguageSettings {
             })
             .collect::<Vec<_>>()
     }
+
+    /// Applies the properties of `.editorconfig` files to these settings. Properties are skipped
+    /// when `is_overridden` returns true for the setting's key and the directory of the
+    /// `.editorconfig` file that set them.
+    pub(crate) fn apply_editorconfig(
+        &mut self,
+        properties: &EditorconfigProperties,
+        is_overridden: impl Fn(&str, &Path) -> bool,
+    ) {
+        let property = |name: &str, key: &str| {
+            properties
+                .get(name)
+                .filter(|property| !is_overridden(key, &property.directory))
+                .map(|property| property.value.as_str())
+        };
+
+        match property("indent_style", "hard_tabs") {
+            Some("tab") => self.hard_tabs = true,
+            Some("space") => self.hard_tabs = false,
+            _ => {}
+        }
+
+        let tab_width = property("tab_width", "tab_size");
+        let tab_size = match property("indent_size", "tab_size") {
+            Some("tab") => tab_width,
+            Some(indent_size) => Some(indent_size),
+            None => tab_width,
+        };
+        if let Some(tab_size) = tab_size.and_then(|tab_size| tab_size.parse::<NonZeroU32>().ok()) {
+            self.tab_size =
+                tab_size.clamp(NonZeroU32::new(1).unwrap(), NonZeroU32::new(16).unwrap());
+        }
+
+        match property("end_of_line", "line_ending") {
+            Some("lf") => self.line_ending = LineEndingSetting::Lf,
+            Some("crlf") => self.line_ending = LineEndingSetting::Crlf,
+            _ => {}
+        }
+
+        match property("insert_final_newline", "ensure_final_newline_on_save") {
+            Some("true") => self.ensure_final_newline_on_save = true,
+            Some("false") => self.ensure_final_newline_on_save = false,
+            _ => {}
+        }
+
+        match property(
+            "trim_trailing_whitespace",
+            "remove_trailing_whitespace_on_save",
+        ) {
+            Some("true") => self.remove_trailing_whitespace_on_save = true,
+            Some("false") => self.remove_trailing_whitespace_on_save = false,
+            _ => {}
+        }
+
+        if let Some(max_line_length) = property("max_line_length", "preferred_line_length")
+            .and_then(|max_line_length| max_line_length.parse::<u32>().ok())
+        {
+            self.preferred_line_length = max_line_length;
+        }
+    }
 }
 
 /// The provider that supplies inline completions.
@@ -274,6 +371,11 @@ pub struct LanguageSettingsContent {
     /// Default: true
     #[serde(default)]
     pub ensure_final_newline_on_save: Option<bool>,
+    /// Which line endings to use when saving a buffer.
+    ///
+    /// Default: detect
+    #[serde(default)]
+    pub line_ending: Option<LineEndingSetting>,
     /// How to perform a buffer format.
     ///
     /// Default: auto
@@ -351,6 +453,11 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub linked_edits: Option<bool>,
+    /// Whether to highlight the code with semantic tokens provided by the language server,
+    /// on top of the tree-sitter based syntax highlighting.
+    ///
+    /// Default: false
+    pub semantic_tokens: Option<bool>,
     /// Task configuration for this language.
     ///
     /// Default: {}
@@ -506,6 +613,19 @@ impl<'de> Deserialize<'de> for FormatOnSave {
     }
 }
 
+/// Controls which line endings are used when saving a buffer.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LineEndingSetting {
+    /// Keep the line endings that were detected when the file was loaded.
+    #[default]
+    Detect,
+    /// Use `\n` line endings.
+    Lf,
+    /// Use `\r\n` line endings.
+    Crlf,
+}
+
 /// Controls how whitespace should be displayedin the editor.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -1067,6 +1187,7 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.code_actions_on_format.clone(),
     );
     merge(&mut settings.linked_edits, src.linked_edits);
+    merge(&mut settings.semantic_tokens, src.semantic_tokens);
     merge(&mut settings.tasks, src.tasks.clone());
 
     merge(
@@ -1084,6 +1205,7 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         &mut settings.ensure_final_newline_on_save,
         src.ensure_final_newline_on_save,
     );
+    merge(&mut settings.line_ending, src.line_ending);
     merge(
         &mut settings.enable_language_server,
         src.enable_language_server,
@@ -0,0 +1,103 @@
+use std::{ops::Range, sync::Arc};
+
+use text::Anchor;
+
+/// A range of a buffer, classified by a language server with a semantic token.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SemanticToken {
+    /// The range of the buffer covered by the token.
+    pub range: Range<Anchor>,
+    /// The dot-separated name of the highlight this token maps to, e.g. `variable.mutable`.
+    /// It is matched against the syntax theme the same way as tree-sitter capture names are.
+    pub highlight_name: Arc<str>,
+}
+
+/// Maps LSP semantic token types to the names used by the syntax themes,
+/// for the types that have a closer match than their own name.
+const TOKEN_TYPE_HIGHLIGHTS: &[(&str, &str)] = &[
+    ("class", "type"),
+    ("interface", "type"),
+    ("struct", "type"),
+    ("typeParameter", "type"),
+    ("typeAlias", "type"),
+    ("builtinType", "type.builtin"),
+    ("parameter", "variable.parameter"),
+    ("enumMember", "variant"),
+    ("event", "property"),
+    ("method", "function.method"),
+    ("macro", "function.macro"),
+    ("modifier", "keyword"),
+    ("regexp", "string.regex"),
+    ("decorator", "attribute"),
+    ("selfKeyword", "variable.special"),
+    ("escapeSequence", "string.escape"),
+];
+
+/// Maps LSP semantic token modifiers to the names used by the syntax themes,
+/// for the modifiers that have a closer match than their own name.
+const TOKEN_MODIFIER_HIGHLIGHTS: &[(&str, &str)] = &[("documentation", "doc")];
+
+/// Returns the name of the highlight for a semantic token with the given type and modifiers:
+/// the theme name of the token type, followed by the names of its modifiers.
+///
+/// For example, a `method` token with the `static` and `defaultLibrary` modifiers
+/// maps to `function.method.static.default_library`.
+pub fn semantic_token_highlight_name<'a>(
+    token_type: &str,
+    modifiers: impl IntoIterator<Item = &'a str>,
+) -> String {
+    let mut name = TOKEN_TYPE_HIGHLIGHTS
+        .iter()
+        .find(|(lsp_name, _)| *lsp_name == token_type)
+        .map_or_else(|| to_snake_case(token_type), |(_, name)| name.to_string());
+    for modifier in modifiers {
+        name.push('.');
+        match TOKEN_MODIFIER_HIGHLIGHTS
+            .iter()
+            .find(|(lsp_name, _)| *lsp_name == modifier)
+        {
+            Some((_, modifier_name)) => name.push_str(modifier_name),
+            None => name.push_str(&to_snake_case(modifier)),
+        }
+    }
+    name
+}
+
+fn to_snake_case(lsp_name: &str) -> String {
+    let mut name = String::with_capacity(lsp_name.len());
+    for c in lsp_name.chars() {
+        if c.is_ascii_uppercase() {
+            name.push('_');
+            name.push(c.to_ascii_lowercase());
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_semantic_token_highlight_name() {
+        assert_eq!(semantic_token_highlight_name("variable", []), "variable");
+        assert_eq!(
+            semantic_token_highlight_name("variable", ["mutable"]),
+            "variable.mutable"
+        );
+        assert_eq!(
+            semantic_token_highlight_name("method", ["static", "defaultLibrary"]),
+            "function.method.static.default_library"
+        );
+        assert_eq!(
+            semantic_token_highlight_name("comment", ["documentation"]),
+            "comment.doc"
+        );
+        assert_eq!(
+            semantic_token_highlight_name("formatSpecifier", []),
+            "format_specifier"
+        );
+    }
+}
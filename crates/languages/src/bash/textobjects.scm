@@ -0,0 +1,7 @@
+(function_definition
+    body: (_
+        "{"
+        _* @function.inside
+        "}")) @function.around
+
+(comment)+ @comment.around
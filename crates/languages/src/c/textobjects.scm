@@ -0,0 +1,60 @@
+(declaration
+    declarator: (function_declarator)) @function.around
+
+(function_definition
+    body: (_
+        "{"
+        _* @function.inside
+        "}")) @function.around
+
+(struct_specifier
+    body: (_
+        "{"
+        _* @class.inside
+        "}")) @class.around
+
+(union_specifier
+    body: (_
+        "{"
+        _* @class.inside
+        "}")) @class.around
+
+(enum_specifier
+    body: (_
+        "{"
+        _* @class.inside
+        "}")) @class.around
+
+(comment)+ @comment.around
+
+(parameter_list
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(parameter_list
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(parameter_list
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(argument_list
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(argument_list
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(argument_list
+    .
+    (_) @parameter.inside @parameter.around
+    .)
@@ -0,0 +1,98 @@
+(function_declaration
+    body: (_
+        "{"
+        _* @function.inside
+        "}")) @function.around
+
+(method_declaration
+    body: (_
+        "{"
+        _* @function.inside
+        "}")) @function.around
+
+(func_literal
+    body: (_
+        "{"
+        _* @function.inside
+        "}")) @function.around
+
+(type_declaration
+    (type_spec
+        (struct_type
+            (field_declaration_list
+                "{"
+                _* @class.inside
+                "}")))) @class.around
+
+(type_declaration
+    (type_spec
+        (interface_type
+            "{"
+            _* @class.inside
+            "}"))) @class.around
+
+(comment)+ @comment.around
+
+(parameter_list
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(parameter_list
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(parameter_list
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(argument_list
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(argument_list
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(argument_list
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(type_parameter_list
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(type_parameter_list
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(type_parameter_list
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(type_arguments
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(type_arguments
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(type_arguments
+    .
+    (_) @parameter.inside @parameter.around
+    .)
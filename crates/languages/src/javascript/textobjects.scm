@@ -0,0 +1,75 @@
+(function_declaration
+    body: (_
+        "{"
+        _* @function.inside
+        "}")) @function.around
+
+(generator_function_declaration
+    body: (_
+        "{"
+        _* @function.inside
+        "}")) @function.around
+
+(function_expression
+    body: (_
+        "{"
+        _* @function.inside
+        "}")) @function.around
+
+(method_definition
+    body: (_
+        "{"
+        _* @function.inside
+        "}")) @function.around
+
+(arrow_function
+    body: (statement_block
+        "{"
+        _* @function.inside
+        "}")) @function.around
+
+(class_declaration
+    body: (_
+        "{"
+        _* @class.inside
+        "}")) @class.around
+
+(class
+    body: (_
+        "{"
+        _* @class.inside
+        "}")) @class.around
+
+(comment)+ @comment.around
+
+(formal_parameters
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(formal_parameters
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(formal_parameters
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(arguments
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(arguments
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(arguments
+    .
+    (_) @parameter.inside @parameter.around
+    .)
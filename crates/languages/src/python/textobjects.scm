@@ -0,0 +1,55 @@
+(function_definition
+    body: (_) @function.inside) @function.around
+
+(class_definition
+    body: (_) @class.inside) @class.around
+
+(comment)+ @comment.around
+
+(parameters
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(parameters
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(parameters
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(lambda_parameters
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(lambda_parameters
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(lambda_parameters
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(argument_list
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(argument_list
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(argument_list
+    .
+    (_) @parameter.inside @parameter.around
+    .)
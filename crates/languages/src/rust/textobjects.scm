@@ -0,0 +1,197 @@
+(function_signature_item) @function.around
+
+(function_item
+    body: (_
+        "{"
+        _* @function.inside
+        "}")) @function.around
+
+(closure_expression
+    body: (_
+        "{"
+        _* @function.inside
+        "}")) @function.around
+
+(struct_item
+    body: (_
+        "{"
+        _* @class.inside
+        "}")) @class.around
+
+(enum_item
+    body: (_
+        "{"
+        _* @class.inside
+        "}")) @class.around
+
+(union_item
+    body: (_
+        "{"
+        _* @class.inside
+        "}")) @class.around
+
+(trait_item
+    body: (_
+        "{"
+        _* @class.inside
+        "}")) @class.around
+
+(impl_item
+    body: (_
+        "{"
+        _* @class.inside
+        "}")) @class.around
+
+(mod_item
+    body: (_
+        "{"
+        _* @class.inside
+        "}")) @class.around
+
+(line_comment)+ @comment.around
+
+(block_comment) @comment.around
+
+(parameters
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(parameters
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(parameters
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(closure_parameters
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(closure_parameters
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(closure_parameters
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(arguments
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(arguments
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(arguments
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(type_parameters
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(type_parameters
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(type_parameters
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(type_arguments
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(type_arguments
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(type_arguments
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(tuple_expression
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(tuple_expression
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(tuple_expression
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(array_expression
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(array_expression
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(array_expression
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(tuple_type
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(tuple_type
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(tuple_type
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(tuple_pattern
+    (_) @parameter.inside @parameter.around
+    .
+    "," @parameter.around)
+
+(tuple_pattern
+    "," @parameter.around
+    .
+    (_) @parameter.inside @parameter.around
+    .)
+
+(tuple_pattern
+    .
+    (_) @parameter.inside @parameter.around
+    .)
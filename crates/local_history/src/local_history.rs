@@ -0,0 +1,226 @@
+mod local_history_settings;
+mod local_history_view;
+mod persistence;
+
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use gpui::{actions, AppContext, Context, EventEmitter, Global, Model, ModelContext};
+use language::{Buffer, BufferEvent};
+use project::{buffer_store::BufferStoreEvent, Project};
+use settings::{Settings, SettingsLocation};
+use workspace::Workspace;
+
+pub use local_history_settings::LocalHistorySettings;
+pub use local_history_view::LocalHistoryView;
+pub use persistence::{LocalHistoryEntry, LocalHistorySource, LOCAL_HISTORY};
+
+actions!(local_history, [ShowFileHistory, RestoreFile]);
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+pub fn init(cx: &mut AppContext) {
+    LocalHistorySettings::register(cx);
+
+    let local_history = cx.new_model(|_| LocalHistory);
+    cx.set_global(GlobalLocalHistory(local_history.clone()));
+
+    cx.observe_new_views(move |workspace: &mut Workspace, cx| {
+        workspace.register_action(LocalHistoryView::deploy);
+
+        let project = workspace.project().clone();
+        if project.read(cx).is_local() {
+            local_history.update(cx, |local_history, cx| {
+                local_history.track_project(&project, cx)
+            });
+        }
+    })
+    .detach();
+}
+
+struct GlobalLocalHistory(Model<LocalHistory>);
+
+impl Global for GlobalLocalHistory {}
+
+/// Takes snapshots of the buffers of local projects when they are saved, and before they are
+/// reloaded because their files changed on disk, so that their contents can be restored later.
+pub struct LocalHistory;
+
+#[derive(Clone, Debug)]
+pub enum LocalHistoryEvent {
+    EntryAdded { abs_path: PathBuf },
+}
+
+impl EventEmitter<LocalHistoryEvent> for LocalHistory {}
+
+impl LocalHistory {
+    pub fn global(cx: &AppContext) -> Option<Model<Self>> {
+        cx.try_global::<GlobalLocalHistory>()
+            .map(|local_history| local_history.0.clone())
+    }
+
+    fn track_project(&mut self, project: &Model<Project>, cx: &mut ModelContext<Self>) {
+        let (buffer_store, buffers) = {
+            let project = project.read(cx);
+            (project.buffer_store(), project.opened_buffers(cx))
+        };
+        for buffer in buffers {
+            self.track_buffer(&buffer, cx);
+        }
+
+        cx.subscribe(&buffer_store, |this, _, event, cx| {
+            if let BufferStoreEvent::BufferAdded(buffer) = event {
+                this.track_buffer(buffer, cx);
+            }
+        })
+        .detach();
+    }
+
+    fn track_buffer(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        cx.subscribe(buffer, |this, buffer, event, cx| {
+            let source = match event {
+                BufferEvent::Saved => LocalHistorySource::Save,
+                BufferEvent::FileHandleChanged if Self::reload_pending(buffer.read(cx)) => {
+                    LocalHistorySource::Reload
+                }
+                _ => return,
+            };
+            this.snapshot_buffer(&buffer, source, cx);
+        })
+        .detach();
+    }
+
+    /// Whether the buffer's file changed on disk while it had no unsaved edits, in which case
+    /// the buffer is reloaded. The reload happens asynchronously, so the buffer still has its
+    /// previous contents at this point.
+    fn reload_pending(buffer: &Buffer) -> bool {
+        let Some(file) = buffer.file() else {
+            return false;
+        };
+        !buffer.is_dirty() && !file.is_deleted() && file.mtime() != buffer.saved_mtime()
+    }
+
+    fn snapshot_buffer(
+        &mut self,
+        buffer: &Model<Buffer>,
+        source: LocalHistorySource,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let buffer = buffer.read(cx);
+        let Some(file) = buffer.file().and_then(|file| file.as_local()) else {
+            return;
+        };
+        let settings = *LocalHistorySettings::get(
+            Some(SettingsLocation {
+                worktree_id: file.worktree_id(cx),
+                path: file.path().as_ref(),
+            }),
+            cx,
+        );
+        if !settings.enabled
+            || file.is_private()
+            || buffer.len() as u64 > settings.max_file_size_kb * 1024
+        {
+            return;
+        }
+
+        let abs_path = file.abs_path(cx);
+        let text = buffer.as_rope().clone();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs() as i64);
+        cx.spawn(|this, mut cx| async move {
+            let added = LOCAL_HISTORY
+                .save_entry(abs_path.clone(), source, timestamp, text.to_string())
+                .await?;
+            if added {
+                this.update(&mut cx, |_, cx| {
+                    cx.emit(LocalHistoryEvent::EntryAdded { abs_path })
+                })?;
+            }
+
+            let oldest_timestamp = timestamp - settings.max_age_days as i64 * SECONDS_PER_DAY;
+            LOCAL_HISTORY
+                .prune(oldest_timestamp, settings.max_total_size_mb * 1024 * 1024)
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use futures::StreamExt;
+    use gpui::TestAppContext;
+    use language::LineEnding;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+
+    #[gpui::test]
+    async fn test_snapshots_on_save_and_before_reload(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.executor().allow_parking();
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/test_snapshots", json!({ "a.txt": "one" }))
+            .await;
+        let project = Project::test(fs.clone(), ["/test_snapshots".as_ref()], cx).await;
+        let local_history = cx.new_model(|_| LocalHistory);
+        local_history.update(cx, |local_history, cx| {
+            local_history.track_project(&project, cx)
+        });
+        let mut events = cx.events(&local_history);
+
+        let abs_path = Path::new("/test_snapshots/a.txt");
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer(abs_path, cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        // The buffer's contents are stored before it's reloaded because its file changed.
+        fs.save(abs_path, &"two".into(), LineEnding::Unix)
+            .await
+            .unwrap();
+        events.next().await.unwrap();
+        cx.run_until_parked();
+        assert_eq!(buffer.read_with(cx, |buffer, _| buffer.text()), "two");
+
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "three, ")], None, cx));
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+            .await
+            .unwrap();
+        events.next().await.unwrap();
+
+        let entries = LOCAL_HISTORY.entries_for_path(abs_path).unwrap();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (
+                    entry.source,
+                    LOCAL_HISTORY.entry_content(entry.id).unwrap().unwrap()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (LocalHistorySource::Save, "three, two".to_string()),
+                (LocalHistorySource::Reload, "one".to_string()),
+            ]
+        );
+    }
+
+    pub(crate) fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            LocalHistorySettings::register(cx);
+        });
+    }
+}
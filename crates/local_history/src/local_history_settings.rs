@@ -0,0 +1,46 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct LocalHistorySettings {
+    pub enabled: bool,
+    pub max_age_days: u32,
+    pub max_file_size_kb: u64,
+    pub max_total_size_mb: u64,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct LocalHistorySettingsContent {
+    /// Whether to keep snapshots of files when they are saved, or before they are reloaded
+    /// because they changed on disk.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// How many days to keep snapshots for.
+    ///
+    /// Default: 30
+    pub max_age_days: Option<u32>,
+    /// Files larger than this size (in kilobytes) are not snapshotted.
+    ///
+    /// Default: 1024
+    pub max_file_size_kb: Option<u64>,
+    /// The maximum total size (in megabytes) of all snapshots. The oldest snapshots are
+    /// removed when it is exceeded.
+    ///
+    /// Default: 256
+    pub max_total_size_mb: Option<u64>,
+}
+
+impl Settings for LocalHistorySettings {
+    const KEY: Option<&'static str> = Some("local_history");
+
+    type FileContent = LocalHistorySettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut gpui::AppContext,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
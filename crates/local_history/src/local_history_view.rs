@@ -0,0 +1,634 @@
+use std::path::PathBuf;
+
+use editor::Editor;
+use git::diff::{BufferDiff, DiffHunk};
+use gpui::{
+    uniform_list, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, KeyContext,
+    Model, Subscription, Task, UniformListScrollHandle,
+};
+use language::{Buffer, BufferEvent};
+use menu::{SelectFirst, SelectLast, SelectNext, SelectPrev};
+use rope::Rope;
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, ListItem, ListItemSpacing, Tooltip};
+use util::ResultExt;
+use workspace::{
+    item::{Item, ItemEvent},
+    SplitDirection, Workspace,
+};
+
+use crate::{
+    LocalHistory, LocalHistoryEntry, LocalHistoryEvent, RestoreFile, ShowFileHistory, LOCAL_HISTORY,
+};
+
+/// Lists the snapshots of a file in the local history, and shows how the selected snapshot
+/// differs from the file's buffer. The snapshot can be restored as a whole or hunk by hunk.
+pub struct LocalHistoryView {
+    buffer: Model<Buffer>,
+    abs_path: PathBuf,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    diff_scroll_handle: UniformListScrollHandle,
+    entries: Vec<LocalHistoryEntry>,
+    selected_entry: Option<usize>,
+    snapshot: Option<EntrySnapshot>,
+    local_timezone: UtcOffset,
+    load_entries_task: Option<Task<()>>,
+    load_snapshot_task: Option<Task<()>>,
+    update_diff_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// The contents of the selected entry, and the hunks in which the buffer differs from them.
+struct EntrySnapshot {
+    entry_id: i64,
+    content: Rope,
+    hunks: Vec<DiffHunk>,
+    rows: Vec<DiffRow>,
+}
+
+/// A row of the diff between the selected entry and the buffer.
+enum DiffRow {
+    HunkHeader {
+        hunk_ix: usize,
+        location: SharedString,
+    },
+    Deleted(SharedString),
+    Added(SharedString),
+}
+
+impl LocalHistoryView {
+    pub(crate) fn deploy(
+        workspace: &mut Workspace,
+        _: &ShowFileHistory,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+            return;
+        };
+        let Some(abs_path) = buffer
+            .read(cx)
+            .file()
+            .and_then(|file| Some(file.as_local()?.abs_path(cx)))
+        else {
+            return;
+        };
+
+        let existing_view = workspace
+            .items_of_type::<LocalHistoryView>(cx)
+            .find(|view| view.read(cx).buffer == buffer);
+        if let Some(existing_view) = existing_view {
+            workspace.activate_item(&existing_view, true, true, cx);
+            return;
+        }
+
+        let view = cx.new_view(|cx| Self::new(buffer, abs_path, cx));
+        workspace.split_item(SplitDirection::Right, Box::new(view), cx);
+    }
+
+    fn new(buffer: Model<Buffer>, abs_path: PathBuf, cx: &mut ViewContext<Self>) -> Self {
+        let mut subscriptions = vec![cx.subscribe(&buffer, |this, _, event, cx| {
+            if let BufferEvent::Edited = event {
+                this.update_diff(cx);
+            }
+        })];
+        if let Some(local_history) = LocalHistory::global(cx) {
+            subscriptions.push(
+                cx.subscribe(&local_history, |this, _, event, cx| match event {
+                    LocalHistoryEvent::EntryAdded { abs_path } => {
+                        if *abs_path == this.abs_path {
+                            this.load_entries(cx);
+                        }
+                    }
+                }),
+            );
+        }
+
+        let local_offset = chrono::Local::now().offset().local_minus_utc();
+        let mut this = Self {
+            buffer,
+            abs_path,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            diff_scroll_handle: UniformListScrollHandle::new(),
+            entries: Vec::new(),
+            selected_entry: None,
+            snapshot: None,
+            local_timezone: UtcOffset::from_whole_seconds(local_offset).unwrap_or(UtcOffset::UTC),
+            load_entries_task: None,
+            load_snapshot_task: None,
+            update_diff_task: None,
+            _subscriptions: subscriptions,
+        };
+        this.load_entries(cx);
+        this
+    }
+
+    /// Reloads the file's entries, keeping the selected entry selected.
+    fn load_entries(&mut self, cx: &mut ViewContext<Self>) {
+        let abs_path = self.abs_path.clone();
+        let entries = cx
+            .background_executor()
+            .spawn(async move { LOCAL_HISTORY.entries_for_path(&abs_path) });
+        self.load_entries_task = Some(cx.spawn(|this, mut cx| async move {
+            let entries = entries.await.log_err().unwrap_or_default();
+            this.update(&mut cx, |this, cx| {
+                let selected_entry_id = this.selected_entry().map(|entry| entry.id);
+                this.entries = entries;
+                this.load_entries_task = None;
+                let selected_ix = selected_entry_id
+                    .and_then(|id| this.entries.iter().position(|entry| entry.id == id))
+                    .unwrap_or(0);
+                this.selected_entry = None;
+                this.select_entry(selected_ix, cx);
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn selected_entry(&self) -> Option<&LocalHistoryEntry> {
+        self.entries.get(self.selected_entry?)
+    }
+
+    fn select_entry(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        self.selected_entry = Some(ix);
+        self.scroll_handle.scroll_to_item(ix);
+        cx.notify();
+
+        let entry_id = entry.id;
+        if self
+            .snapshot
+            .as_ref()
+            .is_some_and(|snapshot| snapshot.entry_id == entry_id)
+        {
+            return;
+        }
+
+        let content = cx
+            .background_executor()
+            .spawn(async move { LOCAL_HISTORY.entry_content(entry_id) });
+        self.load_snapshot_task = Some(cx.spawn(|this, mut cx| async move {
+            let content = content.await.log_err().flatten().unwrap_or_default();
+            this.update(&mut cx, |this, cx| {
+                this.snapshot = Some(EntrySnapshot {
+                    entry_id,
+                    content: Rope::from(content.as_str()),
+                    hunks: Vec::new(),
+                    rows: Vec::new(),
+                });
+                this.load_snapshot_task = None;
+                this.update_diff(cx);
+            })
+            .ok();
+        }));
+    }
+
+    fn update_diff(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(snapshot) = self.snapshot.as_ref() else {
+            return;
+        };
+        let entry_id = snapshot.entry_id;
+        let content = snapshot.content.clone();
+        let buffer_snapshot = self.buffer.read(cx).text_snapshot();
+        self.update_diff_task = Some(cx.spawn(|this, mut cx| async move {
+            let (hunks, rows) = cx
+                .background_executor()
+                .spawn(async move {
+                    let mut diff = BufferDiff::new(&buffer_snapshot);
+                    diff.update(&content, &buffer_snapshot).await;
+                    let hunks = diff
+                        .hunks_intersecting_range(
+                            text::Anchor::MIN..text::Anchor::MAX,
+                            &buffer_snapshot,
+                        )
+                        .collect::<Vec<_>>();
+                    let rows = diff_rows(&hunks, &content, &buffer_snapshot);
+                    (hunks, rows)
+                })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                if let Some(snapshot) = this
+                    .snapshot
+                    .as_mut()
+                    .filter(|snapshot| snapshot.entry_id == entry_id)
+                {
+                    snapshot.hunks = hunks;
+                    snapshot.rows = rows;
+                    cx.notify();
+                }
+            })
+            .ok();
+        }));
+    }
+
+    /// Replaces the buffer's contents with the selected snapshot.
+    fn restore_file(&mut self, _: &RestoreFile, cx: &mut ViewContext<Self>) {
+        let Some(snapshot) = self.snapshot.as_ref() else {
+            return;
+        };
+        let buffer = self.buffer.clone();
+        let diff = buffer.read(cx).diff(snapshot.content.to_string(), cx);
+        cx.spawn(|_, mut cx| async move {
+            let diff = diff.await;
+            buffer
+                .update(&mut cx, |buffer, cx| buffer.apply_diff(diff, cx))
+                .ok();
+        })
+        .detach();
+    }
+
+    /// Replaces the lines of the given hunk with their contents in the selected snapshot.
+    fn restore_hunk(&mut self, hunk_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(snapshot) = self.snapshot.as_ref() else {
+            return;
+        };
+        let Some(hunk) = snapshot.hunks.get(hunk_ix) else {
+            return;
+        };
+        let text = snapshot
+            .content
+            .slice(hunk.diff_base_byte_range.clone())
+            .to_string();
+        let range = hunk.buffer_range.clone();
+        self.buffer.update(cx, |buffer, cx| {
+            buffer.edit([(range, text)], None, cx);
+        });
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        let next_ix = self.selected_entry.map_or(0, |ix| ix + 1);
+        self.select_entry(next_ix, cx);
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        if let Some(prev_ix) = self
+            .selected_entry
+            .unwrap_or(self.entries.len())
+            .checked_sub(1)
+        {
+            self.select_entry(prev_ix, cx);
+        }
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, cx: &mut ViewContext<Self>) {
+        self.select_entry(0, cx);
+    }
+
+    fn select_last(&mut self, _: &SelectLast, cx: &mut ViewContext<Self>) {
+        if let Some(last_ix) = self.entries.len().checked_sub(1) {
+            self.select_entry(last_ix, cx);
+        }
+    }
+
+    fn file_name(&self) -> String {
+        self.abs_path
+            .file_name()
+            .unwrap_or(self.abs_path.as_os_str())
+            .to_string_lossy()
+            .to_string()
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("LocalHistory");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        h_flex()
+            .px_2()
+            .py_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                Label::new(format!("Local History of {}", self.file_name()))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                Button::new("restore-file", "Restore File")
+                    .label_size(LabelSize::Small)
+                    .disabled(self.snapshot.is_none())
+                    .tooltip(|cx| Tooltip::for_action("Restore File", &RestoreFile, cx))
+                    .on_click(cx.listener(|view, _, cx| view.restore_file(&RestoreFile, cx))),
+            )
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let entry = &self.entries[ix];
+        let timestamp = OffsetDateTime::from_unix_timestamp(entry.timestamp)
+            .map(|timestamp| {
+                time_format::format_localized_timestamp(
+                    timestamp,
+                    OffsetDateTime::now_utc(),
+                    self.local_timezone,
+                    time_format::TimestampFormat::EnhancedAbsolute,
+                )
+            })
+            .unwrap_or_default();
+
+        ListItem::new(ix)
+            .spacing(ListItemSpacing::Sparse)
+            .selected(self.selected_entry == Some(ix))
+            .on_click(cx.listener(move |view, _, cx| view.select_entry(ix, cx)))
+            .child(
+                v_flex().child(Label::new(timestamp)).child(
+                    Label::new(format!(
+                        "{} · {}",
+                        entry.source.label(),
+                        format_size(entry.size)
+                    ))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                ),
+            )
+    }
+
+    fn render_diff(&self, cx: &mut ViewContext<Self>) -> AnyElement {
+        let message = if self.entries.is_empty() {
+            Some("No local history for this file")
+        } else if self.snapshot.is_none() {
+            Some("Loading…")
+        } else if self
+            .snapshot
+            .as_ref()
+            .is_some_and(|snapshot| snapshot.hunks.is_empty())
+        {
+            Some("The snapshot matches the current contents")
+        } else {
+            None
+        };
+        if let Some(message) = message {
+            return v_flex()
+                .size_full()
+                .justify_center()
+                .child(
+                    h_flex()
+                        .justify_center()
+                        .child(Label::new(message).color(Color::Muted)),
+                )
+                .into_any_element();
+        }
+
+        let row_count = self
+            .snapshot
+            .as_ref()
+            .map_or(0, |snapshot| snapshot.rows.len());
+        uniform_list(
+            cx.view().clone(),
+            "local-history-diff",
+            row_count,
+            |view, range, cx| {
+                let Some(snapshot) = view.snapshot.as_ref() else {
+                    return Vec::new();
+                };
+                snapshot.rows[range]
+                    .iter()
+                    .map(|row| view.render_diff_row(row, cx))
+                    .collect()
+            },
+        )
+        .size_full()
+        .track_scroll(self.diff_scroll_handle.clone())
+        .into_any_element()
+    }
+
+    fn render_diff_row(&self, row: &DiffRow, cx: &mut ViewContext<Self>) -> AnyElement {
+        let status_colors = cx.theme().status();
+        let row_div = h_flex().h_6().px_2().w_full();
+        match row {
+            DiffRow::HunkHeader { hunk_ix, location } => {
+                let hunk_ix = *hunk_ix;
+                row_div
+                    .justify_between()
+                    .bg(cx.theme().colors().editor_subheader_background)
+                    .child(
+                        Label::new(location.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Button::new(("restore-hunk", hunk_ix), "Restore")
+                            .label_size(LabelSize::Small)
+                            .on_click(
+                                cx.listener(move |view, _, cx| view.restore_hunk(hunk_ix, cx)),
+                            ),
+                    )
+                    .into_any_element()
+            }
+            DiffRow::Deleted(line) => row_div
+                .bg(status_colors.deleted_background)
+                .font_buffer(cx)
+                .text_buffer(cx)
+                .whitespace_nowrap()
+                .child(format!("- {line}"))
+                .into_any_element(),
+            DiffRow::Added(line) => row_div
+                .bg(status_colors.created_background)
+                .font_buffer(cx)
+                .text_buffer(cx)
+                .whitespace_nowrap()
+                .child(format!("+ {line}"))
+                .into_any_element(),
+        }
+    }
+}
+
+/// Lays out the given hunks as rows: a header for each hunk, followed by its lines in the
+/// snapshot and then its lines in the buffer.
+fn diff_rows(hunks: &[DiffHunk], content: &Rope, buffer: &text::BufferSnapshot) -> Vec<DiffRow> {
+    let mut rows = Vec::new();
+    for (hunk_ix, hunk) in hunks.iter().enumerate() {
+        let row_range = &hunk.row_range;
+        let location = if row_range.is_empty() {
+            format!("After line {}", row_range.start)
+        } else {
+            format!("Lines {}–{}", row_range.start + 1, row_range.end)
+        };
+        rows.push(DiffRow::HunkHeader {
+            hunk_ix,
+            location: location.into(),
+        });
+
+        let old_text = content.slice(hunk.diff_base_byte_range.clone()).to_string();
+        rows.extend(
+            old_text
+                .lines()
+                .map(|line| DiffRow::Deleted(line.to_string().into())),
+        );
+        let new_text = buffer
+            .text_for_range(hunk.buffer_range.clone())
+            .collect::<String>();
+        rows.extend(
+            new_text
+                .lines()
+                .map(|line| DiffRow::Added(line.to_string().into())),
+        );
+    }
+    rows
+}
+
+fn format_size(size: usize) -> String {
+    const KB: f64 = 1024.;
+    const MB: f64 = KB * 1024.;
+    let size = size as f64;
+    if size < KB {
+        format!("{size} B")
+    } else if size < MB {
+        format!("{:.1} KB", size / KB)
+    } else {
+        format!("{:.1} MB", size / MB)
+    }
+}
+
+impl Render for LocalHistoryView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::restore_file))
+            .child(self.render_header(cx))
+            .child(
+                h_flex()
+                    .size_full()
+                    .items_start()
+                    .child(
+                        v_flex()
+                            .w(px(240.))
+                            .h_full()
+                            .border_r_1()
+                            .border_color(cx.theme().colors().border)
+                            .child(
+                                uniform_list(
+                                    cx.view().clone(),
+                                    "local-history-entries",
+                                    self.entries.len(),
+                                    |view, range, cx| {
+                                        range.map(|ix| view.render_entry(ix, cx)).collect()
+                                    },
+                                )
+                                .size_full()
+                                .track_scroll(self.scroll_handle.clone()),
+                            ),
+                    )
+                    .child(div().flex_1().h_full().child(self.render_diff(cx))),
+            )
+    }
+}
+
+impl EventEmitter<ItemEvent> for LocalHistoryView {}
+
+impl FocusableView for LocalHistoryView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for LocalHistoryView {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_content_text(&self, _: &WindowContext) -> Option<SharedString> {
+        Some(format!("History: {}", self.file_name()).into())
+    }
+
+    fn tab_icon(&self, _: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::HistoryRerun))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::init_test, LocalHistorySource};
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+    use serde_json::json;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[gpui::test]
+    async fn test_restore_hunk_and_file(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.executor().allow_parking();
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/test_restore", json!({ "a.txt": "a\nB\nc\nD\n" }))
+            .await;
+        let project = Project::test(fs, ["/test_restore".as_ref()], cx).await;
+        let abs_path = PathBuf::from("/test_restore/a.txt");
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer(&abs_path, cx))
+            .await
+            .unwrap();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        LOCAL_HISTORY
+            .save_entry(
+                abs_path.clone(),
+                LocalHistorySource::Save,
+                timestamp,
+                "a\nb\nc\nd\n".into(),
+            )
+            .await
+            .unwrap();
+
+        let cx = cx.add_empty_window();
+        let view = cx.new_view(|cx| LocalHistoryView::new(buffer.clone(), abs_path, cx));
+        cx.run_until_parked();
+        view.update(cx, |view, cx| {
+            assert_eq!(view.entries.len(), 1);
+            assert_eq!(view.snapshot.as_ref().unwrap().hunks.len(), 2);
+            view.restore_hunk(1, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "a\nB\nc\nd\n"
+        );
+
+        view.update(cx, |view, cx| {
+            assert_eq!(view.snapshot.as_ref().unwrap().hunks.len(), 1);
+            view.restore_file(&RestoreFile, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "a\nb\nc\nd\n"
+        );
+        view.update(cx, |view, _| {
+            assert!(view.snapshot.as_ref().unwrap().hunks.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(2048), "2.0 KB");
+        assert_eq!(format_size(3 * 1024 * 1024 + 512 * 1024), "3.5 MB");
+    }
+}
@@ -0,0 +1,230 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use db::{
+    define_connection, query,
+    sqlez::{
+        bindable::{Bind, Column, StaticColumnCount},
+        statement::Statement,
+    },
+    sqlez_macros::sql,
+};
+
+define_connection!(pub static ref LOCAL_HISTORY: LocalHistoryDb<()> =
+    &[sql!(
+        CREATE TABLE local_history_entries(
+            entry_id INTEGER PRIMARY KEY AUTOINCREMENT,
+            abs_path BLOB NOT NULL,
+            source TEXT NOT NULL,
+            timestamp INTEGER NOT NULL,
+            size INTEGER NOT NULL,
+            content TEXT NOT NULL
+        ) STRICT;
+
+        CREATE INDEX local_history_entries_by_path ON local_history_entries(abs_path, entry_id);
+    )];
+);
+
+/// What caused a snapshot of a file to be taken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LocalHistorySource {
+    /// The buffer was saved.
+    Save,
+    /// The file changed on disk, and the buffer was about to be reloaded.
+    Reload,
+}
+
+impl LocalHistorySource {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LocalHistorySource::Save => "save",
+            LocalHistorySource::Reload => "reload",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LocalHistorySource::Save => "Saved",
+            LocalHistorySource::Reload => "Before Reload",
+        }
+    }
+}
+
+impl StaticColumnCount for LocalHistorySource {}
+impl Bind for LocalHistorySource {
+    fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
+        self.as_str().bind(statement, start_index)
+    }
+}
+
+impl Column for LocalHistorySource {
+    fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
+        let source = match statement.column_text(start_index)? {
+            "save" => LocalHistorySource::Save,
+            "reload" => LocalHistorySource::Reload,
+            source => return Err(anyhow!("unknown local history source {source:?}")),
+        };
+        Ok((source, start_index + 1))
+    }
+}
+
+/// A snapshot of a file in the local history, without its contents.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocalHistoryEntry {
+    pub id: i64,
+    pub source: LocalHistorySource,
+    /// When the snapshot was taken, in seconds since the Unix epoch.
+    pub timestamp: i64,
+    /// The size of the snapshot's contents in bytes.
+    pub size: usize,
+}
+
+impl StaticColumnCount for LocalHistoryEntry {
+    fn column_count() -> usize {
+        4
+    }
+}
+
+impl Column for LocalHistoryEntry {
+    fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
+        let ((id, source, timestamp, size), next_index) =
+            <(i64, LocalHistorySource, i64, usize)>::column(statement, start_index)?;
+        Ok((
+            LocalHistoryEntry {
+                id,
+                source,
+                timestamp,
+                size,
+            },
+            next_index,
+        ))
+    }
+}
+
+impl LocalHistoryDb {
+    /// Stores a snapshot of the file at the given path, unless it has the same contents as the
+    /// file's latest snapshot. Returns whether a snapshot was stored.
+    pub async fn save_entry(
+        &self,
+        abs_path: PathBuf,
+        source: LocalHistorySource,
+        timestamp: i64,
+        content: String,
+    ) -> Result<bool> {
+        self.write(move |conn| {
+            conn.with_savepoint("save_local_history_entry", || {
+                let latest_content = conn.select_row_bound::<&Path, String>(sql!(
+                    SELECT content FROM local_history_entries
+                    WHERE abs_path = ?
+                    ORDER BY entry_id DESC
+                    LIMIT 1
+                ))?(&abs_path)?;
+                if latest_content.as_ref() == Some(&content) {
+                    return Ok(false);
+                }
+
+                conn.exec_bound(sql!(
+                    INSERT INTO local_history_entries(abs_path, source, timestamp, size, content)
+                    VALUES (?, ?, ?, ?, ?)
+                ))?((abs_path, source, timestamp, content.len(), content))?;
+                Ok(true)
+            })
+        })
+        .await
+    }
+
+    query! {
+        pub fn entries_for_path(abs_path: &Path) -> Result<Vec<LocalHistoryEntry>> {
+            SELECT entry_id, source, timestamp, size FROM local_history_entries
+            WHERE abs_path = ?
+            ORDER BY entry_id DESC
+        }
+    }
+
+    query! {
+        pub fn entry_content(entry_id: i64) -> Result<Option<String>> {
+            SELECT content FROM local_history_entries
+            WHERE entry_id = ?
+        }
+    }
+
+    /// Deletes the snapshots taken before the given timestamp, and then the oldest snapshots
+    /// until the total size of the remaining ones is within the given limit.
+    pub async fn prune(&self, oldest_timestamp: i64, max_total_size: u64) -> Result<()> {
+        self.write(move |conn| {
+            conn.exec_bound(sql!(
+                DELETE FROM local_history_entries WHERE timestamp < ?;
+            ))?(oldest_timestamp)?;
+            conn.exec_bound(sql!(
+                DELETE FROM local_history_entries WHERE entry_id IN (
+                    SELECT entry_id FROM (
+                        SELECT
+                            entry_id,
+                            SUM(size) OVER (ORDER BY entry_id DESC) AS total_size
+                        FROM local_history_entries
+                    )
+                    WHERE total_size > ?
+                );
+            ))?(max_total_size)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    async fn test_local_history_entries() {
+        let db = LocalHistoryDb(db::open_test_db("test_local_history_entries").await);
+        let path = PathBuf::from("/project/src/main.rs");
+        let other_path = PathBuf::from("/project/src/lib.rs");
+
+        assert!(db
+            .save_entry(path.clone(), LocalHistorySource::Save, 10, "one".into())
+            .await
+            .unwrap());
+        // Snapshots with the same contents as the latest one are skipped.
+        assert!(!db
+            .save_entry(path.clone(), LocalHistorySource::Reload, 20, "one".into())
+            .await
+            .unwrap());
+        assert!(db
+            .save_entry(path.clone(), LocalHistorySource::Reload, 30, "two!".into())
+            .await
+            .unwrap());
+        assert!(db
+            .save_entry(
+                other_path.clone(),
+                LocalHistorySource::Save,
+                40,
+                "three".into()
+            )
+            .await
+            .unwrap());
+
+        let entries = db.entries_for_path(&path).unwrap();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.source, entry.timestamp, entry.size))
+                .collect::<Vec<_>>(),
+            vec![
+                (LocalHistorySource::Reload, 30, 4),
+                (LocalHistorySource::Save, 10, 3),
+            ]
+        );
+        assert_eq!(
+            db.entry_content(entries[0].id).unwrap(),
+            Some("two!".to_string())
+        );
+
+        // Old snapshots are removed first, then the oldest ones over the size limit.
+        db.prune(20, 9).await.unwrap();
+        assert_eq!(db.entries_for_path(&path).unwrap().len(), 1);
+        db.prune(20, 5).await.unwrap();
+        assert_eq!(db.entries_for_path(&path).unwrap(), Vec::new());
+        assert_eq!(db.entries_for_path(&other_path).unwrap().len(), 1);
+    }
+}
@@ -12,7 +12,7 @@ use language::{
     AutoindentMode, Buffer, BufferChunks, BufferRow, BufferSnapshot, Capability, CharClassifier,
     CharKind, Chunk, CursorShape, DiagnosticEntry, File, IndentGuide, IndentSize, Language,
     LanguageScope, OffsetRangeExt, OffsetUtf16, Outline, OutlineItem, Point, PointUtf16, Selection,
-    TextDimension, ToOffset as _, ToOffsetUtf16 as _, ToPoint as _, ToPointUtf16 as _,
+    TextDimension, TextObject, ToOffset as _, ToOffsetUtf16 as _, ToPoint as _, ToPointUtf16 as _,
     TransactionId, Unclipped,
 };
 use smallvec::SmallVec;
@@ -107,6 +107,7 @@ pub enum Event {
     Discarded,
     DirtyChanged,
     DiagnosticsUpdated,
+    SemanticTokensUpdated(BufferId),
 }
 
 /// A diff hunk, representing a range of consequent lines in a multibuffer.
@@ -1742,6 +1743,9 @@ impl MultiBuffer {
             }
             language::BufferEvent::Reparsed => Event::Reparsed(buffer.read(cx).remote_id()),
             language::BufferEvent::DiagnosticsUpdated => Event::DiagnosticsUpdated,
+            language::BufferEvent::SemanticTokensUpdated => {
+                Event::SemanticTokensUpdated(buffer.read(cx).remote_id())
+            }
             language::BufferEvent::Closed => Event::Closed,
             language::BufferEvent::Discarded => Event::Discarded,
             language::BufferEvent::CapabilityChanged => {
@@ -1778,7 +1782,7 @@ impl MultiBuffer {
         &self,
         point: T,
         cx: &'a AppContext,
-    ) -> &'a LanguageSettings {
+    ) -> Cow<'a, LanguageSettings> {
         let mut language = None;
         let mut file = None;
         if let Some((buffer, offset, _)) = self.point_to_buffer_offset(point, cx) {
@@ -3390,6 +3394,31 @@ impl MultiBufferSnapshot {
         ))
     }
 
+    /// Returns the smallest range of the given text object that contains the given range, or None if
+    /// there is no such text object within the excerpt containing the range
+    pub fn innermost_enclosing_text_object<T: ToOffset>(
+        &self,
+        range: Range<T>,
+        text_object: TextObject,
+    ) -> Option<Range<usize>> {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        let excerpt = self.excerpt_containing(range.clone())?;
+        let buffer_range = excerpt.map_range_to_buffer(range);
+
+        let object_range = excerpt
+            .buffer()
+            .text_object_ranges(buffer_range.clone())
+            .filter(|(object_range, object)| {
+                *object == text_object
+                    && object_range.start <= buffer_range.start
+                    && object_range.end >= buffer_range.end
+                    && excerpt.contains_buffer_range(object_range.clone())
+            })
+            .map(|(object_range, _)| object_range)
+            .min_by_key(|object_range| object_range.len())?;
+        Some(excerpt.map_range_from_buffer(object_range))
+    }
+
     /// Returns enclosing bracket ranges containing the given range or returns None if the range is
     /// not contained in a single excerpt
     pub fn enclosing_bracket_ranges<T: ToOffset>(
@@ -3580,7 +3609,7 @@ impl MultiBufferSnapshot {
         &'a self,
         point: T,
         cx: &'a AppContext,
-    ) -> &'a LanguageSettings {
+    ) -> Cow<'a, LanguageSettings> {
         let mut language = None;
         let mut file = None;
         if let Some((buffer, offset)) = self.point_to_buffer_offset(point) {
@@ -293,3 +293,8 @@ pub fn local_tasks_file_relative_path() -> &'static Path {
 pub fn local_vscode_tasks_file_relative_path() -> &'static Path {
     Path::new(".vscode/tasks.json")
 }
+
+/// Returns the relative path to an `.editorconfig` file within a project.
+pub fn local_editorconfig_file_relative_path() -> &'static Path {
+    Path::new(".editorconfig")
+}
@@ -16,11 +16,12 @@ use gpui::{
 };
 use http_client::Url;
 use language::{
+    language_settings::{language_settings, LineEndingSetting},
     proto::{
         deserialize_line_ending, deserialize_version, serialize_line_ending, serialize_version,
         split_operations,
     },
-    Buffer, BufferEvent, Capability, File as _, Language, Operation,
+    Buffer, BufferEvent, Capability, File as _, Language, LineEnding, Operation,
 };
 use rpc::{proto, AnyProtoClient, ErrorExt as _, TypedEnvelope};
 use smol::channel::Receiver;
@@ -431,7 +432,12 @@ impl LocalBufferStore {
         let buffer = buffer_handle.read(cx);
 
         let text = buffer.as_rope().clone();
-        let line_ending = buffer.line_ending();
+        let line_ending = match language_settings(buffer.language(), buffer.file(), cx).line_ending
+        {
+            LineEndingSetting::Detect => buffer.line_ending(),
+            LineEndingSetting::Lf => LineEnding::Unix,
+            LineEndingSetting::Crlf => LineEnding::Windows,
+        };
         let version = buffer.version();
         let buffer_id = buffer.remote_id();
         if buffer.file().is_some_and(|file| !file.is_created()) {
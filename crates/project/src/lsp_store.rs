@@ -89,6 +89,10 @@ pub use worktree::{
 
 const SERVER_LAUNCHING_BEFORE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 pub const SERVER_PROGRESS_THROTTLE_TIMEOUT: Duration = Duration::from_millis(100);
+/// How long to wait after the last edit of a buffer before pulling its diagnostics.
+const PULL_DIAGNOSTICS_DEBOUNCE: Duration = Duration::from_millis(125);
+/// How long to wait before pulling the workspace diagnostics again after a pull completes.
+const WORKSPACE_DIAGNOSTICS_PULL_INTERVAL: Duration = Duration::from_secs(1);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FormatTrigger {
@@ -136,6 +140,11 @@ pub struct LocalLspStore {
     yarn: Model<YarnPathStore>,
     pub language_servers: HashMap<LanguageServerId, LanguageServerState>,
     buffers_being_formatted: HashSet<BufferId>,
+    buffer_semantic_tokens: HashMap<BufferId, LspSemanticTokens>,
+    /// The result ids of the diagnostics last pulled from each language server, by document.
+    diagnostic_result_ids: HashMap<LanguageServerId, HashMap<lsp::Url, String>>,
+    pull_diagnostics_tasks: HashMap<BufferId, Task<()>>,
+    workspace_diagnostics_tasks: HashMap<LanguageServerId, Task<()>>,
     last_workspace_edits_by_language_server: HashMap<LanguageServerId, ProjectTransaction>,
     language_server_watched_paths: HashMap<LanguageServerId, Model<LanguageServerWatchedPaths>>,
     language_server_watcher_registrations:
@@ -223,7 +232,7 @@ impl LocalLspStore {
                 })?;
 
             let settings = buffer.handle.update(&mut cx, |buffer, cx| {
-                language_settings(buffer.language(), buffer.file(), cx).clone()
+                language_settings(buffer.language(), buffer.file(), cx).into_owned()
             })?;
 
             let remove_trailing_whitespace = settings.remove_trailing_whitespace_on_save;
@@ -744,6 +753,7 @@ pub enum LspStoreEvent {
     },
     Notification(String),
     RefreshInlayHints,
+    RefreshSemanticTokens,
     DiagnosticsUpdated {
         language_server_id: LanguageServerId,
         path: ProjectPath,
@@ -794,8 +804,10 @@ impl LspStore {
         client.add_model_request_handler(Self::handle_inlay_hints);
         client.add_model_request_handler(Self::handle_get_project_symbols);
         client.add_model_request_handler(Self::handle_resolve_inlay_hint);
+        client.add_model_request_handler(Self::handle_semantic_tokens);
         client.add_model_request_handler(Self::handle_open_buffer_for_symbol);
         client.add_model_request_handler(Self::handle_refresh_inlay_hints);
+        client.add_model_request_handler(Self::handle_refresh_semantic_tokens);
         client.add_model_request_handler(Self::handle_on_type_formatting);
         client.add_model_request_handler(Self::handle_apply_additional_edits_for_completion);
         client.add_model_request_handler(Self::handle_lsp_command::<GetCodeActions>);
@@ -810,6 +822,13 @@ impl LspStore {
         client.add_model_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_model_request_handler(Self::handle_lsp_command::<lsp_ext_command::ExpandMacro>);
         client.add_model_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
+        client.add_model_request_handler(Self::handle_lsp_command::<PrepareCallHierarchy>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetIncomingCalls>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetOutgoingCalls>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetCodeLens>);
+        client.add_model_request_handler(Self::handle_lsp_command::<PrepareTypeHierarchy>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSupertypes>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSubtypes>);
     }
 
     pub fn as_remote(&self) -> Option<&RemoteLspStore> {
@@ -896,6 +915,10 @@ impl LspStore {
                 language_server_watcher_registrations: Default::default(),
                 current_lsp_settings: ProjectSettings::get_global(cx).lsp.clone(),
                 buffers_being_formatted: Default::default(),
+                buffer_semantic_tokens: Default::default(),
+                diagnostic_result_ids: Default::default(),
+                pull_diagnostics_tasks: Default::default(),
+                workspace_diagnostics_tasks: Default::default(),
                 last_formatting_failure: None,
                 prettier_store,
                 environment,
@@ -1005,7 +1028,12 @@ impl LspStore {
 
                 self.register_buffer_with_language_servers(buffer, cx);
             }
-            BufferStoreEvent::BufferDropped(_) => {}
+            BufferStoreEvent::BufferDropped(buffer_id) => {
+                if let Some(local) = self.as_local_mut() {
+                    local.buffer_semantic_tokens.remove(buffer_id);
+                    local.pull_diagnostics_tasks.remove(buffer_id);
+                }
+            }
         }
     }
 
@@ -1075,7 +1103,8 @@ impl LspStore {
     ) {
         match event {
             language::BufferEvent::Edited { .. } => {
-                self.on_buffer_edited(buffer, cx);
+                self.on_buffer_edited(buffer.clone(), cx);
+                self.pull_diagnostics_debounced(buffer, cx);
             }
 
             language::BufferEvent::Saved => {
@@ -1225,7 +1254,8 @@ impl LspStore {
         });
 
         let buffer_file = buffer.read(cx).file().cloned();
-        let settings = language_settings(Some(&new_language), buffer_file.as_ref(), cx).clone();
+        let settings =
+            language_settings(Some(&new_language), buffer_file.as_ref(), cx).into_owned();
         let buffer_file = File::from_dyn(buffer_file.as_ref());
 
         let worktree_id = if let Some(file) = buffer_file {
@@ -1407,8 +1437,10 @@ impl LspStore {
                         language_servers_to_start.push((file.worktree.clone(), language.name()));
                     }
                 }
-                language_formatters_to_check
-                    .push((buffer_file.map(|f| f.worktree_id(cx)), settings.clone()));
+                language_formatters_to_check.push((
+                    buffer_file.map(|f| f.worktree_id(cx)),
+                    settings.into_owned(),
+                ));
             }
         }
 
@@ -1850,7 +1882,7 @@ impl LspStore {
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<Option<Transaction>>> {
         let options = buffer.update(cx, |buffer, cx| {
-            lsp_command::lsp_formatting_options(language_settings(
+            lsp_command::lsp_formatting_options(&language_settings(
                 buffer.language_at(position).as_ref(),
                 buffer.file(),
                 cx,
@@ -2525,6 +2557,154 @@ impl LspStore {
         }
     }
 
+    /// Fetches the semantic tokens of the buffer from its primary language server and assigns them to the buffer,
+    /// requesting only the changes since the previous response when the server supports it.
+    pub fn semantic_tokens(
+        &mut self,
+        buffer_handle: Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let buffer = buffer_handle.read(cx);
+        let buffer_id = buffer.remote_id();
+
+        if let Some((client, project_id)) = self.upstream_client() {
+            let request = proto::GetSemanticTokens {
+                project_id,
+                buffer_id: buffer_id.into(),
+                version: serialize_version(&buffer.version()),
+            };
+            return cx.spawn(move |_, mut cx| async move {
+                let response = client
+                    .request(request)
+                    .await
+                    .context("semantic tokens proto request")?;
+                buffer_handle
+                    .update(&mut cx, |buffer, _| {
+                        buffer.wait_for_version(deserialize_version(&response.version))
+                    })?
+                    .await?;
+                let semantic_tokens = response
+                    .tokens
+                    .into_iter()
+                    .map(deserialize_semantic_token)
+                    .collect::<Result<Arc<[_]>>>()?;
+                buffer_handle.update(&mut cx, |buffer, cx| {
+                    buffer.set_semantic_tokens(semantic_tokens, cx)
+                })
+            });
+        }
+
+        let enabled = language_settings(buffer.language(), buffer.file(), cx).semantic_tokens;
+        let server = self
+            .primary_language_server_for_buffer(buffer, cx)
+            .map(|(_, server)| server.clone())
+            .filter(|_| enabled);
+        let semantic_tokens_options = server.as_ref().and_then(|server| {
+            match server.capabilities().semantic_tokens_provider? {
+                lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(options) => {
+                    Some(options)
+                }
+                lsp::SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(
+                    options,
+                ) => Some(options.semantic_tokens_options),
+            }
+        });
+        let file = File::from_dyn(buffer.file()).and_then(File::as_local);
+        let (Some(server), Some(options), Some(file)) = (server, semantic_tokens_options, file)
+        else {
+            if let Some(local) = self.as_local_mut() {
+                local.buffer_semantic_tokens.remove(&buffer_id);
+            }
+            buffer_handle.update(cx, |buffer, cx| {
+                if buffer.snapshot().has_semantic_tokens() {
+                    buffer.set_semantic_tokens(Arc::from([]), cx);
+                }
+            });
+            return Task::ready(Ok(()));
+        };
+
+        let supports_delta = matches!(
+            options.full,
+            Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) })
+        );
+        let previous_tokens = self
+            .as_local()
+            .and_then(|local| local.buffer_semantic_tokens.get(&buffer_id))
+            .filter(|previous| previous.server_id == server.server_id() && supports_delta)
+            .and_then(|previous| Some((previous.result_id.clone()?, previous.data.clone())));
+        let text_document =
+            lsp::TextDocumentIdentifier::new(lsp::Url::from_file_path(file.abs_path(cx)).unwrap());
+        let snapshot = buffer.text_snapshot();
+        let legend = options.legend;
+
+        cx.spawn(move |this, mut cx| async move {
+            let (result_id, data) = if let Some((previous_result_id, mut data)) = previous_tokens {
+                let response = server
+                    .request::<lsp::request::SemanticTokensFullDeltaRequest>(
+                        lsp::SemanticTokensDeltaParams {
+                            text_document,
+                            previous_result_id,
+                            work_done_progress_params: Default::default(),
+                            partial_result_params: Default::default(),
+                        },
+                    )
+                    .await?;
+                match response {
+                    Some(lsp::SemanticTokensFullDeltaResult::Tokens(tokens)) => {
+                        (tokens.result_id, tokens.data)
+                    }
+                    Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(delta)) => {
+                        apply_semantic_tokens_edits(&mut data, delta.edits);
+                        (delta.result_id, data)
+                    }
+                    Some(lsp::SemanticTokensFullDeltaResult::PartialTokensDelta { edits }) => {
+                        apply_semantic_tokens_edits(&mut data, edits);
+                        (None, data)
+                    }
+                    None => (None, Vec::new()),
+                }
+            } else {
+                let response = server
+                    .request::<lsp::request::SemanticTokensFullRequest>(lsp::SemanticTokensParams {
+                        text_document,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    })
+                    .await?;
+                match response {
+                    Some(lsp::SemanticTokensResult::Tokens(tokens)) => {
+                        (tokens.result_id, tokens.data)
+                    }
+                    Some(lsp::SemanticTokensResult::Partial(partial)) => (None, partial.data),
+                    None => (None, Vec::new()),
+                }
+            };
+
+            let semantic_tokens = cx
+                .background_executor()
+                .spawn({
+                    let data = data.clone();
+                    async move { semantic_tokens_from_lsp(&snapshot, &legend, &data) }
+                })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                if let Some(local) = this.as_local_mut() {
+                    local.buffer_semantic_tokens.insert(
+                        buffer_id,
+                        LspSemanticTokens {
+                            server_id: server.server_id(),
+                            result_id,
+                            data,
+                        },
+                    );
+                }
+                buffer_handle.update(cx, |buffer, cx| {
+                    buffer.set_semantic_tokens(semantic_tokens, cx)
+                });
+            })
+        })
+    }
+
     pub fn signature_help<T: ToPointUtf16>(
         &self,
         buffer: &Model<Buffer>,
@@ -3297,6 +3477,8 @@ impl LspStore {
                         .or_default()
                         .insert(server.server_id(), vec![snapshot]);
                 }
+                self.pull_diagnostics(buffer_handle.clone(), cx)
+                    .detach_and_log_err(cx);
             }
         }
     }
@@ -3312,6 +3494,11 @@ impl LspStore {
             None => return,
         };
 
+        if let Some(local) = self.as_local_mut() {
+            local
+                .buffer_semantic_tokens
+                .remove(&buffer.read(cx).remote_id());
+        }
         buffer.update(cx, |buffer, cx| {
             let worktree_id = old_file.worktree_id(cx);
 
@@ -3339,6 +3526,11 @@ impl LspStore {
         });
     }
 
+    /// Allocates a group id for diagnostics that do not come from language servers.
+    pub fn next_diagnostic_group_id(&mut self) -> usize {
+        post_inc(&mut self.next_diagnostic_group_id)
+    }
+
     pub fn update_diagnostic_entries(
         &mut self,
         server_id: LanguageServerId,
@@ -4914,6 +5106,17 @@ impl LspStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_refresh_semantic_tokens(
+        this: Model<Self>,
+        _: TypedEnvelope<proto::RefreshSemanticTokens>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |_, cx| {
+            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+        })?;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_inlay_hints(
         this: Model<Self>,
         envelope: TypedEnvelope<proto::InlayHints>,
@@ -4959,6 +5162,38 @@ impl LspStore {
         })
     }
 
+    async fn handle_semantic_tokens(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GetSemanticTokens>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::GetSemanticTokensResponse> {
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        let buffer = this.update(&mut cx, |this, cx| {
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&envelope.payload.version))
+            })?
+            .await
+            .with_context(|| format!("waiting for version for buffer {}", buffer.entity_id()))?;
+
+        this.update(&mut cx, |this, cx| this.semantic_tokens(buffer.clone(), cx))?
+            .await
+            .context("semantic tokens fetch")?;
+
+        buffer.update(&mut cx, |buffer, _| {
+            let snapshot = buffer.snapshot();
+            proto::GetSemanticTokensResponse {
+                tokens: snapshot
+                    .semantic_tokens_in_range(0..snapshot.len())
+                    .map(serialize_semantic_token)
+                    .collect(),
+                version: serialize_version(&buffer.version()),
+            }
+        })
+    }
+
     async fn handle_resolve_inlay_hint(
         this: Model<Self>,
         envelope: TypedEnvelope<proto::ResolveInlayHint>,
@@ -5714,10 +5949,10 @@ impl LspStore {
                     });
                 }
 
-                self.as_local_mut()
-                    .unwrap()
-                    .language_server_watched_paths
-                    .remove(&server_id);
+                let local = self.as_local_mut().unwrap();
+                local.language_server_watched_paths.remove(&server_id);
+                local.diagnostic_result_ids.remove(&server_id);
+                local.workspace_diagnostics_tasks.remove(&server_id);
                 self.language_server_statuses.remove(&server_id);
                 cx.notify();
 
@@ -6145,6 +6380,42 @@ impl LspStore {
             })
             .detach();
 
+        language_server
+            .on_request::<lsp::request::SemanticTokensRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+                            this.downstream_client.as_ref().map(|(client, project_id)| {
+                                client.send(proto::RefreshSemanticTokens {
+                                    project_id: *project_id,
+                                })
+                            })
+                        })?
+                        .transpose()?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::WorkspaceDiagnosticRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            this.refresh_pulled_diagnostics(server_id, cx);
+                        })?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
         language_server
             .on_request::<lsp::request::ShowMessageRequest, _, _>({
                 let this = this.clone();
@@ -6314,6 +6585,295 @@ impl LspStore {
             .detach();
     }
 
+    /// Requests the diagnostics of the buffer from its language servers that support pulling them,
+    /// unless the buffer is edited again before [`PULL_DIAGNOSTICS_DEBOUNCE`] elapses.
+    fn pull_diagnostics_debounced(&mut self, buffer: Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let buffer_id = buffer.read(cx).remote_id();
+        let pulls_diagnostics = self
+            .language_servers_for_buffer(buffer.read(cx), cx)
+            .any(|(_, server)| diagnostic_options(server).is_some());
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        if !pulls_diagnostics {
+            return;
+        }
+        let task = cx.spawn(move |this, mut cx| async move {
+            cx.background_executor()
+                .timer(PULL_DIAGNOSTICS_DEBOUNCE)
+                .await;
+            if let Ok(task) = this.update(&mut cx, |this, cx| this.pull_diagnostics(buffer, cx)) {
+                task.await.log_err();
+            }
+        });
+        local.pull_diagnostics_tasks.insert(buffer_id, task);
+    }
+
+    /// Requests the diagnostics of the buffer from its language servers that support pulling them,
+    /// for the servers that don't publish them on their own.
+    pub fn pull_diagnostics(
+        &mut self,
+        buffer_handle: Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(local) = self.as_local() else {
+            return Task::ready(Ok(()));
+        };
+        let buffer = buffer_handle.read(cx);
+        let buffer_id = buffer.remote_id();
+        let Some(file) = File::from_dyn(buffer.file()).and_then(File::as_local) else {
+            return Task::ready(Ok(()));
+        };
+        let uri = lsp::Url::from_file_path(file.abs_path(cx)).unwrap();
+
+        let requests = self
+            .language_servers_for_buffer(buffer, cx)
+            .filter_map(|(adapter, server)| {
+                let options = diagnostic_options(server)?;
+                let server_id = server.server_id();
+                let version = self
+                    .buffer_snapshots
+                    .get(&buffer_id)
+                    .and_then(|snapshots| snapshots.get(&server_id)?.last())
+                    .map(|snapshot| snapshot.version);
+                let previous_result_id = local
+                    .diagnostic_result_ids
+                    .get(&server_id)
+                    .and_then(|result_ids| result_ids.get(&uri))
+                    .cloned();
+                let request = server.request::<lsp::request::DocumentDiagnosticRequest>(
+                    lsp::DocumentDiagnosticParams {
+                        text_document: lsp::TextDocumentIdentifier::new(uri.clone()),
+                        identifier: options.identifier,
+                        previous_result_id,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    },
+                );
+                let adapter = adapter.clone();
+                Some(async move { (adapter, server_id, version, request.await) })
+            })
+            .collect::<Vec<_>>();
+        if requests.is_empty() {
+            return Task::ready(Ok(()));
+        }
+
+        cx.spawn(move |this, mut cx| async move {
+            for (adapter, server_id, version, response) in join_all(requests).await {
+                let Some(response) = response.log_err() else {
+                    continue;
+                };
+                let (report, related_documents) = match response {
+                    lsp::DocumentDiagnosticReportResult::Report(
+                        lsp::DocumentDiagnosticReport::Full(report),
+                    ) => (
+                        Some(lsp::DocumentDiagnosticReportKind::Full(
+                            report.full_document_diagnostic_report,
+                        )),
+                        report.related_documents,
+                    ),
+                    lsp::DocumentDiagnosticReportResult::Report(
+                        lsp::DocumentDiagnosticReport::Unchanged(report),
+                    ) => (
+                        Some(lsp::DocumentDiagnosticReportKind::Unchanged(
+                            report.unchanged_document_diagnostic_report,
+                        )),
+                        report.related_documents,
+                    ),
+                    // Only related documents were reported, so the diagnostics of the document
+                    // itself and its result id are kept as they are, unless it's one of them.
+                    lsp::DocumentDiagnosticReportResult::Partial(partial) => {
+                        (None, partial.related_documents)
+                    }
+                };
+                this.update(&mut cx, |this, cx| {
+                    let reports = report
+                        .map(|report| (uri.clone(), report))
+                        .into_iter()
+                        .chain(related_documents.unwrap_or_default());
+                    for (report_uri, report) in reports {
+                        let version = if report_uri == uri { version } else { None };
+                        this.apply_pulled_diagnostics(
+                            server_id, &adapter, report_uri, version, report, cx,
+                        );
+                    }
+                })?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Requests the diagnostics of the whole workspace from the language server, if it supports pulling them,
+    /// reporting the result ids of the documents that were pulled before. The request is issued again once
+    /// it completes, so that servers holding it open until something changes keep streaming reports.
+    pub fn pull_workspace_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(local) = self.as_local() else {
+            return;
+        };
+        if local
+            .language_servers
+            .get(&server_id)
+            .and_then(|state| match state {
+                LanguageServerState::Running { server, .. } => diagnostic_options(server),
+                LanguageServerState::Starting(_) => None,
+            })
+            .filter(|options| options.workspace_diagnostics)
+            .is_none()
+        {
+            return;
+        }
+
+        let task = cx.spawn(move |this, mut cx| async move {
+            loop {
+                let Ok(Some((adapter, request))) = this.update(&mut cx, |this, _| {
+                    this.request_workspace_diagnostics(server_id)
+                }) else {
+                    return;
+                };
+                let Some(response) = request.await.log_err() else {
+                    return;
+                };
+                let reports = match response {
+                    lsp::WorkspaceDiagnosticReportResult::Report(report) => report.items,
+                    lsp::WorkspaceDiagnosticReportResult::Partial(partial) => partial.items,
+                };
+                let applied = this.update(&mut cx, |this, cx| {
+                    for report in reports {
+                        let (uri, version, report) = match report {
+                            lsp::WorkspaceDocumentDiagnosticReport::Full(report) => (
+                                report.uri,
+                                report.version,
+                                lsp::DocumentDiagnosticReportKind::Full(
+                                    report.full_document_diagnostic_report,
+                                ),
+                            ),
+                            lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => (
+                                report.uri,
+                                report.version,
+                                lsp::DocumentDiagnosticReportKind::Unchanged(
+                                    report.unchanged_document_diagnostic_report,
+                                ),
+                            ),
+                        };
+                        let version = version.map(|version| version as i32);
+                        this.apply_pulled_diagnostics(
+                            server_id, &adapter, uri, version, report, cx,
+                        );
+                    }
+                });
+                if applied.is_err() {
+                    return;
+                }
+                // Don't flood servers that answer right away instead of holding the request open.
+                cx.background_executor()
+                    .timer(WORKSPACE_DIAGNOSTICS_PULL_INTERVAL)
+                    .await;
+            }
+        });
+        if let Some(local) = self.as_local_mut() {
+            local.workspace_diagnostics_tasks.insert(server_id, task);
+        }
+    }
+
+    fn request_workspace_diagnostics(
+        &self,
+        server_id: LanguageServerId,
+    ) -> Option<(
+        Arc<CachedLspAdapter>,
+        impl Future<Output = Result<lsp::WorkspaceDiagnosticReportResult>>,
+    )> {
+        let local = self.as_local()?;
+        let LanguageServerState::Running {
+            adapter, server, ..
+        } = local.language_servers.get(&server_id)?
+        else {
+            return None;
+        };
+        let options = diagnostic_options(server)?;
+        let previous_result_ids = local
+            .diagnostic_result_ids
+            .get(&server_id)
+            .into_iter()
+            .flatten()
+            .map(|(uri, result_id)| lsp::PreviousResultId {
+                uri: uri.clone(),
+                value: result_id.clone(),
+            })
+            .collect();
+        let request = server.request::<lsp::request::WorkspaceDiagnosticRequest>(
+            lsp::WorkspaceDiagnosticParams {
+                identifier: options.identifier,
+                previous_result_ids,
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            },
+        );
+        Some((adapter.clone(), request))
+    }
+
+    /// Pulls the diagnostics of every open buffer and of the workspace again,
+    /// when the language server asks for it, e.g. after a configuration change.
+    fn refresh_pulled_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let buffers = self.buffer_store.read(cx).buffers().collect::<Vec<_>>();
+        for buffer in buffers {
+            let uses_server = self
+                .language_servers_for_buffer(buffer.read(cx), cx)
+                .any(|(_, server)| server.server_id() == server_id);
+            if uses_server {
+                self.pull_diagnostics(buffer, cx).detach_and_log_err(cx);
+            }
+        }
+        self.pull_workspace_diagnostics(server_id, cx);
+    }
+
+    fn apply_pulled_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        adapter: &CachedLspAdapter,
+        uri: lsp::Url,
+        version: Option<i32>,
+        report: lsp::DocumentDiagnosticReportKind,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let result_ids = local.diagnostic_result_ids.entry(server_id).or_default();
+        match report {
+            lsp::DocumentDiagnosticReportKind::Full(report) => {
+                match report.result_id {
+                    Some(result_id) => result_ids.insert(uri.clone(), result_id),
+                    None => result_ids.remove(&uri),
+                };
+                let mut params = lsp::PublishDiagnosticsParams {
+                    uri,
+                    diagnostics: report.items,
+                    version,
+                };
+                adapter.process_diagnostics(&mut params);
+                self.update_diagnostics(
+                    server_id,
+                    params,
+                    &adapter.disk_based_diagnostic_sources,
+                    cx,
+                )
+                .log_err();
+            }
+            // The diagnostics from the previous report are still valid.
+            lsp::DocumentDiagnosticReportKind::Unchanged(report) => {
+                result_ids.insert(uri, report.result_id);
+            }
+        }
+    }
+
     pub fn update_diagnostics(
         &mut self,
         language_server_id: LanguageServerId,
@@ -6569,6 +7129,7 @@ impl LspStore {
                 });
             }
         });
+        self.refresh_pulled_diagnostics(server_id, cx);
 
         cx.notify();
     }
@@ -7387,6 +7948,14 @@ struct LspBufferSnapshot {
     snapshot: TextBufferSnapshot,
 }
 
+/// The last semantic tokens response of a language server for a buffer,
+/// used to request only the changes since then.
+struct LspSemanticTokens {
+    server_id: LanguageServerId,
+    result_id: Option<String>,
+    data: Vec<lsp::SemanticToken>,
+}
+
 /// A prompt requested by LSP server.
 #[derive(Clone, Debug)]
 pub struct LanguageServerPromptRequest {
@@ -7538,6 +8107,118 @@ impl DiagnosticSummary {
     }
 }
 
+fn apply_semantic_tokens_edits(
+    data: &mut Vec<lsp::SemanticToken>,
+    mut edits: Vec<lsp::SemanticTokensEdit>,
+) {
+    fn encode(tokens: &[lsp::SemanticToken]) -> impl Iterator<Item = u32> + '_ {
+        tokens.iter().flat_map(|token| {
+            [
+                token.delta_line,
+                token.delta_start,
+                token.length,
+                token.token_type,
+                token.token_modifiers_bitset,
+            ]
+        })
+    }
+
+    // Edits refer to the integers of the encoded tokens, five per token, and may start or end
+    // in the middle of a token. They are applied from the last one, so that the offsets of the
+    // remaining ones stay valid.
+    let mut integers = encode(data).collect::<Vec<_>>();
+    edits.sort_unstable_by_key(|edit| edit.start);
+    for edit in edits.into_iter().rev() {
+        let start = (edit.start as usize).min(integers.len());
+        let end = (start + edit.delete_count as usize).min(integers.len());
+        let inserted = edit.data.unwrap_or_default();
+        integers.splice(start..end, encode(&inserted));
+    }
+    *data = integers
+        .chunks_exact(5)
+        .map(|token| lsp::SemanticToken {
+            delta_line: token[0],
+            delta_start: token[1],
+            length: token[2],
+            token_type: token[3],
+            token_modifiers_bitset: token[4],
+        })
+        .collect();
+}
+
+fn semantic_tokens_from_lsp(
+    snapshot: &TextBufferSnapshot,
+    legend: &lsp::SemanticTokensLegend,
+    data: &[lsp::SemanticToken],
+) -> Arc<[language::SemanticToken]> {
+    let mut highlight_names = HashMap::<(u32, u32), Option<Arc<str>>>::default();
+    let mut line = 0;
+    let mut start_column = 0;
+    data.iter()
+        .filter_map(|token| {
+            if token.delta_line == 0 {
+                start_column += token.delta_start;
+            } else {
+                line += token.delta_line;
+                start_column = token.delta_start;
+            }
+
+            let highlight_name = highlight_names
+                .entry((token.token_type, token.token_modifiers_bitset))
+                .or_insert_with(|| {
+                    let token_type = legend.token_types.get(token.token_type as usize)?;
+                    let modifiers = legend
+                        .token_modifiers
+                        .iter()
+                        .enumerate()
+                        .filter(|(ix, _)| *ix < 32 && token.token_modifiers_bitset & (1 << ix) != 0)
+                        .map(|(_, modifier)| modifier.as_str());
+                    Some(
+                        language::semantic_token_highlight_name(token_type.as_str(), modifiers)
+                            .into(),
+                    )
+                })
+                .clone()?;
+            let start = snapshot
+                .clip_point_utf16(Unclipped(PointUtf16::new(line, start_column)), Bias::Left);
+            let end = snapshot.clip_point_utf16(
+                Unclipped(PointUtf16::new(line, start_column + token.length)),
+                Bias::Right,
+            );
+            if start == end {
+                return None;
+            }
+            Some(language::SemanticToken {
+                range: snapshot.anchor_after(start)..snapshot.anchor_before(end),
+                highlight_name,
+            })
+        })
+        .collect()
+}
+
+fn serialize_semantic_token(token: &language::SemanticToken) -> proto::SemanticToken {
+    proto::SemanticToken {
+        start: Some(serialize_anchor(&token.range.start)),
+        end: Some(serialize_anchor(&token.range.end)),
+        highlight_name: token.highlight_name.to_string(),
+    }
+}
+
+fn deserialize_semantic_token(token: proto::SemanticToken) -> Result<language::SemanticToken> {
+    let start = token
+        .start
+        .and_then(deserialize_anchor)
+        .context("invalid semantic token start")?;
+    let end = token
+        .end
+        .and_then(deserialize_anchor)
+        .context("invalid semantic token end")?;
+    Ok(language::SemanticToken {
+        range: start..end,
+        highlight_name: token.highlight_name.into(),
+    })
+}
+
 fn glob_literal_prefix(glob: &str) -> &str {
     let is_absolute = glob.starts_with(path::MAIN_SEPARATOR);
 
@@ -7948,6 +8629,15 @@ fn include_text(server: &lsp::LanguageServer) -> Option<bool> {
     }
 }
 
+fn diagnostic_options(server: &lsp::LanguageServer) -> Option<lsp::DiagnosticOptions> {
+    match server.capabilities().diagnostic_provider? {
+        lsp::DiagnosticServerCapabilities::Options(options) => Some(options),
+        lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
+            Some(options.diagnostic_options)
+        }
+    }
+}
+
 #[cfg(test)]
 #[test]
 fn test_glob_literal_prefix() {
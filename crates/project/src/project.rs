@@ -38,7 +38,14 @@ use futures::{
     StreamExt,
 };
 
-use git::{blame::Blame, repository::GitRepository};
+use git::{
+    blame::Blame,
+    commit::{CommitDetails, CommitLog},
+    conflict::ConflictStages,
+    repository::{GitRepository, Stash},
+    status::FileStatus,
+    Oid,
+};
 use gpui::{
     AnyModel, AppContext, AsyncAppContext, BorrowAppContext, Context, EventEmitter, Hsla, Model,
     ModelContext, SharedString, Task, WeakModel, WindowContext,
@@ -77,7 +84,7 @@ use std::{
 use task_store::TaskStore;
 use terminals::Terminals;
 use text::{Anchor, BufferId};
-use util::{paths::compare_paths, ResultExt as _};
+use util::{paths::compare_paths, post_inc, ResultExt as _};
 use worktree::{CreatedEntry, Snapshot, Traversal};
 use worktree_store::{WorktreeStore, WorktreeStoreEvent};
 
@@ -148,6 +155,9 @@ pub struct Project {
     _subscriptions: Vec<gpui::Subscription>,
     buffers_needing_diff: HashSet<WeakModel<Buffer>>,
     git_diff_debouncer: DebouncedDelay<Self>,
+    /// The fetches, pulls and pushes in progress, with their descriptions.
+    pending_git_operations: Vec<(usize, SharedString)>,
+    next_git_operation_id: usize,
     remotely_created_models: Arc<Mutex<RemotelyCreatedModels>>,
     terminals: Terminals,
     node: Option<NodeRuntime>,
@@ -263,6 +273,7 @@ pub enum Event {
     Reshared,
     Rejoined,
     RefreshInlayHints,
+    RefreshSemanticTokens,
     RevealInProjectPanel(ProjectEntryId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
 }
@@ -377,6 +388,40 @@ pub struct CodeAction {
     pub lsp_action: lsp::CodeAction,
 }
 
+/// A code lens provided by a language server, such as a reference count
+/// or a command to run a test, shown above the line it refers to.
+#[derive(Clone, Debug)]
+pub struct CodeLens {
+    /// The id of the language server that produced this code lens.
+    pub server_id: LanguageServerId,
+    /// The range of the buffer this code lens refers to.
+    pub range: Range<Anchor>,
+    /// The raw code lens provided by the language server.
+    pub lsp_lens: lsp::CodeLens,
+    /// Whether the language server can execute the command of this code lens.
+    /// Commands meant to be handled by the client, such as showing the references, are not run.
+    pub executable: bool,
+}
+
+impl CodeLens {
+    /// The code action running the command of this code lens, if the language server can execute it.
+    pub fn code_action(&self) -> Option<CodeAction> {
+        if !self.executable {
+            return None;
+        }
+        let command = self.lsp_lens.command.clone()?;
+        Some(CodeAction {
+            server_id: self.server_id,
+            range: self.range.clone(),
+            lsp_action: lsp::CodeAction {
+                title: command.title.clone(),
+                command: Some(command),
+                ..lsp::CodeAction::default()
+            },
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ResolveState {
     Resolved,
@@ -430,6 +475,39 @@ pub struct LocationLink {
     pub target: Location,
 }
 
+/// A symbol that calls or is called by other symbols, as reported by a language server.
+#[derive(Clone, Debug)]
+pub struct CallHierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The identifier of the symbol, e.g. the name of a function.
+    pub location: Location,
+    /// The item as reported by the language server, needed to query its calls.
+    pub lsp_item: lsp::CallHierarchyItem,
+}
+
+/// Calls between the requested [`CallHierarchyItem`] and another item.
+#[derive(Clone, Debug)]
+pub struct CallHierarchyCall {
+    /// The caller for incoming calls, or the callee for outgoing calls.
+    pub item: CallHierarchyItem,
+    /// The ranges of the calls, always located in the calling item.
+    pub call_sites: Vec<Location>,
+}
+
+/// A type that has supertypes or subtypes, as reported by a language server.
+#[derive(Clone, Debug)]
+pub struct TypeHierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The identifier of the type, e.g. the name of a trait.
+    pub location: Location,
+    /// The item as reported by the language server, needed to query its supertypes and subtypes.
+    pub lsp_item: lsp::TypeHierarchyItem,
+}
+
 #[derive(Debug)]
 pub struct DocumentHighlight {
     pub range: Range<language::Anchor>,
@@ -666,8 +744,11 @@ impl Project {
                 ssh_client: None,
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
+                pending_git_operations: Vec::new(),
+                next_git_operation_id: 0,
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostics: HashMap::default(),
                 },
                 node: Some(node),
                 hosted_project_id: None,
@@ -790,8 +871,11 @@ impl Project {
                 ssh_client: Some(ssh.clone()),
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
+                pending_git_operations: Vec::new(),
+                next_git_operation_id: 0,
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostics: HashMap::default(),
                 },
                 node: Some(node),
                 hosted_project_id: None,
@@ -996,8 +1080,11 @@ impl Project {
                 },
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
+                pending_git_operations: Vec::new(),
+                next_git_operation_id: 0,
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostics: HashMap::default(),
                 },
                 node: None,
                 hosted_project_id: None,
@@ -1223,6 +1310,10 @@ impl Project {
         project
     }
 
+    pub fn buffer_store(&self) -> Model<BufferStore> {
+        self.buffer_store.clone()
+    }
+
     pub fn lsp_store(&self) -> Model<LspStore> {
         self.lsp_store.clone()
     }
@@ -2120,6 +2211,7 @@ impl Project {
                 };
             }
             LspStoreEvent::RefreshInlayHints => cx.emit(Event::RefreshInlayHints),
+            LspStoreEvent::RefreshSemanticTokens => cx.emit(Event::RefreshSemanticTokens),
             LspStoreEvent::LanguageServerPrompt(prompt) => {
                 cx.emit(Event::LanguageServerPrompt(prompt.clone()))
             }
@@ -2619,6 +2711,88 @@ impl Project {
         )
     }
 
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            PrepareCallHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn incoming_calls(
+        &mut self,
+        item: &CallHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetIncomingCalls { item: item.clone() },
+            cx,
+        )
+    }
+
+    pub fn outgoing_calls(
+        &mut self,
+        item: &CallHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetOutgoingCalls { item: item.clone() },
+            cx,
+        )
+    }
+
+    pub fn prepare_type_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            PrepareTypeHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn supertypes(
+        &mut self,
+        item: &TypeHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetSupertypes { item: item.clone() },
+            cx,
+        )
+    }
+
+    pub fn subtypes(
+        &mut self,
+        item: &TypeHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetSubtypes { item: item.clone() },
+            cx,
+        )
+    }
+
     fn document_highlights_impl(
         &mut self,
         buffer: &Model<Buffer>,
@@ -2804,6 +2978,19 @@ impl Project {
         })
     }
 
+    pub fn code_lens(
+        &mut self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CodeLens>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetCodeLens,
+            cx,
+        )
+    }
+
     fn prepare_rename_impl(
         &mut self,
         buffer: Model<Buffer>,
@@ -2897,6 +3084,16 @@ impl Project {
         })
     }
 
+    pub fn semantic_tokens(
+        &mut self,
+        buffer_handle: Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.semantic_tokens(buffer_handle, cx)
+        })
+    }
+
     pub fn search(
         &mut self,
         query: SearchQuery,
@@ -3458,6 +3655,167 @@ impl Project {
         worktree.get_local_repo(&root_entry)?.repo().clone().into()
     }
 
+    pub fn stage_paths(&self, paths: Vec<ProjectPath>, cx: &AppContext) -> Task<Result<()>> {
+        self.worktree_store.read(cx).stage_paths(paths, cx)
+    }
+
+    pub fn unstage_paths(&self, paths: Vec<ProjectPath>, cx: &AppContext) -> Task<Result<()>> {
+        self.worktree_store.read(cx).unstage_paths(paths, cx)
+    }
+
+    pub fn set_index_text(
+        &self,
+        path: ProjectPath,
+        text: Option<String>,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        self.worktree_store.read(cx).set_index_text(path, text, cx)
+    }
+
+    pub fn load_committed_text(
+        &self,
+        path: ProjectPath,
+        cx: &AppContext,
+    ) -> Task<Result<Option<String>>> {
+        self.worktree_store.read(cx).load_committed_text(path, cx)
+    }
+
+    pub fn commit(
+        &self,
+        path: ProjectPath,
+        message: String,
+        amend: bool,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        self.worktree_store
+            .read(cx)
+            .commit(path, message, amend, cx)
+    }
+
+    pub fn head_commit_message(
+        &self,
+        path: ProjectPath,
+        cx: &AppContext,
+    ) -> Task<Result<Option<String>>> {
+        self.worktree_store.read(cx).head_commit_message(path, cx)
+    }
+
+    pub fn git_log(
+        &self,
+        path: ProjectPath,
+        filter_by_path: bool,
+        skip: usize,
+        limit: usize,
+        cx: &AppContext,
+    ) -> Task<Result<CommitLog>> {
+        self.worktree_store
+            .read(cx)
+            .git_log(path, filter_by_path, skip, limit, cx)
+    }
+
+    pub fn commit_details(
+        &self,
+        path: ProjectPath,
+        sha: Oid,
+        file_offset: usize,
+        file_limit: usize,
+        cx: &AppContext,
+    ) -> Task<Result<CommitDetails>> {
+        self.worktree_store
+            .read(cx)
+            .commit_details(path, sha, file_offset, file_limit, cx)
+    }
+
+    pub fn load_conflict_stages(
+        &self,
+        path: ProjectPath,
+        cx: &AppContext,
+    ) -> Task<Result<Option<ConflictStages>>> {
+        self.worktree_store.read(cx).load_conflict_stages(path, cx)
+    }
+
+    pub fn load_revision_text(
+        &self,
+        path: ProjectPath,
+        revision: String,
+        cx: &AppContext,
+    ) -> Task<Result<Option<String>>> {
+        self.worktree_store
+            .read(cx)
+            .load_revision_text(path, revision, cx)
+    }
+
+    pub fn stash_list(&self, path: ProjectPath, cx: &AppContext) -> Task<Result<Vec<Stash>>> {
+        self.worktree_store.read(cx).stash_list(path, cx)
+    }
+
+    pub fn stash_push(
+        &self,
+        path: ProjectPath,
+        message: Option<String>,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        self.worktree_store.read(cx).stash_push(path, message, cx)
+    }
+
+    pub fn stash_pop(&self, path: ProjectPath, index: usize, cx: &AppContext) -> Task<Result<()>> {
+        self.worktree_store.read(cx).stash_pop(path, index, cx)
+    }
+
+    pub fn stash_drop(&self, path: ProjectPath, index: usize, cx: &AppContext) -> Task<Result<()>> {
+        self.worktree_store.read(cx).stash_drop(path, index, cx)
+    }
+
+    pub fn fetch(&mut self, path: ProjectPath, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        let fetch = self.worktree_store.read(cx).fetch(path, cx);
+        self.track_git_operation("Fetching from remote…", fetch, cx)
+    }
+
+    pub fn pull(&mut self, path: ProjectPath, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        let pull = self.worktree_store.read(cx).pull(path, cx);
+        self.track_git_operation("Pulling from upstream…", pull, cx)
+    }
+
+    pub fn push(&mut self, path: ProjectPath, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        let push = self.worktree_store.read(cx).push(path, cx);
+        self.track_git_operation("Pushing to upstream…", push, cx)
+    }
+
+    /// Returns the descriptions of the fetches, pulls and pushes in progress, oldest first.
+    pub fn pending_git_operations(&self) -> impl Iterator<Item = &SharedString> {
+        self.pending_git_operations
+            .iter()
+            .map(|(_, description)| description)
+    }
+
+    fn track_git_operation(
+        &mut self,
+        description: &'static str,
+        operation: Task<Result<()>>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let id = post_inc(&mut self.next_git_operation_id);
+        self.pending_git_operations.push((id, description.into()));
+        cx.notify();
+        cx.spawn(|this, mut cx| async move {
+            let result = operation.await;
+            this.update(&mut cx, |this, cx| {
+                this.pending_git_operations
+                    .retain(|(operation_id, _)| *operation_id != id);
+                cx.notify();
+            })?;
+            result
+        })
+    }
+
+    pub fn git_status(
+        &self,
+        worktree_id: WorktreeId,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<(Arc<Path>, FileStatus)>>> {
+        self.worktree_store.read(cx).git_status(worktree_id, cx)
+    }
+
     pub fn blame_buffer(
         &self,
         buffer: &Model<Buffer>,
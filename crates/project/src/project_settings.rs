@@ -4,18 +4,18 @@ use fs::Fs;
 use gpui::{AppContext, AsyncAppContext, BorrowAppContext, EventEmitter, Model, ModelContext};
 use language::LanguageServerName;
 use paths::{
-    local_settings_file_relative_path, local_tasks_file_relative_path,
-    local_vscode_tasks_file_relative_path,
+    local_editorconfig_file_relative_path, local_settings_file_relative_path,
+    local_tasks_file_relative_path, local_vscode_tasks_file_relative_path,
 };
 use rpc::{proto, AnyProtoClient, TypedEnvelope};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{
-    parse_json_with_comments, InvalidSettingsError, LocalSettingsKind, Settings, SettingsLocation,
-    SettingsSources, SettingsStore,
+    parse_json_with_comments, Editorconfig, InvalidSettingsError, LocalSettingsKind, Settings,
+    SettingsLocation, SettingsSources, SettingsStore,
 };
 use std::{
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
     sync::Arc,
     time::Duration,
 };
@@ -406,10 +406,64 @@ impl SettingsObserver {
                     this.update_local_worktree_settings(&worktree, changes, cx)
                 }
             })
-            .detach()
+            .detach();
+            self.load_editorconfigs_above_worktree(worktree, cx);
         }
     }
 
+    /// Loads the `.editorconfig` files in the directories above the worktree root, up to the
+    /// first one with `root = true`.
+    fn load_editorconfigs_above_worktree(
+        &mut self,
+        worktree: &Model<Worktree>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let SettingsObserverMode::Local(fs) = &self.mode else {
+            return;
+        };
+        if !worktree.read(cx).is_local() {
+            return;
+        }
+
+        let fs = fs.clone();
+        let worktree = worktree.clone();
+        let root_abs_path = worktree.read(cx).abs_path();
+        cx.spawn(move |this, mut cx| async move {
+            let mut settings_contents = Vec::new();
+            for (parent_count, directory) in root_abs_path.ancestors().enumerate().skip(1) {
+                let Ok(content) = fs
+                    .load(&directory.join(local_editorconfig_file_relative_path()))
+                    .await
+                else {
+                    continue;
+                };
+                let is_root = Editorconfig::parse(&content).is_root();
+                // Stored relative to the worktree root, as `..` components followed by the path
+                // back down to the root.
+                let settings_dir = std::iter::repeat(Component::ParentDir)
+                    .take(parent_count)
+                    .collect::<PathBuf>()
+                    .join(root_abs_path.strip_prefix(directory).unwrap());
+                settings_contents.push((
+                    Arc::<Path>::from(settings_dir),
+                    LocalSettingsKind::Editorconfig,
+                    Some(content),
+                ));
+                if is_root {
+                    break;
+                }
+            }
+
+            if settings_contents.is_empty() {
+                return Ok(());
+            }
+            this.update(&mut cx, |this, cx| {
+                this.update_settings(worktree, settings_contents, cx)
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn update_local_worktree_settings(
         &mut self,
         worktree: &Model<Worktree>,
@@ -453,6 +507,13 @@ impl SettingsObserver {
                         .unwrap(),
                 );
                 (settings_dir, LocalSettingsKind::Tasks)
+            } else if path.ends_with(local_editorconfig_file_relative_path()) {
+                let settings_dir = Arc::<Path>::from(
+                    path.ancestors()
+                        .nth(local_editorconfig_file_relative_path().components().count())
+                        .unwrap(),
+                );
+                (settings_dir, LocalSettingsKind::Editorconfig)
             } else {
                 continue;
             };
@@ -1,10 +1,13 @@
 use crate::{Event, *};
 use fs::FakeFs;
 use futures::{future, StreamExt};
+use git::repository::AheadBehind;
 use gpui::{AppContext, SemanticVersion, UpdateGlobal};
 use http_client::Url;
 use language::{
-    language_settings::{language_settings, AllLanguageSettings, LanguageSettingsContent},
+    language_settings::{
+        language_settings, AllLanguageSettings, LanguageSettingsContent, LineEndingSetting,
+    },
     tree_sitter_rust, tree_sitter_typescript, Diagnostic, DiagnosticSet, FakeLspAdapter,
     LanguageConfig, LanguageMatcher, LanguageName, LineEnding, OffsetRangeExt, Point, ToPoint,
 };
@@ -15,8 +18,13 @@ use serde_json::json;
 #[cfg(not(windows))]
 use std::os;
 
-use std::{mem, ops::Range, task::Poll};
-use task::{ResolvedTask, TaskContext};
+use std::{
+    mem,
+    ops::Range,
+    sync::atomic::{AtomicUsize, Ordering::SeqCst},
+    task::Poll,
+};
+use task::{FileLocation, Problem, ProblemSeverity, ResolvedTask, TaskContext, TaskId};
 use unindent::Unindent as _;
 use util::{assert_set_eq, paths::PathMatcher, test::temp_tree, TryFutureExt as _};
 
@@ -295,6 +303,121 @@ async fn test_managing_project_specific_settings(cx: &mut gpui::TestAppContext)
     );
 }
 
+#[gpui::test]
+async fn test_editorconfig_settings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/the-root",
+        json!({
+            ".editorconfig": r#"
+                root = true
+
+                [*]
+                indent_style = space
+                indent_size = 3
+                end_of_line = crlf
+
+                [*.md]
+                trim_trailing_whitespace = false
+                max_line_length = 120
+            "#.unindent(),
+            "a": {
+                "a.rs": "fn a() {\n   A\n}",
+                "README.md": "# A",
+            },
+            "b": {
+                ".editorconfig": "[*.rs]\nindent_style = tab\n",
+                ".zed": {
+                    "settings.json": r#"{ "languages": { "Rust": { "hard_tabs": false } }, "tab_size": 2 }"#,
+                },
+                "b.rs": "fn b() {\n  B\n}",
+            }
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+    let worktree = project.update(cx, |project, cx| project.worktrees(cx).next().unwrap());
+    let rust = rust_lang();
+
+    cx.executor().run_until_parked();
+    cx.update(|cx| {
+        let tree = worktree.read(cx);
+        let settings_for = |path: &str, language: Option<&Arc<Language>>| {
+            let file =
+                File::for_entry(tree.entry_for_path(path).unwrap().clone(), worktree.clone());
+            language_settings(language, Some(&(file as _)), cx).into_owned()
+        };
+
+        let settings_a = settings_for("a/a.rs", Some(&rust));
+        assert!(!settings_a.hard_tabs);
+        assert_eq!(settings_a.tab_size.get(), 3);
+        assert_eq!(settings_a.line_ending, LineEndingSetting::Crlf);
+        assert!(settings_a.remove_trailing_whitespace_on_save);
+
+        let settings_readme = settings_for("a/README.md", None);
+        assert!(!settings_readme.remove_trailing_whitespace_on_save);
+        assert_eq!(settings_readme.preferred_line_length, 120);
+
+        // Settings in `b/.zed/settings.json` take precedence over the `.editorconfig` files in
+        // the same directory and above it.
+        let settings_b = settings_for("b/b.rs", Some(&rust));
+        assert!(!settings_b.hard_tabs);
+        assert_eq!(settings_b.tab_size.get(), 2);
+        assert_eq!(settings_b.line_ending, LineEndingSetting::Crlf);
+    });
+}
+
+#[gpui::test]
+async fn test_editorconfig_settings_above_worktree(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".editorconfig": "[*]\nindent_size = 7\n",
+            "repo": {
+                ".editorconfig": "root = true\n\n[*]\nindent_style = tab\n\n[project/*.md]\nmax_line_length = 90\n",
+                "project": {
+                    ".editorconfig": "[*.rs]\nindent_size = 3\n",
+                    "README.md": "# Project",
+                    "src": {
+                        "main.rs": "fn main() {}",
+                    },
+                },
+            },
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir/repo/project".as_ref()], cx).await;
+    let worktree = project.update(cx, |project, cx| project.worktrees(cx).next().unwrap());
+    let rust = rust_lang();
+
+    cx.executor().run_until_parked();
+    cx.update(|cx| {
+        let tree = worktree.read(cx);
+        let settings_for = |path: &str, language: Option<&Arc<Language>>| {
+            let file =
+                File::for_entry(tree.entry_for_path(path).unwrap().clone(), worktree.clone());
+            language_settings(language, Some(&(file as _)), cx).into_owned()
+        };
+
+        // The file in `/dir` is ignored, as `/dir/repo/.editorconfig` is a root file.
+        let settings_main = settings_for("src/main.rs", Some(&rust));
+        assert!(settings_main.hard_tabs);
+        assert_eq!(settings_main.tab_size.get(), 3);
+
+        // Globs in files above the worktree root match paths relative to their directory.
+        let settings_readme = settings_for("README.md", None);
+        assert!(settings_readme.hard_tabs);
+        assert_eq!(settings_readme.preferred_line_length, 90);
+    });
+}
+
 #[gpui::test]
 async fn test_managing_language_servers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2025,6 +2148,129 @@ async fn test_diagnostics_from_multiple_language_servers(cx: &mut gpui::TestAppC
     });
 }
 
+#[gpui::test]
+async fn test_task_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn a() {}\n",
+            "b.rs": "fn b() {}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let task_id = TaskId("cargo check".to_string());
+    let problem = |path: &str, severity| Problem {
+        path: PathBuf::from(path),
+        file_location: FileLocation::Relative,
+        line: 1,
+        column: Some(4),
+        end_line: None,
+        end_column: None,
+        severity,
+        code: None,
+        message: format!("problem in {path}"),
+        source: None,
+    };
+    let summary = |project: &Project, cx: &AppContext| {
+        let mut summaries = project
+            .diagnostic_summaries(false, cx)
+            .map(|(path, _, summary)| (path.path.to_string_lossy().into_owned(), summary))
+            .collect::<Vec<_>>();
+        summaries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        summaries
+    };
+
+    project.update(cx, |project, cx| {
+        project.publish_task_diagnostics(
+            &task_id,
+            "cargo check",
+            Some(Path::new("/dir")),
+            vec![
+                problem("a.rs", ProblemSeverity::Error),
+                problem("b.rs", ProblemSeverity::Warning),
+            ],
+            cx,
+        );
+        assert_eq!(
+            summary(project, cx),
+            [
+                (
+                    "a.rs".to_string(),
+                    DiagnosticSummary {
+                        error_count: 1,
+                        warning_count: 0,
+                    }
+                ),
+                (
+                    "b.rs".to_string(),
+                    DiagnosticSummary {
+                        error_count: 0,
+                        warning_count: 1,
+                    }
+                ),
+            ]
+        );
+    });
+
+    // A rescan of the output replaces the previous problems, clearing the fixed files.
+    project.update(cx, |project, cx| {
+        project.publish_task_diagnostics(
+            &task_id,
+            "cargo check",
+            Some(Path::new("/dir")),
+            vec![problem("b.rs", ProblemSeverity::Error)],
+            cx,
+        );
+        assert_eq!(
+            summary(project, cx),
+            [(
+                "b.rs".to_string(),
+                DiagnosticSummary {
+                    error_count: 1,
+                    warning_count: 0,
+                }
+            )]
+        );
+    });
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/b.rs", cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        let diagnostics = buffer
+            .snapshot()
+            .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+            .map(|entry| {
+                (
+                    entry.range,
+                    entry.diagnostic.message,
+                    entry.diagnostic.source,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            diagnostics,
+            [(
+                Point::new(0, 3)..Point::new(0, 4),
+                "problem in b.rs".to_string(),
+                Some("cargo check".to_string())
+            )]
+        );
+    });
+
+    // Rerunning the task clears the diagnostics of its previous run.
+    project.update(cx, |project, cx| {
+        project.clear_task_diagnostics(&task_id, cx);
+        assert_eq!(summary(project, cx), []);
+    });
+}
+
 #[gpui::test]
 async fn test_edits_from_lsp2_with_past_version(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2504,14 +2750,15 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
 }
 
 #[gpui::test]
-async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
     init_test(cx);
 
     let fs = FakeFs::new(cx.executor());
     fs.insert_tree(
         "/dir",
         json!({
-            "a.ts": "",
+            "a.rs": "fn a() { b() }",
+            "b.rs": "fn b() {}",
         }),
     )
     .await;
@@ -2519,91 +2766,108 @@ async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
     let project = Project::test(fs, ["/dir".as_ref()], cx).await;
 
     let language_registry = project.read_with(cx, |project, _| project.languages().clone());
-    language_registry.add(typescript_lang());
-    let mut fake_language_servers = language_registry.register_fake_lsp(
-        "TypeScript",
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
         FakeLspAdapter {
             capabilities: lsp::ServerCapabilities {
-                completion_provider: Some(lsp::CompletionOptions {
-                    trigger_characters: Some(vec![":".to_string()]),
-                    ..Default::default()
-                }),
-                ..Default::default()
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..lsp::ServerCapabilities::default()
             },
-            ..Default::default()
+            ..FakeLspAdapter::default()
         },
     );
 
     let buffer = project
-        .update(cx, |p, cx| p.open_local_buffer("/dir/a.ts", cx))
+        .update(cx, |project, cx| project.open_local_buffer("/dir/b.rs", cx))
         .await
         .unwrap();
 
-    let fake_server = fake_language_servers.next().await.unwrap();
-
-    let text = "let a = b.fqn";
-    buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
-    let completions = project.update(cx, |project, cx| {
-        project.completions(&buffer, text.len(), DEFAULT_COMPLETION_CONTEXT, cx)
+    let fake_server = fake_servers.next().await.unwrap();
+    let lsp_item = |path: &str, name: &str, range: lsp::Range| lsp::CallHierarchyItem {
+        name: name.to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: None,
+        uri: lsp::Url::from_file_path(path).unwrap(),
+        range,
+        selection_range: range,
+        data: None,
+    };
+    // Both functions are named at the same columns of their files.
+    let name_range = lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4));
+    let b_item = lsp_item("/dir/b.rs", "b", name_range);
+    let a_item = lsp_item("/dir/a.rs", "a", name_range);
+    fake_server.handle_request::<lsp::request::CallHierarchyPrepare, _, _>(move |params, _| {
+        let b_item = b_item.clone();
+        async move {
+            assert_eq!(
+                params.text_document_position_params.position,
+                lsp::Position::new(0, 3)
+            );
+            Ok(Some(vec![b_item]))
+        }
     });
+    fake_server.handle_request::<lsp::request::CallHierarchyIncomingCalls, _, _>(
+        move |params, _| {
+            let a_item = a_item.clone();
+            async move {
+                assert_eq!(params.item.name, "b");
+                Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                    from: a_item,
+                    from_ranges: vec![lsp::Range::new(
+                        lsp::Position::new(0, 9),
+                        lsp::Position::new(0, 10),
+                    )],
+                }]))
+            }
+        },
+    );
 
-    fake_server
-        .handle_request::<lsp::request::Completion, _, _>(|_, _| async move {
-            Ok(Some(lsp::CompletionResponse::Array(vec![
-                lsp::CompletionItem {
-                    label: "fullyQualifiedName?".into(),
-                    insert_text: Some("fullyQualifiedName".into()),
-                    ..Default::default()
-                },
-            ])))
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, 3, cx)
         })
-        .next()
-        .await;
-    let completions = completions.await.unwrap();
-    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
-    assert_eq!(completions.len(), 1);
-    assert_eq!(completions[0].new_text, "fullyQualifiedName");
-    assert_eq!(
-        completions[0].old_range.to_offset(&snapshot),
-        text.len() - 3..text.len()
-    );
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "b");
 
-    let text = "let a = \"atoms/cmp\"";
-    buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
-    let completions = project.update(cx, |project, cx| {
-        project.completions(&buffer, text.len() - 1, DEFAULT_COMPLETION_CONTEXT, cx)
+    let calls = project
+        .update(cx, |project, cx| project.incoming_calls(&items[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(calls.len(), 1);
+    cx.update(|cx| {
+        let call = &calls[0];
+        assert_eq!(call.item.name, "a");
+        let caller_buffer = call.item.location.buffer.read(cx);
+        assert_eq!(
+            caller_buffer
+                .file()
+                .unwrap()
+                .as_local()
+                .unwrap()
+                .abs_path(cx),
+            Path::new("/dir/a.rs"),
+        );
+        assert_eq!(call.item.location.range.to_offset(caller_buffer), 3..4);
+        assert_eq!(call.call_sites.len(), 1);
+        assert_eq!(call.call_sites[0].buffer, call.item.location.buffer);
+        assert_eq!(call.call_sites[0].range.to_offset(caller_buffer), 9..10);
     });
-
-    fake_server
-        .handle_request::<lsp::request::Completion, _, _>(|_, _| async move {
-            Ok(Some(lsp::CompletionResponse::Array(vec![
-                lsp::CompletionItem {
-                    label: "component".into(),
-                    ..Default::default()
-                },
-            ])))
-        })
-        .next()
-        .await;
-    let completions = completions.await.unwrap();
-    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
-    assert_eq!(completions.len(), 1);
-    assert_eq!(completions[0].new_text, "component");
-    assert_eq!(
-        completions[0].old_range.to_offset(&snapshot),
-        text.len() - 4..text.len() - 1
-    );
 }
 
 #[gpui::test]
-async fn test_completions_with_carriage_returns(cx: &mut gpui::TestAppContext) {
+async fn test_type_hierarchy(cx: &mut gpui::TestAppContext) {
     init_test(cx);
 
     let fs = FakeFs::new(cx.executor());
     fs.insert_tree(
         "/dir",
         json!({
-            "a.ts": "",
+            "a.rs": "trait A {}",
+            "b.rs": "struct B; impl A for B {}",
         }),
     )
     .await;
@@ -2611,53 +2875,851 @@ async fn test_completions_with_carriage_returns(cx: &mut gpui::TestAppContext) {
     let project = Project::test(fs, ["/dir".as_ref()], cx).await;
 
     let language_registry = project.read_with(cx, |project, _| project.languages().clone());
-    language_registry.add(typescript_lang());
-    let mut fake_language_servers = language_registry.register_fake_lsp(
-        "TypeScript",
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
         FakeLspAdapter {
-            capabilities: lsp::ServerCapabilities {
-                completion_provider: Some(lsp::CompletionOptions {
-                    trigger_characters: Some(vec![":".to_string()]),
-                    ..Default::default()
-                }),
-                ..Default::default()
-            },
-            ..Default::default()
+            capabilities: serde_json::from_value(json!({ "typeHierarchyProvider": true })).unwrap(),
+            ..FakeLspAdapter::default()
         },
     );
 
     let buffer = project
-        .update(cx, |p, cx| p.open_local_buffer("/dir/a.ts", cx))
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
         .await
         .unwrap();
 
-    let fake_server = fake_language_servers.next().await.unwrap();
-
-    let text = "let a = b.fqn";
-    buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
-    let completions = project.update(cx, |project, cx| {
-        project.completions(&buffer, text.len(), DEFAULT_COMPLETION_CONTEXT, cx)
+    let fake_server = fake_servers.next().await.unwrap();
+    let lsp_item =
+        |path: &str, name: &str, kind: lsp::SymbolKind, range: lsp::Range| lsp::TypeHierarchyItem {
+            name: name.to_string(),
+            kind,
+            tags: None,
+            detail: None,
+            uri: lsp::Url::from_file_path(path).unwrap(),
+            range,
+            selection_range: range,
+            data: None,
+        };
+    let a_item = lsp_item(
+        "/dir/a.rs",
+        "A",
+        lsp::SymbolKind::INTERFACE,
+        lsp::Range::new(lsp::Position::new(0, 6), lsp::Position::new(0, 7)),
+    );
+    let b_item = lsp_item(
+        "/dir/b.rs",
+        "B",
+        lsp::SymbolKind::STRUCT,
+        lsp::Range::new(lsp::Position::new(0, 7), lsp::Position::new(0, 8)),
+    );
+    fake_server.handle_request::<lsp::request::TypeHierarchyPrepare, _, _>(move |params, _| {
+        let a_item = a_item.clone();
+        async move {
+            assert_eq!(
+                params.text_document_position_params.position,
+                lsp::Position::new(0, 6)
+            );
+            Ok(Some(vec![a_item]))
+        }
+    });
+    fake_server.handle_request::<lsp::request::TypeHierarchySubtypes, _, _>(move |params, _| {
+        let b_item = b_item.clone();
+        async move {
+            assert_eq!(params.item.name, "A");
+            Ok(Some(vec![b_item]))
+        }
     });
 
-    fake_server
-        .handle_request::<lsp::request::Completion, _, _>(|_, _| async move {
-            Ok(Some(lsp::CompletionResponse::Array(vec![
-                lsp::CompletionItem {
-                    label: "fullyQualifiedName?".into(),
-                    insert_text: Some("fully\rQualified\r\nName".into()),
-                    ..Default::default()
-                },
-            ])))
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_type_hierarchy(&buffer, 6, cx)
         })
-        .next()
-        .await;
-    let completions = completions.await.unwrap();
-    assert_eq!(completions.len(), 1);
-    assert_eq!(completions[0].new_text, "fully\nQualified\nName");
-}
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "A");
 
-#[gpui::test(iterations = 10)]
-async fn test_apply_code_actions_with_commands(cx: &mut gpui::TestAppContext) {
+    let subtypes = project
+        .update(cx, |project, cx| project.subtypes(&items[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(subtypes.len(), 1);
+    cx.update(|cx| {
+        let subtype = &subtypes[0];
+        assert_eq!(subtype.name, "B");
+        assert_eq!(subtype.kind, lsp::SymbolKind::STRUCT);
+        let subtype_buffer = subtype.location.buffer.read(cx);
+        assert_eq!(
+            subtype_buffer
+                .file()
+                .unwrap()
+                .as_local()
+                .unwrap()
+                .abs_path(cx),
+            Path::new("/dir/b.rs"),
+        );
+        assert_eq!(subtype.location.range.to_offset(subtype_buffer), 7..8);
+    });
+}
+
+#[gpui::test]
+async fn test_semantic_tokens(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                settings.defaults.semantic_tokens = Some(true);
+            });
+        })
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn main() { let mut x = 1; }",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                semantic_tokens_provider: Some(
+                    lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        lsp::SemanticTokensOptions {
+                            legend: lsp::SemanticTokensLegend {
+                                token_types: vec![
+                                    lsp::SemanticTokenType::FUNCTION,
+                                    lsp::SemanticTokenType::VARIABLE,
+                                ],
+                                token_modifiers: vec![
+                                    lsp::SemanticTokenModifier::DECLARATION,
+                                    lsp::SemanticTokenModifier::new("mutable"),
+                                ],
+                            },
+                            full: Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                            ..lsp::SemanticTokensOptions::default()
+                        },
+                    ),
+                ),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::SemanticTokensFullRequest, _, _>(|_, _| async {
+        Ok(Some(lsp::SemanticTokensResult::Tokens(
+            lsp::SemanticTokens {
+                result_id: Some("1".to_string()),
+                data: vec![
+                    lsp::SemanticToken {
+                        delta_line: 0,
+                        delta_start: 3,
+                        length: 4,
+                        token_type: 0,
+                        token_modifiers_bitset: 0b01,
+                    },
+                    lsp::SemanticToken {
+                        delta_line: 0,
+                        delta_start: 17,
+                        length: 1,
+                        token_type: 1,
+                        token_modifiers_bitset: 0b10,
+                    },
+                ],
+            },
+        )))
+    });
+    fake_server.handle_request::<lsp::request::SemanticTokensFullDeltaRequest, _, _>(
+        |params, _| async move {
+            assert_eq!(params.previous_result_id, "1");
+            // Edits refer to the integers of the encoded tokens and need not be aligned to
+            // the tokens: this one only clears the modifiers of the first token.
+            Ok(Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(
+                lsp::SemanticTokensDelta {
+                    result_id: Some("2".to_string()),
+                    edits: vec![lsp::SemanticTokensEdit {
+                        start: 4,
+                        delete_count: 5,
+                        data: Some(vec![lsp::SemanticToken {
+                            delta_line: 0,
+                            delta_start: 0,
+                            length: 17,
+                            token_type: 1,
+                            token_modifiers_bitset: 1,
+                        }]),
+                    }],
+                },
+            )))
+        },
+    );
+
+    let semantic_tokens = |cx: &mut gpui::TestAppContext| {
+        buffer.read_with(cx, |buffer, _| {
+            let snapshot = buffer.snapshot();
+            snapshot
+                .semantic_tokens_in_range(0..snapshot.len())
+                .map(|token| {
+                    (
+                        token.range.to_offset(&snapshot),
+                        token.highlight_name.to_string(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+
+    project
+        .update(cx, |project, cx| {
+            project.semantic_tokens(buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        semantic_tokens(cx),
+        vec![
+            (3..7, "function.declaration".to_string()),
+            (20..21, "variable.mutable".to_string()),
+        ]
+    );
+
+    project
+        .update(cx, |project, cx| {
+            project.semantic_tokens(buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        semantic_tokens(cx),
+        vec![
+            (3..7, "function".to_string()),
+            (20..21, "variable.mutable".to_string()),
+        ],
+        "The second request should only receive the changed tokens"
+    );
+
+    let mut events = cx.events(&project);
+    fake_server
+        .request::<lsp::request::SemanticTokensRefresh>(())
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert!(
+        std::iter::from_fn(|| events.try_next().ok().flatten())
+            .any(|event| event == Event::RefreshSemanticTokens),
+        "The server's refresh request should make the editors request the tokens again"
+    );
+}
+
+#[gpui::test]
+async fn test_code_lens(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn one() {}\n\nfn two() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                code_lens_provider: Some(lsp::CodeLensOptions {
+                    resolve_provider: Some(true),
+                }),
+                execute_command_provider: Some(lsp::ExecuteCommandOptions {
+                    commands: vec!["run".to_string()],
+                    ..lsp::ExecuteCommandOptions::default()
+                }),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::CodeLensRequest, _, _>(|params, _| async move {
+        assert_eq!(
+            params.text_document.uri,
+            lsp::Url::from_file_path("/dir/a.rs").unwrap(),
+        );
+        Ok(Some(vec![
+            lsp::CodeLens {
+                range: lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 6)),
+                command: Some(lsp::Command {
+                    title: "Run".to_string(),
+                    command: "run".to_string(),
+                    arguments: None,
+                }),
+                data: None,
+            },
+            lsp::CodeLens {
+                range: lsp::Range::new(lsp::Position::new(2, 3), lsp::Position::new(2, 6)),
+                command: None,
+                data: Some(json!("two")),
+            },
+        ]))
+    });
+    fake_server.handle_request::<lsp::request::CodeLensResolve, _, _>(|lens, _| async move {
+        assert_eq!(lens.data, Some(json!("two")));
+        Ok(lsp::CodeLens {
+            command: Some(lsp::Command {
+                title: "1 reference".to_string(),
+                command: "references".to_string(),
+                arguments: None,
+            }),
+            ..lens
+        })
+    });
+
+    let lenses = project
+        .update(cx, |project, cx| project.code_lens(&buffer, cx))
+        .await
+        .unwrap();
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(
+            lenses
+                .iter()
+                .map(|lens| (
+                    lens.range.to_point(buffer),
+                    lens.lsp_lens.command.as_ref().unwrap().title.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (Point::new(0, 3)..Point::new(0, 6), "Run"),
+                (Point::new(2, 3)..Point::new(2, 6), "1 reference"),
+            ],
+            "Lenses without a command should be resolved"
+        );
+    });
+
+    let action = lenses[0].code_action().unwrap();
+    assert_eq!(action.lsp_action.title, "Run");
+    assert_eq!(
+        action.lsp_action.command.unwrap().command,
+        "run".to_string()
+    );
+    assert!(
+        lenses[1].code_action().is_none(),
+        "Commands the server cannot execute should not be run"
+    );
+}
+
+#[gpui::test]
+async fn test_pull_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn a() { A }",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let lsp_store = project.read_with(cx, |project, _| project.lsp_store());
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions {
+                        identifier: Some("rust".to_string()),
+                        ..Default::default()
+                    },
+                )),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let previous_result_ids = Arc::new(Mutex::new(Vec::new()));
+    fake_server.handle_request::<lsp::request::DocumentDiagnosticRequest, _, _>({
+        let previous_result_ids = previous_result_ids.clone();
+        move |params, _| {
+            let previous_result_ids = previous_result_ids.clone();
+            async move {
+                assert_eq!(
+                    params.text_document.uri,
+                    lsp::Url::from_file_path("/dir/a.rs").unwrap(),
+                );
+                assert_eq!(params.identifier.as_deref(), Some("rust"));
+                let report = match params.previous_result_id.clone() {
+                    Some(result_id) => lsp::DocumentDiagnosticReport::Unchanged(
+                        lsp::RelatedUnchangedDocumentDiagnosticReport {
+                            related_documents: None,
+                            unchanged_document_diagnostic_report:
+                                lsp::UnchangedDocumentDiagnosticReport { result_id },
+                        },
+                    ),
+                    None => lsp::DocumentDiagnosticReport::Full(
+                        lsp::RelatedFullDocumentDiagnosticReport {
+                            related_documents: None,
+                            full_document_diagnostic_report: lsp::FullDocumentDiagnosticReport {
+                                result_id: Some("1".to_string()),
+                                items: vec![lsp::Diagnostic {
+                                    range: lsp::Range::new(
+                                        lsp::Position::new(0, 9),
+                                        lsp::Position::new(0, 10),
+                                    ),
+                                    severity: Some(lsp::DiagnosticSeverity::ERROR),
+                                    message: "undefined variable 'A'".to_string(),
+                                    ..Default::default()
+                                }],
+                            },
+                        },
+                    ),
+                };
+                previous_result_ids.lock().push(params.previous_result_id);
+                Ok(lsp::DocumentDiagnosticReportResult::Report(report))
+            }
+        }
+    });
+
+    let assert_diagnostics = |cx: &mut gpui::TestAppContext| {
+        buffer.read_with(cx, |buffer, _| {
+            let snapshot = buffer.snapshot();
+            assert_eq!(
+                snapshot
+                    .diagnostics_in_range::<_, Point>(0..snapshot.len(), false)
+                    .map(|entry| (entry.range, entry.diagnostic.message))
+                    .collect::<Vec<_>>(),
+                vec![(
+                    Point::new(0, 9)..Point::new(0, 10),
+                    "undefined variable 'A'".to_string()
+                )],
+            );
+        });
+    };
+
+    lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.pull_diagnostics(buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_diagnostics(cx);
+
+    // The result id of the previous report is sent back, and an unchanged report keeps the diagnostics.
+    lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.pull_diagnostics(buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_diagnostics(cx);
+    assert_eq!(
+        previous_result_ids.lock().last().cloned(),
+        Some(Some("1".to_string()))
+    );
+
+    // Edits pull the diagnostics again, once the debounce elapses.
+    let requests_before_edit = previous_result_ids.lock().len();
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "\n")], None, cx));
+    cx.executor().advance_clock(Duration::from_millis(200));
+    cx.executor().run_until_parked();
+    assert_eq!(previous_result_ids.lock().len(), requests_before_edit + 1);
+}
+
+#[gpui::test]
+async fn test_pull_workspace_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn a() { b() }",
+            "b.rs": "fn b() { C }",
+            "c.rs": "fn c() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let lsp_store = project.read_with(cx, |project, _| project.lsp_store());
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions {
+                        inter_file_dependencies: true,
+                        workspace_diagnostics: true,
+                        ..Default::default()
+                    },
+                )),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let a_buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let server_id = fake_server.server.server_id();
+    let diagnostic = |message: &str, column: u32| lsp::Diagnostic {
+        range: lsp::Range::new(
+            lsp::Position::new(0, column),
+            lsp::Position::new(0, column + 1),
+        ),
+        severity: Some(lsp::DiagnosticSeverity::ERROR),
+        message: message.to_string(),
+        ..Default::default()
+    };
+
+    // Pulling the diagnostics of a.rs also reports the ones of the related c.rs, and documents
+    // pulled before are unchanged.
+    let document_requests = Arc::new(AtomicUsize::new(0));
+    fake_server.handle_request::<lsp::request::DocumentDiagnosticRequest, _, _>({
+        let document_requests = document_requests.clone();
+        move |params, _| {
+            document_requests.fetch_add(1, SeqCst);
+            let report = match params.previous_result_id {
+                Some(result_id) => lsp::DocumentDiagnosticReport::Unchanged(
+                    lsp::RelatedUnchangedDocumentDiagnosticReport {
+                        related_documents: None,
+                        unchanged_document_diagnostic_report:
+                            lsp::UnchangedDocumentDiagnosticReport { result_id },
+                    },
+                ),
+                None => {
+                    let related_report = lsp::DocumentDiagnosticReportKind::Full(
+                        lsp::FullDocumentDiagnosticReport {
+                            result_id: Some("c1".to_string()),
+                            items: vec![diagnostic("unused function 'c'", 3)],
+                        },
+                    );
+                    let related_uri = lsp::Url::from_file_path("/dir/c.rs").unwrap();
+                    lsp::DocumentDiagnosticReport::Full(lsp::RelatedFullDocumentDiagnosticReport {
+                        related_documents: Some(
+                            [(related_uri, related_report)].into_iter().collect(),
+                        ),
+                        full_document_diagnostic_report: lsp::FullDocumentDiagnosticReport {
+                            result_id: Some("a1".to_string()),
+                            items: Vec::new(),
+                        },
+                    })
+                }
+            };
+            async move { Ok(lsp::DocumentDiagnosticReportResult::Report(report)) }
+        }
+    });
+
+    // The workspace pull reports b.rs once, then that it is unchanged.
+    let workspace_requests = Arc::new(Mutex::new(Vec::new()));
+    fake_server.handle_request::<lsp::request::WorkspaceDiagnosticRequest, _, _>({
+        let workspace_requests = workspace_requests.clone();
+        move |params, _| {
+            let b_uri = lsp::Url::from_file_path("/dir/b.rs").unwrap();
+            let b_previous_result_id = params
+                .previous_result_ids
+                .iter()
+                .find(|previous| previous.uri == b_uri)
+                .map(|previous| previous.value.clone());
+            let report = match b_previous_result_id.clone() {
+                Some(result_id) => lsp::WorkspaceDocumentDiagnosticReport::Unchanged(
+                    lsp::WorkspaceUnchangedDocumentDiagnosticReport {
+                        uri: b_uri,
+                        version: None,
+                        unchanged_document_diagnostic_report:
+                            lsp::UnchangedDocumentDiagnosticReport { result_id },
+                    },
+                ),
+                None => lsp::WorkspaceDocumentDiagnosticReport::Full(
+                    lsp::WorkspaceFullDocumentDiagnosticReport {
+                        uri: b_uri,
+                        version: None,
+                        full_document_diagnostic_report: lsp::FullDocumentDiagnosticReport {
+                            result_id: Some("b1".to_string()),
+                            items: vec![diagnostic("undefined variable 'C'", 9)],
+                        },
+                    },
+                ),
+            };
+            workspace_requests.lock().push(b_previous_result_id);
+            async move {
+                Ok(lsp::WorkspaceDiagnosticReportResult::Report(
+                    lsp::WorkspaceDiagnosticReport {
+                        items: vec![report],
+                    },
+                ))
+            }
+        }
+    });
+
+    lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.pull_diagnostics(a_buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    lsp_store.update(cx, |lsp_store, cx| {
+        lsp_store.pull_workspace_diagnostics(server_id, cx)
+    });
+    cx.executor().run_until_parked();
+    assert_eq!(*workspace_requests.lock(), vec![None]);
+
+    let b_buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/b.rs", cx))
+        .await
+        .unwrap();
+    let c_buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/c.rs", cx))
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    let diagnostics = |buffer: &Model<Buffer>, cx: &mut gpui::TestAppContext| {
+        buffer.read_with(cx, |buffer, _| {
+            let snapshot = buffer.snapshot();
+            snapshot
+                .diagnostics_in_range::<_, Point>(0..snapshot.len(), false)
+                .map(|entry| (entry.range, entry.diagnostic.message))
+                .collect::<Vec<_>>()
+        })
+    };
+    assert_eq!(
+        diagnostics(&b_buffer, cx),
+        vec![(
+            Point::new(0, 9)..Point::new(0, 10),
+            "undefined variable 'C'".to_string()
+        )],
+    );
+    assert_eq!(
+        diagnostics(&c_buffer, cx),
+        vec![(
+            Point::new(0, 3)..Point::new(0, 4),
+            "unused function 'c'".to_string()
+        )],
+    );
+
+    // Once the pull completes, it is issued again with the result ids of the previous reports.
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(
+        *workspace_requests.lock(),
+        vec![None, Some("b1".to_string())]
+    );
+    assert_eq!(
+        diagnostics(&b_buffer, cx),
+        vec![(
+            Point::new(0, 9)..Point::new(0, 10),
+            "undefined variable 'C'".to_string()
+        )],
+    );
+
+    // A refresh request from the server pulls the open buffers and the workspace again.
+    let document_requests_before_refresh = document_requests.load(SeqCst);
+    fake_server
+        .request::<lsp::request::WorkspaceDiagnosticRefresh>(())
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert!(document_requests.load(SeqCst) > document_requests_before_refresh);
+    assert_eq!(workspace_requests.lock().len(), 3);
+}
+
+#[gpui::test]
+async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.ts": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                completion_provider: Some(lsp::CompletionOptions {
+                    trigger_characters: Some(vec![":".to_string()]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.ts", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_language_servers.next().await.unwrap();
+
+    let text = "let a = b.fqn";
+    buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
+    let completions = project.update(cx, |project, cx| {
+        project.completions(&buffer, text.len(), DEFAULT_COMPLETION_CONTEXT, cx)
+    });
+
+    fake_server
+        .handle_request::<lsp::request::Completion, _, _>(|_, _| async move {
+            Ok(Some(lsp::CompletionResponse::Array(vec![
+                lsp::CompletionItem {
+                    label: "fullyQualifiedName?".into(),
+                    insert_text: Some("fullyQualifiedName".into()),
+                    ..Default::default()
+                },
+            ])))
+        })
+        .next()
+        .await;
+    let completions = completions.await.unwrap();
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+    assert_eq!(completions.len(), 1);
+    assert_eq!(completions[0].new_text, "fullyQualifiedName");
+    assert_eq!(
+        completions[0].old_range.to_offset(&snapshot),
+        text.len() - 3..text.len()
+    );
+
+    let text = "let a = \"atoms/cmp\"";
+    buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
+    let completions = project.update(cx, |project, cx| {
+        project.completions(&buffer, text.len() - 1, DEFAULT_COMPLETION_CONTEXT, cx)
+    });
+
+    fake_server
+        .handle_request::<lsp::request::Completion, _, _>(|_, _| async move {
+            Ok(Some(lsp::CompletionResponse::Array(vec![
+                lsp::CompletionItem {
+                    label: "component".into(),
+                    ..Default::default()
+                },
+            ])))
+        })
+        .next()
+        .await;
+    let completions = completions.await.unwrap();
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+    assert_eq!(completions.len(), 1);
+    assert_eq!(completions[0].new_text, "component");
+    assert_eq!(
+        completions[0].old_range.to_offset(&snapshot),
+        text.len() - 4..text.len() - 1
+    );
+}
+
+#[gpui::test]
+async fn test_completions_with_carriage_returns(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.ts": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                completion_provider: Some(lsp::CompletionOptions {
+                    trigger_characters: Some(vec![":".to_string()]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.ts", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_language_servers.next().await.unwrap();
+
+    let text = "let a = b.fqn";
+    buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
+    let completions = project.update(cx, |project, cx| {
+        project.completions(&buffer, text.len(), DEFAULT_COMPLETION_CONTEXT, cx)
+    });
+
+    fake_server
+        .handle_request::<lsp::request::Completion, _, _>(|_, _| async move {
+            Ok(Some(lsp::CompletionResponse::Array(vec![
+                lsp::CompletionItem {
+                    label: "fullyQualifiedName?".into(),
+                    insert_text: Some("fully\rQualified\r\nName".into()),
+                    ..Default::default()
+                },
+            ])))
+        })
+        .next()
+        .await;
+    let completions = completions.await.unwrap();
+    assert_eq!(completions.len(), 1);
+    assert_eq!(completions[0].new_text, "fully\nQualified\nName");
+}
+
+#[gpui::test(iterations = 10)]
+async fn test_apply_code_actions_with_commands(cx: &mut gpui::TestAppContext) {
     init_test(cx);
 
     let fs = FakeFs::new(cx.executor());
@@ -4614,6 +5676,399 @@ async fn test_search_ordering(cx: &mut gpui::TestAppContext) {
     assert!(search.next().await.is_none())
 }
 
+#[gpui::test]
+async fn test_git_stash_and_remote_operations(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "a.txt": "a",
+        }),
+    )
+    .await;
+    fs.with_git_state(Path::new("/dir/.git"), true, |state| {
+        state.branch_name = Some("main".into());
+        state.upstream = Some(AheadBehind {
+            ahead: 2,
+            behind: 1,
+        });
+    });
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    cx.executor().run_until_parked();
+    let root_path = project.read_with(cx, |project, cx| {
+        let worktree = project.worktrees(cx).next().unwrap().read(cx);
+        let repository = worktree.snapshot().root_git_entry().unwrap();
+        assert_eq!(
+            repository.ahead_behind(),
+            Some(AheadBehind {
+                ahead: 2,
+                behind: 1
+            })
+        );
+        ProjectPath {
+            worktree_id: worktree.id(),
+            path: Path::new("").into(),
+        }
+    });
+
+    let stash_messages = |cx: &mut gpui::TestAppContext| {
+        let stash_list =
+            project.update(cx, |project, cx| project.stash_list(root_path.clone(), cx));
+        async move {
+            stash_list
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|stash| stash.message)
+                .collect::<Vec<_>>()
+        }
+    };
+    project
+        .update(cx, |project, cx| {
+            project.stash_push(root_path.clone(), None, cx)
+        })
+        .await
+        .unwrap();
+    project
+        .update(cx, |project, cx| {
+            project.stash_push(root_path.clone(), Some("second".into()), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(stash_messages(cx).await, ["On main: second", "WIP on main"]);
+
+    project
+        .update(cx, |project, cx| {
+            project.stash_pop(root_path.clone(), 1, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(stash_messages(cx).await, ["On main: second"]);
+    project
+        .update(cx, |project, cx| {
+            project.stash_drop(root_path.clone(), 0, cx)
+        })
+        .await
+        .unwrap();
+    assert!(stash_messages(cx).await.is_empty());
+    assert!(project
+        .update(cx, |project, cx| project.stash_drop(
+            root_path.clone(),
+            0,
+            cx
+        ))
+        .await
+        .is_err());
+
+    let push = project.update(cx, |project, cx| project.push(root_path.clone(), cx));
+    project.read_with(cx, |project, _| {
+        assert_eq!(
+            project
+                .pending_git_operations()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["Pushing to upstream…"]
+        );
+    });
+    push.await.unwrap();
+    project.read_with(cx, |project, _| {
+        assert_eq!(project.pending_git_operations().count(), 0);
+    });
+    project
+        .update(cx, |project, cx| project.pull(root_path.clone(), cx))
+        .await
+        .unwrap();
+    fs.with_git_state(Path::new("/dir/.git"), false, |state| {
+        assert_eq!(state.upstream, Some(AheadBehind::default()));
+    });
+}
+
+#[gpui::test]
+async fn test_git_branch_rename_and_delete(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "a.txt": "a",
+        }),
+    )
+    .await;
+    fs.with_git_state(Path::new("/dir/.git"), true, |state| {
+        state.branch_name = Some("main".into());
+        state.branches = ["topic".to_string(), "old".to_string()]
+            .into_iter()
+            .collect();
+    });
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    cx.executor().run_until_parked();
+    let repo = project.read_with(cx, |project, cx| {
+        project.get_first_worktree_root_repo(cx).unwrap()
+    });
+    let branch_names = || {
+        repo.branches()
+            .unwrap()
+            .into_iter()
+            .map(|branch| (branch.name.to_string(), branch.is_head))
+            .collect::<Vec<_>>()
+    };
+
+    repo.rename_branch("topic", "feature").unwrap();
+    assert_eq!(
+        branch_names(),
+        [
+            ("feature".to_string(), false),
+            ("main".to_string(), true),
+            ("old".to_string(), false),
+        ]
+    );
+    assert!(repo.rename_branch("missing", "other").is_err());
+    assert!(repo.rename_branch("old", "feature").is_err());
+
+    repo.rename_branch("main", "trunk").unwrap();
+    assert_eq!(repo.branch_name().as_deref(), Some("trunk"));
+
+    repo.delete_branch("old").unwrap();
+    assert_eq!(
+        branch_names(),
+        [("feature".to_string(), false), ("trunk".to_string(), true)]
+    );
+    assert!(repo.delete_branch("old").is_err());
+    assert!(repo.delete_branch("trunk").is_err());
+}
+
+#[gpui::test]
+async fn test_git_stage_unstage_and_commit(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "a.txt": "one\nTWO\n",
+            "b.txt": "new\n",
+        }),
+    )
+    .await;
+    let dot_git = Path::new("/dir/.git");
+    fs.with_git_state(dot_git, true, |state| {
+        state
+            .head_contents
+            .insert("a.txt".into(), "one\ntwo\n".into());
+        state.index_contents = state.head_contents.clone();
+    });
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    cx.executor().run_until_parked();
+    let worktree_id = project.read_with(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let project_path = |path: &str| ProjectPath {
+        worktree_id,
+        path: Path::new(path).into(),
+    };
+    let index_contents = || {
+        let mut contents = None;
+        fs.with_git_state(dot_git, false, |state| {
+            let mut index_contents = state
+                .index_contents
+                .iter()
+                .map(|(path, text)| (path.to_string_lossy().to_string(), text.clone()))
+                .collect::<Vec<_>>();
+            index_contents.sort();
+            contents = Some(index_contents);
+        });
+        contents.unwrap()
+    };
+
+    project
+        .update(cx, |project, cx| {
+            project.stage_paths(vec![project_path("a.txt"), project_path("b.txt")], cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        index_contents(),
+        [
+            ("a.txt".to_string(), "one\nTWO\n".to_string()),
+            ("b.txt".to_string(), "new\n".to_string()),
+        ]
+    );
+
+    project
+        .update(cx, |project, cx| {
+            project.unstage_paths(vec![project_path("b.txt")], cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        index_contents(),
+        [("a.txt".to_string(), "one\nTWO\n".to_string())]
+    );
+
+    project
+        .update(cx, |project, cx| {
+            project.commit(project_path(""), "Shout two".into(), false, cx)
+        })
+        .await
+        .unwrap();
+    fs.with_git_state(dot_git, false, |state| {
+        assert_eq!(state.head_contents, state.index_contents);
+        assert_eq!(state.commit_messages, ["Shout two"]);
+    });
+    let message = project
+        .update(cx, |project, cx| {
+            project.head_commit_message(project_path(""), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(message.as_deref(), Some("Shout two"));
+    let committed_text = project
+        .update(cx, |project, cx| {
+            project.load_committed_text(project_path("a.txt"), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(committed_text.as_deref(), Some("one\nTWO\n"));
+
+    assert!(project
+        .update(cx, |project, cx| {
+            project.commit(project_path(""), "Nothing".into(), false, cx)
+        })
+        .await
+        .is_err());
+}
+
+#[gpui::test]
+async fn test_git_log_and_commit_details(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "a.txt": "a\n",
+            "src": {
+                "b.txt": "b\n",
+                "c.txt": "c\n",
+            },
+        }),
+    )
+    .await;
+    fs.set_commits_for_repo(
+        Path::new("/dir/.git"),
+        vec![
+            fake_commit(
+                "2222222222222222222222222222222222222222",
+                "Change everything",
+                &[
+                    ("a.txt", Some("A\n"), Some("a\n")),
+                    ("src/b.txt", None, Some("b\n")),
+                    ("src/c.txt", Some("C\n"), Some("c\n")),
+                ],
+            ),
+            fake_commit(
+                "1111111111111111111111111111111111111111",
+                "Add files",
+                &[
+                    ("a.txt", None, Some("A\n")),
+                    ("src/c.txt", None, Some("C\n")),
+                ],
+            ),
+        ],
+    );
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    cx.executor().run_until_parked();
+    let worktree_id = project.read_with(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let project_path = |path: &str| ProjectPath {
+        worktree_id,
+        path: Path::new(path).into(),
+    };
+    let mut log = |path: &str, filter_by_path: bool, skip: usize| {
+        let log = project.update(cx, |project, cx| {
+            project.git_log(project_path(path), filter_by_path, skip, 10, cx)
+        });
+        async move {
+            log.await
+                .unwrap()
+                .commits
+                .into_iter()
+                .map(|commit| commit.subject)
+                .collect::<Vec<_>>()
+        }
+    };
+
+    assert_eq!(log("", false, 0).await, ["Change everything", "Add files"]);
+    assert_eq!(log("src/b.txt", false, 1).await, ["Add files"]);
+    assert_eq!(log("src/b.txt", true, 0).await, ["Change everything"]);
+
+    // The changed files are loaded a page at a time.
+    let sha = "2222222222222222222222222222222222222222".parse().unwrap();
+    let details = project
+        .update(cx, |project, cx| {
+            project.commit_details(project_path(""), sha, 0, 2, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(details.message, "Change everything\n");
+    assert_eq!(details.changed_file_count, 3);
+    assert_eq!(
+        details
+            .changes
+            .iter()
+            .map(|change| change.path.to_string_lossy().to_string())
+            .collect::<Vec<_>>(),
+        ["a.txt", "src/b.txt"]
+    );
+    assert_eq!(details.changes[1].old_text, None);
+    let details = project
+        .update(cx, |project, cx| {
+            project.commit_details(project_path(""), sha, 2, 2, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(details.changes.len(), 1);
+    assert_eq!(details.changes[0].new_text.as_deref(), Some("c\n"));
+}
+
+fn fake_commit(
+    sha: &str,
+    subject: &str,
+    changes: &[(&str, Option<&str>, Option<&str>)],
+) -> git::commit::CommitDetails {
+    git::commit::CommitDetails {
+        summary: git::commit::CommitSummary {
+            sha: sha.parse().unwrap(),
+            author_name: "Jane Doe".into(),
+            author_email: "jane@example.com".into(),
+            timestamp: 1700000000,
+            subject: subject.into(),
+        },
+        message: format!("{subject}\n"),
+        changes: changes
+            .iter()
+            .map(|(path, old_text, new_text)| git::commit::CommitFileChange {
+                path: git::repository::RepoPath::from(Path::new(path)),
+                old_text: old_text.map(Into::into),
+                new_text: new_text.map(Into::into),
+            })
+            .collect(),
+        changed_file_count: changes.len(),
+    }
+}
+
 #[gpui::test]
 async fn test_create_entry(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -15,7 +15,8 @@ use itertools::Itertools;
 use language::{ContextProvider, File, Language, Location};
 use settings::{parse_json_with_comments, SettingsLocation};
 use task::{
-    ResolvedTask, TaskContext, TaskId, TaskTemplate, TaskTemplates, TaskVariables, VariableName,
+    ResolvedTask, TaskContext, TaskDependencyTree, TaskId, TaskTemplate, TaskTemplates,
+    TaskVariables, VariableName,
 };
 use text::{Point, ToPoint};
 use util::{post_inc, NumericPrefixWithSuffix, ResultExt as _};
@@ -216,6 +217,38 @@ impl Inventory {
         }
     }
 
+    /// Builds the dependency tree of the resolved task given, resolving every dependency with the context of the task depending on it.
+    /// Dependencies are looked up by label among the tasks from the same source first, then among global and worktree tasks.
+    pub fn resolve_dependencies(
+        &self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+    ) -> Result<TaskDependencyTree<(TaskSourceKind, ResolvedTask)>> {
+        let worktree = match &task_source_kind {
+            TaskSourceKind::Worktree { id, .. } => Some(*id),
+            _ => None,
+        };
+        let available_templates = self
+            .templates_from_settings(worktree)
+            .sorted_by_key(|(kind, _)| kind != &task_source_kind)
+            .collect::<Vec<_>>();
+        TaskDependencyTree::build(
+            (task_source_kind, resolved_task),
+            |(_, resolved_task)| resolved_task.original_task(),
+            |(_, dependent_task), label| {
+                available_templates
+                    .iter()
+                    .filter(|(_, template)| template.label == label)
+                    .find_map(|(kind, template)| {
+                        let resolved_task = template
+                            .resolve_task(&kind.to_id_base(), dependent_task.task_context())?;
+                        Some((kind.clone(), resolved_task))
+                    })
+            },
+        )
+        .context("resolving task dependencies")
+    }
+
     /// Registers task "usage" as being scheduled – to be used for LRU sorting when listing all tasks.
     pub fn task_scheduled(
         &mut self,
@@ -814,6 +847,93 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_task_dependencies_resolution(cx: &mut TestAppContext) {
+        init_test(cx);
+        let inventory = cx.update(Inventory::new);
+        let worktree_id = WorktreeId::from_usize(1);
+        let worktree_tasks_kind = TaskSourceKind::Worktree {
+            id: worktree_id,
+            directory_in_worktree: PathBuf::from(".zed"),
+            id_base: "local worktree tasks from directory \".zed\"".into(),
+        };
+        inventory.update(cx, |inventory, _| {
+            inventory
+                .update_file_based_tasks(
+                    None,
+                    Some(
+                        &json!([
+                            { "label": "build", "command": "echo global build" },
+                            { "label": "lint", "command": "echo global lint" },
+                        ])
+                        .to_string(),
+                    ),
+                )
+                .unwrap();
+            inventory
+                .update_file_based_tasks(
+                    Some(SettingsLocation {
+                        worktree_id,
+                        path: Path::new(".zed"),
+                    }),
+                    Some(
+                        &json!([
+                            { "label": "build", "command": "echo $ZED_COLUMN" },
+                            { "label": "check", "depends_on": ["lint", "build"] },
+                            { "label": "cyclic", "depends_on": ["cyclic"] },
+                        ])
+                        .to_string(),
+                    ),
+                )
+                .unwrap();
+        });
+
+        let task_context = TaskContext {
+            task_variables: TaskVariables::from_iter([(VariableName::Column, "7".to_string())]),
+            ..TaskContext::default()
+        };
+        let resolve = |label: &str, cx: &mut TestAppContext| {
+            inventory.update(cx, |inventory, _| {
+                let template = inventory
+                    .templates_from_settings(Some(worktree_id))
+                    .find(|(kind, template)| {
+                        kind == &worktree_tasks_kind && template.label == label
+                    })
+                    .unwrap()
+                    .1;
+                let resolved_task = template
+                    .resolve_task(&worktree_tasks_kind.to_id_base(), &task_context)
+                    .unwrap();
+                inventory.resolve_dependencies(worktree_tasks_kind.clone(), resolved_task)
+            })
+        };
+
+        let tree = resolve("check", cx).unwrap();
+        assert_eq!(tree.task.1.original_task().label, "check");
+        assert_eq!(
+            tree.dependencies
+                .iter()
+                .map(|dependency| {
+                    let (kind, task) = &dependency.task;
+                    (
+                        kind == &worktree_tasks_kind,
+                        task.resolved.as_ref().unwrap().command_label.clone(),
+                    )
+                })
+                .collect::<Vec<_>>(),
+            vec![
+                (false, "echo global lint".to_string()),
+                (true, "echo 7".to_string()),
+            ],
+            "Dependencies should prefer the tasks from the same source and get resolved with the same context"
+        );
+
+        assert!(
+            resolve("cyclic", cx).is_err(),
+            "Cyclic dependencies should not be resolved"
+        );
+    }
+
     fn init_test(_cx: &mut TestAppContext) {
         if std::env::var("RUST_LOG").is_ok() {
             env_logger::try_init().ok();
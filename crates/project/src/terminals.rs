@@ -1,8 +1,10 @@
 use crate::Project;
 use anyhow::Context as _;
-use collections::HashMap;
+use collections::{HashMap, HashSet};
 use gpui::{AnyWindowHandle, AppContext, Context, Entity, Model, ModelContext, WeakModel};
 use itertools::Itertools;
+use language::{Diagnostic, DiagnosticEntry, PointUtf16, Unclipped};
+use lsp::{DiagnosticSeverity, LanguageServerId};
 use settings::{Settings, SettingsLocation};
 use smol::channel::bounded;
 use std::{
@@ -11,7 +13,7 @@ use std::{
     iter,
     path::{Path, PathBuf},
 };
-use task::{Shell, SpawnInTerminal};
+use task::{Problem, ProblemSeverity, Shell, SpawnInTerminal, TaskId};
 use terminal::{
     terminal_settings::{self, TerminalSettings},
     TaskState, TaskStatus, Terminal, TerminalBuilder,
@@ -23,6 +25,13 @@ use util::ResultExt;
 
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakModel<terminal::Terminal>>,
+    pub(crate) task_diagnostics: HashMap<TaskId, TaskDiagnostics>,
+}
+
+/// Diagnostics, published from the problems that the task's problem matchers found in its output.
+pub(crate) struct TaskDiagnostics {
+    server_id: LanguageServerId,
+    abs_paths: HashSet<PathBuf>,
 }
 
 /// Terminals are opened either for the users shell, or to run a task.
@@ -136,6 +145,7 @@ impl Project {
             .as_ref()
             .and_then(|path| self.python_venv_directory(path, settings, cx));
         let mut python_venv_activate_command = None;
+        let mut matched_task = None;
 
         let (spawn_task, shell) = match kind {
             TerminalKind::Shell(_) => {
@@ -164,7 +174,12 @@ impl Project {
                 }
             }
             TerminalKind::Task(spawn_task) => {
+                self.clear_task_diagnostics(&spawn_task.id, cx);
+                if !spawn_task.problem_matchers.is_empty() {
+                    matched_task = Some((spawn_task.id.clone(), spawn_task.label.clone()));
+                }
                 let task_state = Some(TaskState {
+                    spawn_in_terminal: spawn_task.clone(),
                     id: spawn_task.id,
                     full_label: spawn_task.full_label,
                     label: spawn_task.label,
@@ -172,6 +187,8 @@ impl Project {
                     hide: spawn_task.hide,
                     status: TaskStatus::Running,
                     completion_rx,
+                    problem_matchers: spawn_task.problem_matchers,
+                    problems: Vec::new(),
                 });
 
                 env.extend(spawn_task.env);
@@ -248,6 +265,27 @@ impl Project {
             })
             .detach();
 
+            if let Some((task_id, task_label)) = matched_task {
+                let cwd = path.clone();
+                cx.subscribe(&terminal_handle, move |project, terminal, event, cx| {
+                    if let terminal::Event::TaskProblemsMatched = event {
+                        let Some(problems) =
+                            terminal.read(cx).task().map(|task| task.problems.clone())
+                        else {
+                            return;
+                        };
+                        project.publish_task_diagnostics(
+                            &task_id,
+                            &task_label,
+                            cwd.as_deref(),
+                            problems,
+                            cx,
+                        );
+                    }
+                })
+                .detach();
+            }
+
             if let Some(activate_command) = python_venv_activate_command {
                 self.activate_python_virtual_environment(activate_command, &terminal_handle, cx);
             }
@@ -332,6 +370,103 @@ impl Project {
     pub fn local_terminal_handles(&self) -> &Vec<WeakModel<terminal::Terminal>> {
         &self.terminals.local_handles
     }
+
+    /// Replaces the diagnostics of the task with the problems, matched in its terminal output.
+    pub(crate) fn publish_task_diagnostics(
+        &mut self,
+        task_id: &TaskId,
+        task_label: &str,
+        cwd: Option<&Path>,
+        problems: Vec<Problem>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let mut diagnostics_by_path = HashMap::<PathBuf, Vec<_>>::default();
+        for problem in problems {
+            let Some(abs_path) = problem.abs_path(cwd) else {
+                continue;
+            };
+            let start = PointUtf16::new(
+                problem.line.saturating_sub(1),
+                problem.column.unwrap_or(1).saturating_sub(1),
+            );
+            let end = match problem.end_line {
+                Some(end_line) => PointUtf16::new(
+                    end_line.saturating_sub(1),
+                    problem.end_column.unwrap_or(1).saturating_sub(1),
+                ),
+                None => match problem.end_column {
+                    Some(end_column) => PointUtf16::new(start.row, end_column.saturating_sub(1)),
+                    None => start,
+                },
+            };
+            let severity = match problem.severity {
+                ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+                ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+                ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+                ProblemSeverity::Hint => DiagnosticSeverity::HINT,
+            };
+            let group_id = self
+                .lsp_store
+                .update(cx, |lsp_store, _| lsp_store.next_diagnostic_group_id());
+            diagnostics_by_path
+                .entry(abs_path)
+                .or_default()
+                .push(DiagnosticEntry {
+                    range: Unclipped(start)..Unclipped(end.max(start)),
+                    diagnostic: Diagnostic {
+                        source: Some(problem.source.unwrap_or_else(|| task_label.to_string())),
+                        code: problem.code,
+                        severity,
+                        message: problem.message,
+                        group_id,
+                        is_primary: true,
+                        is_disk_based: true,
+                        ..Diagnostic::default()
+                    },
+                });
+        }
+
+        let languages = self.languages.clone();
+        let task_diagnostics = self
+            .terminals
+            .task_diagnostics
+            .entry(task_id.clone())
+            .or_insert_with(|| TaskDiagnostics {
+                server_id: languages.next_language_server_id(),
+                abs_paths: HashSet::default(),
+            });
+        let server_id = task_diagnostics.server_id;
+        let stale_paths = task_diagnostics
+            .abs_paths
+            .drain()
+            .filter(|abs_path| !diagnostics_by_path.contains_key(abs_path))
+            .collect::<Vec<_>>();
+        task_diagnostics
+            .abs_paths
+            .extend(diagnostics_by_path.keys().cloned());
+
+        for abs_path in stale_paths {
+            self.update_diagnostic_entries(server_id, abs_path, None, Vec::new(), cx)
+                .log_err();
+        }
+        for (abs_path, diagnostics) in diagnostics_by_path {
+            self.update_diagnostic_entries(server_id, abs_path, None, diagnostics, cx)
+                .log_err();
+        }
+    }
+
+    /// Removes all diagnostics, published for the previous run of the task.
+    pub(crate) fn clear_task_diagnostics(&mut self, task_id: &TaskId, cx: &mut ModelContext<Self>) {
+        let Some(task_diagnostics) = self.terminals.task_diagnostics.get_mut(task_id) else {
+            return;
+        };
+        let server_id = task_diagnostics.server_id;
+        let abs_paths = task_diagnostics.abs_paths.drain().collect::<Vec<_>>();
+        for abs_path in abs_paths {
+            self.update_diagnostic_entries(server_id, abs_path, None, Vec::new(), cx)
+                .log_err();
+        }
+    }
 }
 
 pub fn wrap_for_ssh(
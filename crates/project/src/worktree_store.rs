@@ -12,6 +12,13 @@ use futures::{
     future::{BoxFuture, Shared},
     FutureExt, SinkExt,
 };
+use git::{
+    commit::{CommitDetails, CommitFileChange, CommitLog, CommitSummary},
+    conflict::ConflictStages,
+    repository::{GitRepository, RepoPath, Stash},
+    status::{FileStatus, StatusCode},
+    Oid,
+};
 use gpui::{
     AppContext, AsyncAppContext, EntityId, EventEmitter, Model, ModelContext, Task, WeakModel,
 };
@@ -28,7 +35,7 @@ use text::ReplicaId;
 use util::{paths::compare_paths, ResultExt};
 use worktree::{Entry, ProjectEntryId, Worktree, WorktreeId, WorktreeSettings};
 
-use crate::{search::SearchQuery, ProjectPath};
+use crate::{search::SearchQuery, NoRepositoryError, ProjectPath};
 
 struct MatchingEntry {
     worktree_path: Arc<Path>,
@@ -75,6 +82,24 @@ impl WorktreeStore {
         client.add_model_request_handler(Self::handle_copy_project_entry);
         client.add_model_request_handler(Self::handle_delete_project_entry);
         client.add_model_request_handler(Self::handle_expand_project_entry);
+        client.add_model_request_handler(Self::handle_stage);
+        client.add_model_request_handler(Self::handle_unstage);
+        client.add_model_request_handler(Self::handle_set_index_text);
+        client.add_model_request_handler(Self::handle_load_committed_text);
+        client.add_model_request_handler(Self::handle_commit);
+        client.add_model_request_handler(Self::handle_get_head_commit_message);
+        client.add_model_request_handler(Self::handle_get_git_status);
+        client.add_model_request_handler(Self::handle_git_log);
+        client.add_model_request_handler(Self::handle_get_commit_details);
+        client.add_model_request_handler(Self::handle_load_conflict_stages);
+        client.add_model_request_handler(Self::handle_load_revision_text);
+        client.add_model_request_handler(Self::handle_git_stash_list);
+        client.add_model_request_handler(Self::handle_git_stash_push);
+        client.add_model_request_handler(Self::handle_git_stash_pop);
+        client.add_model_request_handler(Self::handle_git_stash_drop);
+        client.add_model_request_handler(Self::handle_git_fetch);
+        client.add_model_request_handler(Self::handle_git_pull);
+        client.add_model_request_handler(Self::handle_git_push);
     }
 
     pub fn local(retain_worktrees: bool, fs: Arc<dyn Fs>) -> Self {
@@ -648,6 +673,597 @@ impl WorktreeStore {
         }
     }
 
+    /// Stages the given paths in the git repositories containing them.
+    pub fn stage_paths(&self, paths: Vec<ProjectPath>, cx: &AppContext) -> Task<Result<()>> {
+        self.update_index(paths, true, cx)
+    }
+
+    /// Resets the given paths to their HEAD state in the git repositories containing them.
+    pub fn unstage_paths(&self, paths: Vec<ProjectPath>, cx: &AppContext) -> Task<Result<()>> {
+        self.update_index(paths, false, cx)
+    }
+
+    fn update_index(
+        &self,
+        paths: Vec<ProjectPath>,
+        stage: bool,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            let mut paths_by_worktree = HashMap::<WorktreeId, Vec<String>>::default();
+            for path in paths {
+                paths_by_worktree
+                    .entry(path.worktree_id)
+                    .or_default()
+                    .push(path.path.to_string_lossy().to_string());
+            }
+            return cx.spawn(|_| async move {
+                for (worktree_id, paths) in paths_by_worktree {
+                    let worktree_id = worktree_id.to_proto();
+                    if stage {
+                        client
+                            .request(proto::Stage {
+                                project_id,
+                                worktree_id,
+                                paths,
+                            })
+                            .await?;
+                    } else {
+                        client
+                            .request(proto::Unstage {
+                                project_id,
+                                worktree_id,
+                                paths,
+                            })
+                            .await?;
+                    }
+                }
+                Ok(())
+            });
+        }
+
+        let mut repositories = HashMap::default();
+        for path in paths {
+            match self.local_repository_for_path(&path, cx) {
+                Ok((work_directory_id, repo, repo_path)) => {
+                    repositories
+                        .entry((path.worktree_id, work_directory_id))
+                        .or_insert_with(|| (repo, Vec::new()))
+                        .1
+                        .push(repo_path);
+                }
+                Err(error) => return Task::ready(Err(error)),
+            }
+        }
+        cx.background_executor().spawn(async move {
+            for (repo, repo_paths) in repositories.into_values() {
+                if stage {
+                    repo.stage_paths(&repo_paths)?;
+                } else {
+                    repo.unstage_paths(&repo_paths)?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Overwrites the index entry of the given path, or removes it if `text` is `None`.
+    pub fn set_index_text(
+        &self,
+        path: ProjectPath,
+        text: Option<String>,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            return cx.spawn(|_| async move {
+                client
+                    .request(proto::SetIndexText {
+                        project_id,
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                        text,
+                    })
+                    .await?;
+                Ok(())
+            });
+        }
+
+        let repository = self.local_repository_for_path(&path, cx);
+        cx.background_executor().spawn(async move {
+            let (_, repo, repo_path) = repository?;
+            repo.set_index_text(&repo_path, text)
+        })
+    }
+
+    /// Loads the contents of the given path as of the HEAD commit of its repository.
+    pub fn load_committed_text(
+        &self,
+        path: ProjectPath,
+        cx: &AppContext,
+    ) -> Task<Result<Option<String>>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            return cx.spawn(|_| async move {
+                let response = client
+                    .request(proto::LoadCommittedText {
+                        project_id,
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                    })
+                    .await?;
+                Ok(response.text)
+            });
+        }
+
+        let repository = self.local_repository_for_path(&path, cx);
+        cx.background_executor().spawn(async move {
+            let (_, repo, repo_path) = repository?;
+            Ok(repo.load_committed_text(&repo_path))
+        })
+    }
+
+    /// Commits the staged changes of the repository containing the given path.
+    pub fn commit(
+        &self,
+        path: ProjectPath,
+        message: String,
+        amend: bool,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            return cx.spawn(|_| async move {
+                client
+                    .request(proto::Commit {
+                        project_id,
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                        message,
+                        amend,
+                    })
+                    .await?;
+                Ok(())
+            });
+        }
+
+        let repository = self.local_repository_for_path(&path, cx);
+        cx.background_executor().spawn(async move {
+            let (_, repo, _) = repository?;
+            repo.commit(&message, amend)
+        })
+    }
+
+    /// Returns the message of the HEAD commit of the repository containing the given path.
+    pub fn head_commit_message(
+        &self,
+        path: ProjectPath,
+        cx: &AppContext,
+    ) -> Task<Result<Option<String>>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            return cx.spawn(|_| async move {
+                let response = client
+                    .request(proto::GetHeadCommitMessage {
+                        project_id,
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                    })
+                    .await?;
+                Ok(response.message)
+            });
+        }
+
+        let repository = self.local_repository_for_path(&path, cx);
+        cx.background_executor().spawn(async move {
+            let (_, repo, _) = repository?;
+            Ok(repo.head_commit_message())
+        })
+    }
+
+    /// Returns the changed files of every repository in the given worktree,
+    /// with their paths relative to the worktree root.
+    pub fn git_status(
+        &self,
+        worktree_id: WorktreeId,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<(Arc<Path>, FileStatus)>>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            return cx.spawn(|_| async move {
+                let response = client
+                    .request(proto::GetGitStatus {
+                        project_id,
+                        worktree_id: worktree_id.to_proto(),
+                    })
+                    .await?;
+                Ok(response
+                    .entries
+                    .into_iter()
+                    .map(|entry| {
+                        let status = FileStatus {
+                            index_status: status_code_from_proto(entry.index_status),
+                            worktree_status: status_code_from_proto(entry.worktree_status),
+                        };
+                        (Arc::from(Path::new(&entry.path)), status)
+                    })
+                    .collect())
+            });
+        }
+
+        let Some(worktree) = self.worktree_for_id(worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("worktree not found")));
+        };
+        let Some(worktree) = worktree.read(cx).as_local() else {
+            return Task::ready(Err(anyhow!("worktree is not local")));
+        };
+        let snapshot = worktree.snapshot();
+        let repositories = snapshot
+            .repositories()
+            .filter_map(|(_, repo_entry)| {
+                let local_repo_entry = snapshot.get_local_repo(repo_entry)?;
+                Some((repo_entry.clone(), local_repo_entry.repo().clone()))
+            })
+            .collect::<Vec<_>>();
+        cx.background_executor().spawn(async move {
+            let mut file_statuses = Vec::new();
+            for (repo_entry, repo) in repositories {
+                let status = repo.status(&[PathBuf::from("")])?;
+                file_statuses.extend(status.file_statuses.iter().filter_map(
+                    |(repo_path, file_status)| {
+                        let path = repo_entry.unrelativize(&snapshot, repo_path)?;
+                        Some((path, *file_status))
+                    },
+                ));
+            }
+            file_statuses
+                .sort_by(|(a, _), (b, _)| compare_paths((a.as_ref(), true), (b.as_ref(), true)));
+            Ok(file_statuses)
+        })
+    }
+
+    /// Returns a page of the log of the repository containing the given path, newest commit
+    /// first. If `filter_by_path` is set, only the commits that changed the path are listed.
+    pub fn git_log(
+        &self,
+        path: ProjectPath,
+        filter_by_path: bool,
+        skip: usize,
+        limit: usize,
+        cx: &AppContext,
+    ) -> Task<Result<CommitLog>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            return cx.spawn(|_| async move {
+                let response = client
+                    .request(proto::GitLog {
+                        project_id,
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                        filter_by_path,
+                        skip: skip as u64,
+                        limit: limit as u64,
+                    })
+                    .await?;
+                Ok(CommitLog {
+                    commits: response
+                        .commits
+                        .into_iter()
+                        .map(commit_summary_from_proto)
+                        .collect::<Result<_>>()?,
+                    remote_url: response.remote_url,
+                })
+            });
+        }
+
+        let repository = self.local_repository_for_path(&path, cx);
+        cx.background_executor().spawn(async move {
+            let (_, repo, repo_path) = repository?;
+            let path_filter =
+                (filter_by_path && !repo_path.as_os_str().is_empty()).then_some(&repo_path);
+            Ok(CommitLog {
+                commits: repo.log(path_filter, skip, limit)?,
+                remote_url: repo.remote_url("origin"),
+            })
+        })
+    }
+
+    /// Returns the message of the given commit in the repository containing the given path,
+    /// and the changes it made to a page of its changed files, with paths relative to the
+    /// repository root.
+    pub fn commit_details(
+        &self,
+        path: ProjectPath,
+        sha: Oid,
+        file_offset: usize,
+        file_limit: usize,
+        cx: &AppContext,
+    ) -> Task<Result<CommitDetails>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            return cx.spawn(|_| async move {
+                let response = client
+                    .request(proto::GetCommitDetails {
+                        project_id,
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                        sha: sha.to_string(),
+                        file_offset: file_offset as u64,
+                        file_limit: file_limit as u64,
+                    })
+                    .await?;
+                Ok(CommitDetails {
+                    summary: commit_summary_from_proto(
+                        response.summary.context("missing commit summary")?,
+                    )?,
+                    message: response.message,
+                    changes: response
+                        .changes
+                        .into_iter()
+                        .map(|change| CommitFileChange {
+                            path: RepoPath::from(PathBuf::from(change.path)),
+                            old_text: change.old_text,
+                            new_text: change.new_text,
+                        })
+                        .collect(),
+                    changed_file_count: response.changed_file_count as usize,
+                })
+            });
+        }
+
+        let repository = self.local_repository_for_path(&path, cx);
+        cx.background_executor().spawn(async move {
+            let (_, repo, _) = repository?;
+            repo.commit_details(sha, file_offset, file_limit)
+        })
+    }
+
+    /// Loads the contents of the given conflicted path at the stages of the index,
+    /// or returns `None` if the path is not conflicted.
+    pub fn load_conflict_stages(
+        &self,
+        path: ProjectPath,
+        cx: &AppContext,
+    ) -> Task<Result<Option<ConflictStages>>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            return cx.spawn(|_| async move {
+                let response = client
+                    .request(proto::LoadConflictStages {
+                        project_id,
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                    })
+                    .await?;
+                Ok(response.conflicted.then_some(ConflictStages {
+                    base: response.base,
+                    ours: response.ours,
+                    theirs: response.theirs,
+                }))
+            });
+        }
+
+        let repository = self.local_repository_for_path(&path, cx);
+        cx.background_executor().spawn(async move {
+            let (_, repo, repo_path) = repository?;
+            repo.load_conflict_stages(&repo_path)
+        })
+    }
+
+    /// Loads the contents of the given path as of a revision of its repository.
+    pub fn load_revision_text(
+        &self,
+        path: ProjectPath,
+        revision: String,
+        cx: &AppContext,
+    ) -> Task<Result<Option<String>>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            return cx.spawn(|_| async move {
+                let response = client
+                    .request(proto::LoadRevisionText {
+                        project_id,
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                        revision,
+                    })
+                    .await?;
+                Ok(response.text)
+            });
+        }
+
+        let repository = self.local_repository_for_path(&path, cx);
+        cx.background_executor().spawn(async move {
+            let (_, repo, repo_path) = repository?;
+            repo.load_revision_text(&repo_path, &revision)
+        })
+    }
+
+    /// Returns the stash entries of the repository containing the given path.
+    pub fn stash_list(&self, path: ProjectPath, cx: &AppContext) -> Task<Result<Vec<Stash>>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            return cx.spawn(|_| async move {
+                let response = client
+                    .request(proto::GitStashList {
+                        project_id,
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                    })
+                    .await?;
+                Ok(response
+                    .stashes
+                    .into_iter()
+                    .map(|stash| Stash {
+                        index: stash.index as usize,
+                        message: stash.message,
+                    })
+                    .collect())
+            });
+        }
+
+        let repository = self.local_repository_for_path(&path, cx);
+        cx.background_executor().spawn(async move {
+            let (_, repo, _) = repository?;
+            repo.stash_list()
+        })
+    }
+
+    /// Stashes the local changes of the repository containing the given path.
+    pub fn stash_push(
+        &self,
+        path: ProjectPath,
+        message: Option<String>,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            return cx.spawn(|_| async move {
+                client
+                    .request(proto::GitStashPush {
+                        project_id,
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                        message,
+                    })
+                    .await?;
+                Ok(())
+            });
+        }
+
+        let repository = self.local_repository_for_path(&path, cx);
+        cx.background_executor().spawn(async move {
+            let (_, repo, _) = repository?;
+            repo.stash_push(message.as_deref())
+        })
+    }
+
+    /// Applies and removes a stash entry of the repository containing the given path.
+    pub fn stash_pop(&self, path: ProjectPath, index: usize, cx: &AppContext) -> Task<Result<()>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            return cx.spawn(|_| async move {
+                client
+                    .request(proto::GitStashPop {
+                        project_id,
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                        index: index as u64,
+                    })
+                    .await?;
+                Ok(())
+            });
+        }
+
+        let repository = self.local_repository_for_path(&path, cx);
+        cx.background_executor().spawn(async move {
+            let (_, repo, _) = repository?;
+            repo.stash_pop(index)
+        })
+    }
+
+    /// Removes a stash entry of the repository containing the given path.
+    pub fn stash_drop(&self, path: ProjectPath, index: usize, cx: &AppContext) -> Task<Result<()>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            return cx.spawn(|_| async move {
+                client
+                    .request(proto::GitStashDrop {
+                        project_id,
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                        index: index as u64,
+                    })
+                    .await?;
+                Ok(())
+            });
+        }
+
+        let repository = self.local_repository_for_path(&path, cx);
+        cx.background_executor().spawn(async move {
+            let (_, repo, _) = repository?;
+            repo.stash_drop(index)
+        })
+    }
+
+    /// Fetches the default remote of the repository containing the given path.
+    pub fn fetch(&self, path: ProjectPath, cx: &AppContext) -> Task<Result<()>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            return cx.spawn(|_| async move {
+                client
+                    .request(proto::GitFetch {
+                        project_id,
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                    })
+                    .await?;
+                Ok(())
+            });
+        }
+
+        let repository = self.local_repository_for_path(&path, cx);
+        cx.background_executor().spawn(async move {
+            let (_, repo, _) = repository?;
+            repo.fetch()
+        })
+    }
+
+    /// Pulls the upstream of the current branch of the repository containing the given path.
+    pub fn pull(&self, path: ProjectPath, cx: &AppContext) -> Task<Result<()>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            return cx.spawn(|_| async move {
+                client
+                    .request(proto::GitPull {
+                        project_id,
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                    })
+                    .await?;
+                Ok(())
+            });
+        }
+
+        let repository = self.local_repository_for_path(&path, cx);
+        cx.background_executor().spawn(async move {
+            let (_, repo, _) = repository?;
+            repo.pull()
+        })
+    }
+
+    /// Pushes the current branch of the repository containing the given path to its upstream.
+    pub fn push(&self, path: ProjectPath, cx: &AppContext) -> Task<Result<()>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            return cx.spawn(|_| async move {
+                client
+                    .request(proto::GitPush {
+                        project_id,
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                    })
+                    .await?;
+                Ok(())
+            });
+        }
+
+        let repository = self.local_repository_for_path(&path, cx);
+        cx.background_executor().spawn(async move {
+            let (_, repo, _) = repository?;
+            repo.push()
+        })
+    }
+
+    fn local_repository_for_path(
+        &self,
+        path: &ProjectPath,
+        cx: &AppContext,
+    ) -> Result<(ProjectEntryId, Arc<dyn GitRepository>, RepoPath)> {
+        let worktree = self
+            .worktree_for_id(path.worktree_id, cx)
+            .ok_or_else(|| anyhow!("worktree not found"))?;
+        let worktree = worktree
+            .read(cx)
+            .as_local()
+            .ok_or_else(|| anyhow!("worktree is not local"))?;
+        let (repo_entry, local_repo_entry) = worktree
+            .repo_for_path(&path.path)
+            .ok_or_else(|| anyhow!(NoRepositoryError {}))?;
+        let repo_path = repo_entry
+            .relativize(worktree, &path.path)
+            .context("failed to relativize path")?;
+        Ok((
+            repo_entry.work_directory_id(),
+            local_repo_entry.repo().clone(),
+            repo_path,
+        ))
+    }
+
     /// search over all worktrees and return buffers that *might* match the search.
     pub fn find_search_candidates(
         &self,
@@ -971,6 +1587,396 @@ impl WorktreeStore {
             .ok_or_else(|| anyhow!("invalid request"))?;
         Worktree::handle_expand_entry(worktree, envelope.payload, cx).await
     }
+
+    pub async fn handle_stage(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::Stage>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let paths = envelope
+            .payload
+            .paths
+            .into_iter()
+            .map(|path| ProjectPath {
+                worktree_id,
+                path: Path::new(&path).into(),
+            })
+            .collect();
+        this.update(&mut cx, |this, cx| this.stage_paths(paths, cx))?
+            .await?;
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_unstage(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::Unstage>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let paths = envelope
+            .payload
+            .paths
+            .into_iter()
+            .map(|path| ProjectPath {
+                worktree_id,
+                path: Path::new(&path).into(),
+            })
+            .collect();
+        this.update(&mut cx, |this, cx| this.unstage_paths(paths, cx))?
+            .await?;
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_set_index_text(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::SetIndexText>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: Path::new(&envelope.payload.path).into(),
+        };
+        this.update(&mut cx, |this, cx| {
+            this.set_index_text(path, envelope.payload.text, cx)
+        })?
+        .await?;
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_load_committed_text(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::LoadCommittedText>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::LoadCommittedTextResponse> {
+        let path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: Path::new(&envelope.payload.path).into(),
+        };
+        let text = this
+            .update(&mut cx, |this, cx| this.load_committed_text(path, cx))?
+            .await?;
+        Ok(proto::LoadCommittedTextResponse { text })
+    }
+
+    pub async fn handle_commit(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::Commit>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: Path::new(&envelope.payload.path).into(),
+        };
+        this.update(&mut cx, |this, cx| {
+            this.commit(path, envelope.payload.message, envelope.payload.amend, cx)
+        })?
+        .await?;
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_get_head_commit_message(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GetHeadCommitMessage>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::GetHeadCommitMessageResponse> {
+        let path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: Path::new(&envelope.payload.path).into(),
+        };
+        let message = this
+            .update(&mut cx, |this, cx| this.head_commit_message(path, cx))?
+            .await?;
+        Ok(proto::GetHeadCommitMessageResponse { message })
+    }
+
+    pub async fn handle_get_git_status(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GetGitStatus>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::GetGitStatusResponse> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let file_statuses = this
+            .update(&mut cx, |this, cx| this.git_status(worktree_id, cx))?
+            .await?;
+        Ok(proto::GetGitStatusResponse {
+            entries: file_statuses
+                .into_iter()
+                .map(|(path, status)| proto::GitFileStatusEntry {
+                    path: path.to_string_lossy().to_string(),
+                    index_status: status.index_status.map(status_code_to_proto),
+                    worktree_status: status.worktree_status.map(status_code_to_proto),
+                })
+                .collect(),
+        })
+    }
+
+    pub async fn handle_git_log(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitLog>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::GitLogResponse> {
+        let path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: Path::new(&envelope.payload.path).into(),
+        };
+        let log = this
+            .update(&mut cx, |this, cx| {
+                this.git_log(
+                    path,
+                    envelope.payload.filter_by_path,
+                    envelope.payload.skip as usize,
+                    envelope.payload.limit as usize,
+                    cx,
+                )
+            })?
+            .await?;
+        Ok(proto::GitLogResponse {
+            commits: log
+                .commits
+                .into_iter()
+                .map(commit_summary_to_proto)
+                .collect(),
+            remote_url: log.remote_url,
+        })
+    }
+
+    pub async fn handle_get_commit_details(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GetCommitDetails>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::GetCommitDetailsResponse> {
+        let path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: Path::new(&envelope.payload.path).into(),
+        };
+        let sha = envelope.payload.sha.parse()?;
+        let details = this
+            .update(&mut cx, |this, cx| {
+                this.commit_details(
+                    path,
+                    sha,
+                    envelope.payload.file_offset as usize,
+                    envelope.payload.file_limit as usize,
+                    cx,
+                )
+            })?
+            .await?;
+        Ok(proto::GetCommitDetailsResponse {
+            summary: Some(commit_summary_to_proto(details.summary)),
+            message: details.message,
+            changes: details
+                .changes
+                .into_iter()
+                .map(|change| proto::GitCommitFileChange {
+                    path: change.path.to_string_lossy().to_string(),
+                    old_text: change.old_text,
+                    new_text: change.new_text,
+                })
+                .collect(),
+            changed_file_count: details.changed_file_count as u64,
+        })
+    }
+
+    pub async fn handle_load_conflict_stages(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::LoadConflictStages>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::LoadConflictStagesResponse> {
+        let path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: Path::new(&envelope.payload.path).into(),
+        };
+        let stages = this
+            .update(&mut cx, |this, cx| this.load_conflict_stages(path, cx))?
+            .await?;
+        Ok(match stages {
+            Some(stages) => proto::LoadConflictStagesResponse {
+                conflicted: true,
+                base: stages.base,
+                ours: stages.ours,
+                theirs: stages.theirs,
+            },
+            None => proto::LoadConflictStagesResponse::default(),
+        })
+    }
+
+    pub async fn handle_load_revision_text(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::LoadRevisionText>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::LoadRevisionTextResponse> {
+        let path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: Path::new(&envelope.payload.path).into(),
+        };
+        let text = this
+            .update(&mut cx, |this, cx| {
+                this.load_revision_text(path, envelope.payload.revision, cx)
+            })?
+            .await?;
+        Ok(proto::LoadRevisionTextResponse { text })
+    }
+
+    pub async fn handle_git_stash_list(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitStashList>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::GitStashListResponse> {
+        let path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: Path::new(&envelope.payload.path).into(),
+        };
+        let stashes = this
+            .update(&mut cx, |this, cx| this.stash_list(path, cx))?
+            .await?;
+        Ok(proto::GitStashListResponse {
+            stashes: stashes
+                .into_iter()
+                .map(|stash| proto::GitStashEntry {
+                    index: stash.index as u64,
+                    message: stash.message,
+                })
+                .collect(),
+        })
+    }
+
+    pub async fn handle_git_stash_push(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitStashPush>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: Path::new(&envelope.payload.path).into(),
+        };
+        this.update(&mut cx, |this, cx| {
+            this.stash_push(path, envelope.payload.message, cx)
+        })?
+        .await?;
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_git_stash_pop(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitStashPop>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: Path::new(&envelope.payload.path).into(),
+        };
+        this.update(&mut cx, |this, cx| {
+            this.stash_pop(path, envelope.payload.index as usize, cx)
+        })?
+        .await?;
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_git_stash_drop(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitStashDrop>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: Path::new(&envelope.payload.path).into(),
+        };
+        this.update(&mut cx, |this, cx| {
+            this.stash_drop(path, envelope.payload.index as usize, cx)
+        })?
+        .await?;
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_git_fetch(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitFetch>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: Path::new(&envelope.payload.path).into(),
+        };
+        this.update(&mut cx, |this, cx| this.fetch(path, cx))?
+            .await?;
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_git_pull(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitPull>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: Path::new(&envelope.payload.path).into(),
+        };
+        this.update(&mut cx, |this, cx| this.pull(path, cx))?
+            .await?;
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_git_push(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitPush>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: Path::new(&envelope.payload.path).into(),
+        };
+        this.update(&mut cx, |this, cx| this.push(path, cx))?
+            .await?;
+        Ok(proto::Ack {})
+    }
+}
+
+fn commit_summary_to_proto(summary: CommitSummary) -> proto::GitCommitSummary {
+    proto::GitCommitSummary {
+        sha: summary.sha.to_string(),
+        author_name: summary.author_name,
+        author_email: summary.author_email,
+        timestamp: summary.timestamp,
+        subject: summary.subject,
+    }
+}
+
+fn commit_summary_from_proto(summary: proto::GitCommitSummary) -> Result<CommitSummary> {
+    Ok(CommitSummary {
+        sha: summary.sha.parse()?,
+        author_name: summary.author_name,
+        author_email: summary.author_email,
+        timestamp: summary.timestamp,
+        subject: summary.subject,
+    })
+}
+
+fn status_code_from_proto(code: Option<i32>) -> Option<StatusCode> {
+    use proto::git_file_status_entry::Code;
+    code.and_then(Code::from_i32).map(|code| match code {
+        Code::Modified => StatusCode::Modified,
+        Code::Added => StatusCode::Added,
+        Code::Deleted => StatusCode::Deleted,
+        Code::Renamed => StatusCode::Renamed,
+        Code::Copied => StatusCode::Copied,
+        Code::Unmerged => StatusCode::Unmerged,
+        Code::Untracked => StatusCode::Untracked,
+    })
+}
+
+fn status_code_to_proto(code: StatusCode) -> i32 {
+    use proto::git_file_status_entry::Code;
+    let code = match code {
+        StatusCode::Modified => Code::Modified,
+        StatusCode::Added => Code::Added,
+        StatusCode::Deleted => Code::Deleted,
+        StatusCode::Renamed => Code::Renamed,
+        StatusCode::Copied => Code::Copied,
+        StatusCode::Unmerged => Code::Unmerged,
+        StatusCode::Untracked => Code::Untracked,
+    };
+    code as i32
 }
 
 #[derive(Clone, Debug)]
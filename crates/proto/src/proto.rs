@@ -373,6 +373,49 @@ messages!(
     (GetPermalinkToLine, Foreground),
     (GetPermalinkToLineResponse, Foreground),
     (FlushBufferedMessages, Foreground),
+    (Stage, Foreground),
+    (Unstage, Foreground),
+    (SetIndexText, Foreground),
+    (LoadCommittedText, Foreground),
+    (LoadCommittedTextResponse, Foreground),
+    (Commit, Foreground),
+    (GetHeadCommitMessage, Foreground),
+    (GetHeadCommitMessageResponse, Foreground),
+    (GetGitStatus, Foreground),
+    (GetGitStatusResponse, Foreground),
+    (PrepareCallHierarchy, Background),
+    (PrepareCallHierarchyResponse, Background),
+    (GetIncomingCalls, Background),
+    (GetIncomingCallsResponse, Background),
+    (GetOutgoingCalls, Background),
+    (GetOutgoingCallsResponse, Background),
+    (GetSemanticTokens, Background),
+    (GetSemanticTokensResponse, Background),
+    (RefreshSemanticTokens, Foreground),
+    (GetCodeLens, Background),
+    (GetCodeLensResponse, Background),
+    (PrepareTypeHierarchy, Background),
+    (PrepareTypeHierarchyResponse, Background),
+    (GetSupertypes, Background),
+    (GetSupertypesResponse, Background),
+    (GetSubtypes, Background),
+    (GetSubtypesResponse, Background),
+    (GitLog, Foreground),
+    (GitLogResponse, Foreground),
+    (GetCommitDetails, Foreground),
+    (GetCommitDetailsResponse, Foreground),
+    (LoadConflictStages, Foreground),
+    (LoadConflictStagesResponse, Foreground),
+    (LoadRevisionText, Foreground),
+    (LoadRevisionTextResponse, Foreground),
+    (GitStashList, Foreground),
+    (GitStashListResponse, Foreground),
+    (GitStashPush, Foreground),
+    (GitStashPop, Foreground),
+    (GitStashDrop, Foreground),
+    (GitFetch, Foreground),
+    (GitPull, Foreground),
+    (GitPush, Foreground),
 );
 
 request_messages!(
@@ -500,6 +543,33 @@ request_messages!(
     (OpenServerSettings, OpenBufferResponse),
     (GetPermalinkToLine, GetPermalinkToLineResponse),
     (FlushBufferedMessages, Ack),
+    (Stage, Ack),
+    (Unstage, Ack),
+    (SetIndexText, Ack),
+    (LoadCommittedText, LoadCommittedTextResponse),
+    (Commit, Ack),
+    (GetHeadCommitMessage, GetHeadCommitMessageResponse),
+    (GetGitStatus, GetGitStatusResponse),
+    (PrepareCallHierarchy, PrepareCallHierarchyResponse),
+    (GetIncomingCalls, GetIncomingCallsResponse),
+    (GetOutgoingCalls, GetOutgoingCallsResponse),
+    (GetSemanticTokens, GetSemanticTokensResponse),
+    (RefreshSemanticTokens, Ack),
+    (GetCodeLens, GetCodeLensResponse),
+    (PrepareTypeHierarchy, PrepareTypeHierarchyResponse),
+    (GetSupertypes, GetSupertypesResponse),
+    (GetSubtypes, GetSubtypesResponse),
+    (GitLog, GitLogResponse),
+    (GetCommitDetails, GetCommitDetailsResponse),
+    (LoadConflictStages, LoadConflictStagesResponse),
+    (LoadRevisionText, LoadRevisionTextResponse),
+    (GitStashList, GitStashListResponse),
+    (GitStashPush, Ack),
+    (GitStashPop, Ack),
+    (GitStashDrop, Ack),
+    (GitFetch, Ack),
+    (GitPull, Ack),
+    (GitPush, Ack),
 );
 
 entity_messages!(
@@ -577,6 +647,33 @@ entity_messages!(
     HideToast,
     OpenServerSettings,
     GetPermalinkToLine,
+    Stage,
+    Unstage,
+    SetIndexText,
+    LoadCommittedText,
+    Commit,
+    GetHeadCommitMessage,
+    GetGitStatus,
+    PrepareCallHierarchy,
+    GetIncomingCalls,
+    GetOutgoingCalls,
+    GetSemanticTokens,
+    RefreshSemanticTokens,
+    GetCodeLens,
+    PrepareTypeHierarchy,
+    GetSupertypes,
+    GetSubtypes,
+    GitLog,
+    GetCommitDetails,
+    LoadConflictStages,
+    LoadRevisionText,
+    GitStashList,
+    GitStashPush,
+    GitStashPop,
+    GitStashDrop,
+    GitFetch,
+    GitPull,
+    GitPush,
 );
 
 entity_messages!(
@@ -1370,6 +1370,7 @@ pub async fn spawn_ssh_task(
                     hide: HideStrategy::Never,
                     env: Default::default(),
                     shell: Default::default(),
+                    problem_matchers: Vec::new(),
                 },
                 cx,
             )
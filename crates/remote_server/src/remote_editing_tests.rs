@@ -2,6 +2,10 @@ use crate::headless_project::HeadlessProject;
 use client::{Client, UserStore};
 use clock::FakeSystemClock;
 use fs::{FakeFs, Fs};
+use git::{
+    commit::{CommitDetails, CommitFileChange, CommitSummary},
+    repository::RepoPath,
+};
 use gpui::{Context, Model, TestAppContext};
 use http_client::{BlockedHttpClient, FakeHttpClient};
 use language::{
@@ -682,6 +686,81 @@ async fn test_reconnect(cx: &mut TestAppContext, server_cx: &mut TestAppContext)
     );
 }
 
+#[gpui::test]
+async fn test_remote_git_log(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let (project, _headless, fs) = init_test(cx, server_cx).await;
+    let sha: git::Oid = "1111111111111111111111111111111111111111".parse().unwrap();
+    fs.set_commits_for_repo(
+        Path::new("/code/project1/.git"),
+        vec![CommitDetails {
+            summary: CommitSummary {
+                sha,
+                author_name: "Jane Doe".into(),
+                author_email: "jane@example.com".into(),
+                timestamp: 1700000000,
+                subject: "Add one".into(),
+            },
+            message: "Add one\n\nIt returns one.\n".into(),
+            changes: vec![
+                CommitFileChange {
+                    path: RepoPath::from(Path::new("README.md")),
+                    old_text: None,
+                    new_text: Some("# project 1".into()),
+                },
+                CommitFileChange {
+                    path: RepoPath::from(Path::new("src/lib.rs")),
+                    old_text: Some("fn one() -> usize { 0 }".into()),
+                    new_text: Some("fn one() -> usize { 1 }".into()),
+                },
+            ],
+            changed_file_count: 2,
+        }],
+    );
+
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree("/code/project1", true, cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    let path = ProjectPath {
+        worktree_id: worktree.read_with(cx, |worktree, _| worktree.id()),
+        path: Path::new("src/lib.rs").into(),
+    };
+
+    let log = project
+        .update(cx, |project, cx| {
+            project.git_log(path.clone(), true, 0, 10, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        log.commits
+            .iter()
+            .map(|commit| (commit.sha, commit.subject.as_str()))
+            .collect::<Vec<_>>(),
+        [(sha, "Add one")]
+    );
+
+    let details = project
+        .update(cx, |project, cx| {
+            project.commit_details(path, sha, 1, 10, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(details.message, "Add one\n\nIt returns one.\n");
+    assert_eq!(details.changed_file_count, 2);
+    assert_eq!(
+        details.changes,
+        [CommitFileChange {
+            path: RepoPath::from(Path::new("src/lib.rs")),
+            old_text: Some("fn one() -> usize { 0 }".into()),
+            new_text: Some("fn one() -> usize { 1 }".into()),
+        }]
+    );
+}
+
 fn init_logger() {
     if std::env::var("RUST_LOG").is_ok() {
         env_logger::try_init().ok();
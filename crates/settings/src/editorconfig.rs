@@ -0,0 +1,397 @@
+use collections::HashMap;
+use regex::Regex;
+use std::{path::Path, sync::Arc};
+
+/// The largest `{num1..num2}` range in a section name that gets expanded. Larger ranges are
+/// treated as literal text.
+const MAX_NUMERIC_RANGE_LEN: i64 = 1024;
+
+/// A parsed `.editorconfig` file, as described on https://editorconfig.org.
+#[derive(Debug, Clone, Default)]
+pub struct Editorconfig {
+    root: bool,
+    sections: Vec<EditorconfigSection>,
+}
+
+#[derive(Debug, Clone)]
+struct EditorconfigSection {
+    matcher: Regex,
+    properties: Vec<(String, String)>,
+}
+
+/// The properties that apply to a file, merged from all the `.editorconfig` files in its
+/// ancestor directories.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EditorconfigProperties {
+    properties: HashMap<String, EditorconfigProperty>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditorconfigProperty {
+    /// The value of the property, lowercased.
+    pub value: String,
+    /// The directory containing the `.editorconfig` file that set the property.
+    pub directory: Arc<Path>,
+}
+
+impl Editorconfig {
+    /// Parses the contents of an `.editorconfig` file. Lines that can't be parsed, and sections
+    /// with invalid globs, are ignored, as the specification asks for.
+    pub fn parse(content: &str) -> Self {
+        let mut editorconfig = Self::default();
+        let mut current_section: Option<EditorconfigSection> = None;
+        let mut in_preamble = true;
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(glob) = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+            {
+                in_preamble = false;
+                editorconfig.sections.extend(current_section.take());
+                current_section = glob_to_regex(glob).map(|matcher| EditorconfigSection {
+                    matcher,
+                    properties: Vec::new(),
+                });
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            let value = value.trim().to_lowercase();
+            if key.is_empty() {
+                continue;
+            }
+
+            if in_preamble {
+                if key == "root" {
+                    editorconfig.root = value == "true";
+                }
+            } else if let Some(section) = current_section.as_mut() {
+                section.properties.push((key, value));
+            }
+        }
+        editorconfig.sections.extend(current_section);
+
+        editorconfig
+    }
+
+    /// Whether the file stops the lookup of `.editorconfig` files in parent directories.
+    pub fn is_root(&self) -> bool {
+        self.root
+    }
+
+    /// Adds the properties of the sections matching the given path, relative to the
+    /// `.editorconfig` file's directory, to the given properties.
+    pub fn apply(
+        &self,
+        relative_path: &Path,
+        directory: &Arc<Path>,
+        properties: &mut EditorconfigProperties,
+    ) {
+        let relative_path = relative_path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        for section in &self.sections {
+            if !section.matcher.is_match(&relative_path) {
+                continue;
+            }
+            for (key, value) in &section.properties {
+                if value == "unset" {
+                    properties.properties.remove(key);
+                } else {
+                    properties.properties.insert(
+                        key.clone(),
+                        EditorconfigProperty {
+                            value: value.clone(),
+                            directory: directory.clone(),
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl EditorconfigProperties {
+    pub fn get(&self, key: &str) -> Option<&EditorconfigProperty> {
+        self.properties.get(key)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+    }
+}
+
+/// Translates the glob of an `.editorconfig` section into a regex matching paths relative to the
+/// `.editorconfig` file's directory.
+fn glob_to_regex(glob: &str) -> Option<Regex> {
+    let chars = glob.chars().collect::<Vec<_>>();
+    let mut pattern = String::from("^");
+    if glob.contains('/') {
+        let start = if chars.first() == Some(&'/') { 1 } else { 0 };
+        translate_glob(&chars[start..], &mut pattern);
+    } else {
+        // Globs without a separator match files with that name in any subdirectory.
+        pattern.push_str("(?:.*/)?");
+        translate_glob(&chars, &mut pattern);
+    }
+    pattern.push('$');
+    Regex::new(&pattern).ok()
+}
+
+fn translate_glob(chars: &[char], pattern: &mut String) {
+    let mut ix = 0;
+    while ix < chars.len() {
+        match chars[ix] {
+            '\\' if ix + 1 < chars.len() => {
+                ix += 1;
+                push_literal(chars[ix], pattern);
+            }
+            '*' if chars.get(ix + 1) == Some(&'*') => {
+                ix += 1;
+                if chars.get(ix + 1) == Some(&'/') {
+                    ix += 1;
+                    pattern.push_str("(?:.*/)?");
+                } else {
+                    pattern.push_str(".*");
+                }
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            '[' => match class_end(chars, ix) {
+                Some(end) => {
+                    let mut class = &chars[ix + 1..end];
+                    pattern.push('[');
+                    if let Some(('!' | '^', rest)) = class.split_first() {
+                        pattern.push('^');
+                        class = rest;
+                    }
+                    for &c in class {
+                        if c == '-' {
+                            pattern.push('-');
+                        } else {
+                            pattern.push_str(&regex::escape(&c.to_string()));
+                        }
+                    }
+                    pattern.push(']');
+                    ix = end;
+                }
+                None => push_literal('[', pattern),
+            },
+            '{' => match brace_end(chars, ix) {
+                Some(end) => {
+                    let content = &chars[ix + 1..end];
+                    let alternatives = split_alternatives(content);
+                    if alternatives.len() > 1 {
+                        pattern.push_str("(?:");
+                        for (alternative_ix, alternative) in alternatives.iter().enumerate() {
+                            if alternative_ix > 0 {
+                                pattern.push('|');
+                            }
+                            translate_glob(alternative, pattern);
+                        }
+                        pattern.push(')');
+                    } else if let Some(numbers) = numeric_range(content) {
+                        pattern.push_str("(?:");
+                        pattern.push_str(&numbers.join("|"));
+                        pattern.push(')');
+                    } else {
+                        push_literal('{', pattern);
+                        translate_glob(content, pattern);
+                        push_literal('}', pattern);
+                    }
+                    ix = end;
+                }
+                None => push_literal('{', pattern),
+            },
+            c => push_literal(c, pattern),
+        }
+        ix += 1;
+    }
+}
+
+fn push_literal(c: char, pattern: &mut String) {
+    pattern.push_str(&regex::escape(&c.to_string()));
+}
+
+/// Returns the index of the `]` closing the character class starting at `start`. Classes can't
+/// contain path separators.
+fn class_end(chars: &[char], start: usize) -> Option<usize> {
+    let first = start + 1;
+    for (ix, &c) in chars.iter().enumerate().skip(first) {
+        match c {
+            '/' => return None,
+            ']' if ix > first => return Some(ix),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the index of the `}` matching the `{` at `start`.
+fn brace_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut ix = start;
+    while ix < chars.len() {
+        match chars[ix] {
+            '\\' => ix += 1,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(ix);
+                }
+            }
+            _ => {}
+        }
+        ix += 1;
+    }
+    None
+}
+
+/// Splits the content of a brace expansion at its top-level commas.
+fn split_alternatives(content: &[char]) -> Vec<&[char]> {
+    let mut alternatives = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    let mut ix = 0;
+    while ix < content.len() {
+        match content[ix] {
+            '\\' => ix += 1,
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                alternatives.push(&content[start..ix]);
+                start = ix + 1;
+            }
+            _ => {}
+        }
+        ix += 1;
+    }
+    alternatives.push(&content[start..]);
+    alternatives
+}
+
+/// Expands a `num1..num2` brace expansion into the numbers it matches.
+fn numeric_range(content: &[char]) -> Option<Vec<String>> {
+    let content = content.iter().collect::<String>();
+    let (start, end) = content.split_once("..")?;
+    let start = start.parse::<i64>().ok()?;
+    let end = end.parse::<i64>().ok()?;
+    let (start, end) = (start.min(end), start.max(end));
+    if end - start >= MAX_NUMERIC_RANGE_LEN {
+        return None;
+    }
+    Some((start..=end).map(|number| number.to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unindent::Unindent as _;
+
+    fn properties_for(editorconfig: &Editorconfig, path: &str) -> Vec<(String, String)> {
+        let mut properties = EditorconfigProperties::default();
+        editorconfig.apply(Path::new(path), &Path::new("").into(), &mut properties);
+        let mut properties = properties
+            .properties
+            .into_iter()
+            .map(|(key, property)| (key, property.value))
+            .collect::<Vec<_>>();
+        properties.sort();
+        properties
+    }
+
+    fn matches(glob: &str, path: &str) -> bool {
+        glob_to_regex(glob).unwrap().is_match(path)
+    }
+
+    #[test]
+    fn test_parse_editorconfig() {
+        let editorconfig = Editorconfig::parse(
+            &r#"
+            # top-most EditorConfig file
+            root = true
+
+            [*]
+            end_of_line = LF
+            insert_final_newline = true
+
+            ; Python files
+            [*.py]
+            indent_style = space
+            indent_size = 4
+
+            [Makefile]
+            indent_style = tab
+            insert_final_newline = unset
+
+            not a property
+            "#
+            .unindent(),
+        );
+
+        assert!(editorconfig.is_root());
+        assert_eq!(
+            properties_for(&editorconfig, "src/main.py"),
+            vec![
+                ("end_of_line".into(), "lf".into()),
+                ("indent_size".into(), "4".into()),
+                ("indent_style".into(), "space".into()),
+                ("insert_final_newline".into(), "true".into()),
+            ]
+        );
+        assert_eq!(
+            properties_for(&editorconfig, "Makefile"),
+            vec![
+                ("end_of_line".into(), "lf".into()),
+                ("indent_style".into(), "tab".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_editorconfig_globs() {
+        assert!(matches("*.rs", "main.rs"));
+        assert!(matches("*.rs", "src/main.rs"));
+        assert!(!matches("*.rs", "main.rs.bak"));
+
+        assert!(matches("src/*.rs", "src/main.rs"));
+        assert!(matches("/src/*.rs", "src/main.rs"));
+        assert!(!matches("src/*.rs", "src/bin/main.rs"));
+        assert!(!matches("src/*.rs", "crates/src/main.rs"));
+
+        assert!(matches("src/**.rs", "src/bin/main.rs"));
+        assert!(matches("src/**/main.rs", "src/main.rs"));
+        assert!(matches("src/**/main.rs", "src/a/b/main.rs"));
+
+        assert!(matches("file?.txt", "file1.txt"));
+        assert!(!matches("file?.txt", "file12.txt"));
+
+        assert!(matches("*.{js,ts}", "index.ts"));
+        assert!(matches("{package.json,.travis.yml}", ".travis.yml"));
+        assert!(!matches("*.{js,ts}", "index.rs"));
+        assert!(matches("{a,{b,c}}.txt", "c.txt"));
+
+        assert!(matches("[abc].txt", "b.txt"));
+        assert!(!matches("[!abc].txt", "b.txt"));
+        assert!(matches("[a-c].txt", "c.txt"));
+
+        assert!(matches("file{1..3}.txt", "file2.txt"));
+        assert!(!matches("file{1..3}.txt", "file4.txt"));
+        assert!(matches("{single}.txt", "{single}.txt"));
+        assert!(matches("a\\*.txt", "a*.txt"));
+        assert!(!matches("a\\*.txt", "ab.txt"));
+    }
+}
@@ -1,4 +1,5 @@
 mod editable_setting_control;
+mod editorconfig;
 mod json_schema;
 mod keymap_file;
 mod settings_file;
@@ -10,6 +11,7 @@ use std::{borrow::Cow, fmt, str};
 use util::asset_str;
 
 pub use editable_setting_control::*;
+pub use editorconfig::{Editorconfig, EditorconfigProperties, EditorconfigProperty};
 pub use json_schema::*;
 pub use keymap_file::KeymapFile;
 pub use settings_file::*;
@@ -11,14 +11,14 @@ use std::{
     any::{type_name, Any, TypeId},
     fmt::Debug,
     ops::Range,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
     str,
     sync::{Arc, LazyLock},
 };
 use tree_sitter::Query;
 use util::{merge_non_null_json_value_into, RangeExt, ResultExt as _};
 
-use crate::{SettingsJsonSchemaParams, WorktreeId};
+use crate::{Editorconfig, EditorconfigProperties, SettingsJsonSchemaParams, WorktreeId};
 
 /// A value that can be defined as a user setting.
 ///
@@ -169,6 +169,8 @@ pub struct SettingsStore {
     raw_extension_settings: serde_json::Value,
     raw_local_settings:
         BTreeMap<(WorktreeId, Arc<Path>), HashMap<LocalSettingsKind, serde_json::Value>>,
+    raw_editorconfig_settings: BTreeMap<(WorktreeId, Arc<Path>), (String, Arc<Editorconfig>)>,
+    editorconfig_chains: HashMap<WorktreeId, HashMap<Arc<Path>, Arc<[EditorconfigChainEntry]>>>,
     tab_size_callback: Option<(
         TypeId,
         Box<dyn Fn(&dyn Any) -> Option<usize> + Send + Sync + 'static>,
@@ -179,6 +181,18 @@ pub struct SettingsStore {
     >,
 }
 
+/// An `.editorconfig` file that applies to the files in a worktree directory.
+#[derive(Clone, Debug)]
+struct EditorconfigChainEntry {
+    /// The directory of the file, relative to the worktree root. Files above the worktree root
+    /// use the root itself.
+    directory: Arc<Path>,
+    /// For files above the worktree root, the path of the worktree root relative to the file's
+    /// directory.
+    root_prefix: Option<PathBuf>,
+    editorconfig: Arc<Editorconfig>,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum LocalSettingsKind {
     Settings,
@@ -226,6 +240,8 @@ impl SettingsStore {
             raw_server_settings: None,
             raw_extension_settings: serde_json::json!({}),
             raw_local_settings: Default::default(),
+            raw_editorconfig_settings: Default::default(),
+            editorconfig_chains: Default::default(),
             tab_size_callback: Default::default(),
             setting_file_updates_tx,
             _setting_file_updates: cx.spawn(|cx| async move {
@@ -572,6 +588,29 @@ impl SettingsStore {
             "Attempted to submit tasks into the settings store"
         );
 
+        // `.editorconfig` files are resolved per file when reading settings, so only the
+        // worktree's chains of files need to be recomputed.
+        //
+        // The files above the worktree root are stored under a directory starting with `..`
+        // components, followed by the path back down to the worktree root. For example, a file in
+        // the parent of the `project` worktree's directory is stored under `../project`.
+        if kind == LocalSettingsKind::Editorconfig {
+            match settings_content.filter(|content| !content.is_empty()) {
+                Some(content) => {
+                    self.raw_editorconfig_settings.insert(
+                        (root_id, directory_path),
+                        (content.to_string(), Arc::new(Editorconfig::parse(content))),
+                    );
+                }
+                None => {
+                    self.raw_editorconfig_settings
+                        .remove(&(root_id, directory_path));
+                }
+            }
+            self.recompute_editorconfig_chains(root_id);
+            return Ok(());
+        }
+
         let raw_local_settings = self
             .raw_local_settings
             .entry((root_id, directory_path.clone()))
@@ -618,6 +657,9 @@ impl SettingsStore {
     pub fn clear_local_settings(&mut self, root_id: WorktreeId, cx: &mut AppContext) -> Result<()> {
         self.raw_local_settings
             .retain(|(worktree_id, _), _| worktree_id != &root_id);
+        self.raw_editorconfig_settings
+            .retain(|(worktree_id, _), _| worktree_id != &root_id);
+        self.editorconfig_chains.remove(&root_id);
         self.recompute_values(Some((root_id, "".as_ref())), cx)?;
         Ok(())
     }
@@ -640,6 +682,156 @@ impl SettingsStore {
                     Some((path.clone(), kind, parsed_content))
                 })
             })
+            .chain(
+                self.raw_editorconfig_settings
+                    .range(
+                        (root_id, Path::new("").into())
+                            ..(
+                                WorktreeId::from_usize(root_id.to_usize() + 1),
+                                Path::new("").into(),
+                            ),
+                    )
+                    .map(|((_, path), (content, _))| {
+                        (
+                            path.clone(),
+                            LocalSettingsKind::Editorconfig,
+                            content.clone(),
+                        )
+                    }),
+            )
+    }
+
+    /// Returns the properties from the `.editorconfig` files that apply to the file at the given
+    /// path, with the innermost files taking precedence.
+    pub fn editorconfig_properties(
+        &self,
+        root_id: WorktreeId,
+        path: &Path,
+    ) -> Option<EditorconfigProperties> {
+        let chains = self.editorconfig_chains.get(&root_id)?;
+        let chain = path
+            .ancestors()
+            .find_map(|directory| chains.get(directory))?;
+
+        let mut properties = EditorconfigProperties::default();
+        for entry in chain.iter() {
+            match &entry.root_prefix {
+                Some(root_prefix) => entry.editorconfig.apply(
+                    &root_prefix.join(path),
+                    &entry.directory,
+                    &mut properties,
+                ),
+                None => {
+                    let relative_path = path.strip_prefix(&entry.directory).unwrap_or(path);
+                    entry
+                        .editorconfig
+                        .apply(relative_path, &entry.directory, &mut properties);
+                }
+            }
+        }
+
+        (!properties.is_empty()).then_some(properties)
+    }
+
+    /// Recomputes, for every directory of the worktree containing an `.editorconfig` file, the
+    /// files that apply to the files in that directory, from the outermost to the innermost.
+    fn recompute_editorconfig_chains(&mut self, root_id: WorktreeId) {
+        let mut external = Vec::new();
+        let mut local = Vec::new();
+        for ((_, directory), (_, editorconfig)) in self.raw_editorconfig_settings.range(
+            (root_id, Path::new("").into())
+                ..(
+                    WorktreeId::from_usize(root_id.to_usize() + 1),
+                    Path::new("").into(),
+                ),
+        ) {
+            let parent_count = directory
+                .components()
+                .take_while(|component| *component == Component::ParentDir)
+                .count();
+            if parent_count == 0 {
+                local.push(EditorconfigChainEntry {
+                    directory: directory.clone(),
+                    root_prefix: None,
+                    editorconfig: editorconfig.clone(),
+                });
+            } else {
+                let root_prefix = directory.components().skip(parent_count).collect();
+                external.push((
+                    parent_count,
+                    EditorconfigChainEntry {
+                        directory: Path::new("").into(),
+                        root_prefix: Some(root_prefix),
+                        editorconfig: editorconfig.clone(),
+                    },
+                ));
+            }
+        }
+        external.sort_by_key(|(parent_count, _)| std::cmp::Reverse(*parent_count));
+        let external = external
+            .into_iter()
+            .map(|(_, entry)| entry)
+            .collect::<Vec<_>>();
+
+        let mut directories = local
+            .iter()
+            .map(|entry| entry.directory.clone())
+            .collect::<Vec<_>>();
+        if !external.is_empty()
+            && directories
+                .first()
+                .map_or(true, |dir| !dir.as_os_str().is_empty())
+        {
+            directories.insert(0, Path::new("").into());
+        }
+
+        let mut chains = HashMap::default();
+        for directory in directories {
+            let mut chain = external
+                .iter()
+                .chain(
+                    local
+                        .iter()
+                        .filter(|entry| directory.starts_with(&entry.directory)),
+                )
+                .cloned()
+                .collect::<Vec<_>>();
+            // Files in directories above the innermost `root = true` file are ignored.
+            if let Some(first_applied) =
+                chain.iter().rposition(|entry| entry.editorconfig.is_root())
+            {
+                chain.drain(..first_applied);
+            }
+            chains.insert(directory, chain.into());
+        }
+
+        if chains.is_empty() {
+            self.editorconfig_chains.remove(&root_id);
+        } else {
+            self.editorconfig_chains.insert(root_id, chains);
+        }
+    }
+
+    /// Returns the contents of the local settings files that apply to the file at the given path
+    /// and are located in the given directory or below it.
+    pub fn local_settings_within<'a>(
+        &'a self,
+        root_id: WorktreeId,
+        path: &'a Path,
+        directory: &'a Path,
+    ) -> impl 'a + Iterator<Item = &'a serde_json::Value> {
+        self.raw_local_settings
+            .range(
+                (root_id, directory.into())
+                    ..(
+                        WorktreeId::from_usize(root_id.to_usize() + 1),
+                        Path::new("").into(),
+                    ),
+            )
+            .filter(move |((_, settings_directory), _)| {
+                settings_directory.starts_with(directory) && path.starts_with(settings_directory)
+            })
+            .filter_map(|(_, settings)| settings.get(&LocalSettingsKind::Settings))
     }
 
     pub fn json_schema(
@@ -1,6 +1,10 @@
 use anyhow::{anyhow, Context, Result};
+use regex::{Captures, Regex, RegexBuilder};
 use smallvec::SmallVec;
-use std::{collections::BTreeMap, ops::Range};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Range,
+};
 
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Snippet {
@@ -8,15 +12,101 @@ pub struct Snippet {
     pub tabstops: Vec<TabStop>,
 }
 
-type TabStop = SmallVec<[Range<isize>; 2]>;
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TabStop {
+    pub ranges: SmallVec<[Range<isize>; 2]>,
+    /// The values to choose from, when the tabstop is a choice like `${1|one,two|}`.
+    pub choices: Option<Vec<String>>,
+    /// The transforms of mirrors like `${1/(.*)/${1:/upcase}/}`, along with the index of the range
+    /// they apply to. They are applied to the text of that range when leaving the tabstop.
+    pub transforms: Vec<(usize, Transform)>,
+}
 
 impl Snippet {
+    /// Parses the snippet without resolving its variables, which are replaced with their default
+    /// value or their name.
     pub fn parse(source: &str) -> Result<Self> {
-        let mut text = String::with_capacity(source.len());
-        let mut tabstops = BTreeMap::new();
-        parse_snippet(source, false, &mut text, &mut tabstops)
+        Self::parse_internal(source, &|_: &str| None, false)
+    }
+
+    /// Parses the snippet, resolving variables like `$TM_FILENAME` with the given function.
+    /// Variables for which it returns `None` are unknown, and become placeholders holding their
+    /// default value or their name.
+    pub fn parse_with_variables(
+        source: &str,
+        variables: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self> {
+        Self::parse_internal(source, &variables, true)
+    }
+
+    fn parse_internal(
+        source: &str,
+        variables: &dyn Fn(&str) -> Option<String>,
+        unknown_variables_as_placeholders: bool,
+    ) -> Result<Self> {
+        let mut parser = SnippetParser::new(variables, HashMap::default());
+        parser
+            .parse_snippet(source, false)
             .context("failed to parse snippet")?;
 
+        // Transformed mirrors show the transformed text of their placeholder, which may only appear
+        // later in the snippet, so we parse it again once the text of all placeholders is known.
+        if parser
+            .tabstops
+            .values()
+            .any(|tabstop| !tabstop.transforms.is_empty())
+        {
+            let placeholder_texts = parser.placeholder_texts();
+            parser = SnippetParser::new(variables, placeholder_texts);
+            parser
+                .parse_snippet(source, false)
+                .context("failed to parse snippet")?;
+        }
+
+        Ok(parser.finish(unknown_variables_as_placeholders))
+    }
+}
+
+struct SnippetParser<'a> {
+    variables: &'a dyn Fn(&str) -> Option<String>,
+    placeholder_texts: HashMap<usize, String>,
+    text: String,
+    tabstops: BTreeMap<usize, TabStop>,
+    /// The ranges of unknown variables, which become tabstops after all the others.
+    unknown_variables: Vec<Range<isize>>,
+}
+
+impl<'a> SnippetParser<'a> {
+    fn new(
+        variables: &'a dyn Fn(&str) -> Option<String>,
+        placeholder_texts: HashMap<usize, String>,
+    ) -> Self {
+        Self {
+            variables,
+            placeholder_texts,
+            text: String::new(),
+            tabstops: BTreeMap::new(),
+            unknown_variables: Vec::new(),
+        }
+    }
+
+    fn finish(self, unknown_variables_as_placeholders: bool) -> Snippet {
+        let text = self.text;
+        let mut tabstops = self.tabstops;
+
+        if unknown_variables_as_placeholders {
+            let next_index = tabstops.keys().last().map_or(1, |index| index + 1);
+            for (index, range) in (next_index..).zip(self.unknown_variables) {
+                tabstops.insert(
+                    index,
+                    TabStop {
+                        ranges: [range].into_iter().collect(),
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+
         let len = text.len() as isize;
         let final_tabstop = tabstops.remove(&0);
         let mut tabstops = tabstops.into_values().collect::<Vec<_>>();
@@ -24,95 +114,509 @@ impl Snippet {
         if let Some(final_tabstop) = final_tabstop {
             tabstops.push(final_tabstop);
         } else {
-            let end_tabstop = [len..len].into_iter().collect();
-            if !tabstops.last().map_or(false, |t| *t == end_tabstop) {
+            let end_tabstop = TabStop {
+                ranges: [len..len].into_iter().collect(),
+                ..Default::default()
+            };
+            if !tabstops
+                .last()
+                .map_or(false, |t| t.ranges == end_tabstop.ranges)
+            {
                 tabstops.push(end_tabstop);
             }
         }
 
-        Ok(Snippet { text, tabstops })
+        Snippet { text, tabstops }
     }
-}
 
-fn parse_snippet<'a>(
-    mut source: &'a str,
-    nested: bool,
-    text: &mut String,
-    tabstops: &mut BTreeMap<usize, TabStop>,
-) -> Result<&'a str> {
-    loop {
+    /// Returns the text of each placeholder, as it appears where it isn't transformed.
+    fn placeholder_texts(&self) -> HashMap<usize, String> {
+        self.tabstops
+            .iter()
+            .map(|(index, tabstop)| {
+                let text = tabstop
+                    .ranges
+                    .iter()
+                    .enumerate()
+                    .filter(|(range_ix, _)| {
+                        !tabstop.transforms.iter().any(|(ix, _)| ix == range_ix)
+                    })
+                    .map(|(_, range)| &self.text[range.start as usize..range.end as usize])
+                    .find(|text| !text.is_empty())
+                    .unwrap_or_default();
+                (*index, text.to_string())
+            })
+            .collect()
+    }
+
+    fn push_tabstop_range(&mut self, index: usize, range: Range<usize>) -> usize {
+        let ranges = &mut self.tabstops.entry(index).or_default().ranges;
+        ranges.push(range.start as isize..range.end as isize);
+        ranges.len() - 1
+    }
+
+    fn parse_snippet<'b>(&mut self, mut source: &'b str, nested: bool) -> Result<&'b str> {
+        loop {
+            match source.chars().next() {
+                None => return Ok(""),
+                Some('$') => {
+                    source = self.parse_dollar(&source[1..])?;
+                }
+                Some('\\') => {
+                    // As specified in the LSP spec (`Grammar` section),
+                    // backslashes can escape some characters:
+                    // https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#snippet_syntax
+                    source = &source[1..];
+                    if let Some(c) = source.chars().next() {
+                        if c == '$' || c == '\\' || c == '}' {
+                            self.text.push(c);
+                            // All escapable characters are 1 byte long:
+                            source = &source[1..];
+                        } else {
+                            self.text.push('\\');
+                        }
+                    } else {
+                        self.text.push('\\');
+                    }
+                }
+                Some('}') => {
+                    if nested {
+                        return Ok(source);
+                    } else {
+                        self.text.push('}');
+                        source = &source[1..];
+                    }
+                }
+                Some(_) => {
+                    let chunk_end = source.find(['}', '$', '\\']).unwrap_or(source.len());
+                    let (chunk, rest) = source.split_at(chunk_end);
+                    self.text.push_str(chunk);
+                    source = rest;
+                }
+            }
+        }
+    }
+
+    /// Parses what follows a `$`: a tabstop, a placeholder, a choice, a variable, or just a `$`.
+    fn parse_dollar<'b>(&mut self, source: &'b str) -> Result<&'b str> {
         match source.chars().next() {
-            None => return Ok(""),
-            Some('$') => {
-                source = parse_tabstop(&source[1..], text, tabstops)?;
+            Some(c) if c.is_ascii_digit() => {
+                let (index, rest) = parse_int(source)?;
+                let start = self.text.len();
+                self.push_tabstop_range(index, start..start);
+                Ok(rest)
             }
-            Some('\\') => {
-                // As specified in the LSP spec (`Grammar` section),
-                // backslashes can escape some characters:
-                // https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#snippet_syntax
-                source = &source[1..];
-                if let Some(c) = source.chars().next() {
-                    if c == '$' || c == '\\' || c == '}' {
-                        text.push(c);
-                        // All escapable characters are 1 byte long:
-                        source = &source[1..];
+            Some(c) if is_variable_start(c) => {
+                let (name, rest) = parse_variable_name(source);
+                let start = self.text.len();
+                self.push_variable(name, start, None);
+                Ok(rest)
+            }
+            Some('{') => {
+                let inner = &source[1..];
+                match inner.chars().next() {
+                    Some(c) if c.is_ascii_digit() => self.parse_tabstop(inner),
+                    Some(c) if is_variable_start(c) => self.parse_variable(inner),
+                    _ => Err(anyhow!("expected a tabstop or a variable")),
+                }
+            }
+            _ => {
+                self.text.push('$');
+                Ok(source)
+            }
+        }
+    }
+
+    /// Parses a tabstop in braces, after the `${`.
+    fn parse_tabstop<'b>(&mut self, source: &'b str) -> Result<&'b str> {
+        let (index, mut source) = parse_int(source)?;
+        let start = self.text.len();
+
+        if let Some(rest) = source.strip_prefix(':') {
+            source = self.parse_snippet(rest, true)?;
+            self.push_tabstop_range(index, start..self.text.len());
+        } else if let Some(rest) = source.strip_prefix('|') {
+            let (choices, rest) = parse_choices(rest)?;
+            self.text
+                .push_str(choices.first().map_or("", String::as_str));
+            self.push_tabstop_range(index, start..self.text.len());
+            self.tabstops.entry(index).or_default().choices = Some(choices);
+            source = rest;
+        } else if let Some(rest) = source.strip_prefix('/') {
+            let (transform, rest) = Transform::parse(rest)?;
+            let placeholder_text = self
+                .placeholder_texts
+                .get(&index)
+                .map_or("", String::as_str);
+            self.text.push_str(&transform.apply(placeholder_text));
+            let range_ix = self.push_tabstop_range(index, start..self.text.len());
+            self.tabstops
+                .entry(index)
+                .or_default()
+                .transforms
+                .push((range_ix, transform));
+            source = rest;
+        } else {
+            self.push_tabstop_range(index, start..start);
+        }
+
+        source
+            .strip_prefix('}')
+            .ok_or_else(|| anyhow!("expected a closing brace"))
+    }
+
+    /// Parses a variable in braces, after the `${`.
+    fn parse_variable<'b>(&mut self, source: &'b str) -> Result<&'b str> {
+        let (name, mut source) = parse_variable_name(source);
+        let start = self.text.len();
+
+        if let Some(rest) = source.strip_prefix(':') {
+            let value = (self.variables)(name);
+            match value.as_deref() {
+                Some(value) if !value.is_empty() => {
+                    // Parse the default value separately, only to skip over it.
+                    let mut parser = SnippetParser::new(self.variables, HashMap::default());
+                    source = parser.parse_snippet(rest, true)?;
+                    self.text.push_str(value);
+                }
+                _ => {
+                    source = self.parse_snippet(rest, true)?;
+                    if value.is_none() {
+                        if self.text.len() == start {
+                            self.text.push_str(name);
+                        }
+                        self.unknown_variables
+                            .push(start as isize..self.text.len() as isize);
+                    }
+                }
+            }
+        } else if let Some(rest) = source.strip_prefix('/') {
+            let (transform, rest) = Transform::parse(rest)?;
+            self.push_variable(name, start, Some(&transform));
+            source = rest;
+        } else {
+            self.push_variable(name, start, None);
+        }
+
+        source
+            .strip_prefix('}')
+            .ok_or_else(|| anyhow!("expected a closing brace"))
+    }
+
+    fn push_variable(&mut self, name: &str, start: usize, transform: Option<&Transform>) {
+        match (self.variables)(name) {
+            Some(value) => match transform {
+                Some(transform) => self.text.push_str(&transform.apply(&value)),
+                None => self.text.push_str(&value),
+            },
+            None => {
+                self.text.push_str(name);
+                self.unknown_variables
+                    .push(start as isize..self.text.len() as isize);
+            }
+        }
+    }
+}
+
+/// A regular expression replacement like `/(.*)/${1:/upcase}/g`, applied to the text of a mirror or
+/// the value of a variable.
+#[derive(Clone, Debug)]
+pub struct Transform {
+    regex: Regex,
+    format: Vec<FormatItem>,
+    options: String,
+}
+
+impl PartialEq for Transform {
+    fn eq(&self, other: &Self) -> bool {
+        self.regex.as_str() == other.regex.as_str()
+            && self.format == other.format
+            && self.options == other.options
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum FormatItem {
+    Text(String),
+    Group(usize),
+    Case(usize, Case),
+    /// Inserts `if_matched` when the group matched, or the group itself when it's `None`, and
+    /// `otherwise` when it didn't.
+    Conditional {
+        group: usize,
+        if_matched: Option<String>,
+        otherwise: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Case {
+    Upcase,
+    Downcase,
+    Capitalize,
+    CamelCase,
+    PascalCase,
+}
+
+impl Transform {
+    /// Parses a transform, after the `/` that starts it, up to the closing brace.
+    fn parse(source: &str) -> Result<(Self, &str)> {
+        let (pattern, source) = parse_until(source, '/', |_| false)?;
+        let (format, source) = parse_format(source)?;
+        let options_end = source
+            .find('}')
+            .ok_or_else(|| anyhow!("expected a closing brace"))?;
+        let (options, source) = source.split_at(options_end);
+
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(options.contains('i'))
+            .multi_line(options.contains('m'))
+            .build()
+            .context("invalid regex in snippet transform")?;
+        let transform = Self {
+            regex,
+            format,
+            options: options.to_string(),
+        };
+        Ok((transform, source))
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        let replacement = |captures: &Captures| {
+            let group = |ix: usize| captures.get(ix).map(|group| group.as_str());
+            let mut result = String::new();
+            for item in &self.format {
+                match item {
+                    FormatItem::Text(text) => result.push_str(text),
+                    FormatItem::Group(ix) => result.push_str(group(*ix).unwrap_or_default()),
+                    FormatItem::Case(ix, case) => {
+                        result.push_str(&case.apply(group(*ix).unwrap_or_default()))
+                    }
+                    FormatItem::Conditional {
+                        group: ix,
+                        if_matched,
+                        otherwise,
+                    } => match group(*ix).filter(|group| !group.is_empty()) {
+                        Some(group) => result.push_str(if_matched.as_deref().unwrap_or(group)),
+                        None => result.push_str(otherwise),
+                    },
+                }
+            }
+            result
+        };
+
+        if self.options.contains('g') {
+            self.regex.replace_all(text, replacement).into_owned()
+        } else {
+            self.regex.replace(text, replacement).into_owned()
+        }
+    }
+}
+
+impl Case {
+    fn apply(self, text: &str) -> String {
+        match self {
+            Case::Upcase => text.to_uppercase(),
+            Case::Downcase => text.to_lowercase(),
+            Case::Capitalize => capitalize(text),
+            Case::CamelCase | Case::PascalCase => text
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .enumerate()
+                .map(|(ix, word)| {
+                    if ix == 0 && self == Case::CamelCase {
+                        let mut chars = word.chars();
+                        chars
+                            .next()
+                            .map(|first| first.to_lowercase().chain(chars).collect())
+                            .unwrap_or_default()
                     } else {
-                        text.push('\\');
+                        capitalize(word)
                     }
+                })
+                .collect(),
+        }
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Parses the format of a transform, up to the `/` that ends it.
+fn parse_format(mut source: &str) -> Result<(Vec<FormatItem>, &str)> {
+    let mut items = Vec::new();
+    loop {
+        match source.chars().next() {
+            None => return Err(anyhow!("expected the end of the transform format")),
+            Some('/') => return Ok((items, &source[1..])),
+            Some('$') => {
+                let rest = &source[1..];
+                if let Some(rest) = rest.strip_prefix('{') {
+                    let (item, rest) = parse_format_group(rest)?;
+                    items.push(item);
+                    source = rest;
+                } else if rest.starts_with(|c: char| c.is_ascii_digit()) {
+                    let (group, rest) = parse_int(rest)?;
+                    items.push(FormatItem::Group(group));
+                    source = rest;
                 } else {
-                    text.push('\\');
+                    push_format_text(&mut items, "$");
+                    source = rest;
                 }
             }
-            Some('}') => {
-                if nested {
-                    return Ok(source);
-                } else {
-                    text.push('}');
-                    source = &source[1..];
+            Some('\\') => {
+                let rest = &source[1..];
+                match rest.chars().next() {
+                    Some(c @ ('$' | '\\' | '/' | '}')) => {
+                        push_format_text(&mut items, c.encode_utf8(&mut [0; 1]));
+                        source = &rest[1..];
+                    }
+                    _ => {
+                        push_format_text(&mut items, "\\");
+                        source = rest;
+                    }
                 }
             }
             Some(_) => {
-                let chunk_end = source.find(['}', '$', '\\']).unwrap_or(source.len());
+                let chunk_end = source.find(['/', '$', '\\']).unwrap_or(source.len());
                 let (chunk, rest) = source.split_at(chunk_end);
-                text.push_str(chunk);
+                push_format_text(&mut items, chunk);
                 source = rest;
             }
         }
     }
 }
 
-fn parse_tabstop<'a>(
-    mut source: &'a str,
-    text: &mut String,
-    tabstops: &mut BTreeMap<usize, TabStop>,
-) -> Result<&'a str> {
-    let tabstop_start = text.len();
-    let tabstop_index;
-    if source.starts_with('{') {
-        let (index, rest) = parse_int(&source[1..])?;
-        tabstop_index = index;
-        source = rest;
+/// Parses a group reference in braces, after the `${`, like `${1}`, `${1:/upcase}`, `${1:+yes}`,
+/// `${1:?yes:no}`, `${1:-no}` or `${1:no}`.
+fn parse_format_group(source: &str) -> Result<(FormatItem, &str)> {
+    let (group, source) = parse_int(source)?;
+    if let Some(rest) = source.strip_prefix('}') {
+        return Ok((FormatItem::Group(group), rest));
+    }
+    let source = source
+        .strip_prefix(':')
+        .ok_or_else(|| anyhow!("expected a closing brace"))?;
 
-        if source.starts_with(':') {
-            source = parse_snippet(&source[1..], true, text, tabstops)?;
-        }
+    if let Some(rest) = source.strip_prefix('/') {
+        let name_end = rest
+            .find('}')
+            .ok_or_else(|| anyhow!("expected a closing brace"))?;
+        let case = match &rest[..name_end] {
+            "upcase" => Case::Upcase,
+            "downcase" => Case::Downcase,
+            "capitalize" => Case::Capitalize,
+            "camelcase" => Case::CamelCase,
+            "pascalcase" => Case::PascalCase,
+            name => return Err(anyhow!("unknown format modifier '{name}'")),
+        };
+        Ok((FormatItem::Case(group, case), &rest[name_end + 1..]))
+    } else if let Some(rest) = source.strip_prefix('+') {
+        let (if_matched, rest) = parse_until(rest, '}', |c| c == '\\')?;
+        let item = FormatItem::Conditional {
+            group,
+            if_matched: Some(if_matched),
+            otherwise: String::new(),
+        };
+        Ok((item, rest))
+    } else if let Some(rest) = source.strip_prefix('?') {
+        let (if_matched, rest) = parse_until(rest, ':', |c| c == '\\')?;
+        let (otherwise, rest) = parse_until(rest, '}', |c| c == '\\')?;
+        let item = FormatItem::Conditional {
+            group,
+            if_matched: Some(if_matched),
+            otherwise,
+        };
+        Ok((item, rest))
+    } else {
+        let rest = source.strip_prefix('-').unwrap_or(source);
+        let (otherwise, rest) = parse_until(rest, '}', |c| c == '\\')?;
+        let item = FormatItem::Conditional {
+            group,
+            if_matched: None,
+            otherwise,
+        };
+        Ok((item, rest))
+    }
+}
+
+fn push_format_text(items: &mut Vec<FormatItem>, text: &str) {
+    if let Some(FormatItem::Text(last)) = items.last_mut() {
+        last.push_str(text);
+    } else {
+        items.push(FormatItem::Text(text.to_string()));
+    }
+}
 
-        if source.starts_with('}') {
-            source = &source[1..];
+/// Returns the text up to the given unescaped terminator, and the source after the terminator.
+/// Backslashes escape the terminator and the characters matching `unescape`; other backslashes are
+/// kept.
+fn parse_until(
+    source: &str,
+    terminator: char,
+    unescape: impl Fn(char) -> bool,
+) -> Result<(String, &str)> {
+    let mut text = String::new();
+    let mut chars = source.char_indices();
+    while let Some((ix, c)) = chars.next() {
+        if c == terminator {
+            return Ok((text, &source[ix + c.len_utf8()..]));
+        } else if c == '\\' {
+            match chars.next() {
+                Some((_, next)) if next == terminator || unescape(next) => text.push(next),
+                Some((_, next)) => {
+                    text.push('\\');
+                    text.push(next);
+                }
+                None => text.push('\\'),
+            }
         } else {
-            return Err(anyhow!("expected a closing brace"));
+            text.push(c);
         }
-    } else {
-        let (index, rest) = parse_int(source)?;
-        tabstop_index = index;
-        source = rest;
     }
+    Err(anyhow!("expected '{terminator}'"))
+}
+
+/// Parses the options of a choice, after the first `|`, up to the closing brace.
+fn parse_choices(mut source: &str) -> Result<(Vec<String>, &str)> {
+    let mut choices = Vec::new();
+    let mut choice = String::new();
+    loop {
+        let mut chars = source.chars();
+        match chars.next() {
+            None => return Err(anyhow!("expected the end of the choice")),
+            Some('|') => {
+                choices.push(choice);
+                return Ok((choices, chars.as_str()));
+            }
+            Some(',') => choices.push(std::mem::take(&mut choice)),
+            Some('\\') => match chars.clone().next() {
+                Some(c @ ('$' | '\\' | '}' | ',' | '|')) => {
+                    choice.push(c);
+                    chars.next();
+                }
+                _ => choice.push('\\'),
+            },
+            Some(c) => choice.push(c),
+        }
+        source = chars.as_str();
+    }
+}
 
-    tabstops
-        .entry(tabstop_index)
-        .or_default()
-        .push(tabstop_start as isize..text.len() as isize);
-    Ok(source)
+fn is_variable_start(c: char) -> bool {
+    c == '_' || c.is_ascii_alphabetic()
+}
+
+fn parse_variable_name(source: &str) -> (&str, &str) {
+    let len = source
+        .find(|c: char| c != '_' && !c.is_ascii_alphanumeric())
+        .unwrap_or(source.len());
+    source.split_at(len)
 }
 
 fn parse_int(source: &str) -> Result<(usize, &str)> {
@@ -220,7 +724,100 @@ mod tests {
         assert_eq!(tabstops(&snippet), &[vec![4..4], vec![7..7]]);
     }
 
+    #[test]
+    fn test_snippet_with_variables() {
+        let variables = |name: &str| match name {
+            "TM_FILENAME" => Some("main.rs".to_string()),
+            "TM_SELECTED_TEXT" => Some(String::new()),
+            _ => None,
+        };
+
+        let snippet =
+            Snippet::parse_with_variables("// $TM_FILENAME ${TM_FILENAME}$1", variables).unwrap();
+        assert_eq!(snippet.text, "// main.rs main.rs");
+        assert_eq!(tabstops(&snippet), &[vec![18..18]]);
+
+        // Empty variables are replaced with their default
+        let snippet =
+            Snippet::parse_with_variables("(${TM_SELECTED_TEXT:${1:x}})", variables).unwrap();
+        assert_eq!(snippet.text, "(x)");
+        assert_eq!(tabstops(&snippet), &[vec![1..2], vec![3..3]]);
+
+        // Defaults of variables with a value are skipped
+        let snippet = Snippet::parse_with_variables("${TM_FILENAME:${1:x}}$2", variables).unwrap();
+        assert_eq!(snippet.text, "main.rs");
+        assert_eq!(tabstops(&snippet), &[vec![7..7]]);
+
+        // Unknown variables become placeholders, after all other tabstops
+        let snippet =
+            Snippet::parse_with_variables("$UNKNOWN ${2:b} ${OTHER:c}$0", variables).unwrap();
+        assert_eq!(snippet.text, "UNKNOWN b c");
+        assert_eq!(
+            tabstops(&snippet),
+            &[vec![8..9], vec![0..7], vec![10..11], vec![11..11]]
+        );
+
+        // Without a way to resolve variables, they aren't placeholders
+        let snippet = Snippet::parse("$UNKNOWN ${2:b} ${OTHER:c}$0").unwrap();
+        assert_eq!(snippet.text, "UNKNOWN b c");
+        assert_eq!(tabstops(&snippet), &[vec![8..9], vec![11..11]]);
+
+        // A lone dollar sign is kept as is
+        let snippet = Snippet::parse_with_variables("$ $1", variables).unwrap();
+        assert_eq!(snippet.text, "$ ");
+    }
+
+    #[test]
+    fn test_snippet_with_choices() {
+        let snippet = Snippet::parse("let ${1|mut a,b\\,c|} = $2;").unwrap();
+        assert_eq!(snippet.text, "let mut a = ;");
+        assert_eq!(
+            tabstops(&snippet),
+            &[vec![4..9], vec![12..12], vec![13..13]]
+        );
+        assert_eq!(
+            snippet.tabstops[0].choices,
+            Some(vec!["mut a".to_string(), "b,c".to_string()])
+        );
+        assert_eq!(snippet.tabstops[1].choices, None);
+    }
+
+    #[test]
+    fn test_snippet_with_transforms() {
+        let snippet = Snippet::parse("${1:foo_bar} ${1/(.*)/${1:/upcase}/} $1").unwrap();
+        assert_eq!(snippet.text, "foo_bar FOO_BAR ");
+        assert_eq!(
+            tabstops(&snippet),
+            &[vec![0..7, 8..15, 16..16], vec![16..16]]
+        );
+        let (range_ix, transform) = &snippet.tabstops[0].transforms[0];
+        assert_eq!(*range_ix, 1);
+        assert_eq!(transform.apply("baz"), "BAZ");
+
+        // The transformed placeholder can come after the mirror
+        let snippet = Snippet::parse("${1/(.*)/${1:/pascalcase}/}: ${1:foo_bar}").unwrap();
+        assert_eq!(snippet.text, "FooBar: foo_bar");
+
+        let variables = |name: &str| (name == "TM_FILENAME").then(|| "my_file.rs".to_string());
+        let snippet =
+            Snippet::parse_with_variables("${TM_FILENAME/(.*)\\..+$/${1:/camelcase}/}", variables)
+                .unwrap();
+        assert_eq!(snippet.text, "myFile");
+
+        let transform = |source: &str| Transform::parse(source).unwrap().0;
+        assert_eq!(transform("a/b/g}").apply("aXa"), "bXb");
+        assert_eq!(transform("a/b/}").apply("aXa"), "bXa");
+        assert_eq!(transform("A/b/gi}").apply("aXa"), "bXb");
+        assert_eq!(transform("(a)?x/${1:?yes:no}/}").apply("ax"), "yes");
+        assert_eq!(transform("(a)?x/${1:?yes:no}/}").apply("x"), "no");
+        assert_eq!(transform("(a)?x/${1:+yes}/}").apply("x"), "");
+        assert_eq!(transform("(a)?x/${1:-no}$1/}").apply("ax"), "aa");
+        assert_eq!(transform("(a)?x/${1:no}/}").apply("x"), "no");
+        assert_eq!(transform("(\\w+)/\\/$1\\//}").apply("a"), "/a/");
+        assert_eq!(transform("(.*)/${1:/capitalize}/}").apply("abc"), "Abc");
+    }
+
     fn tabstops(snippet: &Snippet) -> Vec<Vec<Range<isize>>> {
-        snippet.tabstops.iter().map(|t| t.to_vec()).collect()
+        snippet.tabstops.iter().map(|t| t.ranges.to_vec()).collect()
     }
 }
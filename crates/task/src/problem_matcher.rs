@@ -0,0 +1,600 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// A problem matcher used by a task template: either a name of a built-in matcher, or a custom matcher definition.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcherDefinition {
+    /// A name of a built-in problem matcher: `$rustc`, `$tsc`, `$tsc-watch` or `$gcc`.
+    Named(String),
+    /// A custom problem matcher.
+    Custom(ProblemMatcher),
+}
+
+impl ProblemMatcherDefinition {
+    /// Returns the problem matcher defined, or `None` if there is no built-in matcher with the name given.
+    pub fn resolve(&self) -> Option<ProblemMatcher> {
+        match self {
+            Self::Named(name) => ProblemMatcher::builtin(name),
+            Self::Custom(matcher) => Some(matcher.clone()),
+        }
+    }
+}
+
+/// Describes how to extract problems (errors, warnings, etc.) out of the task output, line by line.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemMatcher {
+    /// Name of the tool producing the problems, shown as the source of the corresponding diagnostics.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Severity of the problems whose patterns do not capture any.
+    #[serde(default)]
+    pub severity: ProblemSeverity,
+    /// How to interpret the file paths captured.
+    #[serde(default)]
+    pub file_location: FileLocation,
+    /// A pattern, or a list of patterns matching consecutive output lines, that describe a single problem.
+    #[serde(deserialize_with = "deserialize_one_or_many")]
+    pub pattern: Vec<ProblemPattern>,
+    /// For tasks that keep running, e.g. in watch mode: the lines delimiting each build,
+    /// so that only the problems of the last finished build are reported.
+    #[serde(default)]
+    pub background: Option<BackgroundPattern>,
+}
+
+/// Regexes matching the lines a long-running task prints when it starts and finishes a build.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct BackgroundPattern {
+    /// The regular expression matching the line printed when a build starts.
+    pub begins_pattern: String,
+    /// The regular expression matching the line printed when a build finishes.
+    pub ends_pattern: String,
+}
+
+/// A regex matching a single output line, with the indices of its capture groups that hold problem properties.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    /// The regular expression to match the line with.
+    pub regexp: String,
+    /// Group with the file path.
+    #[serde(default)]
+    pub file: Option<usize>,
+    /// Group with the whole location: `line`, `line,column` or `line,column,end_line,end_column`.
+    #[serde(default)]
+    pub location: Option<usize>,
+    /// Group with the 1-based line number.
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// Group with the 1-based column number.
+    #[serde(default)]
+    pub column: Option<usize>,
+    /// Group with the 1-based end line number.
+    #[serde(default)]
+    pub end_line: Option<usize>,
+    /// Group with the 1-based end column number.
+    #[serde(default)]
+    pub end_column: Option<usize>,
+    /// Group with the severity: `error`, `warning`, `info` or `hint`.
+    #[serde(default)]
+    pub severity: Option<usize>,
+    /// Group with the problem code.
+    #[serde(default)]
+    pub code: Option<usize>,
+    /// Group with the problem message, `0` for the whole line.
+    #[serde(default)]
+    pub message: Option<usize>,
+    /// Only for the last pattern of several: whether every line it matches produces a separate problem,
+    /// with the properties captured by the previous patterns.
+    #[serde(default, rename = "loop")]
+    pub repeat: bool,
+}
+
+/// Severity of a problem found in the task output.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    /// An error (default).
+    #[default]
+    Error,
+    /// A warning.
+    Warning,
+    /// An informational message.
+    Info,
+    /// A hint.
+    Hint,
+}
+
+impl ProblemSeverity {
+    fn parse(severity: &str) -> Option<Self> {
+        match severity.trim().to_lowercase().as_str() {
+            "error" | "err" | "fatal" => Some(Self::Error),
+            "warning" | "warn" => Some(Self::Warning),
+            "info" | "information" | "note" => Some(Self::Info),
+            "hint" | "help" => Some(Self::Hint),
+            _ => None,
+        }
+    }
+}
+
+/// How to interpret the file paths captured by a problem matcher.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FileLocation {
+    /// Absolute paths are used as is, relative ones are resolved against the task's working directory (default).
+    #[default]
+    AutoDetect,
+    /// All paths are absolute.
+    Absolute,
+    /// All paths are relative to the task's working directory.
+    Relative,
+}
+
+/// A problem found in the task output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// The file path, as printed by the task.
+    pub path: PathBuf,
+    /// How to interpret the `path`.
+    pub file_location: FileLocation,
+    /// 1-based line of the problem.
+    pub line: u32,
+    /// 1-based column of the problem, if known.
+    pub column: Option<u32>,
+    /// 1-based end line of the problem, if known.
+    pub end_line: Option<u32>,
+    /// 1-based end column of the problem, if known.
+    pub end_column: Option<u32>,
+    /// Severity of the problem.
+    pub severity: ProblemSeverity,
+    /// A problem code, e.g. `E0308`.
+    pub code: Option<String>,
+    /// The problem description.
+    pub message: String,
+    /// Name of the tool that reported the problem.
+    pub source: Option<String>,
+}
+
+impl Problem {
+    /// Resolves the problem path against the working directory of the task, if needed.
+    pub fn abs_path(&self, cwd: Option<&Path>) -> Option<PathBuf> {
+        match self.file_location {
+            FileLocation::Absolute => Some(self.path.clone()),
+            FileLocation::AutoDetect if self.path.is_absolute() => Some(self.path.clone()),
+            FileLocation::AutoDetect | FileLocation::Relative => Some(cwd?.join(&self.path)),
+        }
+    }
+}
+
+impl ProblemMatcher {
+    /// Returns a built-in problem matcher by its name, e.g. `$rustc`.
+    pub fn builtin(name: &str) -> Option<Self> {
+        let matcher = match name {
+            "$rustc" => Self {
+                source: Some("rustc".to_string()),
+                severity: ProblemSeverity::Error,
+                file_location: FileLocation::AutoDetect,
+                pattern: vec![
+                    ProblemPattern {
+                        regexp: r"^(warning|error)(?:\[(\w+)\])?: (.*)$".to_string(),
+                        severity: Some(1),
+                        code: Some(2),
+                        message: Some(3),
+                        ..ProblemPattern::default()
+                    },
+                    ProblemPattern {
+                        regexp: r"^\s*-->\s+(.*?):(\d+):(\d+)$".to_string(),
+                        file: Some(1),
+                        line: Some(2),
+                        column: Some(3),
+                        ..ProblemPattern::default()
+                    },
+                ],
+                background: None,
+            },
+            "$tsc" => Self {
+                source: Some("ts".to_string()),
+                severity: ProblemSeverity::Error,
+                file_location: FileLocation::Relative,
+                pattern: vec![ProblemPattern {
+                    regexp: r"^([^\s].*)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+TS(\d+)\s*:\s*(.*)$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    severity: Some(4),
+                    code: Some(5),
+                    message: Some(6),
+                    ..ProblemPattern::default()
+                }],
+                background: None,
+            },
+            "$tsc-watch" => Self {
+                background: Some(BackgroundPattern {
+                    begins_pattern: r"(?:Starting compilation in watch mode|File change detected\. Starting incremental compilation)\.\.\.".to_string(),
+                    ends_pattern: r"Found \d+ errors?\. Watching for file changes\.".to_string(),
+                }),
+                ..Self::builtin("$tsc")?
+            },
+            "$gcc" => Self {
+                source: Some("gcc".to_string()),
+                severity: ProblemSeverity::Error,
+                file_location: FileLocation::AutoDetect,
+                pattern: vec![ProblemPattern {
+                    regexp: r"^(.*?):(\d+):(\d*):?\s+(?:fatal\s+)?(warning|error):\s+(.*)$"
+                        .to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    severity: Some(4),
+                    message: Some(5),
+                    ..ProblemPattern::default()
+                }],
+                background: None,
+            },
+            _ => return None,
+        };
+        Some(matcher)
+    }
+
+    /// Finds the problems to report for the output a task printed so far: all of them,
+    /// or only the ones of the last finished build for the matchers with a [`BackgroundPattern`].
+    /// Fails if any of the matcher's patterns is not a valid regex.
+    pub fn find_current_problems(&self, output: &str) -> anyhow::Result<Vec<Problem>> {
+        let Some(background) = &self.background else {
+            return self.find_problems(output.lines());
+        };
+        let begins_regex = Regex::new(&background.begins_pattern)
+            .with_context(|| format!("parsing begins pattern {:?}", background.begins_pattern))?;
+        let ends_regex = Regex::new(&background.ends_pattern)
+            .with_context(|| format!("parsing ends pattern {:?}", background.ends_pattern))?;
+
+        let lines = output.lines().collect::<Vec<_>>();
+        let Some(build_end) = lines.iter().rposition(|line| ends_regex.is_match(line)) else {
+            return Ok(Vec::new());
+        };
+        let build_start = lines[..build_end]
+            .iter()
+            .rposition(|line| begins_regex.is_match(line))
+            .map_or(0, |ix| ix + 1);
+        self.find_problems(lines[build_start..build_end].iter().copied())
+    }
+
+    /// Finds all problems in the task output lines given.
+    /// Fails if any of the matcher's patterns is not a valid regex.
+    pub fn find_problems<'a>(
+        &self,
+        lines: impl IntoIterator<Item = &'a str>,
+    ) -> anyhow::Result<Vec<Problem>> {
+        let regexes = self
+            .pattern
+            .iter()
+            .map(|pattern| {
+                Regex::new(&pattern.regexp)
+                    .with_context(|| format!("parsing problem pattern {:?}", pattern.regexp))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let Some(last_pattern_ix) = regexes.len().checked_sub(1) else {
+            anyhow::bail!("Problem matcher has no patterns");
+        };
+
+        let mut problems = Vec::new();
+        let mut matched = ProblemData::default();
+        let mut next_pattern_ix = 0;
+        for line in lines {
+            if next_pattern_ix > 0 {
+                if let Some(captures) = regexes[next_pattern_ix].captures(line) {
+                    let pattern = &self.pattern[next_pattern_ix];
+                    if next_pattern_ix == last_pattern_ix {
+                        let mut problem_data = matched.clone();
+                        problem_data.capture(pattern, &captures);
+                        problems.extend(self.problem(problem_data));
+                        if !pattern.repeat {
+                            next_pattern_ix = 0;
+                        }
+                    } else {
+                        matched.capture(pattern, &captures);
+                        next_pattern_ix += 1;
+                    }
+                    continue;
+                }
+                next_pattern_ix = 0;
+            }
+
+            if let Some(captures) = regexes[0].captures(line) {
+                matched = ProblemData::default();
+                matched.capture(&self.pattern[0], &captures);
+                if last_pattern_ix == 0 {
+                    problems.extend(self.problem(std::mem::take(&mut matched)));
+                } else {
+                    next_pattern_ix = 1;
+                }
+            }
+        }
+        Ok(problems)
+    }
+
+    fn problem(&self, data: ProblemData) -> Option<Problem> {
+        Some(Problem {
+            path: PathBuf::from(data.file?),
+            file_location: self.file_location,
+            line: data.line?,
+            column: data.column,
+            end_line: data.end_line,
+            end_column: data.end_column,
+            severity: data
+                .severity
+                .as_deref()
+                .and_then(ProblemSeverity::parse)
+                .unwrap_or(self.severity),
+            code: data.code,
+            message: data.message?,
+            source: self.source.clone(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct ProblemData {
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<String>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl ProblemData {
+    fn capture(&mut self, pattern: &ProblemPattern, captures: &Captures) {
+        let group = |ix: Option<usize>| {
+            let group = captures.get(ix?)?.as_str().trim();
+            (!group.is_empty()).then(|| group.to_string())
+        };
+        let number = |ix: Option<usize>| group(ix)?.parse::<u32>().ok();
+
+        if let Some(file) = group(pattern.file) {
+            self.file = Some(file);
+        }
+        if let Some(location) = group(pattern.location) {
+            let mut numbers = location.split(',').map(|n| n.trim().parse::<u32>().ok());
+            self.line = numbers.next().flatten().or(self.line);
+            self.column = numbers.next().flatten().or(self.column);
+            self.end_line = numbers.next().flatten().or(self.end_line);
+            self.end_column = numbers.next().flatten().or(self.end_column);
+        }
+        self.line = number(pattern.line).or(self.line);
+        self.column = number(pattern.column).or(self.column);
+        self.end_line = number(pattern.end_line).or(self.end_line);
+        self.end_column = number(pattern.end_column).or(self.end_column);
+        self.severity = group(pattern.severity).or(self.severity.take());
+        self.code = group(pattern.code).or(self.code.take());
+        self.message = group(pattern.message).or(self.message.take());
+    }
+}
+
+fn deserialize_one_or_many<'de, D>(deserializer: D) -> Result<Vec<ProblemPattern>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(ProblemPattern),
+        Many(Vec<ProblemPattern>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(pattern) => vec![pattern],
+        OneOrMany::Many(patterns) => patterns,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rustc_problems() {
+        let output = r#"   Compiling demo v0.1.0 (/tmp/demo)
+warning: unused variable: `x`
+ --> src/main.rs:2:9
+  |
+2 |     let x = 5;
+  |         ^ help: if this is intentional, prefix it with an underscore: `_x`
+error[E0308]: mismatched types
+  --> src/lib.rs:14:21
+   |
+error: aborting due to 1 previous error
+"#;
+        let problems = ProblemMatcher::builtin("$rustc")
+            .unwrap()
+            .find_problems(output.lines())
+            .unwrap();
+        assert_eq!(
+            problems,
+            vec![
+                Problem {
+                    path: PathBuf::from("src/main.rs"),
+                    file_location: FileLocation::AutoDetect,
+                    line: 2,
+                    column: Some(9),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Warning,
+                    code: None,
+                    message: "unused variable: `x`".to_string(),
+                    source: Some("rustc".to_string()),
+                },
+                Problem {
+                    path: PathBuf::from("src/lib.rs"),
+                    file_location: FileLocation::AutoDetect,
+                    line: 14,
+                    column: Some(21),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Error,
+                    code: Some("E0308".to_string()),
+                    message: "mismatched types".to_string(),
+                    source: Some("rustc".to_string()),
+                },
+            ]
+        );
+
+        assert_eq!(
+            problems[0].abs_path(Some(Path::new("/tmp/demo"))),
+            Some(PathBuf::from("/tmp/demo/src/main.rs"))
+        );
+        assert_eq!(problems[0].abs_path(None), None);
+    }
+
+    #[test]
+    fn test_tsc_problems() {
+        let output = "src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\nFound 1 error.";
+        let problems = ProblemMatcher::builtin("$tsc")
+            .unwrap()
+            .find_problems(output.lines())
+            .unwrap();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].path, PathBuf::from("src/index.ts"));
+        assert_eq!((problems[0].line, problems[0].column), (3, Some(7)));
+        assert_eq!(problems[0].code.as_deref(), Some("2322"));
+        assert_eq!(
+            problems[0].message,
+            "Type 'string' is not assignable to type 'number'."
+        );
+    }
+
+    #[test]
+    fn test_background_problems() {
+        let matcher = ProblemMatcher::builtin("$tsc-watch").unwrap();
+        let first_build = "\
+[10:00:00 AM] Starting compilation in watch mode...
+src/a.ts(1,1): error TS1005: ';' expected.
+src/b.ts(2,2): error TS2304: Cannot find name 'x'.
+[10:00:01 AM] Found 2 errors. Watching for file changes.
+";
+        let paths = |problems: Vec<Problem>| {
+            problems
+                .into_iter()
+                .map(|problem| problem.path.to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            paths(matcher.find_current_problems(first_build).unwrap()),
+            ["src/a.ts", "src/b.ts"]
+        );
+
+        // Problems of a build in progress are not reported until it finishes.
+        let second_build_started = format!(
+            "{first_build}[10:01:00 AM] File change detected. Starting incremental compilation...
+src/b.ts(2,2): error TS2304: Cannot find name 'x'.
+"
+        );
+        assert_eq!(
+            paths(
+                matcher
+                    .find_current_problems(&second_build_started)
+                    .unwrap()
+            ),
+            ["src/a.ts", "src/b.ts"]
+        );
+        let second_build_finished = format!(
+            "{second_build_started}[10:01:01 AM] Found 1 error. Watching for file changes.\n"
+        );
+        assert_eq!(
+            paths(
+                matcher
+                    .find_current_problems(&second_build_finished)
+                    .unwrap()
+            ),
+            ["src/b.ts"]
+        );
+
+        assert_eq!(
+            paths(
+                matcher
+                    .find_current_problems(
+                        "[10:00:00 AM] Starting compilation in watch mode...\nsrc/a.ts(1,1): error TS1005: ';' expected.\n"
+                    )
+                    .unwrap()
+            ),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_custom_looping_matcher() {
+        let definition: ProblemMatcherDefinition = serde_json_lenient::from_str(
+            r#"{
+                "source": "lint",
+                "severity": "warning",
+                "pattern": [
+                    { "regexp": "^([^\\s].*)$", "file": 1 },
+                    { "regexp": "^\\s+(\\d+):(\\d+)\\s+(error|warning)\\s+(.*)$", "line": 1, "column": 2, "severity": 3, "message": 4, "loop": true }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let matcher = definition.resolve().unwrap();
+        let output = "src/a.js\n  1:2  error  Missing semicolon\n  4:1  warning  Unused import\n\nsrc/b.js\n  7:3  error  Undefined variable";
+        let problems = matcher.find_problems(output.lines()).unwrap();
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.path.to_string_lossy().to_string(),
+                    problem.line,
+                    problem.severity,
+                    problem.message.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "src/a.js".to_string(),
+                    1,
+                    ProblemSeverity::Error,
+                    "Missing semicolon"
+                ),
+                (
+                    "src/a.js".to_string(),
+                    4,
+                    ProblemSeverity::Warning,
+                    "Unused import"
+                ),
+                (
+                    "src/b.js".to_string(),
+                    7,
+                    ProblemSeverity::Error,
+                    "Undefined variable"
+                ),
+            ]
+        );
+
+        let single_pattern: ProblemMatcherDefinition = serde_json_lenient::from_str(
+            r#"{ "pattern": { "regexp": "^(.+):(\\d+): (.+)$", "file": 1, "line": 2, "message": 3 } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            single_pattern
+                .resolve()
+                .unwrap()
+                .find_problems(["main.py:3: oops"])
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            serde_json_lenient::from_str::<ProblemMatcherDefinition>(r#""$unknown""#)
+                .unwrap()
+                .resolve(),
+            None
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use anyhow::{anyhow, bail};
+use futures::{
+    future::{self, LocalBoxFuture},
+    Future, FutureExt,
+};
+
+use crate::{DependsOrder, TaskTemplate};
+
+/// A task together with all tasks it transitively depends on, as declared by [`TaskTemplate::depends_on`].
+///
+/// Generic over the task representation, so that it can hold both templates and tasks resolved from them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskDependencyTree<T> {
+    /// The task to run after all of its dependencies have finished successfully.
+    pub task: T,
+    /// How to run the dependencies of the task.
+    pub depends_order: DependsOrder,
+    /// The trees of the direct dependencies of the task, in their declaration order.
+    pub dependencies: Vec<TaskDependencyTree<T>>,
+}
+
+impl<T> TaskDependencyTree<T> {
+    /// Builds the dependency tree of the given task, looking up each dependency label with `find_dependency`,
+    /// which receives the task that declares the dependency.
+    ///
+    /// Fails if a dependency cannot be found, or if the dependencies form a cycle.
+    pub fn build(
+        task: T,
+        template: impl Fn(&T) -> &TaskTemplate,
+        find_dependency: impl Fn(&T, &str) -> Option<T>,
+    ) -> anyhow::Result<Self> {
+        Self::build_inner(task, &template, &find_dependency, &mut Vec::new())
+    }
+
+    fn build_inner(
+        task: T,
+        template: &impl Fn(&T) -> &TaskTemplate,
+        find_dependency: &impl Fn(&T, &str) -> Option<T>,
+        labels_stack: &mut Vec<String>,
+    ) -> anyhow::Result<Self> {
+        let task_template = template(&task);
+        let label = task_template.label.clone();
+        let depends_order = task_template.depends_order;
+        let depends_on = task_template.depends_on.clone();
+        if let Some(cycle_start) = labels_stack.iter().position(|parent| parent == &label) {
+            let cycle = labels_stack[cycle_start..]
+                .iter()
+                .chain([&label])
+                .map(|label| format!("{label:?}"))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            bail!("Task dependency cycle detected: {cycle}");
+        }
+
+        labels_stack.push(label);
+        let mut dependencies = Vec::with_capacity(depends_on.len());
+        for dependency_label in &depends_on {
+            let dependency = find_dependency(&task, dependency_label).ok_or_else(|| {
+                anyhow!(
+                    "Task {:?} depends on unknown task {dependency_label:?}",
+                    labels_stack.last().unwrap()
+                )
+            })?;
+            dependencies.push(Self::build_inner(
+                dependency,
+                template,
+                find_dependency,
+                labels_stack,
+            )?);
+        }
+        labels_stack.pop();
+
+        Ok(Self {
+            task,
+            depends_order,
+            dependencies,
+        })
+    }
+
+    /// Runs all dependencies of the task with `spawn`, then the task itself.
+    /// A failing dependency prevents all tasks depending on it from being spawned, and fails the whole run.
+    pub fn run<'a, F, Fut>(&'a self, spawn: &'a F) -> LocalBoxFuture<'a, anyhow::Result<()>>
+    where
+        F: Fn(&'a T) -> Fut,
+        Fut: Future<Output = anyhow::Result<()>> + 'a,
+    {
+        async move {
+            match self.depends_order {
+                DependsOrder::Sequence => {
+                    for dependency in &self.dependencies {
+                        dependency.run(spawn).await?;
+                    }
+                }
+                DependsOrder::Parallel => {
+                    future::try_join_all(
+                        self.dependencies
+                            .iter()
+                            .map(|dependency| dependency.run(spawn)),
+                    )
+                    .await?;
+                }
+            }
+            spawn(&self.task).await
+        }
+        .boxed_local()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    fn template(label: &str, depends_on: &[&str], depends_order: DependsOrder) -> TaskTemplate {
+        TaskTemplate {
+            label: label.to_string(),
+            command: format!("echo {label}"),
+            depends_on: depends_on.iter().map(|label| label.to_string()).collect(),
+            depends_order,
+            ..TaskTemplate::default()
+        }
+    }
+
+    fn build_tree(
+        root: &TaskTemplate,
+        templates: &[TaskTemplate],
+    ) -> anyhow::Result<TaskDependencyTree<TaskTemplate>> {
+        TaskDependencyTree::build(
+            root.clone(),
+            |template| template,
+            |_, label| {
+                templates
+                    .iter()
+                    .find(|template| template.label == label)
+                    .cloned()
+            },
+        )
+    }
+
+    fn run_tree(
+        tree: &TaskDependencyTree<TaskTemplate>,
+        failing_label: Option<&str>,
+    ) -> (anyhow::Result<()>, Vec<String>) {
+        let spawned = RefCell::new(Vec::new());
+        let spawn = |template: &TaskTemplate| {
+            spawned.borrow_mut().push(template.label.clone());
+            let failed = Some(template.label.as_str()) == failing_label;
+            let label = template.label.clone();
+            async move {
+                if failed {
+                    bail!("Task {label:?} failed");
+                }
+                Ok(())
+            }
+        };
+        let result = futures::executor::block_on(tree.run(&spawn));
+        (result, spawned.into_inner())
+    }
+
+    #[test]
+    fn test_sequential_dependencies() {
+        let templates = vec![
+            template("build", &["lint", "compile"], DependsOrder::Sequence),
+            template("lint", &[], DependsOrder::Sequence),
+            template("compile", &["codegen"], DependsOrder::Sequence),
+            template("codegen", &[], DependsOrder::Sequence),
+        ];
+        let tree = build_tree(&templates[0], &templates).unwrap();
+
+        let (result, spawned) = run_tree(&tree, None);
+        assert!(result.is_ok());
+        assert_eq!(spawned, vec!["lint", "codegen", "compile", "build"]);
+
+        let (result, spawned) = run_tree(&tree, Some("codegen"));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Task \"codegen\" failed",
+            "Dependency failures should fail the whole run"
+        );
+        assert_eq!(
+            spawned,
+            vec!["lint", "codegen"],
+            "Tasks depending on a failed task should not be spawned"
+        );
+    }
+
+    #[test]
+    fn test_parallel_dependencies() {
+        let templates = vec![
+            template("all", &["a", "b"], DependsOrder::Parallel),
+            template("a", &[], DependsOrder::Sequence),
+            template("b", &[], DependsOrder::Sequence),
+        ];
+        let tree = build_tree(&templates[0], &templates).unwrap();
+
+        let (result, spawned) = run_tree(&tree, None);
+        assert!(result.is_ok());
+        assert_eq!(spawned, vec!["a", "b", "all"]);
+
+        let (result, spawned) = run_tree(&tree, Some("a"));
+        assert!(result.is_err());
+        assert!(!spawned.contains(&"all".to_string()));
+    }
+
+    #[test]
+    fn test_invalid_dependencies() {
+        let templates = vec![
+            template("a", &["b"], DependsOrder::Sequence),
+            template("b", &["c"], DependsOrder::Sequence),
+            template("c", &["a"], DependsOrder::Sequence),
+            template("d", &["missing"], DependsOrder::Sequence),
+            template("e", &["e"], DependsOrder::Sequence),
+        ];
+
+        let error = build_tree(&templates[0], &templates).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Task dependency cycle detected: \"a\" -> \"b\" -> \"c\" -> \"a\""
+        );
+
+        let error = build_tree(&templates[3], &templates).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Task \"d\" depends on unknown task \"missing\""
+        );
+
+        let error = build_tree(&templates[4], &templates).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Task dependency cycle detected: \"e\" -> \"e\""
+        );
+    }
+}
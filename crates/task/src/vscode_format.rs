@@ -3,7 +3,10 @@ use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    BackgroundPattern, DependsOrder, FileLocation, ProblemMatcher, ProblemMatcherDefinition,
+    ProblemPattern, ProblemSeverity, TaskTemplate, TaskTemplates, VariableName,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -22,6 +25,190 @@ struct VsCodeTaskDefinition {
     #[serde(flatten)]
     other_attributes: HashMap<String, serde_json_lenient::Value>,
     options: Option<TaskOptions>,
+    depends_on: Option<DependsOn>,
+    depends_order: Option<VsCodeDependsOrder>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum DependsOn {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+enum VsCodeDependsOrder {
+    Sequence,
+    Parallel,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> OneOrMany<T> {
+    fn into_vec(self) -> Vec<T> {
+        match self {
+            Self::One(item) => vec![item],
+            Self::Many(items) => items,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemMatcher {
+    Named(String),
+    Custom(Box<VsCodeCustomProblemMatcher>),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeCustomProblemMatcher {
+    base: Option<String>,
+    owner: Option<String>,
+    source: Option<String>,
+    severity: Option<ProblemSeverity>,
+    file_location: Option<OneOrMany<String>>,
+    pattern: Option<OneOrMany<VsCodeProblemPattern>>,
+    background: Option<VsCodeBackground>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeBackground {
+    begins_pattern: Option<VsCodeBackgroundPattern>,
+    ends_pattern: Option<VsCodeBackgroundPattern>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeBackgroundPattern {
+    Regexp(String),
+    Object { regexp: String },
+}
+
+impl VsCodeBackgroundPattern {
+    fn into_regexp(self) -> String {
+        match self {
+            Self::Regexp(regexp) | Self::Object { regexp } => regexp,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemPattern {
+    regexp: String,
+    file: Option<usize>,
+    location: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    severity: Option<usize>,
+    code: Option<usize>,
+    message: Option<usize>,
+    #[serde(default, rename = "loop")]
+    repeat: bool,
+}
+
+impl VsCodeProblemMatcher {
+    fn into_zed_format(self) -> anyhow::Result<ProblemMatcherDefinition> {
+        let matcher = match self {
+            Self::Named(name) => {
+                if ProblemMatcher::builtin(&name).is_none() {
+                    bail!("Unsupported problem matcher {name:?}");
+                }
+                return Ok(ProblemMatcherDefinition::Named(name));
+            }
+            Self::Custom(matcher) => *matcher,
+        };
+
+        let base = match &matcher.base {
+            Some(base) => Some(
+                ProblemMatcher::builtin(base)
+                    .ok_or_else(|| anyhow::anyhow!("Unsupported base problem matcher {base:?}"))?,
+            ),
+            None => None,
+        };
+        let pattern = match matcher.pattern {
+            Some(pattern) => {
+                let mut patterns = pattern.into_vec();
+                // VS Code fills the missing groups of a single pattern with the defaults.
+                if let [pattern] = patterns.as_mut_slice() {
+                    pattern.file.get_or_insert(1);
+                    if pattern.location.is_none() && pattern.line.is_none() {
+                        pattern.line = Some(2);
+                        pattern.column.get_or_insert(3);
+                    }
+                    pattern.message.get_or_insert(0);
+                }
+                patterns
+                    .into_iter()
+                    .map(|pattern| ProblemPattern {
+                        regexp: pattern.regexp,
+                        file: pattern.file,
+                        location: pattern.location,
+                        line: pattern.line,
+                        column: pattern.column,
+                        end_line: pattern.end_line,
+                        end_column: pattern.end_column,
+                        severity: pattern.severity,
+                        code: pattern.code,
+                        message: pattern.message,
+                        repeat: pattern.repeat,
+                    })
+                    .collect()
+            }
+            None => match &base {
+                Some(base) => base.pattern.clone(),
+                None => bail!("Problem matcher has neither `pattern` nor `base`"),
+            },
+        };
+        // Relative paths are resolved against the task's working directory,
+        // the base directory VS Code allows to specify is not supported.
+        let file_location = match matcher
+            .file_location
+            .map(OneOrMany::into_vec)
+            .as_ref()
+            .and_then(|location| location.first())
+            .map(String::as_str)
+        {
+            Some("absolute") => FileLocation::Absolute,
+            Some("relative") => FileLocation::Relative,
+            Some(_) => FileLocation::AutoDetect,
+            None => base
+                .as_ref()
+                .map_or(FileLocation::AutoDetect, |base| base.file_location),
+        };
+
+        Ok(ProblemMatcherDefinition::Custom(ProblemMatcher {
+            source: matcher
+                .source
+                .or(matcher.owner)
+                .or_else(|| base.as_ref()?.source.clone()),
+            severity: matcher
+                .severity
+                .or_else(|| Some(base.as_ref()?.severity))
+                .unwrap_or_default(),
+            file_location,
+            pattern,
+            background: matcher
+                .background
+                .and_then(|background| {
+                    Some(BackgroundPattern {
+                        begins_pattern: background.begins_pattern?.into_regexp(),
+                        ends_pattern: background.ends_pattern?.into_regexp(),
+                    })
+                })
+                .or_else(|| base.as_ref()?.background.clone()),
+        }))
+    }
 }
 
 #[derive(Clone, Deserialize, PartialEq, Debug)]
@@ -85,29 +272,51 @@ impl EnvVariableReplacer {
 
 impl VsCodeTaskDefinition {
     fn into_zed_format(self, replacer: &EnvVariableReplacer) -> anyhow::Result<TaskTemplate> {
-        if self.other_attributes.contains_key("dependsOn") {
-            bail!("Encountered unsupported `dependsOn` key during deserialization");
-        }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
-        let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+        let depends_on = match self.depends_on {
+            Some(DependsOn::Single(label)) => vec![label],
+            Some(DependsOn::Multiple(labels)) => labels,
+            None => Vec::new(),
         };
-
-        let (command, args) = match command {
-            Command::Npm { script } => ("npm".to_owned(), vec!["run".to_string(), script]),
-            Command::Shell { command, args } => (command, args),
-            Command::Gulp { task } => ("gulp".to_owned(), vec![task]),
+        // Unlike Zed, VS Code runs dependencies in parallel by default.
+        let depends_order = match self.depends_order {
+            Some(VsCodeDependsOrder::Sequence) => DependsOrder::Sequence,
+            Some(VsCodeDependsOrder::Parallel) => DependsOrder::Parallel,
+            None if depends_on.is_empty() => DependsOrder::default(),
+            None => DependsOrder::Parallel,
+        };
+        // `type` might not be set in tasks that only use `dependsOn` to run other tasks.
+        let (command, args) = match self.command {
+            Some(Command::Npm { script }) => ("npm".to_owned(), vec!["run".to_string(), script]),
+            Some(Command::Shell { command, args }) => (command, args),
+            Some(Command::Gulp { task }) => ("gulp".to_owned(), vec![task]),
+            None if !depends_on.is_empty() => (String::new(), Vec::new()),
+            None => bail!("Missing `type` field in task"),
         };
         // Per VSC docs, only `command`, `args` and `options` support variable substitution.
         let command = replacer.replace(&command);
         let args = args.into_iter().map(|arg| replacer.replace(&arg)).collect();
+        // Problem matchers are parsed separately, so that unsupported ones do not prevent the task from being added.
+        let problem_matchers = self
+            .other_attributes
+            .get("problemMatcher")
+            .and_then(|problem_matcher| {
+                serde_json_lenient::from_value::<OneOrMany<VsCodeProblemMatcher>>(
+                    problem_matcher.clone(),
+                )
+                .log_err()
+            })
+            .map(OneOrMany::into_vec)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|problem_matcher| problem_matcher.into_zed_format().log_err())
+            .collect();
         let mut ret = TaskTemplate {
             label: self.label,
             command,
             args,
+            depends_on,
+            depends_order,
+            problem_matchers,
             ..Default::default()
         };
         if let Some(options) = self.options {
@@ -154,8 +363,9 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
-        vscode_format::{Command, VsCodeTaskDefinition},
-        TaskTemplate, TaskTemplates, VsCodeTaskFile,
+        vscode_format::{Command, DependsOn, VsCodeTaskDefinition},
+        DependsOrder, FileLocation, ProblemMatcher, ProblemMatcherDefinition, TaskTemplate,
+        TaskTemplates, VsCodeTaskFile,
     };
 
     use super::EnvVariableReplacer;
@@ -212,6 +422,8 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
             },
             VsCodeTaskDefinition {
                 label: "tsc: watch ./src".to_string(),
@@ -226,6 +438,8 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
             },
             VsCodeTaskDefinition {
                 label: "npm: build:compiler".to_string(),
@@ -234,6 +448,8 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
             },
             VsCodeTaskDefinition {
                 label: "npm: build:tests".to_string(),
@@ -242,6 +458,8 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
             },
         ];
 
@@ -257,6 +475,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcherDefinition::Named("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
@@ -268,18 +487,21 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                problem_matchers: vec![ProblemMatcherDefinition::Named("$tsc-watch".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matchers: vec![ProblemMatcherDefinition::Named("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcherDefinition::Named("$tsc".to_string())],
                 ..Default::default()
             },
         ];
@@ -301,6 +523,8 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
             },
             VsCodeTaskDefinition {
                 label: "Build Extension".to_string(),
@@ -309,6 +533,8 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
             },
             VsCodeTaskDefinition {
                 label: "Build Server".to_string(),
@@ -318,6 +544,8 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
             },
             VsCodeTaskDefinition {
                 label: "Build Server (Release)".to_string(),
@@ -327,6 +555,8 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
             },
             VsCodeTaskDefinition {
                 label: "Pretest".to_string(),
@@ -335,18 +565,30 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
             },
             VsCodeTaskDefinition {
                 label: "Build Server and Extension".to_string(),
                 command: None,
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: Some(DependsOn::Multiple(vec![
+                    "Build Server".to_string(),
+                    "Build Extension".to_string(),
+                ])),
+                depends_order: None,
             },
             VsCodeTaskDefinition {
                 label: "Build Server (Release) and Extension".to_string(),
                 command: None,
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: Some(DependsOn::Multiple(vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ])),
+                depends_order: None,
             },
         ];
         assert_eq!(vscode_definitions.tasks.len(), expected.len());
@@ -355,33 +597,64 @@ mod tests {
             .iter()
             .zip(expected)
             .for_each(|(lhs, rhs)| compare_without_other_attributes(lhs.clone(), rhs));
+        let rustc = ProblemMatcherDefinition::Named("$rustc".to_string());
+        let tsc_relative = ProblemMatcherDefinition::Custom(ProblemMatcher {
+            file_location: FileLocation::Relative,
+            ..ProblemMatcher::builtin("$tsc").unwrap()
+        });
+        let tsc_watch_relative = ProblemMatcherDefinition::Custom(ProblemMatcher {
+            file_location: FileLocation::Relative,
+            ..ProblemMatcher::builtin("$tsc-watch").unwrap()
+        });
         let expected = vec![
             TaskTemplate {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                problem_matchers: vec![tsc_watch_relative],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matchers: vec![tsc_relative.clone()],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matchers: vec![rustc.clone()],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matchers: vec![rustc.clone()],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matchers: vec![tsc_relative.clone()],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![rustc.clone()],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![rustc.clone()],
                 ..Default::default()
             },
         ];
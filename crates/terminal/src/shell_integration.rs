@@ -0,0 +1,782 @@
+//! Shell integration through the `OSC 133` semantic prompt sequences, as emitted by shells set up
+//! for terminals like iTerm2, WezTerm or VS Code: https://gitlab.freedesktop.org/Per_Bothner/specifications/blob/master/proposals/semantic-prompts.md
+//!
+//! Alacritty ignores these sequences, so the PTY output is scanned for them before it reaches the
+//! terminal. As the terminal's cursor is only known after alacritty parsed everything preceding a
+//! mark, the output after each mark is held back until the terminal recorded the mark at its
+//! cursor, when processing the wakeup that follows the output up to the mark.
+//!
+//! Only Unix PTYs are scanned, so on Windows no command blocks are recorded.
+
+use std::{
+    ops::Range,
+    time::{Duration, Instant},
+};
+
+use alacritty_terminal::{
+    event::EventListener,
+    grid::Dimensions,
+    index::{Boundary, Column, Line, Point as AlacPoint},
+    term::TermMode,
+    Term,
+};
+use collections::VecDeque;
+use parking_lot::Mutex;
+
+/// The most command blocks kept per terminal, older ones are dropped first.
+const MAX_COMMAND_BLOCKS: usize = 1000;
+/// OSCs longer than this can't be shell integration marks and are not buffered.
+const MAX_MARK_LEN: usize = 64;
+
+/// A semantic prompt mark, `OSC 133 ; <kind> ST`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellMark {
+    /// `A`: the shell starts printing its prompt.
+    PromptStart,
+    /// `B`: the prompt ended and the user types the command.
+    CommandStart,
+    /// `C`: the command got submitted and its output starts.
+    CommandExecuted,
+    /// `D [; exit code]`: the command finished.
+    CommandFinished { exit_code: Option<i32> },
+}
+
+/// A command run in the terminal's shell, along with its prompt and output.
+///
+/// Points have their lines counted from the top of the scrollback, so they don't change as the
+/// terminal scrolls. Once the scrollback is full, its oldest lines get dropped without changing
+/// the scrollback size, so blocks recorded before that point to lines below their actual ones.
+#[derive(Debug, Clone)]
+pub struct CommandBlock {
+    /// From the start of the prompt to the start of the command typed.
+    pub prompt: Range<AlacPoint<usize>>,
+    /// The command line, once it got submitted.
+    pub command: Option<String>,
+    /// The command's output, ending where the command finished.
+    pub output: Option<Range<AlacPoint<usize>>>,
+    /// The exit code reported by the shell, if any.
+    pub exit_code: Option<i32>,
+    /// How long the command ran for, once it finished.
+    pub duration: Option<Duration>,
+    started_at: Option<Instant>,
+}
+
+impl CommandBlock {
+    pub fn is_finished(&self) -> bool {
+        self.duration.is_some()
+    }
+}
+
+/// A finished command's prompt line in the viewport, to be marked in the gutter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandMark {
+    /// The grid line, same as in the terminal's [`crate::IndexedCell`]s.
+    pub line: Line,
+    pub exit_code: Option<i32>,
+}
+
+/// The shell integration state shared between the terminal and its PTY reader.
+#[derive(Default)]
+pub struct ShellIntegration {
+    blocks: Mutex<VecDeque<CommandBlock>>,
+    /// The last mark found in the PTY output, which is held back after it until it got recorded.
+    pending_mark: Mutex<Option<ShellMark>>,
+    /// Makes the PTY reader read the held back output once the pending mark got recorded.
+    #[cfg(unix)]
+    resume_reading: std::sync::OnceLock<std::os::unix::net::UnixStream>,
+}
+
+impl ShellIntegration {
+    pub fn command_blocks(&self) -> Vec<CommandBlock> {
+        self.blocks.lock().iter().cloned().collect()
+    }
+
+    pub(crate) fn clear(&self) {
+        self.blocks.lock().clear();
+    }
+
+    pub(crate) fn has_pending_mark(&self) -> bool {
+        self.pending_mark.lock().is_some()
+    }
+
+    /// Records the pending mark at the terminal's cursor, which must have parsed the output up to
+    /// the mark, and lets the PTY reader read the output after it.
+    pub(crate) fn record_pending_mark<T: EventListener>(&self, term: &Term<T>) {
+        let Some(mark) = self.pending_mark.lock().take() else {
+            return;
+        };
+        self.record_mark(mark, term);
+        #[cfg(unix)]
+        if let Some(mut resume_reading) = self.resume_reading.get() {
+            std::io::Write::write_all(&mut resume_reading, &[0]).ok();
+        }
+    }
+
+    /// Records the mark at the terminal's cursor, which must be right after the mark.
+    fn record_mark<T: EventListener>(&self, mark: ShellMark, term: &Term<T>) {
+        // Full screen applications don't run commands from a prompt.
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+
+        let point = to_absolute(term.grid().cursor.point, term);
+        let mut blocks = self.blocks.lock();
+        match mark {
+            ShellMark::PromptStart => {
+                if blocks.len() == MAX_COMMAND_BLOCKS {
+                    blocks.pop_front();
+                }
+                blocks.push_back(CommandBlock {
+                    prompt: point..point,
+                    command: None,
+                    output: None,
+                    exit_code: None,
+                    duration: None,
+                    started_at: None,
+                });
+            }
+            ShellMark::CommandStart => {
+                if let Some(block) = blocks.back_mut().filter(|block| block.output.is_none()) {
+                    block.prompt.end = point;
+                }
+            }
+            ShellMark::CommandExecuted => {
+                if let Some(block) = blocks.back_mut().filter(|block| block.output.is_none()) {
+                    block.command = text_between(term, block.prompt.end, point)
+                        .map(|command| command.trim().to_string());
+                    block.output = Some(point..point);
+                    block.started_at = Some(Instant::now());
+                }
+            }
+            ShellMark::CommandFinished { exit_code } => {
+                if let Some(block) = blocks.back_mut().filter(|block| !block.is_finished()) {
+                    if let (Some(output), Some(started_at)) =
+                        (block.output.as_mut(), block.started_at)
+                    {
+                        output.end = point;
+                        block.exit_code = exit_code;
+                        block.duration = Some(started_at.elapsed());
+                    }
+                }
+            }
+        }
+    }
+
+    /// The marks of the finished commands with their prompt in the terminal's viewport.
+    pub(crate) fn command_marks<T: EventListener>(&self, term: &Term<T>) -> Vec<CommandMark> {
+        let history_size = term.grid().history_size() as i32;
+        let display_offset = term.grid().display_offset() as i32;
+        let visible_lines = -display_offset..term.screen_lines() as i32 - display_offset;
+        self.blocks
+            .lock()
+            .iter()
+            .filter(|block| block.is_finished())
+            .filter_map(|block| {
+                let line = block.prompt.start.line as i32 - history_size;
+                visible_lines.contains(&line).then_some(CommandMark {
+                    line: Line(line),
+                    exit_code: block.exit_code,
+                })
+            })
+            .collect()
+    }
+
+    /// The display offset showing the closest prompt above or below the viewport's top.
+    pub(crate) fn prompt_display_offset<T: EventListener>(
+        &self,
+        term: &Term<T>,
+        previous: bool,
+    ) -> Option<usize> {
+        let history_size = term.grid().history_size();
+        let viewport_top = history_size - term.grid().display_offset();
+        let blocks = self.blocks.lock();
+        let prompt_line = if previous {
+            blocks
+                .iter()
+                .rev()
+                .map(|block| block.prompt.start.line)
+                .find(|line| *line < viewport_top)?
+        } else {
+            blocks
+                .iter()
+                .map(|block| block.prompt.start.line)
+                .find(|line| *line > viewport_top)
+                .unwrap_or(history_size)
+        };
+        Some(history_size.saturating_sub(prompt_line))
+    }
+
+    /// The text printed by the last finished command, if it's still in the scrollback.
+    pub(crate) fn last_command_output<T: EventListener>(&self, term: &Term<T>) -> Option<String> {
+        let blocks = self.blocks.lock();
+        let output = blocks
+            .iter()
+            .rev()
+            .find(|block| block.is_finished())?
+            .output
+            .clone()?;
+        text_between(term, output.start, output.end)
+    }
+}
+
+fn to_absolute<T>(point: AlacPoint, term: &Term<T>) -> AlacPoint<usize> {
+    let line = point.line.0 + term.grid().history_size() as i32;
+    AlacPoint::new(line.max(0) as usize, point.column)
+}
+
+/// The text from `start` to right before `end`, the parts not in the scrollback anymore omitted.
+fn text_between<T>(
+    term: &Term<T>,
+    start: AlacPoint<usize>,
+    end: AlacPoint<usize>,
+) -> Option<String> {
+    let history_size = term.grid().history_size() as i32;
+    let to_grid = |point: AlacPoint<usize>| {
+        AlacPoint::new(Line(point.line as i32 - history_size), point.column)
+    };
+    let topmost_point = AlacPoint::new(term.topmost_line(), Column(0));
+    let start = to_grid(start).max(topmost_point);
+    let end = to_grid(end);
+    if end <= start {
+        return None;
+    }
+    let end = end.sub(term, Boundary::Grid, 1);
+    Some(term.bounds_to_string(start, end))
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum ParserState {
+    #[default]
+    Ground,
+    Escape,
+    Osc,
+    OscEscape,
+}
+
+/// Finds `OSC 133` marks in the PTY output, which might be split across reads.
+#[derive(Debug, Default)]
+pub(crate) struct ShellMarkParser {
+    state: ParserState,
+    osc: Vec<u8>,
+    osc_too_long: bool,
+}
+
+impl ShellMarkParser {
+    /// Scans the bytes up to the end of the first mark in them, returning how many bytes it
+    /// took and the mark.
+    pub(crate) fn advance(&mut self, bytes: &[u8]) -> Option<(usize, ShellMark)> {
+        for (ix, &byte) in bytes.iter().enumerate() {
+            match self.state {
+                ParserState::Ground => {
+                    if byte == 0x1b {
+                        self.state = ParserState::Escape;
+                    }
+                }
+                ParserState::Escape => {
+                    self.state = match byte {
+                        b']' => {
+                            self.osc.clear();
+                            self.osc_too_long = false;
+                            ParserState::Osc
+                        }
+                        0x1b => ParserState::Escape,
+                        _ => ParserState::Ground,
+                    };
+                }
+                ParserState::Osc => match byte {
+                    0x07 => {
+                        self.state = ParserState::Ground;
+                        if let Some(mark) = self.finish_osc() {
+                            return Some((ix + 1, mark));
+                        }
+                    }
+                    0x1b => self.state = ParserState::OscEscape,
+                    _ if self.osc.len() < MAX_MARK_LEN => self.osc.push(byte),
+                    _ => self.osc_too_long = true,
+                },
+                ParserState::OscEscape => {
+                    // Any escape ends the OSC, but only `ESC \` terminates it properly.
+                    if byte == b'\\' {
+                        self.state = ParserState::Ground;
+                        if let Some(mark) = self.finish_osc() {
+                            return Some((ix + 1, mark));
+                        }
+                    } else {
+                        self.state = ParserState::Escape;
+                        return self
+                            .advance(&bytes[ix..])
+                            .map(|(len, mark)| (ix + len, mark));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn finish_osc(&mut self) -> Option<ShellMark> {
+        if self.osc_too_long {
+            return None;
+        }
+        let osc = std::str::from_utf8(&self.osc).ok()?;
+        let mut params = osc.strip_prefix("133;")?.split(';');
+        match params.next()? {
+            "A" => Some(ShellMark::PromptStart),
+            "B" => Some(ShellMark::CommandStart),
+            "C" => Some(ShellMark::CommandExecuted),
+            "D" => Some(ShellMark::CommandFinished {
+                exit_code: params.next().and_then(|code| code.parse().ok()),
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(unix)]
+pub(crate) use pty::ShellIntegrationPty;
+
+#[cfg(unix)]
+mod pty {
+    use std::{
+        io::{self, Read, Write},
+        os::unix::net::UnixStream,
+        sync::Arc,
+    };
+
+    use alacritty_terminal::{
+        event::{OnResize, WindowSize},
+        tty::{ChildEvent, EventedPty, EventedReadWrite},
+    };
+    use polling::{Event, PollMode, Poller};
+
+    use super::{ShellIntegration, ShellMark, ShellMarkParser};
+
+    /// Wraps the PTY to hold its output back after each shell integration mark, until the
+    /// terminal recorded the mark at its cursor.
+    ///
+    /// Output held back would only be read again once the PTY has more, so a socket registered
+    /// like the PTY is kept readable while there's any left to read.
+    pub(crate) struct ShellIntegrationPty<T> {
+        pty: T,
+        shell_integration: Arc<ShellIntegration>,
+        parser: ShellMarkParser,
+        /// Read from the PTY, not scanned for marks yet.
+        input: Vec<u8>,
+        /// Scanned and ready to be parsed by the terminal.
+        output: Vec<u8>,
+        /// The mark at the end of `output`.
+        output_mark: Option<ShellMark>,
+        child_exited: bool,
+        wake_rx: UnixStream,
+        wake_tx: UnixStream,
+    }
+
+    impl<T: EventedPty> ShellIntegrationPty<T> {
+        pub(crate) fn new(pty: T, shell_integration: Arc<ShellIntegration>) -> io::Result<Self> {
+            let (wake_rx, wake_tx) = UnixStream::pair()?;
+            wake_rx.set_nonblocking(true)?;
+            wake_tx.set_nonblocking(true)?;
+            shell_integration
+                .resume_reading
+                .set(wake_tx.try_clone()?)
+                .ok();
+            Ok(Self {
+                pty,
+                shell_integration,
+                parser: ShellMarkParser::default(),
+                input: Vec::new(),
+                output: Vec::new(),
+                output_mark: None,
+                child_exited: false,
+                wake_rx,
+                wake_tx,
+            })
+        }
+
+        fn fill_output(&mut self) -> io::Result<()> {
+            if self.input.is_empty() {
+                let mut buf = [0; 4096];
+                let len = self.pty.reader().read(&mut buf)?;
+                self.input.extend_from_slice(&buf[..len]);
+            }
+            match self.parser.advance(&self.input) {
+                Some((len, mark)) => {
+                    self.output.extend(self.input.drain(..len));
+                    self.output_mark = Some(mark);
+                }
+                None => self.output.append(&mut self.input),
+            }
+            Ok(())
+        }
+    }
+
+    impl<T: EventedPty> Read for ShellIntegrationPty<T> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let mut wake_buf = [0; 64];
+            while matches!(self.wake_rx.read(&mut wake_buf), Ok(len) if len > 0) {}
+
+            // The remaining output is read at once when the shell exits, so don't hold it back.
+            if self.shell_integration.has_pending_mark() && !self.child_exited {
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            if self.output.is_empty() {
+                self.fill_output()?;
+            }
+
+            let len = buf.len().min(self.output.len());
+            buf[..len].copy_from_slice(&self.output[..len]);
+            self.output.drain(..len);
+            if self.output.is_empty() {
+                if let Some(mark) = self.output_mark.take() {
+                    *self.shell_integration.pending_mark.lock() = Some(mark);
+                }
+            }
+
+            let has_more_output = !self.input.is_empty() || !self.output.is_empty();
+            if has_more_output && !self.shell_integration.has_pending_mark() {
+                self.wake_tx.write_all(&[0]).ok();
+            }
+            Ok(len)
+        }
+    }
+
+    impl<T: EventedPty> EventedReadWrite for ShellIntegrationPty<T> {
+        type Reader = Self;
+        type Writer = T::Writer;
+
+        unsafe fn register(
+            &mut self,
+            poll: &Arc<Poller>,
+            interest: Event,
+            mode: PollMode,
+        ) -> io::Result<()> {
+            unsafe {
+                self.pty.register(poll, interest, mode)?;
+                poll.add_with_mode(
+                    &self.wake_rx,
+                    Event::readable(interest.key),
+                    PollMode::Level,
+                )
+            }
+        }
+
+        fn reregister(
+            &mut self,
+            poll: &Arc<Poller>,
+            interest: Event,
+            mode: PollMode,
+        ) -> io::Result<()> {
+            self.pty.reregister(poll, interest, mode)?;
+            poll.modify_with_mode(
+                &self.wake_rx,
+                Event::readable(interest.key),
+                PollMode::Level,
+            )
+        }
+
+        fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+            self.pty.deregister(poll)?;
+            poll.delete(&self.wake_rx)
+        }
+
+        fn reader(&mut self) -> &mut Self {
+            self
+        }
+
+        fn writer(&mut self) -> &mut T::Writer {
+            self.pty.writer()
+        }
+    }
+
+    impl<T: EventedPty> EventedPty for ShellIntegrationPty<T> {
+        fn next_child_event(&mut self) -> Option<ChildEvent> {
+            let event = self.pty.next_child_event();
+            if let Some(ChildEvent::Exited(_)) = event {
+                self.child_exited = true;
+            }
+            event
+        }
+    }
+
+    impl<T: OnResize> OnResize for ShellIntegrationPty<T> {
+        fn on_resize(&mut self, window_size: WindowSize) {
+            self.pty.on_resize(window_size)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alacritty_terminal::{
+        event::VoidListener,
+        grid::Scroll,
+        term::{test::TermSize, Config},
+        vte::ansi::Processor,
+    };
+
+    use super::*;
+
+    fn marks(parser: &mut ShellMarkParser, mut bytes: &[u8]) -> Vec<(usize, ShellMark)> {
+        let mut marks = Vec::new();
+        let mut offset = 0;
+        while let Some((len, mark)) = parser.advance(bytes) {
+            offset += len;
+            marks.push((offset, mark));
+            bytes = &bytes[len..];
+        }
+        marks
+    }
+
+    #[test]
+    fn test_parse_shell_marks() {
+        let mut parser = ShellMarkParser::default();
+        let output =
+            b"\x1b]133;A\x07$ \x1b]133;B\x1b\\ls\r\n\x1b]133;C\x07a.txt\r\n\x1b]133;D;1\x07";
+        assert_eq!(
+            marks(&mut parser, output),
+            vec![
+                (8, ShellMark::PromptStart),
+                (19, ShellMark::CommandStart),
+                (31, ShellMark::CommandExecuted),
+                (48, ShellMark::CommandFinished { exit_code: Some(1) }),
+            ]
+        );
+
+        // Parameters are ignored, and exit codes are optional.
+        assert_eq!(
+            marks(&mut parser, b"\x1b]133;A;cl=m;aid=1\x07\x1b]133;D\x07"),
+            vec![
+                (19, ShellMark::PromptStart),
+                (27, ShellMark::CommandFinished { exit_code: None }),
+            ]
+        );
+
+        // Other sequences are skipped.
+        assert_eq!(
+            marks(
+                &mut parser,
+                b"\x1b]0;133;A\x07\x1b[31m\x1b]8;;http://a\x1b\\"
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_parse_split_shell_marks() {
+        let mut parser = ShellMarkParser::default();
+        assert_eq!(marks(&mut parser, b"output\x1b]13"), vec![]);
+        assert_eq!(
+            marks(&mut parser, b"3;D;0\x1b"),
+            vec![],
+            "marks should only be found once terminated"
+        );
+        assert_eq!(
+            marks(&mut parser, b"\\\x1b]133;A\x07"),
+            vec![
+                (1, ShellMark::CommandFinished { exit_code: Some(0) }),
+                (9, ShellMark::PromptStart),
+            ]
+        );
+
+        // An escape interrupting the OSC starts a new sequence.
+        assert_eq!(
+            marks(&mut parser, b"\x1b]133;B\x1b\x1b]133;C\x07"),
+            vec![(16, ShellMark::CommandExecuted)]
+        );
+
+        let long_osc = [b"\x1b]133;A".as_slice(), &[b';'; MAX_MARK_LEN], b"\x07"].concat();
+        assert_eq!(marks(&mut parser, &long_osc), vec![]);
+    }
+
+    fn test_term(columns: usize, lines: usize) -> Term<VoidListener> {
+        Term::new(
+            Config::default(),
+            &TermSize::new(columns, lines),
+            VoidListener,
+        )
+    }
+
+    /// Writes the output to the terminal, recording its marks like the terminal does after
+    /// parsing the output up to each of them.
+    fn write_output(
+        term: &mut Term<VoidListener>,
+        shell_integration: &ShellIntegration,
+        output: &str,
+    ) {
+        let mut parser = ShellMarkParser::default();
+        let mut processor: Processor = Processor::new();
+        let mut bytes = output.as_bytes();
+        while !bytes.is_empty() {
+            let (len, mark) = match parser.advance(bytes) {
+                Some((len, mark)) => (len, Some(mark)),
+                None => (bytes.len(), None),
+            };
+            for byte in &bytes[..len] {
+                processor.advance(term, *byte);
+            }
+            if let Some(mark) = mark {
+                *shell_integration.pending_mark.lock() = Some(mark);
+                shell_integration.record_pending_mark(term);
+            }
+            bytes = &bytes[len..];
+        }
+        assert!(!shell_integration.has_pending_mark());
+    }
+
+    fn scroll_to_prompt(
+        term: &mut Term<VoidListener>,
+        shell_integration: &ShellIntegration,
+        previous: bool,
+    ) -> Option<usize> {
+        let display_offset = shell_integration.prompt_display_offset(term, previous)?;
+        let delta = display_offset as i32 - term.grid().display_offset() as i32;
+        term.scroll_display(Scroll::Delta(delta));
+        Some(display_offset)
+    }
+
+    fn point(line: usize, column: usize) -> AlacPoint<usize> {
+        AlacPoint::new(line, Column(column))
+    }
+
+    #[test]
+    fn test_record_command_blocks() {
+        let mut term = test_term(20, 10);
+        let shell_integration = ShellIntegration::default();
+        let summary = |shell_integration: &ShellIntegration| {
+            shell_integration
+                .command_blocks()
+                .into_iter()
+                .map(|block| {
+                    let finished = block.is_finished();
+                    (
+                        block.prompt,
+                        block.command,
+                        block.output,
+                        block.exit_code,
+                        finished,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        write_output(
+            &mut term,
+            &shell_integration,
+            "\x1b]133;A\x07$ \x1b]133;B\x07echo hi\r\n\x1b]133;C\x07hi\r\n\x1b]133;D;0\x07",
+        );
+        assert_eq!(
+            summary(&shell_integration),
+            vec![(
+                point(0, 0)..point(0, 2),
+                Some("echo hi".to_string()),
+                Some(point(1, 0)..point(2, 0)),
+                Some(0),
+                true,
+            )]
+        );
+        assert_eq!(
+            shell_integration.last_command_output(&term).as_deref(),
+            Some("hi")
+        );
+
+        write_output(
+            &mut term,
+            &shell_integration,
+            "\x1b]133;A\x07$ \x1b]133;B\x07false\r\n\x1b]133;C\x07\x1b]133;D;1\x07\x1b]133;A\x07$ \x1b]133;B\x07",
+        );
+        assert_eq!(
+            summary(&shell_integration)[1..],
+            [
+                (
+                    point(2, 0)..point(2, 2),
+                    Some("false".to_string()),
+                    Some(point(3, 0)..point(3, 0)),
+                    Some(1),
+                    true,
+                ),
+                (point(3, 0)..point(3, 2), None, None, None, false),
+            ]
+        );
+        assert_eq!(
+            shell_integration.last_command_output(&term),
+            None,
+            "the last command printed nothing"
+        );
+
+        // Full screen applications' marks are ignored.
+        write_output(
+            &mut term,
+            &shell_integration,
+            "\x1b[?1049h\x1b]133;A\x07$ \x1b]133;B\x07",
+        );
+        assert_eq!(shell_integration.command_blocks().len(), 3);
+    }
+
+    #[test]
+    fn test_command_marks_and_prompt_scrolling() {
+        let mut term = test_term(20, 5);
+        let shell_integration = ShellIntegration::default();
+        for exit_code in 0..3 {
+            write_output(
+                &mut term,
+                &shell_integration,
+                &format!(
+                    "\x1b]133;A\x07$ \x1b]133;B\x07cmd\r\n\x1b]133;C\x07a\r\nb\r\nc\r\n\x1b]133;D;{exit_code}\x07"
+                ),
+            );
+        }
+        write_output(
+            &mut term,
+            &shell_integration,
+            "\x1b]133;A\x07$ \x1b]133;B\x07",
+        );
+        assert_eq!(term.grid().history_size(), 8);
+        assert_eq!(
+            shell_integration.last_command_output(&term).as_deref(),
+            Some("a\nb\nc")
+        );
+
+        // Only the finished commands in view are marked.
+        assert_eq!(
+            shell_integration.command_marks(&term),
+            vec![CommandMark {
+                line: Line(0),
+                exit_code: Some(2),
+            }]
+        );
+
+        assert_eq!(
+            scroll_to_prompt(&mut term, &shell_integration, true),
+            Some(4)
+        );
+        assert_eq!(
+            scroll_to_prompt(&mut term, &shell_integration, true),
+            Some(8)
+        );
+        assert_eq!(scroll_to_prompt(&mut term, &shell_integration, true), None);
+        assert_eq!(
+            scroll_to_prompt(&mut term, &shell_integration, false),
+            Some(4)
+        );
+        assert_eq!(
+            shell_integration.command_marks(&term),
+            vec![
+                CommandMark {
+                    line: Line(-4),
+                    exit_code: Some(1),
+                },
+                CommandMark {
+                    line: Line(0),
+                    exit_code: Some(2),
+                },
+            ]
+        );
+        assert_eq!(
+            scroll_to_prompt(&mut term, &shell_integration, false),
+            Some(0)
+        );
+        assert_eq!(
+            scroll_to_prompt(&mut term, &shell_integration, false),
+            Some(0)
+        );
+    }
+}
@@ -3,6 +3,7 @@ pub mod mappings;
 pub use alacritty_terminal;
 
 mod pty_info;
+pub mod shell_integration;
 pub mod terminal_settings;
 
 use alacritty_terminal::{
@@ -21,6 +22,7 @@ use alacritty_terminal::{
     vi_mode::{ViModeCursor, ViMotion},
     vte::ansi::{
         ClearMode, CursorStyle as AlacCursorStyle, Handler, NamedPrivateMode, PrivateMode,
+        Processor,
     },
     Term,
 };
@@ -41,11 +43,12 @@ use futures::StreamExt;
 use pty_info::PtyProcessInfo;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
+use shell_integration::{CommandBlock, CommandMark, ShellIntegration};
 use smol::channel::{Receiver, Sender};
-use task::{HideStrategy, Shell, TaskId};
+use task::{HideStrategy, Problem, ProblemMatcher, Shell, SpawnInTerminal, TaskId};
 use terminal_settings::{AlternateScroll, CursorShape, TerminalSettings};
 use theme::{ActiveTheme, Theme};
-use util::truncate_and_trailoff;
+use util::{truncate_and_trailoff, ResultExt};
 
 use std::{
     cmp::{self, min},
@@ -79,6 +82,9 @@ actions!(
         ScrollPageDown,
         ScrollToTop,
         ScrollToBottom,
+        ScrollToPreviousPrompt,
+        ScrollToNextPrompt,
+        CopyLastCommandOutput,
         ToggleViMode,
     ]
 );
@@ -91,6 +97,8 @@ const SCROLL_MULTIPLIER: f32 = 4.;
 #[cfg(not(target_os = "macos"))]
 const SCROLL_MULTIPLIER: f32 = 1.;
 const MAX_SEARCH_LINES: usize = 100;
+/// How long the output of a running task has to stay unchanged before it gets scanned for problems.
+const PROBLEM_MATCHING_DEBOUNCE: Duration = Duration::from_millis(500);
 const DEBUG_TERMINAL_WIDTH: Pixels = px(500.);
 const DEBUG_TERMINAL_HEIGHT: Pixels = px(30.);
 const DEBUG_CELL_WIDTH: Pixels = px(5.);
@@ -108,6 +116,8 @@ pub enum Event {
     SelectionsChanged,
     NewNavigationTarget(Option<MaybeNavigationTarget>),
     Open(MaybeNavigationTarget),
+    /// The problems found in the task's output changed.
+    TaskProblemsMatched,
 }
 
 #[derive(Clone, Debug)]
@@ -141,6 +151,9 @@ enum InternalEvent {
     // Adjusted mouse position, should open
     FindHyperlink(Point<Pixels>, bool),
     Copy,
+    // Shell integration events
+    ScrollToPrompt { previous: bool },
+    CopyLastCommandOutput,
     // Vi mode events
     ToggleViMode,
     ViMotion(ViMotion),
@@ -390,6 +403,7 @@ impl TerminalBuilder {
         //Spawn a task so the Alacritty EventLoop can communicate with us in a view context
         //TODO: Remove with a bounded sender which can be dispatched on &self
         let (events_tx, events_rx) = unbounded();
+        let shell_integration = Arc::new(ShellIntegration::default());
         //Set up the terminal...
         let mut term = Term::new(
             config.clone(),
@@ -422,6 +436,10 @@ impl TerminalBuilder {
 
         let pty_info = PtyProcessInfo::new(&pty);
 
+        // Windows PTYs aren't scanned for shell integration marks, so no commands get recorded.
+        #[cfg(unix)]
+        let pty = shell_integration::ShellIntegrationPty::new(pty, shell_integration.clone())?;
+
         //And connect them together
         let event_loop = EventLoop::new(
             term.clone(),
@@ -441,6 +459,7 @@ impl TerminalBuilder {
             completion_tx,
             term,
             term_config: config,
+            shell_integration,
             events: VecDeque::with_capacity(10), //Should never get this high.
             last_content: Default::default(),
             last_mouse: None,
@@ -457,6 +476,7 @@ impl TerminalBuilder {
             url_regex: RegexSearch::new(URL_REGEX).unwrap(),
             word_regex: RegexSearch::new(WORD_REGEX).unwrap(),
             vi_mode_enabled: false,
+            problem_matching: None,
         };
 
         Ok(TerminalBuilder {
@@ -555,6 +575,8 @@ pub struct TerminalContent {
     pub cursor_char: char,
     pub size: TerminalSize,
     pub last_hovered_word: Option<HoveredWord>,
+    /// Exit status marks of the commands run from the prompts in view.
+    pub command_marks: Vec<CommandMark>,
 }
 
 #[derive(Clone)]
@@ -579,6 +601,7 @@ impl Default for TerminalContent {
             cursor_char: Default::default(),
             size: Default::default(),
             last_hovered_word: None,
+            command_marks: Vec::new(),
         }
     }
 }
@@ -594,6 +617,7 @@ pub struct Terminal {
     completion_tx: Sender<()>,
     term: Arc<FairMutex<Term<ZedListener>>>,
     term_config: Config,
+    shell_integration: Arc<ShellIntegration>,
     events: VecDeque<InternalEvent>,
     /// This is only used for mouse mode cell change detection
     last_mouse: Option<(AlacPoint, AlacDirection)>,
@@ -613,9 +637,12 @@ pub struct Terminal {
     word_regex: RegexSearch,
     task: Option<TaskState>,
     vi_mode_enabled: bool,
+    problem_matching: Option<Task<()>>,
 }
 
 pub struct TaskState {
+    /// The task the terminal got spawned for, to spawn it again with.
+    pub spawn_in_terminal: SpawnInTerminal,
     pub id: TaskId,
     pub full_label: String,
     pub label: String,
@@ -623,6 +650,10 @@ pub struct TaskState {
     pub status: TaskStatus,
     pub completion_rx: Receiver<()>,
     pub hide: HideStrategy,
+    pub problem_matchers: Vec<ProblemMatcher>,
+    /// Problems found in the task output: while the task is running, it gets rescanned
+    /// whenever its output settles, so watch-mode tasks report each rebuild's problems.
+    pub problems: Vec<Problem>,
 }
 
 /// A status of the current terminal tab's task.
@@ -691,7 +722,13 @@ impl Terminal {
                 //NOOP, Handled in render
             }
             AlacTermEvent::Wakeup => {
+                if self.shell_integration.has_pending_mark() {
+                    // Locking fairly waits for the PTY reader to parse the output up to the mark.
+                    let term = self.term.lock();
+                    self.shell_integration.record_pending_mark(&term);
+                }
                 cx.emit(Event::Wakeup);
+                self.schedule_problem_matching(cx);
 
                 if self.pty_info.has_changed() {
                     cx.emit(Event::TitleChanged);
@@ -773,6 +810,7 @@ impl Terminal {
                     term.grid_mut().reset_region((new_cursor.line + 1)..);
                 }
 
+                self.shell_integration.clear();
                 cx.emit(Event::Wakeup);
             }
             InternalEvent::Scroll(scroll) => {
@@ -855,6 +893,21 @@ impl Terminal {
                     cx.write_to_clipboard(ClipboardItem::new_string(txt))
                 }
             }
+            InternalEvent::ScrollToPrompt { previous } => {
+                if let Some(display_offset) = self
+                    .shell_integration
+                    .prompt_display_offset(term, *previous)
+                {
+                    let delta = display_offset as i32 - term.grid().display_offset() as i32;
+                    term.scroll_display(AlacScroll::Delta(delta));
+                    self.refresh_hovered_word();
+                }
+            }
+            InternalEvent::CopyLastCommandOutput => {
+                if let Some(output) = self.shell_integration.last_command_output(term) {
+                    cx.write_to_clipboard(ClipboardItem::new_string(output))
+                }
+            }
             InternalEvent::ScrollToAlacPoint(point) => {
                 term.scroll_to_point(*point);
                 self.refresh_hovered_word();
@@ -1115,6 +1168,25 @@ impl Terminal {
             .push_back(InternalEvent::Scroll(AlacScroll::Bottom));
     }
 
+    pub fn scroll_to_previous_prompt(&mut self) {
+        self.events
+            .push_back(InternalEvent::ScrollToPrompt { previous: true });
+    }
+
+    pub fn scroll_to_next_prompt(&mut self) {
+        self.events
+            .push_back(InternalEvent::ScrollToPrompt { previous: false });
+    }
+
+    pub fn copy_last_command_output(&mut self) {
+        self.events.push_back(InternalEvent::CopyLastCommandOutput);
+    }
+
+    /// The commands run in the shell, as reported by its shell integration, oldest first.
+    pub fn command_blocks(&self) -> Vec<CommandBlock> {
+        self.shell_integration.command_blocks()
+    }
+
     ///Resize the terminal and the PTY.
     pub fn set_size(&mut self, new_size: TerminalSize) {
         if self.last_content.size != new_size {
@@ -1287,10 +1359,15 @@ impl Terminal {
             self.process_terminal_event(&e, &mut terminal, cx)
         }
 
-        self.last_content = Self::make_content(&terminal, &self.last_content);
+        self.last_content =
+            Self::make_content(&terminal, &self.last_content, &self.shell_integration);
     }
 
-    fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
+    fn make_content(
+        term: &Term<ZedListener>,
+        last_content: &TerminalContent,
+        shell_integration: &ShellIntegration,
+    ) -> TerminalContent {
         let content = term.renderable_content();
         TerminalContent {
             cells: content
@@ -1315,6 +1392,7 @@ impl Terminal {
             cursor_char: term.grid()[content.cursor.point].c,
             size: last_content.size,
             last_hovered_word: last_content.last_hovered_word.clone(),
+            command_marks: shell_integration.command_marks(term),
         }
     }
 
@@ -1343,6 +1421,38 @@ impl Terminal {
         lines
     }
 
+    /// The text of the terminal's last lines, at most `max_lines` of them, to restore its history
+    /// from after a restart. There's no history to take while in the alternate screen.
+    pub fn history_text(&self, max_lines: usize) -> Option<String> {
+        let term = self.term.lock_unfair();
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return None;
+        }
+
+        let end_line = term.bottommost_line();
+        let start_line = cmp::max(term.topmost_line(), Line(end_line.0 + 1 - max_lines as i32));
+        let text = term.bounds_to_string(
+            AlacPoint::new(start_line, Column(0)),
+            AlacPoint::new(end_line, term.last_column()),
+        );
+        Some(text.trim_end().to_string())
+    }
+
+    /// Writes a history taken with [`Self::history_text`] into the terminal, dimmed, so it shows
+    /// above whatever the terminal's process writes next.
+    pub fn restore_history(&mut self, history: &str) {
+        if history.is_empty() {
+            return;
+        }
+
+        let history = format!("\x1b[2m{}\x1b[0m\r\n", history.replace('\n', "\r\n"));
+        let mut parser: Processor = Processor::new();
+        let mut term = self.term.lock();
+        for byte in history.bytes() {
+            parser.advance(&mut *term, byte);
+        }
+    }
+
     pub fn focus_in(&self) {
         if self.last_content.mode.contains(TermMode::FOCUS_IN_OUT) {
             self.write_to_pty("\x1b[I".to_string());
@@ -1694,6 +1804,37 @@ impl Terminal {
         Task::ready(())
     }
 
+    fn schedule_problem_matching(&mut self, cx: &mut ModelContext<Self>) {
+        let Some(task) = &self.task else {
+            return;
+        };
+        if task.status != TaskStatus::Running || task.problem_matchers.is_empty() {
+            return;
+        }
+        let term = self.term.clone();
+        let problem_matchers = task.problem_matchers.clone();
+        self.problem_matching = Some(cx.spawn(|terminal, mut cx| async move {
+            cx.background_executor()
+                .timer(PROBLEM_MATCHING_DEBOUNCE)
+                .await;
+            let problems = cx
+                .background_executor()
+                .spawn(async move { match_problems(&problem_matchers, &terminal_output(&term)) })
+                .await;
+            terminal
+                .update(&mut cx, |terminal, cx| {
+                    terminal.problem_matching = None;
+                    if let Some(task) = &mut terminal.task {
+                        if task.status == TaskStatus::Running && task.problems != problems {
+                            task.problems = problems;
+                            cx.emit(Event::TaskProblemsMatched);
+                        }
+                    }
+                })
+                .ok();
+        }));
+    }
+
     fn register_task_finished(
         &mut self,
         error_code: Option<i32>,
@@ -1721,6 +1862,15 @@ impl Terminal {
             }
         };
 
+        if !task.problem_matchers.is_empty() {
+            self.problem_matching = None;
+            let problems = match_problems(&task.problem_matchers, &terminal_output(&self.term));
+            if task.problems != problems {
+                task.problems = problems;
+                cx.emit(Event::TaskProblemsMatched);
+            }
+        }
+
         let (finished_successfully, task_line, command_line) = task_summary(task, error_code);
         // SAFETY: the invocation happens on non `TaskStatus::Running` tasks, once,
         // after either `AlacTermEvent::Exit` or `AlacTermEvent::ChildExit` events that are spawned
@@ -1742,6 +1892,22 @@ impl Terminal {
 }
 
 const TASK_DELIMITER: &str = "⏵ ";
+fn terminal_output(term: &FairMutex<Term<ZedListener>>) -> String {
+    let term = term.lock_unfair();
+    term.bounds_to_string(
+        AlacPoint::new(term.topmost_line(), Column(0)),
+        AlacPoint::new(term.bottommost_line(), term.last_column()),
+    )
+}
+
+fn match_problems(problem_matchers: &[ProblemMatcher], output: &str) -> Vec<Problem> {
+    problem_matchers
+        .iter()
+        .filter_map(|matcher| matcher.find_current_problems(output).log_err())
+        .flatten()
+        .collect()
+}
+
 fn task_summary(task: &TaskState, error_code: Option<i32>) -> (bool, String, String) {
     let escaped_full_label = task.full_label.replace("\r\n", "\r").replace('\n', "\r");
     let (success, task_line) = match error_code {
@@ -40,6 +40,7 @@ pub struct TerminalSettings {
     pub alternate_scroll: AlternateScroll,
     pub option_as_meta: bool,
     pub copy_on_select: bool,
+    pub persist_scrollback: bool,
     pub button: bool,
     pub dock: TerminalDockPosition,
     pub default_width: Pixels,
@@ -158,6 +159,11 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: false
     pub copy_on_select: Option<bool>,
+    /// Whether to store the last lines of the terminals' scrollback, to restore
+    /// them after a restart.
+    ///
+    /// Default: false
+    pub persist_scrollback: Option<bool>,
     /// Whether to show the terminal button in the status bar.
     ///
     /// Default: true
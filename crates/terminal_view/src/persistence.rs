@@ -35,6 +35,10 @@ define_connection! {
             DROP TABLE terminals;
 
             ALTER TABLE terminals2 RENAME TO terminals;
+        ),
+        sql!(
+            ALTER TABLE terminals ADD COLUMN history TEXT;
+            ALTER TABLE terminals ADD COLUMN task TEXT;
         )];
 }
 
@@ -51,14 +55,21 @@ impl TerminalDb {
         }
     }
 
+    // The history is kept as is when `None`, as it can't be taken in the alternate screen.
     query! {
-        pub async fn save_working_directory(
+        pub async fn save_terminal(
             item_id: ItemId,
             workspace_id: WorkspaceId,
-            working_directory: PathBuf
+            working_directory: PathBuf,
+            history: Option<String>,
+            task: Option<String>
         ) -> Result<()> {
-            INSERT OR REPLACE INTO terminals(item_id, workspace_id, working_directory)
-            VALUES (?, ?, ?)
+            INSERT INTO terminals(item_id, workspace_id, working_directory, history, task)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT DO UPDATE SET
+                working_directory = ?3,
+                history = IFNULL(?4, history),
+                task = ?5
         }
     }
 
@@ -70,6 +81,17 @@ impl TerminalDb {
         }
     }
 
+    query! {
+        pub fn get_history_and_task(
+            item_id: ItemId,
+            workspace_id: WorkspaceId
+        ) -> Result<Option<(Option<String>, Option<String>)>> {
+            SELECT history, task
+            FROM terminals
+            WHERE item_id = ? AND workspace_id = ?
+        }
+    }
+
     pub async fn delete_unloaded_items(
         &self,
         workspace: WorkspaceId,
@@ -96,3 +118,54 @@ impl TerminalDb {
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    async fn test_save_and_get_terminal() {
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+
+        TERMINAL_DB
+            .save_terminal(
+                1234,
+                workspace_id,
+                PathBuf::from("/tmp"),
+                Some("$ echo hi\nhi".to_owned()),
+                Some("{}".to_owned()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            TERMINAL_DB
+                .get_history_and_task(1234, workspace_id)
+                .unwrap(),
+            Some((Some("$ echo hi\nhi".to_owned()), Some("{}".to_owned())))
+        );
+
+        // The history is kept when there's none to save, the task is not.
+        TERMINAL_DB
+            .save_terminal(1234, workspace_id, PathBuf::from("/tmp/dir"), None, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            TERMINAL_DB
+                .get_working_directory(1234, workspace_id)
+                .unwrap(),
+            Some(PathBuf::from("/tmp/dir"))
+        );
+        assert_eq!(
+            TERMINAL_DB
+                .get_history_and_task(1234, workspace_id)
+                .unwrap(),
+            Some((Some("$ echo hi\nhi".to_owned()), None))
+        );
+        assert_eq!(
+            TERMINAL_DB
+                .get_history_and_task(4321, workspace_id)
+                .unwrap(),
+            None
+        );
+    }
+}
@@ -45,6 +45,8 @@ pub struct LayoutState {
     display_offset: usize,
     hyperlink_tooltip: Option<AnyElement>,
     gutter: Pixels,
+    /// Display lines of the finished commands' prompts, colored by their exit status.
+    command_marks: Vec<(i32, Hsla)>,
     last_hovered_word: Option<HoveredWord>,
     block_below_cursor_element: Option<AnyElement>,
 }
@@ -735,11 +737,24 @@ impl Element for TerminalElement {
                     cursor_char,
                     selection,
                     cursor,
+                    command_marks,
                     ..
                 } = &self.terminal.read(cx).last_content;
                 let mode = *mode;
                 let display_offset = *display_offset;
 
+                let command_marks = command_marks
+                    .iter()
+                    .map(|mark| {
+                        let color = match mark.exit_code {
+                            Some(0) => theme.status().success,
+                            Some(_) => theme.status().error,
+                            None => theme.status().hint,
+                        };
+                        (mark.line.0 + display_offset as i32, color)
+                    })
+                    .collect();
+
                 // searches, highlights to a single range representations
                 let mut relative_highlighted_ranges = Vec::new();
                 for search_match in search_matches {
@@ -855,6 +870,7 @@ impl Element for TerminalElement {
                     display_offset,
                     hyperlink_tooltip,
                     gutter,
+                    command_marks,
                     last_hovered_word,
                     block_below_cursor_element,
                 }
@@ -919,6 +935,15 @@ impl Element for TerminalElement {
                         rect.paint(origin, &layout.dimensions, cx);
                     }
 
+                    for (line, color) in &layout.command_marks {
+                        let line_height = layout.dimensions.line_height;
+                        let mark_origin =
+                            point(bounds.origin.x, origin.y + line_height * *line as f32);
+                        let mark_bounds =
+                            Bounds::new(mark_origin, size(layout.gutter * 0.3, line_height));
+                        cx.paint_quad(fill(mark_bounds, *color));
+                    }
+
                     for (relative_highlighted_range, color) in
                         layout.relative_highlighted_ranges.iter()
                     {
@@ -3,7 +3,7 @@ use std::{ops::ControlFlow, path::PathBuf, sync::Arc};
 use crate::{default_working_directory, TerminalView};
 use collections::{HashMap, HashSet};
 use db::kvp::KEY_VALUE_STORE;
-use futures::future::join_all;
+use futures::{channel::mpsc::UnboundedSender, future::join_all};
 use gpui::{
     actions, Action, AnchorCorner, AnyView, AppContext, AsyncWindowContext, Entity, EventEmitter,
     ExternalPaths, FocusHandle, FocusableView, IntoElement, Model, ParentElement, Pixels, Render,
@@ -17,7 +17,7 @@ use settings::Settings;
 use task::{RevealStrategy, Shell, SpawnInTerminal, TaskId};
 use terminal::{
     terminal_settings::{TerminalDockPosition, TerminalSettings},
-    Terminal,
+    TaskStatus, Terminal,
 };
 use ui::{
     h_flex, ButtonCommon, Clickable, ContextMenu, IconButton, IconSize, PopoverMenu, Selectable,
@@ -299,8 +299,16 @@ impl TerminalPanel {
                     panel._subscriptions.push(cx.subscribe(
                         &workspace,
                         |terminal_panel, _, e, cx| {
-                            if let workspace::Event::SpawnTask(spawn_in_terminal) = e {
-                                terminal_panel.spawn_task(spawn_in_terminal, cx);
+                            if let workspace::Event::SpawnTask {
+                                action: spawn_in_terminal,
+                                completion_tx,
+                            } = e
+                            {
+                                terminal_panel.spawn_task(
+                                    spawn_in_terminal,
+                                    completion_tx.clone(),
+                                    cx,
+                                );
                             };
                         },
                     ))
@@ -391,7 +399,12 @@ impl TerminalPanel {
             .detach_and_log_err(cx);
     }
 
-    fn spawn_task(&mut self, spawn_in_terminal: &SpawnInTerminal, cx: &mut ViewContext<Self>) {
+    fn spawn_task(
+        &mut self,
+        spawn_in_terminal: &SpawnInTerminal,
+        completion_tx: Option<UnboundedSender<bool>>,
+        cx: &mut ViewContext<Self>,
+    ) {
         let mut spawn_task = spawn_in_terminal.clone();
         // Set up shell args unconditionally, as tasks are always spawned inside of a shell.
         let Some((shell, mut user_args)) = (match spawn_in_terminal.shell.clone() {
@@ -476,15 +489,13 @@ impl TerminalPanel {
         let use_new_terminal = spawn_in_terminal.use_new_terminal;
 
         if allow_concurrent_runs && use_new_terminal {
-            self.spawn_in_new_terminal(spawn_task, cx)
-                .detach_and_log_err(cx);
+            self.spawn_in_new_terminal_reporting_completion(spawn_task, completion_tx, cx);
             return;
         }
 
         let terminals_for_task = self.terminals_for_task(&spawn_in_terminal.full_label, cx);
         if terminals_for_task.is_empty() {
-            self.spawn_in_new_terminal(spawn_task, cx)
-                .detach_and_log_err(cx);
+            self.spawn_in_new_terminal_reporting_completion(spawn_task, completion_tx, cx);
             return;
         }
         let (existing_item_index, existing_terminal) = terminals_for_task
@@ -496,7 +507,9 @@ impl TerminalPanel {
                 !use_new_terminal,
                 "Should have handled 'allow_concurrent_runs && use_new_terminal' case above"
             );
-            self.replace_terminal(spawn_task, existing_item_index, existing_terminal, cx);
+            let new_terminal =
+                self.replace_terminal(spawn_task, existing_item_index, existing_terminal, cx);
+            report_task_completion(new_terminal, completion_tx, cx);
         } else {
             self.deferred_tasks.insert(
                 spawn_in_terminal.id.clone(),
@@ -505,16 +518,19 @@ impl TerminalPanel {
                     terminal_panel
                         .update(&mut cx, |terminal_panel, cx| {
                             if use_new_terminal {
-                                terminal_panel
-                                    .spawn_in_new_terminal(spawn_task, cx)
-                                    .detach_and_log_err(cx);
+                                terminal_panel.spawn_in_new_terminal_reporting_completion(
+                                    spawn_task,
+                                    completion_tx,
+                                    cx,
+                                );
                             } else {
-                                terminal_panel.replace_terminal(
+                                let new_terminal = terminal_panel.replace_terminal(
                                     spawn_task,
                                     existing_item_index,
                                     existing_terminal,
                                     cx,
                                 );
+                                report_task_completion(new_terminal, completion_tx, cx);
                             }
                         })
                         .ok();
@@ -523,6 +539,23 @@ impl TerminalPanel {
         }
     }
 
+    fn spawn_in_new_terminal_reporting_completion(
+        &mut self,
+        spawn_task: SpawnInTerminal,
+        completion_tx: Option<UnboundedSender<bool>>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let new_terminal = self.spawn_in_new_terminal(spawn_task, cx);
+        cx.spawn(|terminal_panel, mut cx| async move {
+            let new_terminal = new_terminal.await;
+            terminal_panel.update(&mut cx, |_, cx| {
+                report_task_completion(new_terminal.as_ref().ok().cloned(), completion_tx, cx)
+            })?;
+            new_terminal.map(|_| ())
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn spawn_in_new_terminal(
         &mut self,
         spawn_task: SpawnInTerminal,
@@ -676,7 +709,7 @@ impl TerminalPanel {
         terminal_item_index: usize,
         terminal_to_replace: View<TerminalView>,
         cx: &mut ViewContext<'_, Self>,
-    ) -> Option<()> {
+    ) -> Option<Model<Terminal>> {
         let project = self
             .workspace
             .update(cx, |workspace, _| workspace.project().clone())
@@ -690,7 +723,7 @@ impl TerminalPanel {
                 .log_err()
         })?;
         terminal_to_replace.update(cx, |terminal_to_replace, cx| {
-            terminal_to_replace.set_terminal(new_terminal, cx);
+            terminal_to_replace.set_terminal(new_terminal.clone(), cx);
         });
 
         match reveal {
@@ -707,7 +740,7 @@ impl TerminalPanel {
             RevealStrategy::Never => {}
         }
 
-        Some(())
+        Some(new_terminal)
     }
 
     fn has_no_terminals(&self, cx: &WindowContext) -> bool {
@@ -719,6 +752,34 @@ impl TerminalPanel {
     }
 }
 
+/// Reports whether the task in the terminal given finished successfully, if anyone waits for that.
+/// A terminal that failed to spawn is reported as a failed task.
+fn report_task_completion<V: 'static>(
+    terminal: Option<Model<Terminal>>,
+    completion_tx: Option<UnboundedSender<bool>>,
+    cx: &mut ViewContext<V>,
+) {
+    let Some(completion_tx) = completion_tx else {
+        return;
+    };
+    let Some(terminal) = terminal else {
+        completion_tx.unbounded_send(false).ok();
+        return;
+    };
+    let task_completed = terminal.read(cx).wait_for_completed_task(cx);
+    cx.spawn(|_, cx| async move {
+        task_completed.await;
+        let success = terminal
+            .read_with(&cx, |terminal, _| {
+                terminal.task().map(|task| task.status)
+                    == Some(TaskStatus::Completed { success: true })
+            })
+            .unwrap_or(false);
+        completion_tx.unbounded_send(success).ok();
+    })
+    .detach();
+}
+
 async fn wait_for_terminals_tasks(
     terminals_for_task: Vec<(usize, View<TerminalView>)>,
     cx: &mut AsyncWindowContext,
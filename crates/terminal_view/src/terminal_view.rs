@@ -20,9 +20,10 @@ use terminal::{
         term::{search::RegexSearch, TermMode},
     },
     terminal_settings::{CursorShape, TerminalBlink, TerminalSettings, WorkingDirectory},
-    Clear, Copy, Event, MaybeNavigationTarget, Paste, ScrollLineDown, ScrollLineUp, ScrollPageDown,
-    ScrollPageUp, ScrollToBottom, ScrollToTop, ShowCharacterPalette, TaskStatus, Terminal,
-    TerminalSize, ToggleViMode,
+    Clear, Copy, CopyLastCommandOutput, Event, MaybeNavigationTarget, Paste, ScrollLineDown,
+    ScrollLineUp, ScrollPageDown, ScrollPageUp, ScrollToBottom, ScrollToNextPrompt,
+    ScrollToPreviousPrompt, ScrollToTop, ShowCharacterPalette, TaskStatus, Terminal, TerminalSize,
+    ToggleViMode,
 };
 use terminal_element::{is_blank, TerminalElement};
 use terminal_panel::TerminalPanel;
@@ -41,6 +42,7 @@ use anyhow::Context;
 use serde::Deserialize;
 use settings::{Settings, SettingsStore};
 use smol::Timer;
+use task::SpawnInTerminal;
 use zed_actions::InlineAssist;
 
 use std::{
@@ -60,6 +62,9 @@ const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
 
 const GIT_DIFF_PATH_PREFIXES: &[char] = &['a', 'b'];
 
+/// How many of the terminal's last lines are stored to be restored after a restart.
+const MAX_RESTORED_HISTORY_LINES: usize = 1000;
+
 ///Event to transmit the scroll from the element to the view
 #[derive(Clone, Debug, PartialEq)]
 pub struct ScrollTerminal(pub i32);
@@ -112,6 +117,8 @@ pub struct TerminalView {
     show_title: bool,
     block_below_cursor: Option<Rc<BlockProperties>>,
     scroll_top: Pixels,
+    /// The task that ran in the terminal before a restart, offered to be spawned again.
+    restored_task: Option<SpawnInTerminal>,
     _subscriptions: Vec<Subscription>,
     _terminal_subscriptions: Vec<Subscription>,
 }
@@ -192,6 +199,7 @@ impl TerminalView {
             show_title: TerminalSettings::get_global(cx).toolbar.title,
             block_below_cursor: None,
             scroll_top: Pixels::ZERO,
+            restored_task: None,
             _subscriptions: vec![
                 focus_in,
                 focus_out,
@@ -231,6 +239,7 @@ impl TerminalView {
                 .action("New Terminal", Box::new(NewTerminal))
                 .separator()
                 .action("Copy", Box::new(Copy))
+                .action("Copy Last Command Output", Box::new(CopyLastCommandOutput))
                 .action("Paste", Box::new(Paste))
                 .action("Select All", Box::new(SelectAll))
                 .action("Clear", Box::new(Clear))
@@ -431,6 +440,22 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn scroll_to_previous_prompt(
+        &mut self,
+        _: &ScrollToPreviousPrompt,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_previous_prompt());
+        cx.notify();
+    }
+
+    fn scroll_to_next_prompt(&mut self, _: &ScrollToNextPrompt, cx: &mut ViewContext<Self>) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_next_prompt());
+        cx.notify();
+    }
+
     fn toggle_vi_mode(&mut self, _: &ToggleViMode, cx: &mut ViewContext<Self>) {
         self.terminal.update(cx, |term, _| term.toggle_vi_mode());
         cx.notify();
@@ -505,6 +530,62 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn offer_restored_task(&mut self, task: SpawnInTerminal, cx: &mut ViewContext<Self>) {
+        let terminal_view = cx.view().downgrade();
+        let label = format!("Task \"{}\" ran here before the restart", task.label);
+        self.restored_task = Some(task);
+        self.set_block_below_cursor(
+            BlockProperties {
+                height: 2,
+                render: Box::new(move |_| {
+                    let rerun_view = terminal_view.clone();
+                    let dismiss_view = terminal_view.clone();
+                    h_flex()
+                        .size_full()
+                        .gap_2()
+                        .child(Label::new(label.clone()).color(Color::Muted))
+                        .child(Button::new("rerun-restored-task", "Rerun").on_click(
+                            move |_, cx| {
+                                rerun_view
+                                    .update(cx, |terminal_view, cx| {
+                                        terminal_view.rerun_restored_task(cx)
+                                    })
+                                    .ok();
+                            },
+                        ))
+                        .child(Button::new("dismiss-restored-task", "Dismiss").on_click(
+                            move |_, cx| {
+                                dismiss_view
+                                    .update(cx, |terminal_view, cx| {
+                                        terminal_view.dismiss_restored_task(cx)
+                                    })
+                                    .ok();
+                            },
+                        ))
+                        .into_any_element()
+                }),
+            },
+            cx,
+        );
+    }
+
+    fn rerun_restored_task(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(task) = self.restored_task.take() else {
+            return;
+        };
+        self.clear_block_below_cursor(cx);
+        self.workspace
+            .update(cx, |workspace, cx| workspace.spawn_in_terminal(task, cx))
+            .ok();
+        cx.emit(ItemEvent::UpdateTab);
+    }
+
+    fn dismiss_restored_task(&mut self, cx: &mut ViewContext<Self>) {
+        self.restored_task = None;
+        self.clear_block_below_cursor(cx);
+        cx.emit(ItemEvent::UpdateTab);
+    }
+
     fn next_blink_epoch(&mut self) -> usize {
         self.blink_epoch += 1;
         self.blink_epoch
@@ -523,6 +604,12 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn copy_last_command_output(&mut self, _: &CopyLastCommandOutput, cx: &mut ViewContext<Self>) {
+        self.terminal
+            .update(cx, |term, _| term.copy_last_command_output());
+        cx.notify();
+    }
+
     ///Attempt to paste the clipboard into the terminal
     fn paste(&mut self, _: &Paste, cx: &mut ViewContext<Self>) {
         if let Some(clipboard_string) = cx.read_from_clipboard().and_then(|item| item.text()) {
@@ -784,6 +871,7 @@ fn subscribe_for_terminal_events(
                 cx.invalidate_character_coordinates();
                 cx.emit(SearchEvent::ActiveMatchChanged)
             }
+            Event::TaskProblemsMatched => {}
         });
     vec![terminal_subscription, terminal_events_subscription]
 }
@@ -980,6 +1068,7 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::send_text))
             .on_action(cx.listener(TerminalView::send_keystroke))
             .on_action(cx.listener(TerminalView::copy))
+            .on_action(cx.listener(TerminalView::copy_last_command_output))
             .on_action(cx.listener(TerminalView::paste))
             .on_action(cx.listener(TerminalView::clear))
             .on_action(cx.listener(TerminalView::scroll_line_up))
@@ -988,6 +1077,8 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::scroll_page_down))
             .on_action(cx.listener(TerminalView::scroll_to_top))
             .on_action(cx.listener(TerminalView::scroll_to_bottom))
+            .on_action(cx.listener(TerminalView::scroll_to_previous_prompt))
+            .on_action(cx.listener(TerminalView::scroll_to_next_prompt))
             .on_action(cx.listener(TerminalView::toggle_vi_mode))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
@@ -1188,14 +1279,22 @@ impl SerializableItem for TerminalView {
         cx: &mut ViewContext<Self>,
     ) -> Option<Task<gpui::Result<()>>> {
         let terminal = self.terminal().read(cx);
-        if terminal.task().is_some() {
-            return None;
-        }
+        let task = terminal
+            .task()
+            .map(|task| &task.spawn_in_terminal)
+            .or(self.restored_task.as_ref())
+            .and_then(|task| serde_json::to_string(task).log_err());
+        let history = if TerminalSettings::get_global(cx).persist_scrollback {
+            terminal.history_text(MAX_RESTORED_HISTORY_LINES)
+        } else {
+            // Clears the history stored while the setting was on.
+            Some(String::new())
+        };
 
         if let Some((cwd, workspace_id)) = terminal.get_cwd().zip(self.workspace_id) {
             Some(cx.background_executor().spawn(async move {
                 TERMINAL_DB
-                    .save_working_directory(item_id, workspace_id, cwd)
+                    .save_terminal(item_id, workspace_id, cwd, history, task)
                     .await
             }))
         } else {
@@ -1236,11 +1335,33 @@ impl SerializableItem for TerminalView {
                 .ok()
                 .flatten();
 
+            let (history, task) = TERMINAL_DB
+                .get_history_and_task(item_id, workspace_id)
+                .log_err()
+                .flatten()
+                .unwrap_or_default();
+            let task =
+                task.and_then(|task| serde_json::from_str::<SpawnInTerminal>(&task).log_err());
+
             let terminal = project.update(&mut cx, |project, cx| {
                 project.create_terminal(TerminalKind::Shell(cwd), window, cx)
             })??;
+            if let Some(history) = history {
+                terminal.update(&mut cx, |terminal, cx| {
+                    if TerminalSettings::get_global(cx).persist_scrollback {
+                        terminal.restore_history(&history);
+                    }
+                })?;
+            }
             pane.update(&mut cx, |_, cx| {
-                cx.new_view(|cx| TerminalView::new(terminal, workspace, Some(workspace_id), cx))
+                cx.new_view(|cx| {
+                    let mut terminal_view =
+                        TerminalView::new(terminal, workspace, Some(workspace_id), cx);
+                    if let Some(task) = task {
+                        terminal_view.offer_restored_task(task, cx);
+                    }
+                    terminal_view
+                })
             })
         })
     }
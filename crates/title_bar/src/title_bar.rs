@@ -445,10 +445,18 @@ impl TitleBar {
             names_and_branches.next().flatten()
         };
         let workspace = self.workspace.upgrade()?;
-        let branch_name = entry
+        let mut branch_name = entry
             .as_ref()
             .and_then(RepositoryEntry::branch)
             .map(|branch| util::truncate_and_trailoff(&branch, MAX_BRANCH_NAME_LENGTH))?;
+        if let Some(ahead_behind) = entry.as_ref().and_then(RepositoryEntry::ahead_behind) {
+            if ahead_behind.ahead > 0 {
+                branch_name.push_str(&format!(" ↑{}", ahead_behind.ahead));
+            }
+            if ahead_behind.behind > 0 {
+                branch_name.push_str(&format!(" ↓{}", ahead_behind.behind));
+            }
+        }
         Some(
             Button::new("project_branch_trigger", branch_name)
                 .color(Color::Muted)
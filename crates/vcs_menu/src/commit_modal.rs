@@ -0,0 +1,228 @@
+use editor::Editor;
+use gpui::{
+    actions, rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
+    Render, Task, View, ViewContext,
+};
+use project::{Project, ProjectPath};
+use ui::{prelude::*, Checkbox, Selection, Tooltip};
+use util::ResultExt;
+use workspace::notifications::NotificationId;
+use workspace::{ModalView, Toast, Workspace};
+
+use crate::repository_path;
+
+actions!(git, [Commit, AmendCommit, ConfirmCommit]);
+
+/// A modal for writing a commit message and committing the staged changes
+/// of the repository containing the active item.
+pub struct CommitModal {
+    project: Model<Project>,
+    workspace: View<Workspace>,
+    repository_path: ProjectPath,
+    message_editor: View<Editor>,
+    amend: bool,
+    pending_commit: Option<Task<()>>,
+}
+
+impl CommitModal {
+    pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &Commit, cx| {
+            Self::toggle(workspace, false, cx);
+        });
+        workspace.register_action(|workspace, _: &AmendCommit, cx| {
+            Self::toggle(workspace, true, cx);
+        });
+    }
+
+    fn toggle(workspace: &mut Workspace, amend: bool, cx: &mut ViewContext<Workspace>) {
+        let Some(repository_path) = repository_path(workspace, cx) else {
+            struct NoRepository;
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<NoRepository>(),
+                    "No git repository found to commit to",
+                ),
+                cx,
+            );
+            return;
+        };
+        let project = workspace.project().clone();
+        let workspace_handle = cx.view().clone();
+        workspace.toggle_modal(cx, |cx| {
+            CommitModal::new(project, workspace_handle, repository_path, amend, cx)
+        });
+    }
+
+    fn new(
+        project: Model<Project>,
+        workspace: View<Workspace>,
+        repository_path: ProjectPath,
+        amend: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let message_editor = cx.new_view(|cx| {
+            let mut editor = Editor::auto_height(12, cx);
+            editor.set_placeholder_text("Commit message", cx);
+            editor.set_show_gutter(false, cx);
+            editor.set_show_inline_completions(Some(false), cx);
+            editor.set_use_modal_editing(false);
+            editor
+        });
+
+        let mut this = Self {
+            project,
+            workspace,
+            repository_path,
+            message_editor,
+            amend: false,
+            pending_commit: None,
+        };
+        this.set_amend(amend, cx);
+        this
+    }
+
+    fn set_amend(&mut self, amend: bool, cx: &mut ViewContext<Self>) {
+        self.amend = amend;
+        if amend && self.message_editor.read(cx).text_option(cx).is_none() {
+            let head_commit_message = self
+                .project
+                .read(cx)
+                .head_commit_message(self.repository_path.clone(), cx);
+            cx.spawn(|this, mut cx| async move {
+                let Some(message) = head_commit_message.await.log_err().flatten() else {
+                    return;
+                };
+                this.update(&mut cx, |this, cx| {
+                    if this.amend && this.message_editor.read(cx).text_option(cx).is_none() {
+                        this.message_editor
+                            .update(cx, |editor, cx| editor.set_text(message.trim_end(), cx));
+                    }
+                })
+                .ok();
+            })
+            .detach();
+        }
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &ConfirmCommit, cx: &mut ViewContext<Self>) {
+        if self.pending_commit.is_some() {
+            return;
+        }
+        let Some(message) = self.message_editor.read(cx).text_option(cx) else {
+            return;
+        };
+        let commit =
+            self.project
+                .read(cx)
+                .commit(self.repository_path.clone(), message, self.amend, cx);
+        self.pending_commit = Some(cx.spawn(|this, mut cx| async move {
+            let result = commit.await;
+            this.update(&mut cx, |this, cx| {
+                this.pending_commit = None;
+                match result {
+                    Ok(()) => cx.emit(DismissEvent),
+                    Err(error) => {
+                        struct CommitFailure;
+                        this.workspace.update(cx, |workspace, cx| {
+                            workspace.show_toast(
+                                Toast::new(
+                                    NotificationId::unique::<CommitFailure>(),
+                                    format!("Failed to commit: {error}"),
+                                ),
+                                cx,
+                            )
+                        });
+                        cx.notify();
+                    }
+                }
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl ModalView for CommitModal {}
+impl EventEmitter<DismissEvent> for CommitModal {}
+
+impl FocusableView for CommitModal {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.message_editor.focus_handle(cx)
+    }
+}
+
+impl Render for CommitModal {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let can_commit =
+            self.pending_commit.is_none() && self.message_editor.read(cx).text_option(cx).is_some();
+        let commit_label = match (self.pending_commit.is_some(), self.amend) {
+            (true, _) => "Committing...",
+            (false, true) => "Amend",
+            (false, false) => "Commit",
+        };
+        let focus_handle = self.message_editor.focus_handle(cx);
+
+        v_flex()
+            .key_context("CommitModal")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .elevation_3(cx)
+            .w(rems(34.))
+            .p_2()
+            .gap_2()
+            .child(
+                div()
+                    .min_h(rems(6.))
+                    .bg(cx.theme().colors().editor_background)
+                    .p_2()
+                    .border_1()
+                    .rounded_md()
+                    .border_color(cx.theme().colors().border)
+                    .child(self.message_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Checkbox::new(
+                                    "amend",
+                                    if self.amend {
+                                        Selection::Selected
+                                    } else {
+                                        Selection::Unselected
+                                    },
+                                )
+                                .on_click(cx.listener(
+                                    |this, selection: &Selection, cx| {
+                                        this.set_amend(*selection == Selection::Selected, cx);
+                                    },
+                                )),
+                            )
+                            .child(Label::new("Amend last commit").color(Color::Muted)),
+                    )
+                    .child(
+                        Button::new("commit", commit_label)
+                            .style(ButtonStyle::Filled)
+                            .color(Color::Accent)
+                            .disabled(!can_commit)
+                            .tooltip(move |cx| {
+                                Tooltip::for_action_in(
+                                    "Commit staged changes",
+                                    &ConfirmCommit,
+                                    &focus_handle,
+                                    cx,
+                                )
+                            })
+                            .on_click(cx.listener(|this, _, cx| this.confirm(&ConfirmCommit, cx))),
+                    ),
+            )
+    }
+}
@@ -1,29 +1,63 @@
+mod commit_modal;
+mod remote_operations;
+mod rename_branch_modal;
+mod stash_list;
+
 use anyhow::{Context, Result};
 use fuzzy::{StringMatch, StringMatchCandidate};
-use git::repository::Branch;
+use git::repository::{Branch, GitRepository};
 use gpui::{
     actions, rems, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView,
     InteractiveElement, IntoElement, ParentElement, Render, SharedString, Styled, Subscription,
     Task, View, ViewContext, VisualContext, WindowContext,
 };
 use picker::{Picker, PickerDelegate};
-use std::{ops::Not, sync::Arc};
+use project::ProjectPath;
+use rename_branch_modal::RenameBranchModal;
+use std::{ops::Not, path::Path, sync::Arc};
 use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
 use util::ResultExt;
 use workspace::notifications::NotificationId;
 use workspace::{ModalView, Toast, Workspace};
 
-actions!(branches, [OpenRecent]);
+pub use commit_modal::{AmendCommit, Commit, CommitModal, ConfirmCommit};
+pub use remote_operations::{Fetch, Pull, Push};
+pub use stash_list::{StashChanges, StashList, ToggleStashList};
+
+actions!(branches, [OpenRecent, DeleteBranch, RenameBranch]);
 
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(|workspace: &mut Workspace, _| {
         workspace.register_action(|workspace, action, cx| {
             BranchList::open(workspace, action, cx).log_err();
         });
+        CommitModal::register(workspace, cx);
+        remote_operations::register(workspace, cx);
+        stash_list::register(workspace, cx);
     })
     .detach();
 }
 
+/// Picks the repository to operate on: the one containing the active item if any,
+/// otherwise the one at the root of the first visible worktree.
+pub(crate) fn repository_path(workspace: &Workspace, cx: &AppContext) -> Option<ProjectPath> {
+    let project = workspace.project().read(cx);
+    let active_path = workspace
+        .active_item(cx)
+        .and_then(|item| item.project_path(cx));
+    let worktree_roots = project.visible_worktrees(cx).map(|worktree| ProjectPath {
+        worktree_id: worktree.read(cx).id(),
+        path: Path::new("").into(),
+    });
+    active_path.into_iter().chain(worktree_roots).find(|path| {
+        project
+            .worktree_for_id(path.worktree_id, cx)
+            .map_or(false, |worktree| {
+                worktree.read(cx).repository_for_path(&path.path).is_some()
+            })
+    })
+}
+
 pub struct BranchList {
     pub picker: View<Picker<BranchListDelegate>>,
     rem_width: f32,
@@ -51,6 +85,41 @@ impl BranchList {
 
         Ok(())
     }
+
+    fn delete_branch(&mut self, _: &DeleteBranch, cx: &mut ViewContext<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            let Some(BranchEntry::Branch(branch)) = picker.delegate.selected_entry() else {
+                return;
+            };
+            let name = branch.string.clone();
+            let result = picker.delegate.repository(cx).and_then(|repo| {
+                repo.delete_branch(&name)?;
+                repo.branches()
+            });
+            match result {
+                Ok(branches) => {
+                    picker.delegate.all_branches = branches;
+                    picker.refresh(cx);
+                }
+                Err(error) => picker
+                    .delegate
+                    .display_error_toast(format!("Failed to delete branch '{name}': {error}"), cx),
+            }
+        });
+    }
+
+    fn rename_branch(&mut self, _: &RenameBranch, cx: &mut ViewContext<Self>) {
+        let delegate = &self.picker.read(cx).delegate;
+        let Some(BranchEntry::Branch(branch)) = delegate.selected_entry() else {
+            return;
+        };
+        let name = branch.string.clone();
+        let workspace = delegate.workspace.clone();
+        workspace.update(cx, |workspace, cx| {
+            let workspace_handle = cx.view().clone();
+            workspace.toggle_modal(cx, |cx| RenameBranchModal::new(workspace_handle, name, cx));
+        });
+    }
 }
 impl ModalView for BranchList {}
 impl EventEmitter<DismissEvent> for BranchList {}
@@ -64,6 +133,9 @@ impl FocusableView for BranchList {
 impl Render for BranchList {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         v_flex()
+            .key_context("BranchList")
+            .on_action(cx.listener(Self::delete_branch))
+            .on_action(cx.listener(Self::rename_branch))
             .w(rems(self.rem_width))
             .child(self.picker.clone())
             .on_mouse_down_out(cx.listener(|this, _, cx| {
@@ -122,6 +194,19 @@ impl BranchListDelegate {
         })
     }
 
+    fn selected_entry(&self) -> Option<&BranchEntry> {
+        self.matches.get(self.selected_index)
+    }
+
+    fn repository(&self, cx: &AppContext) -> Result<Arc<dyn GitRepository>> {
+        self.workspace
+            .read(cx)
+            .project()
+            .read(cx)
+            .get_first_worktree_root_repo(cx)
+            .context("failed to get root repository for first worktree")
+    }
+
     fn display_error_toast(&self, message: String, cx: &mut WindowContext<'_>) {
         self.workspace.update(cx, |model, ctx| {
             struct GitCheckoutFailure;
@@ -0,0 +1,61 @@
+use anyhow::Result;
+use gpui::{actions, ModelContext, Task, ViewContext};
+use project::{Project, ProjectPath};
+use workspace::notifications::NotificationId;
+use workspace::{Toast, Workspace};
+
+use crate::repository_path;
+
+actions!(git, [Fetch, Pull, Push]);
+
+pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(|workspace, _: &Fetch, cx| {
+        run(workspace, "fetch", Project::fetch, cx);
+    });
+    workspace.register_action(|workspace, _: &Pull, cx| {
+        run(workspace, "pull", Project::pull, cx);
+    });
+    workspace.register_action(|workspace, _: &Push, cx| {
+        run(workspace, "push", Project::push, cx);
+    });
+}
+
+/// Runs a fetch, pull or push on the repository containing the active item.
+/// The project reports its progress, so only failures are shown here.
+fn run(
+    workspace: &mut Workspace,
+    verb: &'static str,
+    operation: impl FnOnce(&mut Project, ProjectPath, &mut ModelContext<Project>) -> Task<Result<()>>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(repository_path) = repository_path(workspace, cx) else {
+        struct NoRepository;
+        workspace.show_toast(
+            Toast::new(
+                NotificationId::unique::<NoRepository>(),
+                format!("No git repository found to {verb}"),
+            ),
+            cx,
+        );
+        return;
+    };
+    let task = workspace
+        .project()
+        .update(cx, |project, cx| operation(project, repository_path, cx));
+    cx.spawn(|workspace, mut cx| async move {
+        if let Err(error) = task.await {
+            workspace.update(&mut cx, |workspace, cx| {
+                struct GitRemoteOperationFailure;
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<GitRemoteOperationFailure>(),
+                        format!("Failed to {verb}: {error}"),
+                    ),
+                    cx,
+                );
+            })?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
@@ -0,0 +1,102 @@
+use anyhow::Context as _;
+use editor::{Editor, EditorEvent};
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Subscription, View,
+};
+use ui::prelude::*;
+use workspace::notifications::NotificationId;
+use workspace::{ModalView, Toast, Workspace};
+
+/// Asks for the new name of a local branch of the root repository of the first worktree.
+pub struct RenameBranchModal {
+    workspace: View<Workspace>,
+    old_name: String,
+    name_editor: View<Editor>,
+    _subscription: Subscription,
+}
+
+impl ModalView for RenameBranchModal {}
+
+impl EventEmitter<DismissEvent> for RenameBranchModal {}
+
+impl FocusableView for RenameBranchModal {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.name_editor.focus_handle(cx)
+    }
+}
+
+impl RenameBranchModal {
+    pub fn new(workspace: View<Workspace>, old_name: String, cx: &mut ViewContext<Self>) -> Self {
+        let name_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_text(old_name.clone(), cx);
+            editor.select_all(&Default::default(), cx);
+            editor
+        });
+        let subscription = cx.subscribe(&name_editor, |_, _, event, cx| {
+            if let EditorEvent::Blurred = event {
+                cx.emit(DismissEvent);
+            }
+        });
+        Self {
+            workspace,
+            old_name,
+            name_editor,
+            _subscription: subscription,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let new_name = self.name_editor.read(cx).text(cx).trim().replace(' ', "-");
+        if new_name.is_empty() || new_name == self.old_name {
+            cx.emit(DismissEvent);
+            return;
+        }
+        let result = self
+            .workspace
+            .read(cx)
+            .project()
+            .read(cx)
+            .get_first_worktree_root_repo(cx)
+            .context("failed to get root repository for first worktree")
+            .and_then(|repo| repo.rename_branch(&self.old_name, &new_name));
+        if let Err(error) = result {
+            let old_name = &self.old_name;
+            self.workspace.update(cx, |workspace, cx| {
+                struct GitRenameBranchFailure;
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<GitRenameBranchFailure>(),
+                        format!("Failed to rename branch '{old_name}': {error}"),
+                    ),
+                    cx,
+                )
+            });
+        }
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for RenameBranchModal {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .elevation_2(cx)
+            .key_context("RenameBranchModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .w_96()
+            .child(div().px_2().py_1().child(self.name_editor.clone()))
+            .child(div().h_px().w_full().bg(cx.theme().colors().border_variant))
+            .child(
+                h_flex().px_2().py_1().child(
+                    Label::new(format!("Rename branch '{}'", self.old_name))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+            )
+    }
+}
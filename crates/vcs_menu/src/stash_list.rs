@@ -0,0 +1,298 @@
+use fuzzy::{StringMatch, StringMatchCandidate};
+use git::repository::Stash;
+use gpui::{
+    actions, rems, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView,
+    Model, Render, Subscription, Task, View, ViewContext, WindowContext,
+};
+use picker::{Picker, PickerDelegate};
+use project::{Project, ProjectPath};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, KeyBinding, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::notifications::NotificationId;
+use workspace::{ModalView, Toast, Workspace};
+
+use crate::repository_path;
+
+actions!(git, [StashChanges, ToggleStashList]);
+
+pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(stash_changes);
+    workspace.register_action(StashList::toggle);
+}
+
+fn stash_changes(workspace: &mut Workspace, _: &StashChanges, cx: &mut ViewContext<Workspace>) {
+    let Some(repository_path) = repository_path(workspace, cx) else {
+        return;
+    };
+    let stash = workspace
+        .project()
+        .read(cx)
+        .stash_push(repository_path, None, cx);
+    cx.spawn(|workspace, mut cx| async move {
+        if let Err(error) = stash.await {
+            workspace.update(&mut cx, |workspace, cx| {
+                show_error_toast(workspace, format!("Failed to stash changes: {error}"), cx)
+            })?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+fn show_error_toast(workspace: &mut Workspace, message: String, cx: &mut ViewContext<Workspace>) {
+    struct GitStashFailure;
+    workspace.show_toast(
+        Toast::new(NotificationId::unique::<GitStashFailure>(), message),
+        cx,
+    );
+}
+
+/// A modal listing the stash entries of a repository, to pop or drop them.
+pub struct StashList {
+    picker: View<Picker<StashListDelegate>>,
+    _subscription: Subscription,
+}
+
+impl StashList {
+    fn toggle(workspace: &mut Workspace, _: &ToggleStashList, cx: &mut ViewContext<Workspace>) {
+        let Some(repository_path) = repository_path(workspace, cx) else {
+            return;
+        };
+        let project = workspace.project().clone();
+        let workspace_handle = cx.view().clone();
+        workspace.toggle_modal(cx, |cx| {
+            let delegate = StashListDelegate::new(project, workspace_handle, repository_path);
+            Self::new(delegate, cx)
+        });
+    }
+
+    fn new(delegate: StashListDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        let this = Self {
+            picker,
+            _subscription,
+        };
+        this.reload_stashes(cx);
+        this
+    }
+
+    fn reload_stashes(&self, cx: &mut ViewContext<Self>) {
+        let delegate = &self.picker.read(cx).delegate;
+        let stashes = delegate
+            .project
+            .read(cx)
+            .stash_list(delegate.repository_path.clone(), cx);
+        let picker = self.picker.downgrade();
+        cx.spawn(|_, mut cx| async move {
+            let stashes = stashes.await?;
+            picker.update(&mut cx, |picker, cx| {
+                picker.delegate.all_stashes = stashes;
+                picker.refresh(cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+impl ModalView for StashList {}
+impl EventEmitter<DismissEvent> for StashList {}
+
+impl FocusableView for StashList {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for StashList {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+pub struct StashListDelegate {
+    project: Model<Project>,
+    workspace: View<Workspace>,
+    repository_path: ProjectPath,
+    all_stashes: Vec<Stash>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl StashListDelegate {
+    fn new(
+        project: Model<Project>,
+        workspace: View<Workspace>,
+        repository_path: ProjectPath,
+    ) -> Self {
+        Self {
+            project,
+            workspace,
+            repository_path,
+            all_stashes: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for StashListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Select stash...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .all_stashes
+            .iter()
+            .enumerate()
+            .map(|(ix, stash)| StringMatchCandidate {
+                id: ix,
+                char_bag: stash.message.chars().collect(),
+                string: stash.message.clone(),
+            })
+            .collect::<Vec<_>>();
+        cx.spawn(move |picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.matches.len().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    /// Pops the selected stash entry, or drops it if `secondary` is set.
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(stash) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|hit| self.all_stashes.get(hit.candidate_id))
+        else {
+            return;
+        };
+        let project = self.project.read(cx);
+        let (operation, verb) = if secondary {
+            (
+                project.stash_drop(self.repository_path.clone(), stash.index, cx),
+                "drop",
+            )
+        } else {
+            (
+                project.stash_pop(self.repository_path.clone(), stash.index, cx),
+                "pop",
+            )
+        };
+        let workspace = self.workspace.downgrade();
+        cx.spawn(|_, mut cx| async move {
+            if let Err(error) = operation.await {
+                workspace.update(&mut cx, |workspace, cx| {
+                    show_error_toast(workspace, format!("Failed to {verb} stash: {error}"), cx)
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        let stash = self.all_stashes.get(hit.candidate_id)?;
+        Some(
+            ListItem::new(SharedString::from(format!("stash-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            Label::new(format!("stash@{{{}}}", stash.index))
+                                .color(Color::Muted)
+                                .size(LabelSize::Small),
+                        )
+                        .child(HighlightedLabel::new(
+                            hit.string.clone(),
+                            hit.positions.clone(),
+                        )),
+                ),
+        )
+    }
+
+    fn render_footer(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        Some(
+            h_flex()
+                .w_full()
+                .p_2()
+                .gap_2()
+                .justify_end()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Button::new("drop-stash", "Drop")
+                        .label_size(LabelSize::Small)
+                        .key_binding(KeyBinding::for_action(&menu::SecondaryConfirm, cx))
+                        .on_click(|_, cx| cx.dispatch_action(Box::new(menu::SecondaryConfirm))),
+                )
+                .child(
+                    Button::new("pop-stash", "Pop")
+                        .label_size(LabelSize::Small)
+                        .key_binding(KeyBinding::for_action(&menu::Confirm, cx))
+                        .on_click(|_, cx| cx.dispatch_action(Box::new(menu::Confirm))),
+                )
+                .into_any_element(),
+        )
+    }
+}
@@ -10,7 +10,7 @@ use editor::{
 use itertools::Itertools;
 
 use gpui::{actions, impl_actions, ViewContext};
-use language::{BufferSnapshot, CharKind, Point, Selection};
+use language::{BufferSnapshot, CharKind, Point, Selection, TextObject};
 use multi_buffer::MultiBufferRow;
 use serde::Deserialize;
 
@@ -29,6 +29,9 @@ pub enum Object {
     AngleBrackets,
     Argument,
     Tag,
+    Function,
+    Class,
+    Comment,
 }
 
 #[derive(Clone, Deserialize, PartialEq)]
@@ -54,7 +57,10 @@ actions!(
         CurlyBrackets,
         AngleBrackets,
         Argument,
-        Tag
+        Tag,
+        Function,
+        Class,
+        Comment
     ]
 );
 
@@ -100,6 +106,15 @@ pub fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
     Vim::action(editor, cx, |vim, _: &Argument, cx| {
         vim.object(Object::Argument, cx)
     });
+    Vim::action(editor, cx, |vim, _: &Function, cx| {
+        vim.object(Object::Function, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &Class, cx| {
+        vim.object(Object::Class, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &Comment, cx| {
+        vim.object(Object::Comment, cx)
+    });
 }
 
 impl Vim {
@@ -129,13 +144,22 @@ impl Object {
             | Object::AngleBrackets
             | Object::CurlyBrackets
             | Object::SquareBrackets
-            | Object::Argument => true,
+            | Object::Argument
+            | Object::Function
+            | Object::Class
+            | Object::Comment => true,
         }
     }
 
     pub fn always_expands_both_ways(self) -> bool {
         match self {
-            Object::Word { .. } | Object::Sentence | Object::Paragraph | Object::Argument => false,
+            Object::Word { .. }
+            | Object::Sentence
+            | Object::Paragraph
+            | Object::Argument
+            | Object::Function
+            | Object::Class
+            | Object::Comment => false,
             Object::Quotes
             | Object::BackQuotes
             | Object::DoubleQuotes
@@ -167,7 +191,10 @@ impl Object {
             | Object::AngleBrackets
             | Object::VerticalBars
             | Object::Tag
-            | Object::Argument => Mode::Visual,
+            | Object::Argument
+            | Object::Function
+            | Object::Class
+            | Object::Comment => Mode::Visual,
             Object::Paragraph => Mode::VisualLine,
         }
     }
@@ -214,7 +241,47 @@ impl Object {
             Object::AngleBrackets => {
                 surrounding_markers(map, relative_to, around, self.is_multiline(), '<', '>')
             }
-            Object::Argument => argument(map, relative_to, around),
+            Object::Argument => {
+                let target = if around {
+                    TextObject::AroundParameter
+                } else {
+                    TextObject::InsideParameter
+                };
+                // Prefer the parameters captured by the language, which include their separator
+                // when around, over guessing them from the surrounding brackets.
+                match text_object(map, relative_to, target) {
+                    Some(range) if around => Some(expand_to_include_whitespace(map, range, true)),
+                    Some(range) => Some(range),
+                    None => argument(map, relative_to, around),
+                }
+            }
+            Object::Function => text_object(
+                map,
+                relative_to,
+                if around {
+                    TextObject::AroundFunction
+                } else {
+                    TextObject::InsideFunction
+                },
+            ),
+            Object::Class => text_object(
+                map,
+                relative_to,
+                if around {
+                    TextObject::AroundClass
+                } else {
+                    TextObject::InsideClass
+                },
+            ),
+            Object::Comment => text_object(
+                map,
+                relative_to,
+                if around {
+                    TextObject::AroundComment
+                } else {
+                    TextObject::InsideComment
+                },
+            ),
         }
     }
 
@@ -564,6 +631,57 @@ fn argument(
     }
 }
 
+/// Returns the range of the innermost text object of the given kind containing `relative_to`, as
+/// captured by the `textobjects` query of the language. When the language has no capture for the
+/// inside of the text object, the range of the whole text object is returned instead.
+fn text_object(
+    map: &DisplaySnapshot,
+    relative_to: DisplayPoint,
+    target: TextObject,
+) -> Option<Range<DisplayPoint>> {
+    let snapshot = &map.buffer_snapshot;
+    let offset = relative_to.to_offset(map, Bias::Left);
+
+    // Text objects are captured from the syntax tree, so we operate at the buffer level and map back to the display level
+    let excerpt = snapshot.excerpt_containing(offset..offset)?;
+    let buffer = excerpt.buffer();
+    let offset = excerpt.map_offset_to_buffer(offset);
+
+    let innermost = |range: Range<usize>, target: TextObject| {
+        buffer
+            .text_object_ranges(range.clone())
+            .filter(|(object_range, object)| {
+                *object == target
+                    && object_range.start <= range.start
+                    && object_range.end >= range.end
+            })
+            .map(|(object_range, _)| object_range)
+            .min_by_key(|object_range| object_range.len())
+    };
+
+    let result = innermost(offset..offset, target).or_else(|| {
+        // The cursor may be outside of the inside of the text object, e.g. on a function's signature.
+        let around_range = innermost(offset..offset, target.around()?)?;
+        buffer
+            .text_object_ranges(around_range.clone())
+            .filter(|(object_range, object)| {
+                *object == target
+                    && object_range.start >= around_range.start
+                    && object_range.end <= around_range.end
+            })
+            .map(|(object_range, _)| object_range)
+            .max_by_key(|object_range| object_range.len())
+            .or(Some(around_range))
+    })?;
+
+    if excerpt.contains_buffer_range(result.clone()) {
+        let result = excerpt.map_range_from_buffer(result);
+        Some(result.start.to_display_point(map)..result.end.to_display_point(map))
+    } else {
+        None
+    }
+}
+
 fn sentence(
     map: &DisplaySnapshot,
     relative_to: DisplayPoint,
@@ -1413,6 +1531,10 @@ mod test {
         cx.simulate_keystrokes("d a a");
         cx.assert_state("fn boop(ˇarg_b: String) {}", Mode::Normal);
 
+        cx.set_state("fn boop(arg_a: String, ˇarg_b: String) {}", Mode::Normal);
+        cx.simulate_keystrokes("d a a");
+        cx.assert_state("fn boop(arg_a: Stringˇ) {}", Mode::Normal);
+
         cx.set_state("std::namespace::test(\"strinˇg\", a.b.c())", Mode::Normal);
         cx.simulate_keystrokes("v a a");
         cx.assert_state("std::namespace::test(«\"string\", ˇ»a.b.c())", Mode::Visual);
@@ -1453,6 +1575,97 @@ mod test {
         cx.assert_state("let a = [«test::call(first_arg)ˇ»]", Mode::Visual);
     }
 
+    #[gpui::test]
+    async fn test_syntax_text_objects(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+                impl A {
+                    fn a() {
+                        ˇb();
+                        c();
+                    }
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("v i f");
+        cx.assert_state(
+            indoc! {"
+                impl A {
+                    fn a() {
+                        «b();
+                        c();ˇ»
+                    }
+                }"},
+            Mode::Visual,
+        );
+
+        // From the signature, the inside of the function is its body
+        cx.set_state(
+            indoc! {"
+                fn ˇa() {
+                    b();
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("v i f");
+        cx.assert_state(
+            indoc! {"
+                fn a() {
+                    «b();ˇ»
+                }"},
+            Mode::Visual,
+        );
+
+        cx.set_state(
+            indoc! {"
+                impl A {
+                    fn a() {
+                        ˇb();
+                    }
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("v a f");
+        cx.assert_state(
+            indoc! {"
+                impl A {
+                    «fn a() {
+                        b();
+                    }ˇ»
+                }"},
+            Mode::Visual,
+        );
+
+        cx.set_state(
+            indoc! {"
+                impl A {
+                    fn a() {
+                        ˇb();
+                    }
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("d a c");
+        cx.assert_state("ˇ", Mode::Normal);
+
+        cx.set_state(
+            indoc! {"
+                // one
+                // ˇtwo
+                fn a() {}"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("d a g c");
+        cx.assert_state(
+            indoc! {"
+                ˇ
+                fn a() {}"},
+            Mode::Normal,
+        );
+    }
+
     #[gpui::test]
     async fn test_delete_surrounding_character_objects(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
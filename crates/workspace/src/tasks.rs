@@ -1,6 +1,11 @@
+use anyhow::Context as _;
+use futures::{
+    channel::mpsc::{self, UnboundedReceiver},
+    StreamExt,
+};
 use project::TaskSourceKind;
 use remote::ConnectionState;
-use task::{ResolvedTask, TaskContext, TaskTemplate};
+use task::{ResolvedTask, SpawnInTerminal, TaskContext, TaskTemplate};
 use ui::ViewContext;
 
 use crate::Workspace;
@@ -42,23 +47,109 @@ pub fn schedule_task(
 pub fn schedule_resolved_task(
     workspace: &Workspace,
     task_source_kind: TaskSourceKind,
-    mut resolved_task: ResolvedTask,
+    resolved_task: ResolvedTask,
     omit_history: bool,
     cx: &mut ViewContext<'_, Workspace>,
 ) {
-    if let Some(spawn_in_terminal) = resolved_task.resolved.take() {
-        if !omit_history {
-            resolved_task.resolved = Some(spawn_in_terminal.clone());
-            workspace.project().update(cx, |project, cx| {
-                if let Some(task_inventory) =
-                    project.task_store().read(cx).task_inventory().cloned()
-                {
-                    task_inventory.update(cx, |inventory, _| {
-                        inventory.task_scheduled(task_source_kind, resolved_task);
-                    })
-                }
-            });
+    if resolved_task.original_task().depends_on.is_empty() {
+        spawn_resolved_task(workspace, task_source_kind, resolved_task, omit_history, cx);
+        return;
+    }
+
+    let dependency_tree = workspace
+        .project()
+        .read(cx)
+        .task_store()
+        .read(cx)
+        .task_inventory()
+        .context("no task inventory to resolve task dependencies with")
+        .and_then(|inventory| {
+            inventory
+                .read(cx)
+                .resolve_dependencies(task_source_kind, resolved_task)
+        });
+    let dependency_tree = match dependency_tree {
+        Ok(dependency_tree) => dependency_tree,
+        Err(e) => {
+            log::error!("Failed to schedule task: {e:#}");
+            cx.defer(move |workspace, cx| workspace.show_error(&e, cx));
+            return;
         }
-        cx.emit(crate::Event::SpawnTask(Box::new(spawn_in_terminal)));
+    };
+
+    // Only the scheduled task itself gets into the history, not its dependencies.
+    let scheduled_task_id = dependency_tree.task.1.id.clone();
+    cx.spawn(|workspace, cx| async move {
+        let spawn = |(task_source_kind, resolved_task): &(TaskSourceKind, ResolvedTask)| {
+            let workspace = workspace.clone();
+            let mut cx = cx.clone();
+            let task_source_kind = task_source_kind.clone();
+            let resolved_task = resolved_task.clone();
+            let omit_history = omit_history || resolved_task.id != scheduled_task_id;
+            async move {
+                let label = resolved_task.display_label().to_string();
+                let completion_rx = workspace.update(&mut cx, |workspace, cx| {
+                    spawn_resolved_task(
+                        workspace,
+                        task_source_kind,
+                        resolved_task,
+                        omit_history,
+                        cx,
+                    )
+                })?;
+                if let Some(mut completion_rx) = completion_rx {
+                    let success = completion_rx.next().await.unwrap_or(false);
+                    anyhow::ensure!(success, "Task {label:?} failed");
+                }
+                Ok(())
+            }
+        };
+        dependency_tree.run(&spawn).await
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Spawns the task in the terminal, ignoring its dependencies.
+/// Returns a receiver for the task completion status, unless there was nothing to spawn.
+fn spawn_resolved_task(
+    workspace: &Workspace,
+    task_source_kind: TaskSourceKind,
+    mut resolved_task: ResolvedTask,
+    omit_history: bool,
+    cx: &mut ViewContext<'_, Workspace>,
+) -> Option<UnboundedReceiver<bool>> {
+    let spawn_in_terminal = resolved_task.resolved.take()?;
+    if !omit_history {
+        resolved_task.resolved = Some(spawn_in_terminal.clone());
+        workspace.project().update(cx, |project, cx| {
+            if let Some(task_inventory) = project.task_store().read(cx).task_inventory().cloned() {
+                task_inventory.update(cx, |inventory, _| {
+                    inventory.task_scheduled(task_source_kind, resolved_task);
+                })
+            }
+        });
+    }
+    if spawn_in_terminal.command.trim().is_empty() {
+        return None;
+    }
+    let (completion_tx, completion_rx) = mpsc::unbounded();
+    cx.emit(crate::Event::SpawnTask {
+        action: Box::new(spawn_in_terminal),
+        completion_tx: Some(completion_tx),
+    });
+    Some(completion_rx)
+}
+
+impl Workspace {
+    /// Spawns an already resolved task in the terminal, without recording it in the task history.
+    pub fn spawn_in_terminal(
+        &mut self,
+        spawn_in_terminal: SpawnInTerminal,
+        cx: &mut ViewContext<Self>,
+    ) {
+        cx.emit(crate::Event::SpawnTask {
+            action: Box::new(spawn_in_terminal),
+            completion_tx: None,
+        });
     }
 }
@@ -690,7 +690,11 @@ pub enum Event {
     },
     ContactRequestedJoin(u64),
     WorkspaceCreated(WeakView<Workspace>),
-    SpawnTask(Box<SpawnInTerminal>),
+    SpawnTask {
+        action: Box<SpawnInTerminal>,
+        /// Receives whether the task has finished successfully, if anyone waits for its completion.
+        completion_tx: Option<UnboundedSender<bool>>,
+    },
     OpenBundledFile {
         text: Cow<'static, str>,
         title: &'static str,
@@ -20,7 +20,7 @@ use futures::{
 };
 use fuzzy::CharBag;
 use git::{
-    repository::{GitFileStatus, GitRepository, RepoPath},
+    repository::{AheadBehind, GitFileStatus, GitRepository, RepoPath},
     status::GitStatus,
     COOKIES, DOT_GIT, FSMONITOR_DAEMON, GITIGNORE,
 };
@@ -160,6 +160,7 @@ pub struct Snapshot {
 pub struct RepositoryEntry {
     pub(crate) work_directory: WorkDirectoryEntry,
     pub(crate) branch: Option<Arc<str>>,
+    pub(crate) ahead_behind: Option<AheadBehind>,
 
     /// If location_in_repo is set, it means the .git folder is external
     /// and in a parent folder of the project root.
@@ -187,6 +188,11 @@ impl RepositoryEntry {
         self.branch.clone()
     }
 
+    /// Returns how far the branch has diverged from its upstream, if it has one.
+    pub fn ahead_behind(&self) -> Option<AheadBehind> {
+        self.ahead_behind
+    }
+
     pub fn work_directory_id(&self) -> ProjectEntryId {
         *self.work_directory
     }
@@ -225,6 +231,17 @@ impl RepositoryEntry {
             relativize_path(path)
         }
     }
+
+    /// unrelativize is the inverse of relativize: it returns the given repository path
+    /// relative to the project root folder, or None if the path lies outside of the project.
+    pub fn unrelativize(&self, worktree: &Snapshot, path: &RepoPath) -> Option<Arc<Path>> {
+        let entry = worktree.entry_for_id(self.work_directory.0)?;
+        let path = match &self.location_in_repo {
+            Some(location_in_repo) => path.strip_prefix(location_in_repo).ok()?,
+            None => path.as_path(),
+        };
+        Some(entry.path.join(path).into())
+    }
 }
 
 impl From<&RepositoryEntry> for proto::RepositoryEntry {
@@ -232,10 +249,19 @@ impl From<&RepositoryEntry> for proto::RepositoryEntry {
         proto::RepositoryEntry {
             work_directory_id: value.work_directory.to_proto(),
             branch: value.branch.as_ref().map(|str| str.to_string()),
+            commits_ahead: value.ahead_behind.map(|counts| counts.ahead),
+            commits_behind: value.ahead_behind.map(|counts| counts.behind),
         }
     }
 }
 
+fn ahead_behind_from_proto(repository: &proto::RepositoryEntry) -> Option<AheadBehind> {
+    Some(AheadBehind {
+        ahead: repository.commits_ahead?,
+        behind: repository.commits_behind?,
+    })
+}
+
 /// This path corresponds to the 'content path' of a repository in relation
 /// to Zed's project root.
 /// In the majority of the cases, this is the folder that contains the .git folder.
@@ -2118,11 +2144,13 @@ impl Snapshot {
             let work_directory_entry: WorkDirectoryEntry =
                 ProjectEntryId::from_proto(repository.work_directory_id).into();
 
+            let ahead_behind = ahead_behind_from_proto(&repository);
             if let Some(entry) = self.entry_for_id(*work_directory_entry) {
                 let work_directory = RepositoryWorkDirectory(entry.path.clone());
                 if self.repository_entries.get(&work_directory).is_some() {
                     self.repository_entries.update(&work_directory, |repo| {
                         repo.branch = repository.branch.map(Into::into);
+                        repo.ahead_behind = ahead_behind;
                     });
                 } else {
                     self.repository_entries.insert(
@@ -2130,6 +2158,7 @@ impl Snapshot {
                         RepositoryEntry {
                             work_directory: work_directory_entry,
                             branch: repository.branch.map(Into::into),
+                            ahead_behind,
                             // When syncing repository entries from a peer, we don't need
                             // the location_in_repo field, since git operations don't happen locally
                             // anyway.
@@ -2945,6 +2974,7 @@ impl BackgroundScannerState {
             RepositoryEntry {
                 work_directory: work_dir_id.into(),
                 branch: repository.branch_name().map(Into::into),
+                ahead_behind: repository.upstream_ahead_behind(),
                 location_in_repo,
             },
         );
@@ -4500,6 +4530,7 @@ impl BackgroundScanner {
 
                         let repo = &repository.repo_ptr;
                         let branch = repo.branch_name();
+                        let ahead_behind = repo.upstream_ahead_behind();
                         repo.reload_index();
 
                         state
@@ -4510,7 +4541,10 @@ impl BackgroundScanner {
                             .snapshot
                             .snapshot
                             .repository_entries
-                            .update(&work_dir, |entry| entry.branch = branch.map(Into::into));
+                            .update(&work_dir, |entry| {
+                                entry.branch = branch.map(Into::into);
+                                entry.ahead_behind = ahead_behind;
+                            });
                         (work_dir, repository.repo_ptr.clone())
                     }
                 };
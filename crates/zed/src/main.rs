@@ -245,6 +245,7 @@ fn init_ui(
     editor::init(cx);
     image_viewer::init(cx);
     diagnostics::init(cx);
+    diff_view::init(cx);
 
     audio::init(Assets, cx);
     workspace::init(app_state.clone(), cx);
@@ -255,8 +256,12 @@ fn init_ui(
     dev_server_projects::init(app_state.client.clone(), cx);
     outline::init(cx);
     project_symbols::init(cx);
+    call_hierarchy::init(cx);
+    local_history::init(cx);
     project_panel::init(Assets, cx);
     outline_panel::init(Assets, cx);
+    git_panel::init(cx);
+    git_log::init(cx);
     tasks_ui::init(cx);
     snippets_ui::init(cx);
     channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
@@ -26,6 +26,7 @@ pub use open_listener::*;
 use anyhow::Context as _;
 use assets::Assets;
 use futures::{channel::mpsc, select_biased, StreamExt};
+use git_panel::GitPanel;
 use outline_panel::OutlinePanel;
 use project::Item;
 use project_panel::ProjectPanel;
@@ -238,6 +239,7 @@ pub fn initialize_workspace(
 
             let project_panel = ProjectPanel::load(workspace_handle.clone(), cx.clone());
             let outline_panel = OutlinePanel::load(workspace_handle.clone(), cx.clone());
+            let git_panel = GitPanel::load(workspace_handle.clone(), cx.clone());
             let terminal_panel = TerminalPanel::load(workspace_handle.clone(), cx.clone());
             let channels_panel =
                 collab_ui::collab_panel::CollabPanel::load(workspace_handle.clone(), cx.clone());
@@ -251,6 +253,7 @@ pub fn initialize_workspace(
             let (
                 project_panel,
                 outline_panel,
+                git_panel,
                 terminal_panel,
                 assistant_panel,
                 channels_panel,
@@ -259,6 +262,7 @@ pub fn initialize_workspace(
             ) = futures::try_join!(
                 project_panel,
                 outline_panel,
+                git_panel,
                 terminal_panel,
                 assistant_panel,
                 channels_panel,
@@ -270,6 +274,7 @@ pub fn initialize_workspace(
                 workspace.add_panel(assistant_panel, cx);
                 workspace.add_panel(project_panel, cx);
                 workspace.add_panel(outline_panel, cx);
+                workspace.add_panel(git_panel, cx);
                 workspace.add_panel(terminal_panel, cx);
                 workspace.add_panel(channels_panel, cx);
                 workspace.add_panel(chat_panel, cx);
@@ -3411,6 +3416,7 @@ mod tests {
             collab_ui::init(&app_state, cx);
             project_panel::init((), cx);
             outline_panel::init((), cx);
+            git_panel::init(cx);
             terminal_view::init(cx);
             copilot::copilot_chat::init(
                 app_state.fs.clone(),